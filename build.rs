@@ -0,0 +1,42 @@
+//! Generates the C header `inkling.h` into `OUT_DIR` when building with the `ffi` feature.
+//!
+//! The header in `include/inkling.h` is only written when `INKLING_WRITE_HEADER` is set,
+//! which is how the committed copy is updated after changing the C interface:
+//!
+//! ```sh
+//! INKLING_WRITE_HEADER=1 cargo build --features ffi
+//! ```
+
+fn main() {
+    #[cfg(feature = "ffi")]
+    write_header();
+
+    println!("cargo:rerun-if-changed=build.rs");
+}
+
+#[cfg(feature = "ffi")]
+fn write_header() {
+    use std::{env, path::PathBuf};
+
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("could not read cbindgen.toml");
+
+    let bindings = cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(crate_dir.join("src").join("ffi.rs"))
+        .generate()
+        .expect("could not generate the C header");
+
+    bindings.write_to_file(out_dir.join("inkling.h"));
+
+    if env::var_os("INKLING_WRITE_HEADER").is_some() {
+        bindings.write_to_file(crate_dir.join("include").join("inkling.h"));
+    }
+
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed=INKLING_WRITE_HEADER");
+}
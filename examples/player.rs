@@ -1,5 +1,5 @@
 use std::{
-    env::current_dir,
+    env::{args, current_dir},
     fs::read_to_string,
     io::{self, Write as _},
     path::{Path, PathBuf},
@@ -8,19 +8,47 @@ use std::{
 
 use inkling::*;
 
+const DEBUG_HELP: &str = "\
+Debugger commands:
+  s, step               Step to the next line, into other knots
+  n, next               Step to the next line in the current knot or stitch
+  c, continue           Continue to the next breakpoint or choice
+  b, break <target>     Add a breakpoint at a line number or knot.stitch
+  d, delete <target>    Remove a breakpoint
+  w, watch <expression> Watch the value of an expression
+  u, unwatch <expr>     Stop watching an expression
+  bt, stack             Print the stack of choices made in the current stitch
+  p, print              Print all watched expressions
+  h, help               Print this help
+  q, quit               Exit the story
+";
+
 fn main() -> Result<(), io::Error> {
-    let base_dir = current_dir().unwrap();
+    let mut debug = false;
+    let mut path = None;
 
-    let mut assets_dir = base_dir.clone();
-    assets_dir.push("examples");
-    assets_dir.push("assets");
+    for arg in args().skip(1) {
+        match arg.as_str() {
+            "--debug" => debug = true,
+            _ => path = Some(PathBuf::from(arg)),
+        }
+    }
+
+    let path = path.unwrap_or_else(|| {
+        let base_dir = current_dir().unwrap();
+
+        let mut assets_dir = base_dir.clone();
+        assets_dir.push("examples");
+        assets_dir.push("assets");
+
+        [assets_dir.as_path(), Path::new("story.ink")]
+            .iter()
+            .collect()
+    });
 
-    let path: PathBuf = [assets_dir.as_path(), Path::new("story.ink")]
-        .iter()
-        .collect();
     let story = read_story(&path)?;
 
-    match play_story(story) {
+    match play_story(story, debug) {
         Ok(_) => println!("FIN\n"),
         Err(err) => {
             eprintln!("error: {}", err);
@@ -31,26 +59,46 @@ fn main() -> Result<(), io::Error> {
     Ok(())
 }
 
-fn play_story(mut story: Story) -> Result<(), InklingError> {
+fn play_story(mut story: Story, debug: bool) -> Result<(), InklingError> {
     let mut line_buffer = Vec::new();
+    let mut step = None;
+
+    if debug {
+        println!("{}", DEBUG_HELP);
+        step = ask_user_for_command(&mut story);
+    }
+
+    loop {
+        let prompt = match step.take() {
+            Some(step) => story.step(step, &mut line_buffer)?,
+            None => story.resume(&mut line_buffer)?,
+        };
 
-    while let Prompt::Choice(choices) = story.resume(&mut line_buffer)? {
         print_lines(&line_buffer);
         line_buffer.clear();
 
-        let choice = ask_user_for_choice(&choices).unwrap_or_else(|| {
-            println!("Exiting program.");
-            exit(0);
-        });
+        match prompt {
+            Prompt::Done => return Ok(()),
+            Prompt::Breakpoint(pause) => {
+                println!("[{}]", pause);
+                step = ask_user_for_command(&mut story);
+            }
+            Prompt::Choice(choices) => {
+                let choice =
+                    ask_user_for_choice(&choices, &mut story, debug).unwrap_or_else(|| {
+                        println!("Exiting program.");
+                        exit(0);
+                    });
 
-        println!("");
-        story.make_choice(choice)?;
+                println!("");
+                story.make_choice(choice)?;
+            }
+            _ => unreachable!("all prompts are handled by this player"),
+        }
     }
-
-    Ok(())
 }
 
-fn ask_user_for_choice(choices: &[Choice]) -> Option<usize> {
+fn ask_user_for_choice(choices: &[Choice], story: &mut Story, debug: bool) -> Option<usize> {
     println!("Choose:");
 
     for (i, choice) in choices.iter().enumerate() {
@@ -59,24 +107,32 @@ fn ask_user_for_choice(choices: &[Choice]) -> Option<usize> {
 
     println!("     ---");
     println!("  0. Exit story");
+
+    if debug {
+        println!("  Or enter a debugger command.");
+    }
+
     println!("");
 
-    let index = get_choice(choices.len())?;
+    let index = get_choice(choices.len(), story, debug)?;
     Some(index)
 }
 
-fn get_choice(num_choices: usize) -> Option<usize> {
+fn get_choice(num_choices: usize, story: &mut Story, debug: bool) -> Option<usize> {
     loop {
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).unwrap();
+        let input = read_input();
 
-        match input.trim().parse::<usize>() {
+        match input.parse::<usize>() {
             Ok(0) => {
                 return None;
             }
             Ok(i) if i > 0 && i <= num_choices => {
                 return Some(i - 1);
             }
+            Err(_) if debug => match run_command(&input, story) {
+                Command::Done => (),
+                Command::Resume(..) => println!("Make a choice to continue the story."),
+            },
             _ => {
                 println!("Not a valid option, try again:");
             }
@@ -84,6 +140,114 @@ fn get_choice(num_choices: usize) -> Option<usize> {
     }
 }
 
+/// Result of a debugger command.
+enum Command {
+    /// The command was run or printed an error, ask for another.
+    Done,
+    /// Resume the story, stepping if set.
+    Resume(Option<Step>),
+}
+
+fn ask_user_for_command(story: &mut Story) -> Option<Step> {
+    loop {
+        print!("(debug) ");
+        io::stdout().flush().unwrap();
+
+        if let Command::Resume(step) = run_command(&read_input(), story) {
+            return step;
+        }
+    }
+}
+
+fn run_command(input: &str, story: &mut Story) -> Command {
+    let (command, argument) = match input.find(' ') {
+        Some(i) => (&input[..i], input[i..].trim()),
+        None => (input, ""),
+    };
+
+    match command {
+        "s" | "step" => return Command::Resume(Some(Step::Into)),
+        "n" | "next" => return Command::Resume(Some(Step::Over)),
+        "c" | "continue" => return Command::Resume(None),
+        "b" | "break" => match story.add_breakpoint(get_breakpoint(argument)) {
+            Ok(()) => println!("Breakpoints: {}", get_breakpoints(story)),
+            Err(err) => println!("{}", err),
+        },
+        "d" | "delete" => {
+            if !story.remove_breakpoint(&get_breakpoint(argument)) {
+                println!("No breakpoint at '{}'.", argument);
+            }
+
+            println!("Breakpoints: {}", get_breakpoints(story));
+        }
+        "w" | "watch" => match story.add_watch(argument) {
+            Ok(()) => print_watches(story),
+            Err(err) => println!("{}", err),
+        },
+        "u" | "unwatch" => {
+            if !story.remove_watch(argument) {
+                println!("'{}' is not watched.", argument);
+            }
+        }
+        "bt" | "stack" => {
+            for frame in story.get_node_stack() {
+                let line = frame
+                    .line
+                    .map(|line| format!("line {}", line))
+                    .unwrap_or_else(|| "end".to_string());
+
+                println!("  #{} {} in '{}'", frame.depth, line, frame.location);
+            }
+        }
+        "p" | "print" => print_watches(story),
+        "h" | "help" => println!("{}", DEBUG_HELP),
+        "q" | "quit" => {
+            println!("Exiting program.");
+            exit(0);
+        }
+        _ => println!("Unknown command '{}', enter 'help' for a list.", input),
+    }
+
+    Command::Done
+}
+
+fn get_breakpoint(target: &str) -> Breakpoint {
+    match target.parse::<u32>() {
+        Ok(line) => Breakpoint::Line(line),
+        Err(_) => Breakpoint::Location(Location::from(target)),
+    }
+}
+
+fn get_breakpoints(story: &Story) -> String {
+    let breakpoints = story
+        .get_breakpoints()
+        .iter()
+        .map(|breakpoint| breakpoint.to_string())
+        .collect::<Vec<_>>();
+
+    if breakpoints.is_empty() {
+        "none".to_string()
+    } else {
+        breakpoints.join(", ")
+    }
+}
+
+fn print_watches(story: &Story) {
+    for watch in story.get_watches() {
+        match watch.value {
+            Ok(value) => println!("  {} = {}", watch.expression, value.to_ink_source()),
+            Err(err) => println!("  {}: {}", watch.expression, err),
+        }
+    }
+}
+
+fn read_input() -> String {
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+
+    input.trim().to_string()
+}
+
 fn print_lines(lines: &LineBuffer) {
     for line in lines {
         print!("{}", line.text);
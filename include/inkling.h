@@ -0,0 +1,242 @@
+#ifndef INKLING_H
+#define INKLING_H
+
+/* Generated from src/ffi.rs by cbindgen, see build.rs. Do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of resuming a story.
+typedef enum InklingPrompt {
+  // The story reached its end.
+  INKLING_PROMPT_DONE = 0,
+  // The story stopped at a set of choices, which are in the buffer.
+  INKLING_PROMPT_CHOICE = 1,
+  // The story could not be resumed: the message is retrieved with `inkling_last_error`.
+  INKLING_PROMPT_ERROR = 2,
+  // The call panicked: the message is retrieved with `inkling_last_error`.
+  INKLING_PROMPT_PANIC = 3,
+} InklingPrompt;
+
+// Result of a call which can fail.
+typedef enum InklingStatus {
+  // The call succeeded.
+  INKLING_STATUS_OK = 0,
+  // The call failed: the message is retrieved with `inkling_last_error`.
+  INKLING_STATUS_ERROR = 1,
+  // The call panicked: the message is retrieved with `inkling_last_error`.
+  INKLING_STATUS_PANIC = 2,
+} InklingStatus;
+
+// Lines and choices from resuming a story.
+typedef struct InklingBuffer InklingBuffer;
+
+// Story which is followed through the C interface.
+typedef struct InklingStory InklingStory;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Get the message of the last error on the current thread, or null if there was none.
+const char *inkling_last_error(void);
+
+// Read a story from a null-terminated UTF-8 string.
+//
+// Returns null if the story could not be read.
+//
+// # Safety
+// `content` must be null or a valid null-terminated string.
+struct InklingStory *inkling_story_from_string(const char *content);
+
+// Read a story from bytes written by `inkling_story_save`.
+//
+// Returns null if the bytes do not contain a saved story.
+//
+// # Safety
+// `data` must be null or point to `length` readable bytes.
+struct InklingStory *inkling_story_load(const uint8_t *data, size_t length);
+
+// Free a story. Does nothing if it is null.
+//
+// # Safety
+// `story` must be null or a story from this library which has not been freed.
+void inkling_story_free(struct InklingStory *story);
+
+// Write the state of a story to bytes, which are freed with `inkling_bytes_free`.
+//
+// # Safety
+// `story` must be a valid story, and `data` and `length` must be valid for writes.
+enum InklingStatus inkling_story_save(const struct InklingStory *story,
+                                      uint8_t **data,
+                                      size_t *length);
+
+// Free bytes written by `inkling_story_save`. Does nothing if `data` is null.
+//
+// # Safety
+// `data` and `length` must be from the same call to `inkling_story_save`, and the
+// bytes must not have been freed.
+void inkling_bytes_free(uint8_t *data, size_t length);
+
+// Create an empty buffer to resume stories into.
+struct InklingBuffer *inkling_buffer_new(void);
+
+// Free a buffer. Does nothing if it is null.
+//
+// # Safety
+// `buffer` must be null or a buffer from this library which has not been freed.
+void inkling_buffer_free(struct InklingBuffer *buffer);
+
+// Remove all lines and choices from a buffer.
+//
+// # Safety
+// `buffer` must be null or a valid buffer.
+void inkling_buffer_clear(struct InklingBuffer *buffer);
+
+// Resume a story, adding its lines to the buffer until a choice or the end is reached.
+//
+// The choices of the buffer are replaced with those which the story stopped at, or
+// removed if it reached the end.
+//
+// # Safety
+// `story` and `buffer` must be valid.
+enum InklingPrompt inkling_story_resume(struct InklingStory *story, struct InklingBuffer *buffer);
+
+// Make a choice from the set that the story stopped at, by its index in the buffer.
+//
+// # Safety
+// `story` must be valid.
+enum InklingStatus inkling_story_make_choice(struct InklingStory *story, size_t index);
+
+// Move a story to a knot, or to a stitch in it if `stitch` is not null.
+//
+// # Safety
+// `story` must be valid, `knot` a valid string and `stitch` null or a valid string.
+enum InklingStatus inkling_story_move_to(struct InklingStory *story,
+                                         const char *knot,
+                                         const char *stitch);
+
+// Number of lines in a buffer.
+//
+// # Safety
+// `buffer` must be null or a valid buffer.
+size_t inkling_buffer_num_lines(const struct InklingBuffer *buffer);
+
+// Text of a line in a buffer, or null if there is no line at the index.
+//
+// # Safety
+// `buffer` must be null or a valid buffer.
+const char *inkling_buffer_line_text(const struct InklingBuffer *buffer, size_t index);
+
+// Number of tags of a line in a buffer.
+//
+// # Safety
+// `buffer` must be null or a valid buffer.
+size_t inkling_buffer_line_num_tags(const struct InklingBuffer *buffer, size_t index);
+
+// Tag of a line in a buffer, or null if there is no such tag.
+//
+// # Safety
+// `buffer` must be null or a valid buffer.
+const char *inkling_buffer_line_tag(const struct InklingBuffer *buffer,
+                                    size_t index,
+                                    size_t tag_index);
+
+// Number of choices in a buffer.
+//
+// # Safety
+// `buffer` must be null or a valid buffer.
+size_t inkling_buffer_num_choices(const struct InklingBuffer *buffer);
+
+// Text of a choice in a buffer, or null if there is no choice at the index.
+//
+// # Safety
+// `buffer` must be null or a valid buffer.
+const char *inkling_buffer_choice_text(const struct InklingBuffer *buffer, size_t index);
+
+// Number of tags of a choice in a buffer.
+//
+// # Safety
+// `buffer` must be null or a valid buffer.
+size_t inkling_buffer_choice_num_tags(const struct InklingBuffer *buffer, size_t index);
+
+// Tag of a choice in a buffer, or null if there is no such tag.
+//
+// # Safety
+// `buffer` must be null or a valid buffer.
+const char *inkling_buffer_choice_tag(const struct InklingBuffer *buffer,
+                                      size_t index,
+                                      size_t tag_index);
+
+// Get the value of an integer variable.
+//
+// # Safety
+// `story` must be valid, `name` a valid string and `value` valid for writes.
+enum InklingStatus inkling_story_get_int(struct InklingStory *story,
+                                         const char *name,
+                                         int32_t *value);
+
+// Get the value of a floating point variable.
+//
+// # Safety
+// `story` must be valid, `name` a valid string and `value` valid for writes.
+enum InklingStatus inkling_story_get_float(struct InklingStory *story,
+                                           const char *name,
+                                           float *value);
+
+// Get the value of a boolean variable.
+//
+// # Safety
+// `story` must be valid, `name` a valid string and `value` valid for writes.
+enum InklingStatus inkling_story_get_bool(struct InklingStory *story,
+                                          const char *name,
+                                          bool *value);
+
+// Get the value of a string variable.
+//
+// The string is owned by the story and valid until the next call with it.
+//
+// # Safety
+// `story` must be valid, `name` a valid string and `value` valid for writes.
+enum InklingStatus inkling_story_get_string(struct InklingStory *story,
+                                            const char *name,
+                                            const char **value);
+
+// Set the value of an integer variable.
+//
+// # Safety
+// `story` must be valid and `name` a valid string.
+enum InklingStatus inkling_story_set_int(struct InklingStory *story,
+                                         const char *name,
+                                         int32_t value);
+
+// Set the value of a floating point variable.
+//
+// # Safety
+// `story` must be valid and `name` a valid string.
+enum InklingStatus inkling_story_set_float(struct InklingStory *story,
+                                           const char *name,
+                                           float value);
+
+// Set the value of a boolean variable.
+//
+// # Safety
+// `story` must be valid and `name` a valid string.
+enum InklingStatus inkling_story_set_bool(struct InklingStory *story, const char *name, bool value);
+
+// Set the value of a string variable. The string is copied.
+//
+// # Safety
+// `story` must be valid, and `name` and `value` valid strings.
+enum InklingStatus inkling_story_set_string(struct InklingStory *story,
+                                            const char *name,
+                                            const char *value);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* INKLING_H */
@@ -0,0 +1,299 @@
+//! Open documents and positions in their text.
+
+use inkling::{
+    error::{get_diagnostics, Diagnostic},
+    read_story_from_string, Location, Story, Symbol, SymbolKind,
+};
+
+use std::ops::Range;
+
+/// Story file which is open in the editor.
+pub struct Document {
+    /// Current text of the document.
+    pub text: String,
+    /// Story from the latest version of the text which could be read.
+    ///
+    /// Kept while the text contains errors, so that names can still be completed and looked up.
+    pub story: Option<Story>,
+    /// Errors, warnings and notes from reading the current text.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Position in a document, as a zero-based line and UTF-16 offset in the line.
+pub struct Position {
+    pub line: usize,
+    pub character: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Name of a knot, stitch or variable at a position in a document.
+pub struct Word {
+    /// The name, which may be a full `knot.stitch` address.
+    pub text: String,
+    /// Whether the name is in a divert or inside braces, where it refers to an address.
+    pub is_reference: bool,
+}
+
+impl Document {
+    /// Read the story from a text.
+    pub fn new(text: String) -> Self {
+        let mut document = Document {
+            text: String::new(),
+            story: None,
+            diagnostics: Vec::new(),
+        };
+
+        document.update(text);
+        document
+    }
+
+    /// Replace the text and read the story from it again.
+    pub fn update(&mut self, text: String) {
+        match read_story_from_string(&text) {
+            Ok(story) => {
+                self.diagnostics = story.log.get_diagnostics();
+                self.story.replace(story);
+            }
+            Err(error) => self.diagnostics = get_diagnostics(&error),
+        }
+
+        self.text = text;
+    }
+
+    /// Get the text of a line, or an empty string if the line does not exist.
+    pub fn get_line(&self, index: usize) -> &str {
+        self.text.lines().nth(index).unwrap_or("")
+    }
+
+    /// Get the name which contains the position, if any.
+    pub fn get_word(&self, position: Position) -> Option<Word> {
+        let line = self.get_line(position.line);
+        let cursor = get_byte_index(line, position.character);
+
+        let start = line[..cursor]
+            .rfind(|c| !is_name_character(c))
+            .map(|i| i + line[i..].chars().next().unwrap().len_utf8())
+            .unwrap_or(0);
+        let end = line[cursor..]
+            .find(|c| !is_name_character(c))
+            .map(|i| cursor + i)
+            .unwrap_or_else(|| line.len());
+
+        if start < end {
+            Some(Word {
+                text: line[start..end].to_string(),
+                is_reference: is_reference_context(&line[..start]),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Get whether a name typed at the position is a divert target or inside braces.
+    pub fn is_reference_position(&self, position: Position) -> bool {
+        is_reference_context(self.get_prefix(position))
+    }
+
+    /// Get whether a name typed at the position is a divert target.
+    pub fn is_divert_position(&self, position: Position) -> bool {
+        is_divert_context(self.get_prefix(position))
+    }
+
+    /// Get the text of the line before the position, without the part of a name which
+    /// has been typed so far.
+    fn get_prefix(&self, position: Position) -> &str {
+        let line = self.get_line(position.line);
+        line[..get_byte_index(line, position.character)].trim_end_matches(is_name_character)
+    }
+
+    /// Get the knot and stitch which the line with the position is in.
+    ///
+    /// Returns `None` for lines before the first knot.
+    pub fn get_location(&self, position: Position, symbols: &[Symbol]) -> Option<Location> {
+        let mut location = None;
+
+        for symbol in symbols {
+            if symbol.meta_data.line() as usize > position.line + 1 {
+                break;
+            }
+
+            match &symbol.kind {
+                SymbolKind::Knot => location = Some(Location::new(&symbol.name, None)),
+                SymbolKind::Stitch { knot } => {
+                    location = Some(Location::with_stitch(knot, &symbol.name))
+                }
+                SymbolKind::Variable { .. } => (),
+            }
+        }
+
+        location
+    }
+
+    /// Get the range of the name of a symbol on the line where it is defined.
+    ///
+    /// The range is given as UTF-16 offsets in the line. If the name cannot be found
+    /// the range spans the whole line.
+    pub fn get_name_range(&self, symbol: &Symbol) -> Range<usize> {
+        let line = self.get_line(symbol.meta_data.line() as usize - 1);
+
+        let definition = line.trim_start_matches(|c: char| c == '=' || c.is_whitespace());
+        let mut offset = line.len() - definition.len();
+
+        for keyword in &["VAR ", "CONST "] {
+            if definition.starts_with(keyword) {
+                offset += keyword.len();
+            }
+        }
+
+        let range = line[offset..]
+            .find(&symbol.name)
+            .map(|i| offset + i..offset + i + symbol.name.len())
+            .unwrap_or(0..line.len());
+
+        get_utf16_offset(line, range.start)..get_utf16_offset(line, range.end)
+    }
+}
+
+/// Get the UTF-16 offset of a character column in a line.
+pub fn get_utf16_column(line: &str, column: usize) -> usize {
+    line.chars().take(column).map(char::len_utf16).sum()
+}
+
+/// Get the UTF-16 offset of a byte index in a line.
+fn get_utf16_offset(line: &str, index: usize) -> usize {
+    line[..index].chars().map(char::len_utf16).sum()
+}
+
+/// Get the byte index of a UTF-16 offset in a line, clamped to the end of the line.
+fn get_byte_index(line: &str, character: usize) -> usize {
+    let mut num_units = 0;
+
+    for (i, c) in line.char_indices() {
+        if num_units >= character {
+            return i;
+        }
+
+        num_units += c.len_utf16();
+    }
+
+    line.len()
+}
+
+fn is_name_character(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.'
+}
+
+/// Whether a name which follows the text is a divert target or inside of braces.
+fn is_reference_context(prefix: &str) -> bool {
+    let num_open = prefix.matches('{').count();
+    let num_closed = prefix.matches('}').count();
+
+    is_divert_context(prefix) || num_open > num_closed
+}
+
+fn is_divert_context(prefix: &str) -> bool {
+    prefix.trim_end().ends_with("->")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(line: usize, character: usize) -> Position {
+        Position { line, character }
+    }
+
+    const CONTENT: &str = "\
+VAR gold = 3
+The café is open. -> market
+
+=== market ===
+{gold > 1: Buy fish.} -> market.counter
+= stall
+-> END
+= counter
+-> END
+";
+
+    #[test]
+    fn words_are_names_with_dots_and_are_references_after_diverts_or_in_braces() {
+        let document = Document::new(CONTENT.to_string());
+
+        assert_eq!(
+            document.get_word(position(1, 23)),
+            Some(Word {
+                text: "market".to_string(),
+                is_reference: true
+            })
+        );
+        assert_eq!(
+            document.get_word(position(4, 1)),
+            Some(Word {
+                text: "gold".to_string(),
+                is_reference: true
+            })
+        );
+        assert_eq!(
+            document.get_word(position(4, 27)).unwrap().text,
+            "market.counter"
+        );
+        assert!(!document.get_word(position(1, 5)).unwrap().is_reference);
+        assert!(document.get_word(position(2, 0)).is_none());
+    }
+
+    #[test]
+    fn positions_after_diverts_or_inside_braces_are_reference_positions() {
+        let document = Document::new("Go -> mar\n{go\nGo".to_string());
+
+        assert!(document.is_divert_position(position(0, 9)));
+        assert!(document.is_divert_position(position(0, 6)));
+        assert!(!document.is_divert_position(position(1, 3)));
+        assert!(document.is_reference_position(position(1, 3)));
+        assert!(!document.is_reference_position(position(2, 2)));
+    }
+
+    #[test]
+    fn positions_are_counted_in_utf16_units() {
+        let document = Document::new("Café ok -> café_🦀".to_string());
+
+        assert_eq!(document.get_word(position(0, 5)).unwrap().text, "ok");
+        assert_eq!(document.get_word(position(0, 16)).unwrap().text, "café_");
+        assert_eq!(get_utf16_column("Café_🦀x", 6), 7);
+    }
+
+    #[test]
+    fn location_is_the_last_knot_and_stitch_defined_before_the_line() {
+        let document = Document::new(CONTENT.to_string());
+        let symbols = document.story.as_ref().unwrap().get_symbols();
+
+        assert_eq!(document.get_location(position(1, 0), &symbols), None);
+        assert_eq!(
+            document.get_location(position(4, 0), &symbols),
+            Some(Location::new("market", None))
+        );
+        assert_eq!(
+            document.get_location(position(6, 0), &symbols),
+            Some(Location::with_stitch("market", "stall"))
+        );
+    }
+
+    #[test]
+    fn story_is_kept_when_the_text_cannot_be_read() {
+        let mut document = Document::new(CONTENT.to_string());
+        document.update("-> unknown".to_string());
+
+        assert!(document.story.is_some());
+        assert_eq!(document.diagnostics.len(), 1);
+        assert_eq!(&document.text, "-> unknown");
+    }
+
+    #[test]
+    fn name_ranges_skip_definition_markers_and_keywords() {
+        let document = Document::new("VAR A = 1\n-> A_knot\n=== A_knot ===\nText.".to_string());
+        let symbols = document.story.as_ref().unwrap().get_symbols();
+
+        assert_eq!(document.get_name_range(&symbols[0]), 4..5);
+        assert_eq!(document.get_name_range(&symbols[1]), 4..10);
+    }
+}
@@ -0,0 +1,61 @@
+//! Language server for `Ink` stories, which talks to an editor over standard input and output.
+//!
+//! Stories are read again as they are edited. The server publishes their errors, warnings
+//! and to-do comments as diagnostics, and offers go-to-definition of divert targets,
+//! completion of knot, stitch and variable names, hover information for variables and
+//! an outline of knots and stitches.
+//!
+//! Built with the `lsp` feature.
+
+mod document;
+mod rpc;
+mod server;
+
+use std::{
+    io::{self, BufReader},
+    process::exit,
+};
+
+use serde_json::{json, Value};
+
+use server::Server;
+
+/// Error code for messages which are not valid JSON.
+const PARSE_ERROR: i64 = -32700;
+
+fn main() {
+    let mut reader = BufReader::new(io::stdin());
+    let mut writer = io::stdout();
+
+    let mut server = Server::default();
+
+    loop {
+        let body = match rpc::read_message(&mut reader) {
+            Ok(Some(body)) => body,
+            Ok(None) => exit(1),
+            Err(error) => {
+                eprintln!("error: could not read message: {}", error);
+                exit(1);
+            }
+        };
+
+        let replies = match serde_json::from_str::<Value>(&body) {
+            Ok(message) if message["method"] == "exit" => {
+                exit(if server.is_shutdown() { 0 } else { 1 })
+            }
+            Ok(message) => server.handle_message(&message),
+            Err(error) => vec![json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": {"code": PARSE_ERROR, "message": error.to_string()},
+            })],
+        };
+
+        for reply in replies {
+            if let Err(error) = rpc::write_message(&mut writer, &reply) {
+                eprintln!("error: could not write message: {}", error);
+                exit(1);
+            }
+        }
+    }
+}
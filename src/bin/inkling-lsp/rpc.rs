@@ -0,0 +1,102 @@
+//! Reading and writing of JSON-RPC messages with `Content-Length` headers.
+
+use std::io::{self, BufRead, Write};
+
+use serde_json::Value;
+
+/// Read the body of the next message.
+///
+/// Returns `None` if the input has ended before the start of a message.
+pub fn read_message<R: BufRead>(reader: &mut R) -> io::Result<Option<String>> {
+    let mut content_length = None;
+
+    loop {
+        let mut header = String::new();
+
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+
+        let header = header.trim_end();
+
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some(i) = header.find(':') {
+            let (name, value) = header.split_at(i);
+
+            if name.trim().eq_ignore_ascii_case("Content-Length") {
+                let length = value[1..].trim().parse::<usize>().map_err(|_| {
+                    invalid_data(format!("invalid Content-Length header '{}'", header))
+                })?;
+
+                content_length.replace(length);
+            }
+        }
+    }
+
+    let length =
+        content_length.ok_or_else(|| invalid_data("message without a Content-Length header"))?;
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    String::from_utf8(body)
+        .map(Some)
+        .map_err(|_| invalid_data("message is not valid UTF-8"))
+}
+
+/// Write a message with its header and flush the output.
+pub fn write_message<W: Write>(writer: &mut W, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn written_messages_are_read_back_one_at_a_time() {
+        let mut buffer = Vec::new();
+
+        write_message(&mut buffer, &json!({"id": 1, "text": "Crème brûlée"})).unwrap();
+        write_message(&mut buffer, &json!({"id": 2})).unwrap();
+
+        let mut reader = buffer.as_slice();
+
+        let first = read_message(&mut reader).unwrap().unwrap();
+        let second = read_message(&mut reader).unwrap().unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<Value>(&first).unwrap(),
+            json!({"id": 1, "text": "Crème brûlée"})
+        );
+        assert_eq!(
+            serde_json::from_str::<Value>(&second).unwrap(),
+            json!({"id": 2})
+        );
+        assert!(read_message(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn other_headers_are_skipped_and_a_missing_length_is_an_error() {
+        let content = "Content-Type: application/vscode-jsonrpc\r\ncontent-length: 2\r\n\r\n{}";
+        assert_eq!(
+            read_message(&mut content.as_bytes()).unwrap().as_deref(),
+            Some("{}")
+        );
+
+        let content = "Content-Type: application/vscode-jsonrpc\r\n\r\n{}";
+        assert!(read_message(&mut content.as_bytes()).is_err());
+    }
+}
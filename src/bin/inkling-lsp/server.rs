@@ -0,0 +1,380 @@
+//! Handling of requests and notifications from the editor.
+
+use std::collections::HashMap;
+
+use inkling::{
+    error::{Diagnostic, Severity},
+    Symbol, SymbolKind,
+};
+
+use serde_json::{json, Value};
+
+use crate::document::{get_utf16_column, Document, Position};
+
+/// Error code for requests with a method that the server does not handle.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// Error code for requests with missing or invalid parameters.
+const INVALID_PARAMS: i64 = -32602;
+
+/// Kinds of completion items, from the specification.
+const COMPLETION_FUNCTION: u32 = 3;
+const COMPLETION_VARIABLE: u32 = 6;
+const COMPLETION_MODULE: u32 = 9;
+const COMPLETION_KEYWORD: u32 = 14;
+const COMPLETION_CONSTANT: u32 = 21;
+
+/// Kinds of document symbols, from the specification.
+const SYMBOL_NAMESPACE: u32 = 3;
+const SYMBOL_FUNCTION: u32 = 12;
+const SYMBOL_VARIABLE: u32 = 13;
+const SYMBOL_CONSTANT: u32 = 14;
+
+/// State of the language server: the documents which are open in the editor.
+#[derive(Default)]
+pub struct Server {
+    documents: HashMap<String, Document>,
+    is_shutdown: bool,
+}
+
+impl Server {
+    /// Whether a shutdown request has been received.
+    pub fn is_shutdown(&self) -> bool {
+        self.is_shutdown
+    }
+
+    /// Handle a message and return the messages to send in reply.
+    pub fn handle_message(&mut self, message: &Value) -> Vec<Value> {
+        let method = message["method"].as_str().unwrap_or("");
+        let params = &message["params"];
+
+        match message.get("id") {
+            Some(id) if message.get("method").is_some() => {
+                vec![self.handle_request(id, method, params)]
+            }
+            Some(..) => Vec::new(),
+            None => self.handle_notification(method, params),
+        }
+    }
+
+    fn handle_request(&mut self, id: &Value, method: &str, params: &Value) -> Value {
+        let result = match method {
+            "initialize" => Ok(get_capabilities()),
+            "shutdown" => {
+                self.is_shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/definition" => self.definition(params),
+            "textDocument/hover" => self.hover(params),
+            "textDocument/completion" => self.completion(params),
+            "textDocument/documentSymbol" => self.document_symbols(params),
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method '{}'", method))),
+        };
+
+        match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": {"code": code, "message": message},
+            }),
+        }
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let uri = match params["textDocument"]["uri"].as_str() {
+            Some(uri) => uri.to_string(),
+            None => return Vec::new(),
+        };
+
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or("");
+                self.documents
+                    .insert(uri.clone(), Document::new(text.to_string()));
+            }
+            "textDocument/didChange" => {
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+
+                match (self.documents.get_mut(&uri), text) {
+                    (Some(document), Some(text)) => document.update(text.to_string()),
+                    _ => return Vec::new(),
+                }
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+            }
+            _ => return Vec::new(),
+        }
+
+        let diagnostics: Vec<Value> = self
+            .documents
+            .get(&uri)
+            .map(|document| {
+                document
+                    .diagnostics
+                    .iter()
+                    .map(|diagnostic| get_diagnostic(document, diagnostic))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        vec![json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": {"uri": uri, "diagnostics": diagnostics},
+        })]
+    }
+
+    /// Find the knot, stitch or variable that a name in a divert or inside braces refers to.
+    fn definition(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let (uri, document, position) = self.get_document_position(params)?;
+
+        Ok(get_referenced_symbol(document, position)
+            .map(|symbol| json!({"uri": uri, "range": get_name_range(document, &symbol)}))
+            .unwrap_or(Value::Null))
+    }
+
+    /// Show the declared value of a variable and whether it is constant.
+    fn hover(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let (_, document, position) = self.get_document_position(params)?;
+
+        let hover = get_referenced_symbol(document, position).and_then(|symbol| {
+            if let SymbolKind::Variable { value, is_const } = &symbol.kind {
+                let keyword = if *is_const { "CONST" } else { "VAR" };
+                let source = format!("{} {} = {}", keyword, symbol.name, value.to_ink_source());
+
+                Some(json!({
+                    "contents": {"kind": "markdown", "value": format!("```ink\n{}\n```", source)},
+                }))
+            } else {
+                None
+            }
+        });
+
+        Ok(hover.unwrap_or(Value::Null))
+    }
+
+    /// List the names which can be written at a divert or inside braces.
+    ///
+    /// Stitches in the current knot are listed by their name and all stitches by their
+    /// full address. Diverts also list the `END` and `DONE` targets.
+    fn completion(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let (_, document, position) = self.get_document_position(params)?;
+
+        let story = match &document.story {
+            Some(story) if document.is_reference_position(position) => story,
+            _ => return Ok(json!([])),
+        };
+
+        let symbols = story.get_symbols();
+        let location = document.get_location(position, &symbols);
+
+        let mut items = Vec::new();
+
+        for symbol in &symbols {
+            match &symbol.kind {
+                SymbolKind::Knot => {
+                    items.push(get_completion_item(&symbol.name, COMPLETION_MODULE, "knot"))
+                }
+                SymbolKind::Stitch { knot } => {
+                    let detail = format!("stitch in {}", knot);
+
+                    if location.as_ref().map(|location| &location.knot) == Some(knot) {
+                        items.push(get_completion_item(
+                            &symbol.name,
+                            COMPLETION_FUNCTION,
+                            &detail,
+                        ));
+                    }
+
+                    let address = format!("{}.{}", knot, symbol.name);
+                    items.push(get_completion_item(&address, COMPLETION_FUNCTION, &detail));
+                }
+                SymbolKind::Variable { value, is_const } => {
+                    let (kind, keyword) = if *is_const {
+                        (COMPLETION_CONSTANT, "CONST")
+                    } else {
+                        (COMPLETION_VARIABLE, "VAR")
+                    };
+
+                    let detail = format!("{} = {}", keyword, value.to_ink_source());
+                    items.push(get_completion_item(&symbol.name, kind, &detail));
+                }
+            }
+        }
+
+        if document.is_divert_position(position) {
+            items.push(get_completion_item(
+                "END",
+                COMPLETION_KEYWORD,
+                "end the story",
+            ));
+            items.push(get_completion_item(
+                "DONE",
+                COMPLETION_KEYWORD,
+                "end the flow",
+            ));
+        }
+
+        Ok(Value::Array(items))
+    }
+
+    /// List the knots of the story with their stitches, and all global variables.
+    fn document_symbols(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .ok_or_else(|| (INVALID_PARAMS, "missing document".to_string()))?;
+
+        let document = self.get_document(uri)?;
+
+        let symbols = match &document.story {
+            Some(story) => story.get_symbols(),
+            None => return Ok(json!([])),
+        };
+
+        let num_lines = document.text.lines().count().max(1);
+        let mut items: Vec<Value> = Vec::new();
+
+        for (i, symbol) in symbols.iter().enumerate() {
+            let start = symbol.meta_data.line() as usize - 1;
+
+            let end = symbols[i + 1..]
+                .iter()
+                .find(|next| {
+                    matches!(
+                        (&symbol.kind, &next.kind),
+                        (SymbolKind::Knot, SymbolKind::Knot)
+                            | (SymbolKind::Stitch { .. }, SymbolKind::Knot)
+                            | (SymbolKind::Stitch { .. }, SymbolKind::Stitch { .. })
+                    )
+                })
+                .map(|next| next.meta_data.line() as usize - 2)
+                .unwrap_or(num_lines - 1);
+
+            let (kind, end) = match symbol.kind {
+                SymbolKind::Knot => (SYMBOL_NAMESPACE, end),
+                SymbolKind::Stitch { .. } => (SYMBOL_FUNCTION, end),
+                SymbolKind::Variable {
+                    is_const: false, ..
+                } => (SYMBOL_VARIABLE, start),
+                SymbolKind::Variable { is_const: true, .. } => (SYMBOL_CONSTANT, start),
+            };
+
+            let end_character = document.get_line(end).encode_utf16().count();
+
+            let item = json!({
+                "name": symbol.name,
+                "kind": kind,
+                "range": {
+                    "start": {"line": start, "character": 0},
+                    "end": {"line": end, "character": end_character},
+                },
+                "selectionRange": get_name_range(document, symbol),
+                "children": [],
+            });
+
+            match (&symbol.kind, items.last_mut()) {
+                (SymbolKind::Stitch { knot }, Some(parent)) if parent["name"] == knot.as_str() => {
+                    parent["children"].as_array_mut().unwrap().push(item)
+                }
+                _ => items.push(item),
+            }
+        }
+
+        Ok(Value::Array(items))
+    }
+
+    fn get_document(&self, uri: &str) -> Result<&Document, (i64, String)> {
+        self.documents
+            .get(uri)
+            .ok_or_else(|| (INVALID_PARAMS, format!("document '{}' is not open", uri)))
+    }
+
+    fn get_document_position<'a>(
+        &'a self,
+        params: &'a Value,
+    ) -> Result<(&'a str, &'a Document, Position), (i64, String)> {
+        let uri = params["textDocument"]["uri"].as_str();
+        let line = params["position"]["line"].as_u64();
+        let character = params["position"]["character"].as_u64();
+
+        match (uri, line, character) {
+            (Some(uri), Some(line), Some(character)) => {
+                let position = Position {
+                    line: line as usize,
+                    character: character as usize,
+                };
+
+                Ok((uri, self.get_document(uri)?, position))
+            }
+            _ => Err((INVALID_PARAMS, "missing document or position".to_string())),
+        }
+    }
+}
+
+/// Get the capabilities of the server, which are sent in reply to the `initialize` request.
+fn get_capabilities() -> Value {
+    json!({
+        "capabilities": {
+            "textDocumentSync": 1,
+            "definitionProvider": true,
+            "hoverProvider": true,
+            "completionProvider": {"triggerCharacters": [">", "{", "."]},
+            "documentSymbolProvider": true,
+        },
+        "serverInfo": {"name": "inkling-lsp", "version": env!("CARGO_PKG_VERSION")},
+    })
+}
+
+/// Resolve a name in a divert or inside braces from the location it is written at.
+fn get_referenced_symbol(document: &Document, position: Position) -> Option<Symbol> {
+    let story = document.story.as_ref()?;
+    let word = document.get_word(position)?;
+
+    if !word.is_reference {
+        return None;
+    }
+
+    let location = document.get_location(position, &story.get_symbols());
+    story.resolve_address(&word.text, location.as_ref())
+}
+
+fn get_diagnostic(document: &Document, diagnostic: &Diagnostic) -> Value {
+    let line_index = diagnostic.meta_data.line() as usize - 1;
+    let line = document.get_line(line_index);
+    let columns = diagnostic.meta_data.column_range();
+
+    let severity = match diagnostic.severity {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+        Severity::Note => 3,
+    };
+
+    json!({
+        "range": {
+            "start": {"line": line_index, "character": get_utf16_column(line, columns.start)},
+            "end": {"line": line_index, "character": get_utf16_column(line, columns.end)},
+        },
+        "severity": severity,
+        "code": diagnostic.code,
+        "source": "inkling",
+        "message": diagnostic.message,
+    })
+}
+
+fn get_name_range(document: &Document, symbol: &Symbol) -> Value {
+    let line = symbol.meta_data.line() - 1;
+    let range = document.get_name_range(symbol);
+
+    json!({
+        "start": {"line": line, "character": range.start},
+        "end": {"line": line, "character": range.end},
+    })
+}
+
+fn get_completion_item(label: &str, kind: u32, detail: &str) -> Value {
+    json!({"label": label, "kind": kind, "detail": detail})
+}
@@ -0,0 +1,363 @@
+//! Parsing of command line arguments.
+
+use std::path::{Path, PathBuf};
+
+use inkling::GraphFormat;
+
+#[derive(Clone, Debug, PartialEq)]
+/// Command to run, with its options.
+pub enum Command {
+    Play(PlayOptions),
+    Check {
+        path: PathBuf,
+        deny_warnings: bool,
+        json: bool,
+    },
+    Stats {
+        path: PathBuf,
+    },
+    Graph {
+        path: PathBuf,
+        format: GraphFormat,
+    },
+    Strings {
+        path: PathBuf,
+        format: TableFormat,
+        language: String,
+    },
+    Fmt {
+        path: PathBuf,
+        check: bool,
+        write: bool,
+    },
+    Test {
+        paths: Vec<PathBuf>,
+    },
+    Help,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// File format of a table of translatable text.
+pub enum TableFormat {
+    Po,
+    Xliff,
+    Csv,
+}
+
+impl TableFormat {
+    /// Get the format of a file from its extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "po" | "pot" => Some(TableFormat::Po),
+            "xlf" | "xliff" => Some(TableFormat::Xliff),
+            "csv" => Some(TableFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// Options for playing a story.
+pub struct PlayOptions {
+    /// Path to story file.
+    pub path: PathBuf,
+    /// Seed for the random number generator.
+    pub seed: Option<u64>,
+    /// Path to file with choices to make.
+    pub choices: Option<PathBuf>,
+    /// Whether to print output as JSON.
+    pub json: bool,
+    /// Path to file with translated text to load.
+    pub strings: Option<PathBuf>,
+}
+
+/// Parse the command and its options from a set of arguments, without the program name.
+pub fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let command = args.next().ok_or_else(|| "no command given".to_string())?;
+
+    let mut path = None;
+    let mut play_options = PlayOptions::default();
+    let mut deny_warnings = false;
+    let mut check_json = false;
+    let mut graph_format = GraphFormat::Dot;
+    let mut table_format = TableFormat::Po;
+    let mut language = "en".to_string();
+    let mut fmt_check = false;
+    let mut fmt_write = false;
+    let mut test_paths = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match (command.as_str(), arg.as_str()) {
+            ("play", "--seed") => {
+                let value = get_option_value(&arg, args.next())?;
+                let seed = value
+                    .parse::<u64>()
+                    .map_err(|_| format!("invalid seed '{}': must be a positive integer", value))?;
+
+                play_options.seed.replace(seed);
+            }
+            ("play", "--choices") => {
+                let value = get_option_value(&arg, args.next())?;
+                play_options.choices.replace(value.into());
+            }
+            ("play", "--json") => play_options.json = true,
+            ("play", "--strings") => {
+                let value = PathBuf::from(get_option_value(&arg, args.next())?);
+
+                if TableFormat::from_path(&value).is_none() {
+                    return Err(format!(
+                        "invalid string table '{}': must be a .po, .xlf or .csv file",
+                        value.display()
+                    ));
+                }
+
+                play_options.strings.replace(value);
+            }
+            ("check", "--deny-warnings") => deny_warnings = true,
+            ("check", "--json") => check_json = true,
+            ("graph", "--format") => {
+                graph_format = match get_option_value(&arg, args.next())?.as_str() {
+                    "dot" => GraphFormat::Dot,
+                    "mermaid" => GraphFormat::Mermaid,
+                    value => {
+                        return Err(format!(
+                            "invalid format '{}': must be 'dot' or 'mermaid'",
+                            value
+                        ))
+                    }
+                };
+            }
+            ("strings", "--format") => {
+                table_format = match get_option_value(&arg, args.next())?.as_str() {
+                    "po" => TableFormat::Po,
+                    "xliff" => TableFormat::Xliff,
+                    "csv" => TableFormat::Csv,
+                    value => {
+                        return Err(format!(
+                            "invalid format '{}': must be 'po', 'xliff' or 'csv'",
+                            value
+                        ))
+                    }
+                };
+            }
+            ("strings", "--language") => language = get_option_value(&arg, args.next())?,
+            ("fmt", "--check") => fmt_check = true,
+            ("fmt", "--write") => fmt_write = true,
+            (_, option) if option.starts_with("--") => {
+                return Err(format!("unknown option '{}' for '{}'", option, command));
+            }
+            ("test", _) => test_paths.push(PathBuf::from(arg)),
+            _ if path.is_none() => path = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    let get_path = |path: Option<PathBuf>| path.ok_or_else(|| "no story file given".to_string());
+
+    match command.as_str() {
+        "play" => Ok(Command::Play(PlayOptions {
+            path: get_path(path)?,
+            ..play_options
+        })),
+        "check" => Ok(Command::Check {
+            path: get_path(path)?,
+            deny_warnings,
+            json: check_json,
+        }),
+        "stats" => Ok(Command::Stats {
+            path: get_path(path)?,
+        }),
+        "graph" => Ok(Command::Graph {
+            path: get_path(path)?,
+            format: graph_format,
+        }),
+        "strings" => Ok(Command::Strings {
+            path: get_path(path)?,
+            format: table_format,
+            language,
+        }),
+        "fmt" if fmt_check && fmt_write => {
+            Err("options '--check' and '--write' cannot be used together".to_string())
+        }
+        "fmt" => Ok(Command::Fmt {
+            path: get_path(path)?,
+            check: fmt_check,
+            write: fmt_write,
+        }),
+        "test" if test_paths.is_empty() => Err("no test file given".to_string()),
+        "test" => Ok(Command::Test { paths: test_paths }),
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        _ => Err(format!("unknown command '{}'", command)),
+    }
+}
+
+fn get_option_value(option: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("option '{}' requires a value", option))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn play_command_reads_path_and_all_options_in_any_order() {
+        let command = parse(&[
+            "play",
+            "--json",
+            "story.ink",
+            "--seed",
+            "5",
+            "--choices",
+            "c.txt",
+        ]);
+
+        assert_eq!(
+            command,
+            Ok(Command::Play(PlayOptions {
+                path: "story.ink".into(),
+                seed: Some(5),
+                choices: Some("c.txt".into()),
+                json: true,
+                strings: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn check_and_stats_commands_read_path() {
+        assert_eq!(
+            parse(&["check", "story.ink", "--deny-warnings", "--json"]),
+            Ok(Command::Check {
+                path: "story.ink".into(),
+                deny_warnings: true,
+                json: true,
+            })
+        );
+
+        assert_eq!(
+            parse(&["stats", "story.ink"]),
+            Ok(Command::Stats {
+                path: "story.ink".into()
+            })
+        );
+    }
+
+    #[test]
+    fn graph_command_reads_path_and_format_with_dot_as_default() {
+        assert_eq!(
+            parse(&["graph", "story.ink"]),
+            Ok(Command::Graph {
+                path: "story.ink".into(),
+                format: GraphFormat::Dot,
+            })
+        );
+
+        assert_eq!(
+            parse(&["graph", "--format", "mermaid", "story.ink"]),
+            Ok(Command::Graph {
+                path: "story.ink".into(),
+                format: GraphFormat::Mermaid,
+            })
+        );
+
+        assert!(parse(&["graph", "--format", "svg", "story.ink"]).is_err());
+    }
+
+    #[test]
+    fn strings_command_reads_path_format_and_language_with_po_in_english_as_default() {
+        assert_eq!(
+            parse(&["strings", "story.ink"]),
+            Ok(Command::Strings {
+                path: "story.ink".into(),
+                format: TableFormat::Po,
+                language: "en".to_string(),
+            })
+        );
+
+        assert_eq!(
+            parse(&[
+                "strings",
+                "--format",
+                "xliff",
+                "--language",
+                "sv",
+                "story.ink"
+            ]),
+            Ok(Command::Strings {
+                path: "story.ink".into(),
+                format: TableFormat::Xliff,
+                language: "sv".to_string(),
+            })
+        );
+
+        assert!(parse(&["strings", "--format", "json", "story.ink"]).is_err());
+    }
+
+    #[test]
+    fn fmt_command_reads_path_and_either_check_or_write() {
+        assert_eq!(
+            parse(&["fmt", "story.ink"]),
+            Ok(Command::Fmt {
+                path: "story.ink".into(),
+                check: false,
+                write: false,
+            })
+        );
+
+        assert_eq!(
+            parse(&["fmt", "--check", "story.ink"]),
+            Ok(Command::Fmt {
+                path: "story.ink".into(),
+                check: true,
+                write: false,
+            })
+        );
+
+        assert!(parse(&["fmt", "--check", "--write", "story.ink"]).is_err());
+    }
+
+    #[test]
+    fn test_command_reads_one_or_more_paths() {
+        assert_eq!(
+            parse(&["test", "market.inktest", "tests"]),
+            Ok(Command::Test {
+                paths: vec!["market.inktest".into(), "tests".into()]
+            })
+        );
+
+        assert!(parse(&["test"]).is_err());
+        assert!(parse(&["test", "--seed", "5", "market.inktest"]).is_err());
+    }
+
+    #[test]
+    fn play_command_reads_string_table_with_known_extension() {
+        assert_eq!(
+            parse(&["play", "--strings", "fr.xlf", "story.ink"]),
+            Ok(Command::Play(PlayOptions {
+                path: "story.ink".into(),
+                strings: Some("fr.xlf".into()),
+                ..PlayOptions::default()
+            }))
+        );
+
+        assert!(parse(&["play", "--strings", "fr.txt", "story.ink"]).is_err());
+    }
+
+    #[test]
+    fn missing_command_path_or_option_value_yields_error() {
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["check"]).is_err());
+        assert!(parse(&["play", "story.ink", "--seed"]).is_err());
+    }
+
+    #[test]
+    fn unknown_commands_and_options_yield_error() {
+        assert!(parse(&["run", "story.ink"]).is_err());
+        assert!(parse(&["stats", "--json", "story.ink"]).is_err());
+        assert!(parse(&["play", "--seed", "-1", "story.ink"]).is_err());
+    }
+}
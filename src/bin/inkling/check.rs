@@ -0,0 +1,66 @@
+//! Check a story for errors, warnings and to-do comments.
+
+use std::path::Path;
+
+use inkling::{
+    error::{get_diagnostics, Diagnostic, Severity},
+    read_story_from_string,
+};
+
+use crate::{json, read_file, CliError};
+
+/// Read a story and print all logged messages.
+///
+/// Errors in the story are returned to be printed by the caller, unless they are printed
+/// as JSON diagnostics.
+pub fn check(path: &Path, deny_warnings: bool, json: bool) -> Result<(), CliError> {
+    let content = read_file(path)?;
+
+    let story = match read_story_from_string(&content) {
+        Ok(story) => story,
+        Err(error) if json => {
+            let diagnostics = get_diagnostics(&error);
+            print_json_diagnostics(&diagnostics);
+
+            return Err(CliError::InvalidStory {
+                num_errors: diagnostics.len(),
+            });
+        }
+        Err(error) => return Err(error.into()),
+    };
+
+    let diagnostics = story.log.get_diagnostics();
+
+    let num_warnings = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Warning)
+        .count();
+    let num_todo_comments = diagnostics.len() - num_warnings;
+
+    if json {
+        print_json_diagnostics(&diagnostics);
+    } else {
+        for message in story.log.iter() {
+            eprintln!("{}", message);
+        }
+
+        println!(
+            "{}: no errors, {} warning(s), {} to-do comment(s)",
+            path.display(),
+            num_warnings,
+            num_todo_comments
+        );
+    }
+
+    if deny_warnings && num_warnings > 0 {
+        Err(CliError::DeniedWarnings { num_warnings })
+    } else {
+        Ok(())
+    }
+}
+
+fn print_json_diagnostics(diagnostics: &[Diagnostic]) {
+    for diagnostic in diagnostics {
+        println!("{}", json::diagnostic(diagnostic));
+    }
+}
@@ -0,0 +1,33 @@
+//! Write a story back as normalized `Ink` source.
+
+use std::{fs::write, path::Path};
+
+use inkling::format_ink;
+
+use crate::{read_file, CliError};
+
+/// Read a story and print it as formatted source, check that the file is formatted
+/// or write the formatted source back to the file.
+pub fn format(path: &Path, check: bool, write_file: bool) -> Result<(), CliError> {
+    let content = read_file(path)?;
+    let formatted = format_ink(&content)?;
+
+    if check {
+        if content != formatted {
+            return Err(CliError::Unformatted {
+                path: path.to_path_buf(),
+            });
+        }
+    } else if write_file {
+        if content != formatted {
+            write(path, &formatted).map_err(|error| CliError::Write {
+                path: path.to_path_buf(),
+                error,
+            })?;
+        }
+    } else {
+        print!("{}", formatted);
+    }
+
+    Ok(())
+}
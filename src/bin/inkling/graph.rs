@@ -0,0 +1,17 @@
+//! Print the branching structure of a story.
+
+use std::path::Path;
+
+use inkling::{read_story_from_string, GraphFormat};
+
+use crate::{read_file, CliError};
+
+/// Read a story and print its knots, stitches and choices as a graph.
+pub fn graph(path: &Path, format: GraphFormat) -> Result<(), CliError> {
+    let content = read_file(path)?;
+    let graph = read_story_from_string(&content)?.export_graph(format);
+
+    print!("{}", graph);
+
+    Ok(())
+}
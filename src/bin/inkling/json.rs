@@ -0,0 +1,114 @@
+//! JSON objects which are printed for the story output.
+
+use inkling::{error::Diagnostic, Choice, Line};
+use serde::Serialize;
+
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+/// Event from playing a story, written with its name as `event`.
+enum Event<'a> {
+    /// Line of text with its tags.
+    Line { text: &'a str, tags: &'a [String] },
+    /// Set of presented choices.
+    Choices { choices: Vec<ChoiceObject<'a>> },
+    /// Choice which was made.
+    Selected { choice: ChoiceObject<'a> },
+    /// The story ended.
+    End,
+}
+
+#[derive(Serialize)]
+/// Choice and its number in the presented set, starting from 1.
+struct ChoiceObject<'a> {
+    number: usize,
+    text: &'a str,
+    id: &'a str,
+    tags: &'a [String],
+}
+
+impl<'a> ChoiceObject<'a> {
+    fn new(number: usize, choice: &'a Choice) -> Self {
+        ChoiceObject {
+            number,
+            text: &choice.text,
+            id: &choice.id,
+            tags: &choice.tags,
+        }
+    }
+}
+
+fn to_string<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).expect("output objects can always be serialized")
+}
+
+/// Write a line of text as a JSON object.
+pub fn line(line: &Line) -> String {
+    to_string(&Event::Line {
+        text: &line.text,
+        tags: &line.tags,
+    })
+}
+
+/// Write a set of presented choices as a JSON object.
+pub fn choices(choices: &[Choice]) -> String {
+    let choices = choices
+        .iter()
+        .enumerate()
+        .map(|(i, choice)| ChoiceObject::new(i + 1, choice))
+        .collect();
+
+    to_string(&Event::Choices { choices })
+}
+
+/// Write a made choice as a JSON object.
+pub fn selected(number: usize, selected: &Choice) -> String {
+    to_string(&Event::Selected {
+        choice: ChoiceObject::new(number, selected),
+    })
+}
+
+/// Write a diagnostic as a JSON object.
+///
+/// Lines and columns start from 1. The end column is exclusive.
+pub fn diagnostic(diagnostic: &Diagnostic) -> String {
+    to_string(diagnostic)
+}
+
+/// Write the end of the story as a JSON object.
+pub fn end() -> String {
+    to_string(&Event::End)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_are_written_with_text_and_tags() {
+        let line = Line {
+            text: "Hello.\n".to_string(),
+            tags: vec!["mood: happy".to_string()],
+        };
+
+        assert_eq!(
+            super::line(&line),
+            r#"{"event":"line","text":"Hello.\n","tags":["mood: happy"]}"#
+        );
+    }
+
+    #[test]
+    fn choices_are_written_with_their_number() {
+        let mut story = inkling::read_story_from_string("*   Go \"left\"").unwrap();
+        let choices = story
+            .resume(&mut Vec::new())
+            .unwrap()
+            .get_choices()
+            .unwrap();
+
+        assert_eq!(
+            selected(1, &choices[0]),
+            r#"{"event":"selected","choice":{"number":1,"text":"Go \"left\"","id":"$ROOT$:go_left","tags":[]}}"#
+        );
+        assert_eq!(end(), r#"{"event":"end"}"#);
+    }
+}
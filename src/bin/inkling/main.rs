@@ -0,0 +1,232 @@
+//! Command line front end for reading, checking and playing `Ink` stories.
+//!
+//! Run `inkling help` for a list of commands and options.
+
+mod args;
+mod check;
+mod format;
+mod graph;
+mod json;
+mod play;
+mod stats;
+mod strings;
+mod test;
+
+use std::{
+    env,
+    error::Error,
+    fmt,
+    fs::read_to_string,
+    io,
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+use inkling::{
+    error::{parse::print_read_error, ReadError},
+    InklingError, LocalizationError,
+};
+
+use args::{parse_args, Command};
+
+/// Usage text which is printed for `inkling help`.
+const USAGE: &str = "\
+Usage: inkling <command> [options] <story.ink>
+
+Commands:
+    play     Play through a story in the terminal
+    check    Read a story and print all errors, warnings and to-do comments
+    stats    Print the number of knots, stitches, choices and words in a story
+    graph    Print the knots, stitches and choices of a story as a graph
+    strings  Print all translatable text in a story as a string table
+    fmt      Print a story as normalized Ink source
+    test     Play through stories from .inktest files and compare their transcripts
+    help     Print this message
+
+Options for `play`:
+    --seed <number>     Seed for the random number generator of shuffle sequences
+    --choices <file>    Make choices from a file, one per line, before asking for more.
+                        A choice is given by its number, text or id. Empty lines
+                        and lines starting with `#` are skipped.
+    --json              Print lines, choices and the end of the story as JSON objects,
+                        one per line
+    --strings <file>    Load translated text from a string table, as a .po, .xlf
+                        or .csv file
+
+Options for `check`:
+    --deny-warnings     Exit with an error if any warnings are found
+    --json              Print errors, warnings and to-do comments as JSON objects with
+                        stable codes, one per line
+
+Options for `graph`:
+    --format <format>   Format of the graph: `dot` for Graphviz (default) or `mermaid`
+
+Options for `strings`:
+    --format <format>   Format of the table: `po` (default), `xliff` or `csv`
+    --language <code>   Language of the story, written to XLIFF tables (default: `en`)
+
+Options for `fmt`:
+    --check             Exit with an error if the file is not formatted, without printing
+    --write             Write the formatted story back to the file instead of printing it
+
+Options for `test`:
+    <path>...           Test files, or directories to run all .inktest files in. A test
+                        file gives the story with `STORY <path>` and optionally a seed
+                        with `SEED <number>`, followed by the expected lines, choices
+                        to make with `> <choice>`, variable values to check with
+                        `CHECK <name> = <value>` and the end of the story with `-> END`.
+
+Exit codes:
+    0    Success
+    1    The story contains errors or could not be followed, or a test failed
+    2    Invalid arguments or a file could not be read
+";
+
+fn main() {
+    let command = match parse_args(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            exit(2);
+        }
+    };
+
+    let result = match command {
+        Command::Play(options) => play::play(&options),
+        Command::Check {
+            path,
+            deny_warnings,
+            json,
+        } => check::check(&path, deny_warnings, json),
+        Command::Stats { path } => stats::stats(&path),
+        Command::Graph { path, format } => graph::graph(&path, format),
+        Command::Strings {
+            path,
+            format,
+            language,
+        } => strings::strings(&path, format, &language),
+        Command::Fmt { path, check, write } => format::format(&path, check, write),
+        Command::Test { paths } => test::test(&paths),
+        Command::Help => {
+            print!("{}", USAGE);
+            Ok(())
+        }
+    };
+
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        exit(error.exit_code());
+    }
+}
+
+#[derive(Debug)]
+/// Errors which stop a command.
+pub enum CliError {
+    /// A file could not be read.
+    Io { path: PathBuf, error: io::Error },
+    /// A file could not be written.
+    Write { path: PathBuf, error: io::Error },
+    /// The story could not be read.
+    Read(ReadError),
+    /// An error was encountered while following the story.
+    Story(InklingError),
+    /// A string table could not be read or loaded into the story.
+    Localization(LocalizationError),
+    /// A choice from the choices file was not among the presented choices.
+    InvalidChoice { input: String, line: usize },
+    /// Errors were found in the story and printed as diagnostics.
+    InvalidStory { num_errors: usize },
+    /// Warnings were found while they are denied.
+    DeniedWarnings { num_warnings: usize },
+    /// The story file is not formatted.
+    Unformatted { path: PathBuf },
+    /// A test file could not be read.
+    InvalidTest {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    /// Tests did not match the transcripts of their stories.
+    FailedTests { num_failed: usize },
+}
+
+impl CliError {
+    /// Exit code of the program for the error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Io { .. } | CliError::Write { .. } => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl Error for CliError {}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use CliError::*;
+
+        match self {
+            Io { path, error } => {
+                write!(f, "error: could not read '{}': {}", path.display(), error)
+            }
+            Write { path, error } => {
+                write!(f, "error: could not write '{}': {}", path.display(), error)
+            }
+            Read(error) => write!(f, "{}", print_read_error(error)?),
+            Story(error) => write!(f, "error: {}", error),
+            Localization(error) => write!(f, "error: {}", error),
+            InvalidChoice { input, line } => write!(
+                f,
+                "error: choice '{}' on line {} of the choices file is not among the \
+                 presented choices",
+                input, line
+            ),
+            InvalidStory { num_errors } => {
+                write!(f, "error: found {} error(s) in the story", num_errors)
+            }
+            DeniedWarnings { num_warnings } => write!(
+                f,
+                "error: found {} warning(s) while warnings are denied",
+                num_warnings
+            ),
+            Unformatted { path } => write!(
+                f,
+                "error: '{}' is not formatted, run `inkling fmt --write` on it",
+                path.display()
+            ),
+            InvalidTest {
+                path,
+                line,
+                message,
+            } => write!(f, "error: {}:{}: {}", path.display(), line, message),
+            FailedTests { num_failed } => write!(f, "error: {} test(s) failed", num_failed),
+        }
+    }
+}
+
+impl From<InklingError> for CliError {
+    fn from(error: InklingError) -> Self {
+        CliError::Story(error)
+    }
+}
+
+impl From<LocalizationError> for CliError {
+    fn from(error: LocalizationError) -> Self {
+        CliError::Localization(error)
+    }
+}
+
+impl From<ReadError> for CliError {
+    fn from(error: ReadError) -> Self {
+        CliError::Read(error)
+    }
+}
+
+/// Read the content of a file.
+pub fn read_file(path: &Path) -> Result<String, CliError> {
+    read_to_string(path).map_err(|error| CliError::Io {
+        path: path.to_path_buf(),
+        error,
+    })
+}
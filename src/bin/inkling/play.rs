@@ -0,0 +1,181 @@
+//! Play through a story in the terminal.
+
+use std::io::{self, BufRead};
+
+use inkling::{
+    read_story_from_string_with_options, testing::find_choice, Choice, Line, Prompt, ReadOptions,
+};
+
+use crate::{args::PlayOptions, json, read_file, strings::read_string_table, CliError};
+
+/// Play a story, making choices from a file and then from standard input.
+pub fn play(options: &PlayOptions) -> Result<(), CliError> {
+    let content = read_file(&options.path)?;
+
+    if options.seed.is_some() && cfg!(not(feature = "random")) {
+        eprintln!(
+            "warning: `inkling` was not compiled with the `random` feature: the seed has no effect"
+        );
+    }
+
+    let read_options = ReadOptions { seed: options.seed };
+    let mut story = read_story_from_string_with_options(&content, &read_options)?;
+
+    if let Some(path) = &options.strings {
+        let untranslated = story.load_string_table(&read_string_table(path)?)?;
+
+        if !untranslated.is_empty() {
+            eprintln!(
+                "warning: {} text(s) in the story have no translation",
+                untranslated.len()
+            );
+        }
+    }
+
+    let mut scripted_choices = match &options.choices {
+        Some(path) => read_scripted_choices(&read_file(path)?),
+        None => Vec::new(),
+    }
+    .into_iter();
+
+    let output = if options.json {
+        Output::Json
+    } else {
+        Output::Text
+    };
+
+    let mut line_buffer = Vec::new();
+
+    loop {
+        let prompt = story.resume(&mut line_buffer)?;
+
+        output.print_lines(&line_buffer);
+        line_buffer.clear();
+
+        let choices = match prompt {
+            Prompt::Choice(choices) => choices,
+            Prompt::Done => {
+                output.print_end();
+                return Ok(());
+            }
+            _ => unreachable!("no breakpoints are set when playing"),
+        };
+
+        output.print_choices(&choices);
+
+        let index = match scripted_choices.next() {
+            Some((line, input)) => {
+                find_choice(&input, &choices).ok_or(CliError::InvalidChoice { input, line })?
+            }
+            None => match ask_user_for_choice(&choices, &output) {
+                Some(index) => index,
+                None => return Ok(()),
+            },
+        };
+
+        output.print_selected(index, &choices[index]);
+        story.make_choice(index)?;
+    }
+}
+
+/// Read choices from a file with their line numbers, skipping empty and comment lines.
+fn read_scripted_choices(content: &str) -> Vec<(usize, String)> {
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| (i, line.to_string()))
+        .collect()
+}
+
+/// Ask the user for a choice on standard input until a valid one is given.
+///
+/// Returns `None` if the user exits or the input ends.
+fn ask_user_for_choice(choices: &[Choice], output: &Output) -> Option<usize> {
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        let input = lines.next()?.ok()?;
+        let input = input.trim();
+
+        if input == "0" {
+            return None;
+        }
+
+        match find_choice(input, choices) {
+            Some(index) => return Some(index),
+            None => output.print_invalid_choice(),
+        }
+    }
+}
+
+/// Format to print the story in.
+enum Output {
+    Text,
+    Json,
+}
+
+impl Output {
+    fn print_lines(&self, lines: &[Line]) {
+        for line in lines {
+            match self {
+                Output::Text => print!("{}", line.text),
+                Output::Json => println!("{}", json::line(line)),
+            }
+        }
+    }
+
+    fn print_choices(&self, choices: &[Choice]) {
+        match self {
+            Output::Text => {
+                println!("\nChoose:");
+
+                for (i, choice) in choices.iter().enumerate() {
+                    println!("  {}. {}", i + 1, choice.text);
+                }
+
+                println!("     ---");
+                println!("  0. Exit story\n");
+            }
+            Output::Json => println!("{}", json::choices(choices)),
+        }
+    }
+
+    fn print_selected(&self, index: usize, choice: &Choice) {
+        match self {
+            Output::Text => println!("> {}\n", choice.text),
+            Output::Json => println!("{}", json::selected(index + 1, choice)),
+        }
+    }
+
+    fn print_invalid_choice(&self) {
+        match self {
+            Output::Text => println!("Not a valid option, try again:"),
+            Output::Json => eprintln!("Not a valid option, try again:"),
+        }
+    }
+
+    fn print_end(&self) {
+        match self {
+            Output::Text => println!("\nFIN"),
+            Output::Json => println!("{}", json::end()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripted_choices_skip_empty_and_comment_lines_but_keep_line_numbers() {
+        let content = "1\n\n# Pick the forest\n  Go left  \n";
+
+        assert_eq!(
+            read_scripted_choices(content),
+            vec![(1, "1".to_string()), (4, "Go left".to_string())]
+        );
+    }
+}
@@ -0,0 +1,20 @@
+//! Print the size of a story.
+
+use std::path::Path;
+
+use inkling::read_story_from_string;
+
+use crate::{read_file, CliError};
+
+/// Read a story and print its number of knots, stitches, choices and words.
+pub fn stats(path: &Path) -> Result<(), CliError> {
+    let content = read_file(path)?;
+    let stats = read_story_from_string(&content)?.get_stats();
+
+    println!("knots:    {}", stats.num_knots);
+    println!("stitches: {}", stats.num_stitches);
+    println!("choices:  {}", stats.num_choices);
+    println!("words:    {}", stats.num_words);
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+//! Print and read tables of translatable text.
+
+use std::path::Path;
+
+use inkling::{read_story_from_string, StringTable};
+
+use crate::{args::TableFormat, read_file, CliError};
+
+/// Read a story and print all of its translatable text as a table.
+pub fn strings(path: &Path, format: TableFormat, language: &str) -> Result<(), CliError> {
+    let content = read_file(path)?;
+    let table = read_story_from_string(&content)?.get_string_table();
+
+    let output = match format {
+        TableFormat::Po => table.to_po(),
+        TableFormat::Xliff => table.to_xliff(language, None),
+        TableFormat::Csv => table.to_csv(),
+    };
+
+    print!("{}", output);
+
+    Ok(())
+}
+
+/// Read a table of translated text in the format given by the file extension.
+pub fn read_string_table(path: &Path) -> Result<StringTable, CliError> {
+    let content = read_file(path)?;
+
+    // The extension is checked when the arguments are parsed
+    let table = match TableFormat::from_path(path) {
+        Some(TableFormat::Po) => StringTable::from_po(&content)?,
+        Some(TableFormat::Xliff) => StringTable::from_xliff(&content)?,
+        Some(TableFormat::Csv) | None => StringTable::from_csv(&content)?,
+    };
+
+    Ok(table)
+}
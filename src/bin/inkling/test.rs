@@ -0,0 +1,110 @@
+//! Scripted playthroughs of stories from `.inktest` files.
+//!
+//! Tests are read and run by [`inkling::testing`], which describes their format. The
+//! story path of a test is relative to the test file.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use inkling::{
+    read_story_from_string_with_options,
+    testing::{read_test_spec, run_test as run_test_spec, TestOutcome},
+    ReadOptions,
+};
+
+use crate::{read_file, CliError};
+
+/// File extension of test files, which are looked for in directories.
+const EXTENSION: &str = "inktest";
+
+/// Run all tests in a set of files and directories and print a report.
+///
+/// Directories are searched for `.inktest` files, without descending into their
+/// subdirectories. Tests which cannot be read or run are reported as failed.
+pub fn test(paths: &[PathBuf]) -> Result<(), CliError> {
+    let mut num_passed = 0;
+    let mut num_failed = 0;
+
+    for path in get_test_paths(paths)? {
+        match run_test(&path) {
+            Ok(TestOutcome::Passed) => {
+                println!("test {} ... ok", path.display());
+                num_passed += 1;
+            }
+            Ok(TestOutcome::Failed(report)) => {
+                println!("test {} ... FAILED\n{}", path.display(), report);
+                num_failed += 1;
+            }
+            Err(error) => {
+                println!("test {} ... FAILED\n{}\n", path.display(), error);
+                num_failed += 1;
+            }
+        }
+    }
+
+    println!("\n{} passed, {} failed", num_passed, num_failed);
+
+    if num_failed > 0 {
+        Err(CliError::FailedTests { num_failed })
+    } else {
+        Ok(())
+    }
+}
+
+/// Get the test files from a list of files and directories, in order.
+fn get_test_paths(paths: &[PathBuf]) -> Result<Vec<PathBuf>, CliError> {
+    let mut test_paths = Vec::new();
+
+    for path in paths {
+        if path.is_dir() {
+            let entries = fs::read_dir(path).map_err(|error| CliError::Io {
+                path: path.clone(),
+                error,
+            })?;
+
+            let mut in_directory = entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.is_file()
+                        && path.extension().and_then(|ext| ext.to_str()) == Some(EXTENSION)
+                })
+                .collect::<Vec<_>>();
+
+            in_directory.sort();
+            test_paths.extend(in_directory);
+        } else {
+            test_paths.push(path.clone());
+        }
+    }
+
+    Ok(test_paths)
+}
+
+/// Read a test file, play its story and compare the transcript to the expected one.
+fn run_test(path: &Path) -> Result<TestOutcome, CliError> {
+    let spec = read_test_spec(&read_file(path)?).map_err(|error| CliError::InvalidTest {
+        path: path.to_path_buf(),
+        line: error.line,
+        message: error.message,
+    })?;
+
+    let story_path = path
+        .parent()
+        .map(|directory| directory.join(&spec.story))
+        .unwrap_or_else(|| spec.story.clone());
+
+    if spec.seed.is_some() && cfg!(not(feature = "random")) {
+        eprintln!(
+            "warning: `inkling` was not compiled with the `random` feature: the seed has no effect"
+        );
+    }
+
+    let read_options = ReadOptions { seed: spec.seed };
+    let mut story = read_story_from_string_with_options(&read_file(&story_path)?, &read_options)?;
+
+    let name = path.display().to_string();
+
+    Ok(run_test_spec(&mut story, &spec, &name)?)
+}
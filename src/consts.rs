@@ -69,6 +69,9 @@ pub const MULTILINE_COMMENT_END_MARKER: &'static str = "*/";
 /// Marker for line comments which will print a reminder message when encountered.
 pub const TODO_COMMENT_MARKER: &'static str = "TODO:";
 
+/// Line comment which marks the knot or stitch on the same or next line as an entry point.
+pub const LINT_ENTRY_MARKER: &str = "lint: entry";
+
 /*****************************
  * Default names for objects *
  *****************************/
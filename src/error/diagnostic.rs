@@ -0,0 +1,520 @@
+//! Machine-readable diagnostics with stable codes for errors and log messages.
+//!
+//! Every error from reading a story, every error from following it and every message
+//! in its [`Logger`][crate::log::Logger] can be converted into
+//! a [`Diagnostic`][crate::error::Diagnostic]. Diagnostics carry
+//! a stable code and a severity along with their message and origin, which lets tools
+//! such as editor plugins inspect them without parsing the printed errors.
+//!
+//! # Codes
+//! Codes starting with `E` are errors which stop a story from being read:
+//!
+//! *   `E00xx`: errors in the story as a whole or in its prelude
+//! *   `E01xx`: errors in lines, conditions and expressions
+//! *   `E02xx`: errors in knots and stitches
+//! *   `E03xx`: errors from validating the story
+//! *   `E10xx`: errors from following the story
+//!
+//! Codes starting with `W` are warnings and codes starting with `N` are notes, neither of
+//! which stop the story from being read. Codes are never reused for other errors.
+//!
+//! # Example
+//! ```
+//! # use inkling::{error::{get_diagnostics, Severity}, read_story_from_string};
+//! let content = "Hello {world";
+//!
+//! let error = read_story_from_string(content).unwrap_err();
+//! let diagnostics = get_diagnostics(&error);
+//!
+//! assert_eq!(diagnostics[0].code, "E0102");
+//! assert_eq!(diagnostics[0].severity, Severity::Error);
+//! ```
+
+use std::fmt;
+
+use crate::{
+    error::{
+        parse::{
+            address::{InvalidAddressError, InvalidAddressErrorKind},
+            condition::ConditionErrorKind,
+            expression::ExpressionErrorKind,
+            knot::{KnotErrorKind, KnotNameError},
+            line::LineErrorKind,
+            prelude::{PreludeError, PreludeErrorKind},
+            validate::{
+                InvalidVariableExpression, InvalidVariableExpressionError, NameSpaceCollision,
+            },
+            ReadError,
+        },
+        utils::MetaData,
+        InklingError,
+    },
+    log::{LogMessage, MessageKind, RuntimeMessage, Warning},
+};
+
+#[cfg(feature = "serde_support")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(Deserialize, Serialize),
+    serde(from = "SerializedDiagnostic", into = "SerializedDiagnostic")
+)]
+/// Error, warning or note about the content of a story.
+///
+/// With `serde_support` a diagnostic is serialized as its code, severity and message,
+/// followed by the `line`, `column` and `end_column` of its origin. These start from 1
+/// and the end column is exclusive. The byte range and file of the origin are not kept.
+pub struct Diagnostic {
+    /// Stable code which identifies the kind of diagnostic, eg. `E0102`.
+    pub code: String,
+    /// Severity of the diagnostic.
+    pub severity: Severity,
+    /// Description of the diagnostic, without information about where it originated from.
+    pub message: String,
+    /// Information about the origin of the item that caused the diagnostic.
+    pub meta_data: MetaData,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(Deserialize, Serialize),
+    serde(rename_all = "lowercase")
+)]
+/// Severity of a diagnostic.
+pub enum Severity {
+    /// The story could not be read.
+    Error,
+    /// Non-fatal error or incompatibility.
+    Warning,
+    /// Information such as to-do comments.
+    Note,
+}
+
+#[cfg(feature = "serde_support")]
+#[derive(Deserialize, Serialize)]
+/// Diagnostic as it is serialized, with the line and columns of its origin.
+struct SerializedDiagnostic {
+    code: String,
+    severity: Severity,
+    message: String,
+    /// Line of the origin, starting from 1.
+    line: u32,
+    /// First column of the origin, starting from 1.
+    column: usize,
+    /// Column after the end of the origin, which spans at least one column.
+    end_column: usize,
+}
+
+#[cfg(feature = "serde_support")]
+impl From<Diagnostic> for SerializedDiagnostic {
+    fn from(diagnostic: Diagnostic) -> Self {
+        let columns = diagnostic.meta_data.column_range();
+
+        SerializedDiagnostic {
+            code: diagnostic.code,
+            severity: diagnostic.severity,
+            message: diagnostic.message,
+            line: diagnostic.meta_data.line(),
+            column: columns.start + 1,
+            end_column: columns.end.max(columns.start + 1) + 1,
+        }
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl From<SerializedDiagnostic> for Diagnostic {
+    fn from(diagnostic: SerializedDiagnostic) -> Self {
+        let start = diagnostic.column.saturating_sub(1);
+        let end = diagnostic.end_column.saturating_sub(1).max(start);
+
+        Diagnostic {
+            code: diagnostic.code,
+            severity: diagnostic.severity,
+            message: diagnostic.message,
+            meta_data: MetaData {
+                line_index: diagnostic.line.saturating_sub(1),
+                file_id: 0,
+                byte_range: 0..0,
+                column_range: start..end,
+            },
+        }
+    }
+}
+
+/// Get a diagnostic for every error encountered while reading a story.
+///
+/// The diagnostics are returned in the same order as the errors are printed by
+/// [`print_read_error`][crate::error::parse::print_read_error].
+pub fn get_diagnostics(error: &ReadError) -> Vec<Diagnostic> {
+    match error {
+        ReadError::Empty => vec![Diagnostic::error(
+            "E0001",
+            error.to_string(),
+            &MetaData::from(0),
+        )],
+        ReadError::ParseError(err) => {
+            let prelude_errors = err.prelude_errors.iter().map(Diagnostic::from);
+
+            let knot_errors = err.knot_errors.iter().flat_map(|knot_error| {
+                knot_error.line_errors.iter().map(move |line_error| {
+                    let meta_data = line_error.get_meta_data(&knot_error.knot_meta_data);
+                    let message = get_message(|buffer| line_error.write_message(buffer));
+                    Diagnostic::error(get_knot_error_code(line_error), message, meta_data)
+                })
+            });
+
+            prelude_errors.chain(knot_errors).collect()
+        }
+        ReadError::ValidationError(err) => err
+            .invalid_address_errors
+            .iter()
+            .map(Diagnostic::from)
+            .chain(err.name_space_errors.iter().map(Diagnostic::from))
+            .chain(err.variable_errors.iter().map(Diagnostic::from))
+            .collect(),
+    }
+}
+
+impl Diagnostic {
+    /// Create an error diagnostic with a message and the origin of the error.
+    fn error(code: &str, message: String, meta_data: &MetaData) -> Self {
+        Diagnostic {
+            code: code.to_string(),
+            severity: Severity::Error,
+            message,
+            meta_data: meta_data.clone(),
+        }
+    }
+}
+
+/// Get the message that an error writes without information about where it originated from.
+fn get_message<F>(write_message: F) -> String
+where
+    F: FnOnce(&mut String) -> fmt::Result,
+{
+    let mut message = String::new();
+    write_message(&mut message).unwrap();
+
+    message
+}
+
+impl From<&PreludeError> for Diagnostic {
+    fn from(err: &PreludeError) -> Self {
+        use PreludeErrorKind::*;
+
+        let code = match err.kind {
+            DuplicateVariable { .. } => "E0011",
+            InvalidVariable(_) => "E0012",
+            NoVariableAssignment => "E0013",
+            NoVariableName => "E0014",
+        };
+
+        Diagnostic::error(code, err.kind.to_string(), &err.meta_data)
+    }
+}
+
+impl From<&InvalidAddressError> for Diagnostic {
+    fn from(err: &InvalidAddressError) -> Self {
+        use InvalidAddressErrorKind::*;
+
+        let code = match err.kind {
+            BadFormat { .. } => "E0301",
+            UnknownAddress { .. } => "E0302",
+            UnknownKnot { .. } => "E0303",
+            UnknownStitch { .. } => "E0304",
+            UnknownCurrentAddress { .. } => "E0305",
+            ValidatedWithUnvalidatedAddress { .. } => "E0306",
+        };
+
+        let message = get_message(|buffer| err.write_message(buffer));
+        Diagnostic::error(code, message, &err.meta_data)
+    }
+}
+
+impl From<&NameSpaceCollision> for Diagnostic {
+    fn from(err: &NameSpaceCollision) -> Self {
+        let message = get_message(|buffer| err.write_message(buffer));
+        Diagnostic::error("E0311", message, &err.from_meta_data)
+    }
+}
+
+impl From<&InvalidVariableExpression> for Diagnostic {
+    fn from(err: &InvalidVariableExpression) -> Self {
+        let code = match err.kind {
+            InvalidVariableExpressionError::VariableError(_) => "E0321",
+            InvalidVariableExpressionError::Internal(_) => "E0322",
+        };
+
+        let message = get_message(|buffer| err.write_message(buffer));
+        Diagnostic::error(code, message, &err.meta_data)
+    }
+}
+
+impl From<&InklingError> for Diagnostic {
+    /// Create a diagnostic from an error from following a story.
+    ///
+    /// These errors do not originate from a line in the script, so the meta data
+    /// of the diagnostic points to the start of the story.
+    fn from(err: &InklingError) -> Self {
+        Diagnostic::error(
+            get_runtime_error_code(err),
+            err.to_string(),
+            &MetaData::from(0),
+        )
+    }
+}
+
+impl From<&LogMessage> for Diagnostic {
+    fn from(message: &LogMessage) -> Self {
+        let code = match &message.message {
+            MessageKind::Todo(_) => "N0001",
+            MessageKind::Warning(warning) => get_warning_code(warning),
+            MessageKind::Runtime(runtime_message) => get_runtime_message_code(runtime_message),
+        };
+
+        Diagnostic {
+            code: code.to_string(),
+            severity: message.severity(),
+            message: message.message.to_string(),
+            meta_data: message.meta_data.clone(),
+        }
+    }
+}
+
+/// Get the code of a warning.
+fn get_warning_code(warning: &Warning) -> &'static str {
+    use Warning::*;
+
+    match warning {
+        ShuffleSequenceNoRandom => "W0001",
+        UnreachableKnot { .. } => "W0002",
+        UnreachableStitch { .. } => "W0003",
+        UnusedVariable { .. } => "W0004",
+        ConstantCondition { .. } => "W0005",
+        ChoicesCanRunOut => "W0006",
+    }
+}
+
+/// Get the code of a message from following the story.
+fn get_runtime_message_code(message: &RuntimeMessage) -> &'static str {
+    use RuntimeMessage::*;
+
+    match message {
+        FallbackChoice => "N0002",
+        AlternativeExhausted => "N0003",
+        StickyChoicesRevisited { .. } => "N0004",
+        EndInNestedWeave { .. } => "N0005",
+    }
+}
+
+/// Get the code of an error from following the story.
+fn get_runtime_error_code(error: &InklingError) -> &'static str {
+    use InklingError::*;
+
+    match error {
+        AssignedToConst { .. } => "E1001",
+        DivertLimitExceeded { .. } => "E1002",
+        Internal(_) => "E1003",
+        InvalidAddress { .. } => "E1004",
+        InvalidExpression { .. } => "E1005",
+        InvalidChoice { .. } => "E1006",
+        InvalidRewind { .. } => "E1007",
+        InvalidVariable { .. } => "E1008",
+        MadeChoiceWithoutChoice => "E1009",
+        OutOfChoices { .. } => "E1010",
+        OutOfContent => "E1011",
+        PrintInvalidVariable { .. } => "E1012",
+        ReplayDiverged { .. } => "E1013",
+        VariableError(_) => "E1014",
+    }
+}
+
+/// Get the code of an error from parsing a knot.
+fn get_knot_error_code(error: &KnotErrorKind) -> &'static str {
+    use KnotErrorKind::*;
+
+    match error {
+        DuplicateKnotName { .. } => "E0201",
+        DuplicateStitchName { .. } => "E0202",
+        EmptyKnot => "E0203",
+        EmptyStitch { .. } => "E0204",
+        InvalidName { kind, .. } => match kind {
+            KnotNameError::ContainsInvalidCharacter(_) => "E0205",
+            KnotNameError::ContainsWhitespace => "E0206",
+            KnotNameError::Empty => "E0207",
+            KnotNameError::ReservedKeyword { .. } => "E0208",
+        },
+        LineError(err) => get_line_error_code(&err.kind),
+    }
+}
+
+/// Get the code of an error from parsing a line.
+fn get_line_error_code(error: &LineErrorKind) -> &'static str {
+    use LineErrorKind::*;
+
+    match error {
+        StickyAndNonSticky => "E0101",
+        UnmatchedBraces => "E0102",
+        UnmatchedBrackets => "E0103",
+        EmptyDivert => "E0104",
+        EmptyExpression => "E0105",
+        ExpectedEndOfLine { .. } => "E0106",
+        FoundTunnel => "E0107",
+        InvalidAddress { .. } => "E0108",
+        ConditionError(err) => match err.kind {
+            ConditionErrorKind::BadLink => "E0111",
+            ConditionErrorKind::BadValue => "E0112",
+            ConditionErrorKind::CouldNotParse => "E0113",
+            ConditionErrorKind::InvalidExpression(_) => "E0114",
+            ConditionErrorKind::InvalidVariable(_) => "E0115",
+            ConditionErrorKind::MultipleElseStatements => "E0116",
+            ConditionErrorKind::NoCondition => "E0117",
+            ConditionErrorKind::UnmatchedParenthesis => "E0118",
+        },
+        ExpressionError(err) => match err.kind {
+            ExpressionErrorKind::Empty => "E0121",
+            ExpressionErrorKind::InvalidHead { .. } => "E0122",
+            ExpressionErrorKind::InvalidVariable(_) => "E0123",
+            ExpressionErrorKind::NoOperator { .. } => "E0124",
+            ExpressionErrorKind::UnmatchedParenthesis => "E0125",
+        },
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "({}) {}[{}]: {}",
+            self.meta_data, self.severity, self.code, self.message
+        )
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::story::read_story_from_string;
+
+    fn get_codes(content: &str) -> Vec<String> {
+        let error = read_story_from_string(content).unwrap_err();
+
+        get_diagnostics(&error)
+            .into_iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect()
+    }
+
+    #[test]
+    fn parse_errors_yield_diagnostics_in_printed_order() {
+        let content = "\
+VAR = 0
+VAR variable = 10
+Hello {world
+*+  Sticky and non-sticky
+== kn-ot
+Line.
+";
+
+        assert_eq!(get_codes(content), &["E0014", "E0102", "E0101", "E0205"]);
+    }
+
+    #[test]
+    fn validation_errors_yield_diagnostics() {
+        let content = "\
+VAR knot = 2
+-> unknown
+== knot
+Line.
+";
+
+        assert_eq!(get_codes(content), &["E0302", "E0311"]);
+    }
+
+    #[test]
+    fn diagnostic_messages_do_not_include_line_information() {
+        let error = read_story_from_string("Hello {world").unwrap_err();
+        let diagnostic = &get_diagnostics(&error)[0];
+
+        assert_eq!(diagnostic.message, "line has unmatched curly '{}' braces");
+        assert_eq!(diagnostic.meta_data.line(), 1);
+        assert_eq!(diagnostic.meta_data.column(), 7);
+    }
+
+    #[test]
+    fn messages_of_errors_in_knots_do_not_include_line_information() {
+        let content = "\
+== knot
+Line.
+= stitch
+= stitch
+Line.
+";
+
+        let error = read_story_from_string(content).unwrap_err();
+        let diagnostic = &get_diagnostics(&error)[0];
+
+        assert_eq!(diagnostic.code, "E0204");
+        assert_eq!(diagnostic.message, "named stitch 'stitch' has no content");
+        assert_eq!(diagnostic.meta_data.line(), 3);
+    }
+
+    #[test]
+    fn runtime_errors_yield_error_diagnostics_with_codes() {
+        let mut story = read_story_from_string("Line.").unwrap();
+
+        let error = story.make_choice(0).unwrap_err();
+        let diagnostic = Diagnostic::from(&error);
+
+        assert_eq!(diagnostic.code, "E1009");
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.message, error.to_string());
+    }
+
+    #[test]
+    fn log_messages_yield_notes_and_warnings() {
+        let todo = LogMessage::with_kind(MessageKind::Todo("Write".to_string()), &().into());
+        let warning = LogMessage::with_kind(
+            MessageKind::Warning(Warning::ShuffleSequenceNoRandom),
+            &().into(),
+        );
+
+        let todo = Diagnostic::from(&todo);
+        let warning = Diagnostic::from(&warning);
+
+        assert_eq!(
+            (todo.code.as_str(), todo.severity),
+            ("N0001", Severity::Note)
+        );
+        assert_eq!(todo.message, "Write");
+        assert_eq!(
+            (warning.code.as_str(), warning.severity),
+            ("W0001", Severity::Warning)
+        );
+    }
+
+    #[test]
+    fn diagnostics_are_printed_with_line_severity_and_code() {
+        let error = read_story_from_string("Hello {world").unwrap_err();
+        let diagnostic = &get_diagnostics(&error)[0];
+
+        assert_eq!(
+            diagnostic.to_string(),
+            "(line 1) error[E0102]: line has unmatched curly '{}' braces"
+        );
+    }
+}
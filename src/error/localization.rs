@@ -0,0 +1,55 @@
+//! Errors from reading string tables and translating stories.
+
+use std::{error::Error, fmt};
+
+impl Error for LocalizationError {}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Errors from reading a string table or loading it into a story.
+pub enum LocalizationError {
+    /// A string table could not be read.
+    InvalidTable {
+        /// Format that the table was read as.
+        format: &'static str,
+        /// Line of the table where the error was found, starting from 1.
+        line: usize,
+        /// Description of the error.
+        message: String,
+    },
+    /// A translation does not contain every placeholder of its source text exactly once,
+    /// or has text after a divert.
+    ///
+    /// Placeholders such as `{0}` mark variables, alternatives, conditional content and
+    /// diverts in the text. These must all be kept in the translation, so that the story
+    /// branches the same way in every language. Text after a divert would never be shown.
+    InvalidPlaceholders {
+        /// Identifier of the translated text.
+        id: String,
+        /// Translated text with the invalid placeholders.
+        translation: String,
+    },
+}
+
+impl fmt::Display for LocalizationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use LocalizationError::*;
+
+        match self {
+            InvalidTable {
+                format,
+                line,
+                message,
+            } => write!(
+                f,
+                "Invalid {} string table (line {}): {}",
+                format, line, message
+            ),
+            InvalidPlaceholders { id, translation } => write!(
+                f,
+                "Translation of '{}' must contain every placeholder of its source text \
+                 exactly once and no text after diverts: '{}'",
+                id, translation
+            ),
+        }
+    }
+}
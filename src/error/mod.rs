@@ -2,9 +2,17 @@
 
 #[macro_use]
 pub(crate) mod utils;
+mod diagnostic;
+mod localization;
 pub mod parse;
+mod reload;
 pub(crate) mod runtime;
+mod testing;
 
+pub use diagnostic::{get_diagnostics, Diagnostic, Severity};
+pub use localization::LocalizationError;
 pub use parse::ReadError;
-pub use runtime::{variable, InklingError, InternalError};
+pub use reload::ReloadError;
+pub use runtime::{variable, InklingError, InternalError, InvalidExpressionKind};
+pub use testing::TestSpecError;
 pub use utils::MetaData;
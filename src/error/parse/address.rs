@@ -3,7 +3,7 @@
 use std::{error::Error, fmt};
 
 use crate::{
-    error::utils::{write_line_information, MetaData},
+    error::utils::{write_line_information, write_suggestion, MetaData},
     knot::Address,
 };
 
@@ -22,15 +22,25 @@ pub enum InvalidAddressErrorKind {
     /// The address is not formatted correctly.
     BadFormat { line: String },
     /// The address does not reference a knot, stitch or variable in the story.
-    UnknownAddress { name: String },
+    UnknownAddress {
+        name: String,
+        /// Name of a knot, stitch or variable with a similar name, if one exists.
+        suggestion: Option<String>,
+    },
     /// Tried to validate an address but the given current knot did not exist in the system.
     UnknownCurrentAddress { address: Address },
     /// The address references a `Knot` that is not in the story.
-    UnknownKnot { knot_name: String },
+    UnknownKnot {
+        knot_name: String,
+        /// Name of a knot with a similar name, if one exists.
+        suggestion: Option<String>,
+    },
     /// The address references a `Stitch` that is not present in the current `Knot`.
     UnknownStitch {
         knot_name: String,
         stitch_name: String,
+        /// Name of a stitch in the knot with a similar name, if one exists.
+        suggestion: Option<String>,
     },
     /// Tried to validate an address using an unvalidated current address.
     ValidatedWithUnvalidatedAddress {
@@ -47,10 +57,17 @@ impl Error for InvalidAddressError {
 
 impl Error for InvalidAddressErrorKind {}
 
+impl InvalidAddressError {
+    /// Write the error without information about where it originated from.
+    pub(crate) fn write_message<W: fmt::Write>(&self, f: &mut W) -> fmt::Result {
+        write!(f, "Invalid address: {}", self.kind)
+    }
+}
+
 impl fmt::Display for InvalidAddressError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write_line_information(f, &self.meta_data)?;
-        write!(f, "Invalid address: {}", self.kind)
+        self.write_message(f)
     }
 }
 
@@ -60,28 +77,39 @@ impl fmt::Display for InvalidAddressErrorKind {
 
         match self {
             BadFormat { line } => write!(f, "address was incorrectly formatted ('{}')", line),
-            UnknownAddress { name } => write!(
-                f,
-                "could not find knot or variable with name '{}' in the story",
-                name
-            ),
+            UnknownAddress { name, suggestion } => {
+                write!(
+                    f,
+                    "could not find knot or variable with name '{}' in the story",
+                    name
+                )?;
+                write_suggestion(f, suggestion)
+            }
             UnknownCurrentAddress { address } => write!(
                 f,
                 "during validation an address '{:?}' that is not in the system was used as
                  a current address",
                 address
             ),
-            UnknownKnot { knot_name } => {
-                write!(f, "no knot with name '{}' in the story", knot_name)
+            UnknownKnot {
+                knot_name,
+                suggestion,
+            } => {
+                write!(f, "no knot with name '{}' in the story", knot_name)?;
+                write_suggestion(f, suggestion)
             }
             UnknownStitch {
                 knot_name,
                 stitch_name,
-            } => write!(
-                f,
-                "no stitch with name '{}' in knot '{}'",
-                stitch_name, knot_name
-            ),
+                suggestion,
+            } => {
+                write!(
+                    f,
+                    "no stitch with name '{}' in knot '{}'",
+                    stitch_name, knot_name
+                )?;
+                write_suggestion(f, suggestion)
+            }
             ValidatedWithUnvalidatedAddress {
                 needle,
                 current_address,
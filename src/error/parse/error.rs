@@ -31,6 +31,17 @@ pub enum ReadError {
 /// Furthermore, since parsing and validation is done separately, this function will only
 /// print errors found in either step, not both. A file that could not be parsed may have
 /// additional problems that will be discovered during the validation step.
+///
+/// Errors from stories read with [`read_story_from_string`][crate::read_story_from_string]
+/// are followed by the line of the story that they were found in, with the part of the
+/// line that caused the error underlined:
+///
+/// ```text
+/// (line 3) line has unmatched curly '{}' braces
+///   |
+/// 3 | Hello {world
+///   |       ^
+/// ```
 pub fn print_read_error(error: &ReadError) -> Result<String, fmt::Error> {
     match &error {
         ReadError::ParseError(parse_error) => print_parse_error(parse_error),
@@ -39,6 +50,24 @@ pub fn print_read_error(error: &ReadError) -> Result<String, fmt::Error> {
     }
 }
 
+impl ReadError {
+    /// Attach the content of the story that the errors were found in.
+    ///
+    /// This lets [`print_read_error`][crate::error::parse::print_read_error] print the
+    /// line of every error with the error underlined.
+    pub(crate) fn with_source(mut self, source: &str) -> Self {
+        match &mut self {
+            ReadError::ParseError(err) => {
+                err.source.replace(source.to_string());
+            }
+            ReadError::ValidationError(err) => err.set_source(source),
+            ReadError::Empty => (),
+        }
+
+        self
+    }
+}
+
 impl Error for ReadError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match &self {
@@ -4,7 +4,7 @@ use std::{error::Error, fmt};
 
 use crate::error::{
     parse::line::LineError,
-    utils::{write_line_information, MetaData},
+    utils::{write_line_information, write_source_snippet, MetaData},
 };
 
 #[derive(Clone, Debug)]
@@ -91,38 +91,53 @@ impl_from_error![
 ];
 
 /// Get a string with all errors from parsing a `Knot`.
-pub(crate) fn write_knot_error<W: fmt::Write>(buffer: &mut W, error: &KnotError) -> fmt::Result {
+///
+/// If the source content of the story is given, the line of every error is written
+/// below it with the error underlined.
+pub(crate) fn write_knot_error<W: fmt::Write>(
+    buffer: &mut W,
+    error: &KnotError,
+    source: Option<&str>,
+) -> fmt::Result {
     for line_error in &error.line_errors {
+        let meta_data = line_error.get_meta_data(&error.knot_meta_data);
+
         match line_error {
             // All error kinds except these carry their own `MetaData` to use
-            KnotErrorKind::EmptyKnot => {
-                write_line_information(buffer, &error.knot_meta_data)?;
-            }
-            KnotErrorKind::DuplicateKnotName { .. } => {
-                write_line_information(buffer, &error.knot_meta_data)?;
+            KnotErrorKind::EmptyKnot | KnotErrorKind::DuplicateKnotName { .. } => {
+                write_line_information(buffer, meta_data)?;
             }
             _ => (),
         }
 
         write!(buffer, "{}\n", line_error)?;
+
+        if let Some(source) = source {
+            write_source_snippet(buffer, source, meta_data)?;
+        }
     }
 
     Ok(())
 }
 
-impl fmt::Display for KnotError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{} error(s) from parsing knot starting at line {}",
-            self.line_errors.len(),
-            self.knot_meta_data.line()
-        )
+impl KnotErrorKind {
+    /// Get the information about the origin of the line that caused the error.
+    ///
+    /// Errors which refer to the entire knot use the meta data of the knot.
+    pub(crate) fn get_meta_data<'a>(&'a self, knot_meta_data: &'a MetaData) -> &'a MetaData {
+        use KnotErrorKind::*;
+
+        match self {
+            DuplicateKnotName { .. } | EmptyKnot => knot_meta_data,
+            DuplicateStitchName { meta_data, .. }
+            | EmptyStitch { meta_data, .. }
+            | InvalidName { meta_data, .. } => meta_data,
+            LineError(err) => &err.meta_data,
+        }
     }
-}
 
-impl fmt::Display for KnotErrorKind {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    /// Write the error without information about where it originated from.
+    pub(crate) fn write_message<W: fmt::Write>(&self, f: &mut W) -> fmt::Result {
         use KnotErrorKind::*;
 
         match self {
@@ -137,39 +152,48 @@ impl fmt::Display for KnotErrorKind {
             DuplicateStitchName {
                 name,
                 knot_name,
-                meta_data,
                 prev_meta_data,
-            } => {
-                write_line_information(f, meta_data)?;
-                write!(
-                    f,
-                    "encountered another stitch with name '{}' in knot '{}' (previous at {})",
-                    name, knot_name, prev_meta_data
-                )
-            }
+                ..
+            } => write!(
+                f,
+                "encountered another stitch with name '{}' in knot '{}' (previous at {})",
+                name, knot_name, prev_meta_data
+            ),
             EmptyKnot => write!(f, "knot has no content"),
             EmptyStitch {
-                name: Some(name),
-                meta_data,
-            } => {
-                write_line_information(f, meta_data)?;
-                write!(f, "named stitch '{}' has no content", name)
-            }
-            EmptyStitch {
-                name: None,
-                meta_data,
-            } => {
-                write_line_information(f, meta_data)?;
-                write!(f, "root stitch has no content",)
-            }
-            InvalidName {
-                kind, meta_data, ..
-            } => {
-                write_line_information(f, meta_data)?;
-                write!(f, "could not read knot or stitch name: {}", kind)
-            }
-            LineError(err) => write!(f, "{}", err),
+                name: Some(name), ..
+            } => write!(f, "named stitch '{}' has no content", name),
+            EmptyStitch { name: None, .. } => write!(f, "root stitch has no content"),
+            InvalidName { kind, .. } => write!(f, "could not read knot or stitch name: {}", kind),
+            LineError(err) => write!(f, "{}", err.kind),
+        }
+    }
+}
+
+impl fmt::Display for KnotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} error(s) from parsing knot starting at line {}",
+            self.line_errors.len(),
+            self.knot_meta_data.line()
+        )
+    }
+}
+
+impl fmt::Display for KnotErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use KnotErrorKind::*;
+
+        match self {
+            DuplicateKnotName { .. } | EmptyKnot => (),
+            DuplicateStitchName { meta_data, .. }
+            | EmptyStitch { meta_data, .. }
+            | InvalidName { meta_data, .. } => write_line_information(f, meta_data)?,
+            LineError(err) => write_line_information(f, &err.meta_data)?,
         }
+
+        self.write_message(f)
     }
 }
 
@@ -1,6 +1,6 @@
 //! Errors from parsing individual lines in stories.
 
-use std::{error::Error, fmt};
+use std::{error::Error, fmt, ops::Range};
 
 use crate::{
     consts::{CHOICE_MARKER, STICKY_CHOICE_MARKER},
@@ -62,6 +62,45 @@ impl Error for LineErrorKind {
     }
 }
 
+impl LineErrorKind {
+    /// Find the range of bytes in a line which caused the error, if it can be narrowed down.
+    pub(crate) fn find_span(&self, line: &str) -> Option<Range<usize>> {
+        use LineErrorKind::*;
+
+        match self {
+            EmptyDivert => find_str(line, "->"),
+            ExpectedEndOfLine { tail } => find_str(line, tail.trim()),
+            InvalidAddress { address } => find_str(line, address.trim()),
+            UnmatchedBraces => find_unmatched_delimiter(line, '{', '}'),
+            UnmatchedBrackets => find_unmatched_delimiter(line, '[', ']'),
+            _ => None,
+        }
+    }
+}
+
+fn find_str(line: &str, needle: &str) -> Option<Range<usize>> {
+    if needle.is_empty() {
+        return None;
+    }
+
+    line.find(needle).map(|i| i..i + needle.len())
+}
+
+/// Find the first closing delimiter without an opening, or else the first unclosed opening.
+fn find_unmatched_delimiter(line: &str, open: char, close: char) -> Option<Range<usize>> {
+    let mut unclosed = Vec::new();
+
+    for (i, c) in line.char_indices() {
+        if c == open {
+            unclosed.push(i);
+        } else if c == close && unclosed.pop().is_none() {
+            return Some(i..i + 1);
+        }
+    }
+
+    unclosed.first().map(|&i| i..i + 1)
+}
+
 impl_from_error![
     LineErrorKind;
     [ConditionError, ConditionError],
@@ -112,3 +151,43 @@ impl fmt::Display for LineErrorKind {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unmatched_braces_are_found_at_the_first_unmatched_delimiter() {
+        let kind = LineErrorKind::UnmatchedBraces;
+
+        assert_eq!(kind.find_span("Hello {world"), Some(6..7));
+        assert_eq!(kind.find_span("{a} and {b {c}"), Some(8..9));
+        assert_eq!(kind.find_span("Hello} {world}"), Some(5..6));
+        assert_eq!(kind.find_span("{Hello}"), None);
+    }
+
+    #[test]
+    fn unmatched_brackets_are_found_at_the_first_unmatched_delimiter() {
+        let kind = LineErrorKind::UnmatchedBrackets;
+
+        assert_eq!(kind.find_span("*   Hello [world"), Some(10..11));
+        assert_eq!(kind.find_span("*   Hello] world"), Some(9..10));
+    }
+
+    #[test]
+    fn invalid_addresses_are_found_in_line() {
+        let kind = LineErrorKind::InvalidAddress {
+            address: "kn@t".to_string(),
+        };
+
+        assert_eq!(kind.find_span("Go -> kn@t"), Some(6..10));
+    }
+
+    #[test]
+    fn errors_without_a_specific_location_have_no_span() {
+        assert_eq!(
+            LineErrorKind::StickyAndNonSticky.find_span("*+ Choice"),
+            None
+        );
+    }
+}
@@ -5,14 +5,17 @@ use std::{
     fmt::{self, Write},
 };
 
-use crate::error::parse::{
-    knot::{write_knot_error, KnotError},
-    prelude::PreludeError,
+use crate::error::{
+    parse::{
+        knot::{write_knot_error, KnotError},
+        prelude::PreludeError,
+    },
+    utils::write_source_snippet,
 };
 
 impl Error for ParseError {}
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 /// List of errors encountered when parsing a story.
 ///
 /// Note that this may not contain all errors in the story. Individual lines return an error
@@ -25,18 +28,35 @@ pub struct ParseError {
     ///
     /// Each element in this list corresponds to a separate knot in the story.
     pub knot_errors: Vec<KnotError>,
+    /// Content of the story that the errors were found in, used to print source snippets.
+    pub(crate) source: Option<String>,
+}
+
+impl fmt::Debug for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ParseError")
+            .field("prelude_errors", &self.prelude_errors)
+            .field("knot_errors", &self.knot_errors)
+            .finish()
+    }
 }
 
 /// Get a string containing all line errors encountered when parsing a story.
 pub(crate) fn print_parse_error(error: &ParseError) -> Result<String, fmt::Error> {
     let mut buffer = String::new();
 
+    let source = error.source.as_deref();
+
     for prelude_error in &error.prelude_errors {
         write!(&mut buffer, "{}\n", prelude_error)?;
+
+        if let Some(source) = source {
+            write_source_snippet(&mut buffer, source, &prelude_error.meta_data)?;
+        }
     }
 
     for knot_error in &error.knot_errors {
-        write_knot_error(&mut buffer, knot_error)?;
+        write_knot_error(&mut buffer, knot_error, source)?;
     }
 
     Ok(buffer)
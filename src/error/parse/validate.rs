@@ -6,9 +6,9 @@
 //! [`ValidationError`][crate::error::parse::validate::ValidationError].
 
 use crate::error::{
-    parse::address::InvalidAddressError,
+    parse::address::{InvalidAddressError, InvalidAddressErrorKind},
     runtime::variable::VariableError,
-    utils::{write_line_information, MetaData},
+    utils::{write_line_information, write_source_snippet, MetaData},
     InklingError,
 };
 
@@ -17,7 +17,7 @@ use std::{
     fmt::{self, Write},
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 /// Collection of errors encountered when validating a story.
 pub struct ValidationError {
     /// Errors from invalid addresses to knots, stitchs or variables.
@@ -44,6 +44,18 @@ pub struct ValidationError {
     /// See [`Variable`][crate::line::Variable] for more information about valid operations
     /// and comparisons between variables.
     pub variable_errors: Vec<InvalidVariableExpression>,
+    /// Content of the story that the errors were found in, used to print source snippets.
+    pub(crate) source: Option<String>,
+}
+
+impl fmt::Debug for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ValidationError")
+            .field("invalid_address_errors", &self.invalid_address_errors)
+            .field("name_space_errors", &self.name_space_errors)
+            .field("variable_errors", &self.variable_errors)
+            .finish()
+    }
 }
 
 impl ValidationError {
@@ -53,7 +65,28 @@ impl ValidationError {
             invalid_address_errors: Vec::new(),
             name_space_errors: Vec::new(),
             variable_errors: Vec::new(),
+            source: None,
+        }
+    }
+
+    /// Set the content of the story that the errors were found in.
+    ///
+    /// The spans of invalid addresses and name space collisions are narrowed from their
+    /// full lines to the names that caused them.
+    pub(crate) fn set_source(&mut self, source: &str) {
+        for err in self.invalid_address_errors.iter_mut() {
+            if let Some(content) = source.get(err.meta_data.byte_range()) {
+                err.meta_data = narrow_to_address(&err.meta_data, content, &err.kind);
+            }
+        }
+
+        for err in self.name_space_errors.iter_mut() {
+            if let Some(content) = source.get(err.from_meta_data.byte_range()) {
+                err.from_meta_data = err.from_meta_data.narrow_to_str(content, &err.name);
+            }
         }
+
+        self.source.replace(source.to_string());
     }
 
     /// Assert whether no errors have been added to the set.
@@ -124,25 +157,72 @@ pub enum CollisionKind {
     Variable,
 }
 
+/// Narrow the span of a line with an invalid address to the address.
+fn narrow_to_address(
+    meta_data: &MetaData,
+    content: &str,
+    kind: &InvalidAddressErrorKind,
+) -> MetaData {
+    use InvalidAddressErrorKind::*;
+
+    match kind {
+        BadFormat { line } => meta_data.narrow_to_str(content, line),
+        UnknownAddress { name, .. } => meta_data.narrow_to_str(content, name),
+        UnknownKnot { knot_name, .. } => meta_data.narrow_to_str(content, knot_name),
+        UnknownStitch {
+            knot_name,
+            stitch_name,
+            ..
+        } => {
+            let full_address = format!("{}.{}", knot_name, stitch_name);
+
+            if content.contains(&full_address) {
+                meta_data.narrow_to_str(content, &full_address)
+            } else {
+                meta_data.narrow_to_str(content, stitch_name)
+            }
+        }
+        _ => meta_data.clone(),
+    }
+}
+
 /// Print every error that was encountered as a separate line.
+///
+/// If the source content of the story is available, the line of every error is written
+/// below it with the error underlined.
 pub(super) fn print_validation_error(error: &ValidationError) -> Result<String, fmt::Error> {
     let mut buffer = String::new();
 
     for err in &error.invalid_address_errors {
         write!(&mut buffer, "{}\n", err)?;
+        write_snippet(&mut buffer, error, &err.meta_data)?;
     }
 
     for err in &error.name_space_errors {
         write!(&mut buffer, "{}\n", err)?;
+        write_snippet(&mut buffer, error, &err.from_meta_data)?;
     }
 
     for err in &error.variable_errors {
         write!(&mut buffer, "{}\n", err)?;
+        write_snippet(&mut buffer, error, &err.meta_data)?;
     }
 
     Ok(buffer)
 }
 
+/// Write a source snippet for an error if the source content is available.
+fn write_snippet(
+    buffer: &mut String,
+    error: &ValidationError,
+    meta_data: &MetaData,
+) -> fmt::Result {
+    match &error.source {
+        Some(source) => write_source_snippet(buffer, source, meta_data),
+        None => Ok(()),
+    }
+}
+
 impl Error for ValidationError {}
 
 impl Error for NameSpaceCollision {}
@@ -178,23 +258,34 @@ impl fmt::Display for ValidationError {
     }
 }
 
-impl fmt::Display for InvalidVariableExpression {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write_line_information(f, &self.meta_data)?;
-
+impl InvalidVariableExpression {
+    /// Write the error without information about where it originated from.
+    pub(crate) fn write_message<W: Write>(&self, f: &mut W) -> fmt::Result {
         match &self.kind {
             InvalidVariableExpressionError::VariableError(err) => {
                 write!(f, "Invalid {}: {}", &self.expression_kind, err)
             }
             InvalidVariableExpressionError::Internal(err) => write!(
                 f,
-                "Unknown internal inconsistency in {}: {}\n",
+                "Unknown internal inconsistency in {}: {}",
                 &self.expression_kind, err
             ),
         }
     }
 }
 
+impl fmt::Display for InvalidVariableExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_line_information(f, &self.meta_data)?;
+        self.write_message(f)?;
+
+        match &self.kind {
+            InvalidVariableExpressionError::Internal(..) => writeln!(f),
+            _ => Ok(()),
+        }
+    }
+}
+
 impl fmt::Display for ExpressionKind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match &self {
@@ -204,10 +295,9 @@ impl fmt::Display for ExpressionKind {
     }
 }
 
-impl fmt::Display for NameSpaceCollision {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write_line_information(f, &self.from_meta_data)?;
-
+impl NameSpaceCollision {
+    /// Write the error without information about where it originated from.
+    pub(crate) fn write_message<W: Write>(&self, f: &mut W) -> fmt::Result {
         write!(
             f,
             "Name space collision for {} of name '{}' which is also defined as a {} at {}",
@@ -216,6 +306,13 @@ impl fmt::Display for NameSpaceCollision {
     }
 }
 
+impl fmt::Display for NameSpaceCollision {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_line_information(f, &self.from_meta_data)?;
+        self.write_message(f)
+    }
+}
+
 impl fmt::Display for CollisionKind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match &self {
@@ -0,0 +1,75 @@
+//! Errors from reloading a story from a modified script.
+
+use std::{error::Error, fmt};
+
+use crate::{
+    error::{InklingError, ReadError},
+    story::Location,
+};
+
+#[derive(Debug)]
+/// Errors from reloading the script of a running story.
+///
+/// The story is left unchanged when any of these are returned.
+pub enum ReloadError {
+    /// The new script could not be read.
+    Read(ReadError),
+    /// The knot or stitch that the story is at does not exist in the new script.
+    MissingLocation {
+        /// Location of the current knot and stitch.
+        location: Location,
+    },
+    /// The choices made in the current stitch do not lead to the same sets of choices
+    /// in the new script.
+    ChangedStack {
+        /// Location of the current knot and stitch.
+        location: Location,
+        /// Stack of choices made in the stitch.
+        stack: Vec<usize>,
+    },
+    /// The choices that the story is waiting on could not be prepared from the new script.
+    Choices(InklingError),
+}
+
+impl Error for ReloadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReloadError::Read(error) => Some(error),
+            ReloadError::Choices(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ReadError> for ReloadError {
+    fn from(error: ReadError) -> Self {
+        ReloadError::Read(error)
+    }
+}
+
+impl fmt::Display for ReloadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ReloadError::*;
+
+        match self {
+            Read(error) => write!(f, "Could not read the new script: {}", error),
+            MissingLocation { location } => write!(
+                f,
+                "Could not reload the story: '{}' does not exist in the new script",
+                location
+            ),
+            ChangedStack { location, stack } => write!(
+                f,
+                "Could not reload the story: the choices made in '{}' (stack {:?}) do not \
+                 lead to the same choices in the new script",
+                location, stack
+            ),
+            Choices(error) => write!(
+                f,
+                "Could not reload the story: the current choices could not be prepared \
+                 from the new script: {}",
+                error
+            ),
+        }
+    }
+}
@@ -3,9 +3,19 @@
 use std::{error::Error, fmt};
 
 use crate::{
-    error::{runtime::internal::StackError, variable::VariableError, InternalError},
+    error::{
+        parse::{
+            condition::ConditionError,
+            expression::ExpressionError,
+            validate::{InvalidVariableExpressionError, ValidationError},
+        },
+        runtime::internal::StackError,
+        utils::write_suggestion,
+        variable::VariableError,
+        InternalError,
+    },
     line::Variable,
-    story::{Choice, Location},
+    story::{Choice, ChoiceRef, Location},
 };
 
 impl Error for InklingError {}
@@ -29,10 +39,30 @@ impl Error for InklingError {}
 pub enum InklingError {
     /// Tried to assign a new value to a CONST variable.
     AssignedToConst { name: String },
+    /// More diverts than the set limit were followed without reaching a choice or an end.
+    ///
+    /// Likely because the story diverts in a loop without presenting any choices.
+    DivertLimitExceeded {
+        /// Location of the story when the limit was exceeded.
+        location: Location,
+        /// Maximum number of diverts that were allowed.
+        limit: usize,
+    },
     /// Internal errors caused by `inkling`.
     Internal(InternalError),
     /// Use of a `Location` which does not exist in the story.
-    InvalidAddress { location: Location },
+    InvalidAddress {
+        location: Location,
+        /// Address of a knot or stitch with a similar name, if one exists.
+        suggestion: Option<String>,
+    },
+    /// An expression or condition given to the story could not be read.
+    InvalidExpression {
+        /// Expression as it was given.
+        expression: String,
+        /// Reason that the expression could not be read.
+        kind: InvalidExpressionKind,
+    },
     /// An invalid choice index was given to resume the story with.
     InvalidChoice {
         /// Choice input by the user to resume the story with.
@@ -40,8 +70,19 @@ pub enum InklingError {
         /// List of choices that were available for the selection
         presented_choices: Vec<Choice>,
     },
+    /// Tried to rewind the story more steps than there are recorded choices.
+    InvalidRewind {
+        /// Number of steps that the story was asked to rewind.
+        steps: usize,
+        /// Number of steps that were available to rewind.
+        num_available: usize,
+    },
     /// Used a variable name that is not present in the story as an input variable.
-    InvalidVariable { name: String },
+    InvalidVariable {
+        name: String,
+        /// Name of a variable with a similar name, if one exists.
+        suggestion: Option<String>,
+    },
     /// Called `make_choice` when no choice had been requested.
     ///
     /// Likely directly at the start of a story or after a `move_to` call was made.
@@ -52,10 +93,37 @@ pub enum InklingError {
     OutOfContent,
     /// Tried to print a variable that cannot be printed.
     PrintInvalidVariable { name: String, value: Variable },
+    /// A replayed choice could not be made in the story.
+    ///
+    /// Likely because the story has changed since the choices were recorded.
+    ReplayDiverged {
+        /// Index of the choice in the replayed list.
+        step: usize,
+        /// Choice that could not be made.
+        choice: ChoiceRef,
+        /// Location of the story when the choice was to be made.
+        location: Location,
+        /// Choices that were presented. Empty if the story had reached its end.
+        presented_choices: Vec<Choice>,
+    },
     /// Invalid variable assignment or operation.
     VariableError(VariableError),
 }
 
+#[derive(Clone, Debug)]
+/// Reason that an expression or condition given to a story is invalid.
+///
+/// The errors are boxed to keep the size of `InklingError` down.
+pub enum InvalidExpressionKind {
+    /// The expression could not be parsed.
+    Parse(Box<ExpressionError>),
+    /// The condition could not be parsed.
+    ParseCondition(Box<ConditionError>),
+    /// The expression or condition refers to knots, stitches or variables which do not
+    /// exist in the story, or operates on variables of incompatible types.
+    Validation(Box<ValidationError>),
+}
+
 impl From<StackError> for InklingError {
     fn from(err: StackError) -> Self {
         InklingError::Internal(InternalError::BadKnotStack(err))
@@ -76,21 +144,46 @@ impl fmt::Display for InklingError {
             AssignedToConst { name } => {
                 write!(f, "Tried to assign a value to CONST variable '{}'", name)
             }
+            DivertLimitExceeded {
+                location: Location { knot, stitch },
+                limit,
+            } => {
+                write!(
+                    f,
+                    "Story followed more than {} diverts without reaching a choice or an end, \
+                     which is likely an endless loop (knot: {}",
+                    limit, knot
+                )?;
+
+                if let Some(name) = stitch {
+                    write!(f, ", stitch: {}", name)?;
+                }
+
+                write!(f, ")")
+            }
             Internal(err) => write!(f, "INTERNAL ERROR: {}", err),
             InvalidAddress {
                 location: Location { knot, stitch },
-            } => match stitch {
-                Some(stitch_name) => write!(
-                    f,
-                    "Invalid address: knot '{}' does not contain a stitch named '{}'",
-                    knot, stitch_name
-                ),
-                None => write!(
-                    f,
-                    "Invalid address: story does not contain a knot name '{}'",
-                    knot
-                ),
-            },
+                suggestion,
+            } => {
+                match stitch {
+                    Some(stitch_name) => write!(
+                        f,
+                        "Invalid address: knot '{}' does not contain a stitch named '{}'",
+                        knot, stitch_name
+                    ),
+                    None => write!(
+                        f,
+                        "Invalid address: story does not contain a knot name '{}'",
+                        knot
+                    ),
+                }?;
+
+                write_suggestion(f, suggestion)
+            }
+            InvalidExpression { expression, kind } => {
+                write!(f, "Invalid expression '{}': {}", expression, kind)
+            }
             InvalidChoice {
                 selection,
                 presented_choices,
@@ -102,11 +195,22 @@ impl fmt::Display for InklingError {
                 presented_choices.len(),
                 presented_choices.len() - 1
             ),
-            InvalidVariable { name } => write!(
+            InvalidRewind {
+                steps,
+                num_available,
+            } => write!(
                 f,
-                "Invalid variable: no variable with  name '{}' exists in the story",
-                name
+                "Invalid rewind: tried to rewind {} steps but only {} choices are recorded",
+                steps, num_available
             ),
+            InvalidVariable { name, suggestion } => {
+                write!(
+                    f,
+                    "Invalid variable: no variable with  name '{}' exists in the story",
+                    name
+                )?;
+                write_suggestion(f, suggestion)
+            }
             MadeChoiceWithoutChoice => write!(
                 f,
                 "Tried to make a choice, but no choice is currently active. Call `resume` \
@@ -134,7 +238,57 @@ impl fmt::Display for InklingError {
                 "Cannot print variable '{}' which has value '{:?}': invalid type",
                 name, value
             ),
+            ReplayDiverged {
+                step,
+                choice,
+                location,
+                presented_choices,
+            } => {
+                write!(f, "Replay diverged at step {}: ", step)?;
+
+                if presented_choices.is_empty() {
+                    write!(f, "story ended before {} could be made", choice)?;
+                } else {
+                    write!(
+                        f,
+                        "{} was not among the {} presented choices",
+                        choice,
+                        presented_choices.len()
+                    )?;
+                }
+
+                match &location.stitch {
+                    Some(stitch) => write!(f, " (knot: {}, stitch: {})", location.knot, stitch),
+                    None => write!(f, " (knot: {})", location.knot),
+                }
+            }
             VariableError(err) => write!(f, "{}", err),
         }
     }
 }
+
+impl fmt::Display for InvalidExpressionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use InvalidExpressionKind::*;
+
+        match self {
+            Parse(err) => write!(f, "{}", err.kind),
+            ParseCondition(err) => write!(f, "{}", err.kind),
+            Validation(err) => {
+                let address_errors = err
+                    .invalid_address_errors
+                    .iter()
+                    .map(|err| err.kind.to_string());
+
+                let variable_errors = err.variable_errors.iter().map(|err| match &err.kind {
+                    InvalidVariableExpressionError::VariableError(err) => err.to_string(),
+                    InvalidVariableExpressionError::Internal(err) => err.to_string(),
+                });
+
+                let messages = address_errors.chain(variable_errors).collect::<Vec<_>>();
+
+                write!(f, "{}", messages.join("; "))
+            }
+        }
+    }
+}
@@ -3,5 +3,5 @@ pub(crate) mod error;
 pub(crate) mod internal;
 pub mod variable;
 
-pub use error::InklingError;
+pub use error::{InklingError, InvalidExpressionKind};
 pub use internal::InternalError;
@@ -0,0 +1,21 @@
+//! Errors from reading scripted tests.
+
+use std::{error::Error, fmt};
+
+impl Error for TestSpecError {}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Error from reading a scripted test with
+/// [`read_test_spec`][crate::testing::read_test_spec()].
+pub struct TestSpecError {
+    /// Line of the test where the error was found, starting from 1.
+    pub line: usize,
+    /// Description of the error.
+    pub message: String,
+}
+
+impl fmt::Display for TestSpecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid test (line {}): {}", self.line, self.message)
+    }
+}
@@ -1,11 +1,11 @@
 //! Utilities for printing and handling errors.
 
-use std::fmt;
+use std::{fmt, ops::Range};
 
 #[cfg(feature = "serde_support")]
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
 /// Information about the origin of an item.
 ///
@@ -14,6 +14,15 @@ use serde::{Deserialize, Serialize};
 pub struct MetaData {
     /// Which line in the original story the item originated from.
     pub(crate) line_index: u32,
+    /// Identifier of the file that the item originated from.
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub(crate) file_id: u32,
+    /// Range of bytes in the file content that the item spans.
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub(crate) byte_range: Range<usize>,
+    /// Range of characters in the line that the item spans.
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub(crate) column_range: Range<usize>,
 }
 
 impl fmt::Display for MetaData {
@@ -30,6 +39,86 @@ impl MetaData {
     pub fn line(&self) -> u32 {
         self.line_index + 1
     }
+
+    /// Get the column number at which the corresponding data starts in its line.
+    ///
+    /// # Indexing
+    /// Column numbers start from 1 and count characters, not bytes.
+    pub fn column(&self) -> usize {
+        self.column_range.start + 1
+    }
+
+    /// Get the range of characters in the line that the corresponding data spans.
+    ///
+    /// # Indexing
+    /// Columns in the range start from 0.
+    pub fn column_range(&self) -> Range<usize> {
+        self.column_range.clone()
+    }
+
+    /// Get the range of bytes in the file content that the corresponding data spans.
+    pub fn byte_range(&self) -> Range<usize> {
+        self.byte_range.clone()
+    }
+
+    /// Get the identifier of the file that the corresponding data originated from.
+    ///
+    /// Stories which are read from a single string have the identifier 0.
+    pub fn file_id(&self) -> u32 {
+        self.file_id
+    }
+
+    /// Create meta data which spans a piece of content in a line.
+    ///
+    /// The content starts at the given byte offset in the file and column in the line.
+    pub(crate) fn with_span(
+        file_id: u32,
+        line_index: usize,
+        byte_offset: usize,
+        column: usize,
+        content: &str,
+    ) -> Self {
+        MetaData {
+            line_index: line_index as u32,
+            file_id,
+            byte_range: byte_offset..byte_offset + content.len(),
+            column_range: column..column + content.chars().count(),
+        }
+    }
+
+    /// Narrow the span to a range of bytes in the content that the meta data spans.
+    ///
+    /// If the given content is not of the same length as the span, or the range is not
+    /// inside of it, the span cannot be narrowed and a copy is returned.
+    pub(crate) fn narrow_to(&self, content: &str, range: Range<usize>) -> Self {
+        let is_valid = content.len() == self.byte_range.len()
+            && range.start <= range.end
+            && content.get(range.clone()).is_some();
+
+        if !is_valid {
+            return self.clone();
+        }
+
+        let column = self.column_range.start + content[..range.start].chars().count();
+
+        MetaData::with_span(
+            self.file_id,
+            self.line_index as usize,
+            self.byte_range.start + range.start,
+            column,
+            &content[range],
+        )
+    }
+
+    /// Narrow the span to the first occurrence of a string in the spanned content.
+    ///
+    /// If the string is not found a copy of the meta data is returned.
+    pub(crate) fn narrow_to_str(&self, content: &str, needle: &str) -> Self {
+        match content.find(needle) {
+            Some(i) if !needle.is_empty() => self.narrow_to(content, i..i + needle.len()),
+            _ => self.clone(),
+        }
+    }
 }
 
 /// Write meta data information for a line or piece of content in a story.
@@ -40,6 +129,58 @@ pub(crate) fn write_line_information<W: fmt::Write>(
     write!(buffer, "({}) ", meta_data)
 }
 
+/// Write a suggestion for a name to use instead of a misspelled one, if there is one.
+pub(crate) fn write_suggestion<W: fmt::Write>(
+    buffer: &mut W,
+    suggestion: &Option<String>,
+) -> fmt::Result {
+    match suggestion {
+        Some(name) => write!(buffer, ": did you mean '{}'?", name),
+        None => Ok(()),
+    }
+}
+
+/// Write the line of source content that an item originated from, with its span underlined.
+///
+/// The line is written with its number in a gutter to the left and carets below the
+/// spanned characters:
+///
+/// ```text
+///   |
+/// 3 | Hello {world
+///   |       ^^^^^^
+/// ```
+///
+/// Nothing is written if the line is not in the content.
+pub(crate) fn write_source_snippet<W: fmt::Write>(
+    buffer: &mut W,
+    source: &str,
+    meta_data: &MetaData,
+) -> fmt::Result {
+    let line = match source.lines().nth(meta_data.line_index as usize) {
+        Some(line) => line,
+        None => return Ok(()),
+    };
+
+    let line_number = meta_data.line().to_string();
+    let gutter = " ".repeat(line_number.len());
+
+    let num_chars = line.chars().count();
+    let start = meta_data.column_range.start.min(num_chars);
+    let num_carets = meta_data.column_range.len().min(num_chars - start).max(1);
+
+    // Keep tabs in the indentation to align the carets with the line
+    let indent = line
+        .chars()
+        .take(start)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+
+    writeln!(buffer, "{} |", gutter)?;
+    writeln!(buffer, "{} | {}", line_number, line)?;
+    writeln!(buffer, "{} | {}{}", gutter, indent, "^".repeat(num_carets))
+}
+
 /// Wrapper to implement From for variants when the variant is simply encapsulated
 /// in the enum.
 ///
@@ -83,6 +224,9 @@ impl From<usize> for MetaData {
     fn from(line_index: usize) -> Self {
         MetaData {
             line_index: line_index as u32,
+            file_id: 0,
+            byte_range: 0..0,
+            column_range: 0..0,
         }
     }
 }
@@ -90,7 +234,7 @@ impl From<usize> for MetaData {
 #[cfg(test)]
 impl From<()> for MetaData {
     fn from(_: ()) -> Self {
-        MetaData { line_index: 0 }
+        MetaData::from(0)
     }
 }
 
@@ -100,11 +244,71 @@ mod tests {
 
     #[test]
     fn meta_data_from_index_sets_index() {
-        assert_eq!(MetaData::from(6), MetaData { line_index: 6 });
+        assert_eq!(MetaData::from(6).line_index, 6);
     }
 
     #[test]
     fn meta_data_line_number_starts_from_one() {
         assert_eq!(MetaData::from(6).line(), 7);
     }
+
+    #[test]
+    fn meta_data_with_span_counts_bytes_and_characters() {
+        let meta_data = MetaData::with_span(1, 2, 10, 4, "Hallå där");
+
+        assert_eq!(meta_data.file_id(), 1);
+        assert_eq!(meta_data.line(), 3);
+        assert_eq!(meta_data.byte_range(), 10..21);
+        assert_eq!(meta_data.column_range(), 4..13);
+        assert_eq!(meta_data.column(), 5);
+    }
+
+    #[test]
+    fn narrowing_meta_data_offsets_span_from_its_start() {
+        let content = "Hallå {där";
+        let meta_data = MetaData::with_span(0, 0, 10, 4, content);
+
+        let narrowed = meta_data.narrow_to_str(content, "{");
+
+        assert_eq!(narrowed.byte_range(), 17..18);
+        assert_eq!(narrowed.column_range(), 10..11);
+    }
+
+    #[test]
+    fn meta_data_is_not_narrowed_if_content_does_not_match_span() {
+        let meta_data = MetaData::with_span(0, 0, 10, 4, "Hello");
+
+        assert_eq!(meta_data.narrow_to_str("Hello, World!", "World"), meta_data);
+        assert_eq!(meta_data.narrow_to_str("Hello", "World"), meta_data);
+        assert_eq!(meta_data.narrow_to("Hello", 3..8), meta_data);
+    }
+
+    #[test]
+    fn source_snippet_underlines_span_in_line() {
+        let source = "First line\n\tHello {world\n";
+        let meta_data = MetaData::with_span(0, 1, 18, 7, "{");
+
+        let mut buffer = String::new();
+        write_source_snippet(&mut buffer, source, &meta_data).unwrap();
+
+        assert_eq!(buffer, "  |\n2 | \tHello {world\n  | \t      ^\n");
+    }
+
+    #[test]
+    fn source_snippet_underlines_at_least_one_character() {
+        let source = "Hello";
+
+        let mut buffer = String::new();
+        write_source_snippet(&mut buffer, source, &MetaData::from(0)).unwrap();
+
+        assert_eq!(buffer, "  |\n1 | Hello\n  | ^\n");
+    }
+
+    #[test]
+    fn source_snippet_is_empty_if_line_is_not_in_source() {
+        let mut buffer = String::new();
+        write_source_snippet(&mut buffer, "Hello", &MetaData::from(3)).unwrap();
+
+        assert!(buffer.is_empty());
+    }
 }
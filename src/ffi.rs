@@ -0,0 +1,962 @@
+//! C interface to stories, enabled with the `ffi` feature.
+//!
+//! The functions are declared in the generated header `include/inkling.h`. Stories and
+//! line buffers are opaque objects which are created and freed by this library.
+//!
+//! # Ownership
+//! *   Objects returned by `inkling_story_from_string`, `inkling_story_load` and
+//!     `inkling_buffer_new` are owned by the caller and must be freed with
+//!     `inkling_story_free` and `inkling_buffer_free`.
+//! *   Bytes written by `inkling_story_save` are owned by the caller and must be freed
+//!     with `inkling_bytes_free`.
+//! *   Strings returned by the library are owned by the object they were retrieved from.
+//!     Text and tags of lines and choices are valid until the buffer is resumed into,
+//!     cleared or freed. Strings from `inkling_story_get_string` are valid until the next
+//!     call with the story or until it is freed.
+//! *   Strings and bytes given to the library are only read during the call and are
+//!     copied if they are kept.
+//!
+//! # Errors
+//! Functions which can fail return `INKLING_STATUS_ERROR`, a null pointer or
+//! `INKLING_PROMPT_ERROR`. A message describing the error is then retrieved with
+//! `inkling_last_error`, which is valid until the next error on the same thread.
+//!
+//! Panics inside the library do not unwind into the caller. They are caught and reported
+//! as `INKLING_STATUS_PANIC` or `INKLING_PROMPT_PANIC`, or as a null pointer, with the
+//! message set as the last error. A story which was given to the call may then be left
+//! in an inconsistent state and should only be freed.
+
+use crate::{
+    line::Variable,
+    story::{read_story_from_string, Line, Location, Prompt, Story},
+};
+
+use std::{
+    any::Any,
+    cell::RefCell,
+    ffi::{CStr, CString},
+    os::raw::c_char,
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+thread_local! {
+    // A `const` initializer would require Rust 1.59.
+    #[allow(clippy::missing_const_for_thread_local)]
+    /// Message of the last error on the thread.
+    static LAST_ERROR: RefCell<Option<CString>> = RefCell::new(None);
+}
+
+/// Story which is followed through the C interface.
+pub struct InklingStory {
+    story: Story,
+    /// String which was last retrieved from a variable.
+    string_value: Option<CString>,
+}
+
+/// Lines and choices from resuming a story.
+pub struct InklingBuffer {
+    lines: Vec<Text>,
+    choices: Vec<Text>,
+}
+
+/// Text of a line or choice, with its tags.
+struct Text {
+    text: CString,
+    tags: Vec<CString>,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Result of a call which can fail.
+pub enum InklingStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// The call failed: the message is retrieved with `inkling_last_error`.
+    Error = 1,
+    /// The call panicked: the message is retrieved with `inkling_last_error`.
+    Panic = 2,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Result of resuming a story.
+pub enum InklingPrompt {
+    /// The story reached its end.
+    Done = 0,
+    /// The story stopped at a set of choices, which are in the buffer.
+    Choice = 1,
+    /// The story could not be resumed: the message is retrieved with `inkling_last_error`.
+    Error = 2,
+    /// The call panicked: the message is retrieved with `inkling_last_error`.
+    Panic = 3,
+}
+
+/// Get the message of the last error on the current thread, or null if there was none.
+#[no_mangle]
+pub extern "C" fn inkling_last_error() -> *const c_char {
+    catch_panic(ptr::null(), || {
+        LAST_ERROR.with(|error| {
+            error
+                .borrow()
+                .as_ref()
+                .map(|message| message.as_ptr())
+                .unwrap_or(ptr::null())
+        })
+    })
+}
+
+/// Read a story from a null-terminated UTF-8 string.
+///
+/// Returns null if the story could not be read.
+///
+/// # Safety
+/// `content` must be null or a valid null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_story_from_string(content: *const c_char) -> *mut InklingStory {
+    catch_panic(ptr::null_mut(), || {
+        let result = read_str(content)
+            .and_then(|content| read_story_from_string(content).map_err(|error| error.to_string()));
+
+        into_story_pointer(result)
+    })
+}
+
+/// Read a story from bytes written by `inkling_story_save`.
+///
+/// Returns null if the bytes do not contain a saved story.
+///
+/// # Safety
+/// `data` must be null or point to `length` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_story_load(data: *const u8, length: usize) -> *mut InklingStory {
+    catch_panic(ptr::null_mut(), || {
+        let result = if data.is_null() {
+            Err("no data given".to_string())
+        } else {
+            serde_json::from_slice(slice::from_raw_parts(data, length))
+                .map_err(|error| format!("could not load story: {}", error))
+        };
+
+        into_story_pointer(result)
+    })
+}
+
+/// Free a story. Does nothing if it is null.
+///
+/// # Safety
+/// `story` must be null or a story from this library which has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_story_free(story: *mut InklingStory) {
+    catch_panic((), || {
+        if !story.is_null() {
+            drop(Box::from_raw(story));
+        }
+    })
+}
+
+/// Write the state of a story to bytes, which are freed with `inkling_bytes_free`.
+///
+/// # Safety
+/// `story` must be a valid story, and `data` and `length` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_story_save(
+    story: *const InklingStory,
+    data: *mut *mut u8,
+    length: *mut usize,
+) -> InklingStatus {
+    catch_panic(InklingStatus::Panic, || {
+        let result = get_story(story as *mut InklingStory).and_then(|story| {
+            if data.is_null() || length.is_null() {
+                return Err("no output given".to_string());
+            }
+
+            let bytes = serde_json::to_vec(&story.story)
+                .map_err(|error| format!("could not save story: {}", error))?
+                .into_boxed_slice();
+
+            *length = bytes.len();
+            *data = Box::into_raw(bytes) as *mut u8;
+
+            Ok(())
+        });
+
+        into_status(result)
+    })
+}
+
+/// Free bytes written by `inkling_story_save`. Does nothing if `data` is null.
+///
+/// # Safety
+/// `data` and `length` must be from the same call to `inkling_story_save`, and the
+/// bytes must not have been freed.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_bytes_free(data: *mut u8, length: usize) {
+    catch_panic((), || {
+        if !data.is_null() {
+            drop(Box::from_raw(ptr::slice_from_raw_parts_mut(data, length)));
+        }
+    })
+}
+
+/// Create an empty buffer to resume stories into.
+#[no_mangle]
+pub extern "C" fn inkling_buffer_new() -> *mut InklingBuffer {
+    catch_panic(ptr::null_mut(), || {
+        Box::into_raw(Box::new(InklingBuffer {
+            lines: Vec::new(),
+            choices: Vec::new(),
+        }))
+    })
+}
+
+/// Free a buffer. Does nothing if it is null.
+///
+/// # Safety
+/// `buffer` must be null or a buffer from this library which has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_buffer_free(buffer: *mut InklingBuffer) {
+    catch_panic((), || {
+        if !buffer.is_null() {
+            drop(Box::from_raw(buffer));
+        }
+    })
+}
+
+/// Remove all lines and choices from a buffer.
+///
+/// # Safety
+/// `buffer` must be null or a valid buffer.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_buffer_clear(buffer: *mut InklingBuffer) {
+    catch_panic((), || {
+        if let Some(buffer) = buffer.as_mut() {
+            buffer.lines.clear();
+            buffer.choices.clear();
+        }
+    })
+}
+
+/// Resume a story, adding its lines to the buffer until a choice or the end is reached.
+///
+/// The choices of the buffer are replaced with those which the story stopped at, or
+/// removed if it reached the end.
+///
+/// # Safety
+/// `story` and `buffer` must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_story_resume(
+    story: *mut InklingStory,
+    buffer: *mut InklingBuffer,
+) -> InklingPrompt {
+    catch_panic(InklingPrompt::Panic, || {
+        let result = get_story(story).and_then(|story| {
+            let buffer = buffer
+                .as_mut()
+                .ok_or_else(|| "no buffer given".to_string())?;
+
+            let mut lines = Vec::new();
+            let mut prompt = story
+                .story
+                .resume(&mut lines)
+                .map_err(|error| error.to_string())?;
+
+            // Breakpoints cannot be set over this interface, but may have been saved
+            while let Prompt::Breakpoint(..) = prompt {
+                prompt = story
+                    .story
+                    .resume(&mut lines)
+                    .map_err(|error| error.to_string())?;
+            }
+
+            buffer.lines.extend(lines.iter().map(Text::from_line));
+            buffer.choices.clear();
+
+            match prompt {
+                Prompt::Done => Ok(InklingPrompt::Done),
+                Prompt::Choice(choices) => {
+                    buffer.choices.extend(
+                        choices
+                            .iter()
+                            .map(|choice| Text::new(&choice.text, &choice.tags)),
+                    );
+
+                    Ok(InklingPrompt::Choice)
+                }
+                Prompt::Breakpoint(..) => unreachable!("pauses are resumed through"),
+            }
+        });
+
+        result.unwrap_or_else(|error| {
+            set_last_error(error);
+            InklingPrompt::Error
+        })
+    })
+}
+
+/// Make a choice from the set that the story stopped at, by its index in the buffer.
+///
+/// # Safety
+/// `story` must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_story_make_choice(
+    story: *mut InklingStory,
+    index: usize,
+) -> InklingStatus {
+    catch_panic(InklingStatus::Panic, || {
+        let result = get_story(story).and_then(|story| {
+            story
+                .story
+                .make_choice(index)
+                .map_err(|error| error.to_string())
+        });
+
+        into_status(result)
+    })
+}
+
+/// Move a story to a knot, or to a stitch in it if `stitch` is not null.
+///
+/// # Safety
+/// `story` must be valid, `knot` a valid string and `stitch` null or a valid string.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_story_move_to(
+    story: *mut InklingStory,
+    knot: *const c_char,
+    stitch: *const c_char,
+) -> InklingStatus {
+    catch_panic(InklingStatus::Panic, || {
+        let result = get_story(story).and_then(|story| {
+            let knot = read_str(knot)?;
+            let stitch = if stitch.is_null() {
+                None
+            } else {
+                Some(read_str(stitch)?)
+            };
+
+            story
+                .story
+                .move_to(&Location::new(knot, stitch))
+                .map_err(|error| error.to_string())
+        });
+
+        into_status(result)
+    })
+}
+
+/// Number of lines in a buffer.
+///
+/// # Safety
+/// `buffer` must be null or a valid buffer.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_buffer_num_lines(buffer: *const InklingBuffer) -> usize {
+    catch_panic(0, || {
+        buffer
+            .as_ref()
+            .map(|buffer| buffer.lines.len())
+            .unwrap_or(0)
+    })
+}
+
+/// Text of a line in a buffer, or null if there is no line at the index.
+///
+/// # Safety
+/// `buffer` must be null or a valid buffer.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_buffer_line_text(
+    buffer: *const InklingBuffer,
+    index: usize,
+) -> *const c_char {
+    catch_panic(ptr::null(), || {
+        get_text(buffer, index, |buffer| &buffer.lines)
+            .map(|text| text.text.as_ptr())
+            .unwrap_or(ptr::null())
+    })
+}
+
+/// Number of tags of a line in a buffer.
+///
+/// # Safety
+/// `buffer` must be null or a valid buffer.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_buffer_line_num_tags(
+    buffer: *const InklingBuffer,
+    index: usize,
+) -> usize {
+    catch_panic(0, || {
+        get_text(buffer, index, |buffer| &buffer.lines)
+            .map(|text| text.tags.len())
+            .unwrap_or(0)
+    })
+}
+
+/// Tag of a line in a buffer, or null if there is no such tag.
+///
+/// # Safety
+/// `buffer` must be null or a valid buffer.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_buffer_line_tag(
+    buffer: *const InklingBuffer,
+    index: usize,
+    tag_index: usize,
+) -> *const c_char {
+    catch_panic(ptr::null(), || {
+        get_text(buffer, index, |buffer| &buffer.lines)
+            .and_then(|text| text.get_tag(tag_index))
+            .unwrap_or(ptr::null())
+    })
+}
+
+/// Number of choices in a buffer.
+///
+/// # Safety
+/// `buffer` must be null or a valid buffer.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_buffer_num_choices(buffer: *const InklingBuffer) -> usize {
+    catch_panic(0, || {
+        buffer
+            .as_ref()
+            .map(|buffer| buffer.choices.len())
+            .unwrap_or(0)
+    })
+}
+
+/// Text of a choice in a buffer, or null if there is no choice at the index.
+///
+/// # Safety
+/// `buffer` must be null or a valid buffer.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_buffer_choice_text(
+    buffer: *const InklingBuffer,
+    index: usize,
+) -> *const c_char {
+    catch_panic(ptr::null(), || {
+        get_text(buffer, index, |buffer| &buffer.choices)
+            .map(|text| text.text.as_ptr())
+            .unwrap_or(ptr::null())
+    })
+}
+
+/// Number of tags of a choice in a buffer.
+///
+/// # Safety
+/// `buffer` must be null or a valid buffer.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_buffer_choice_num_tags(
+    buffer: *const InklingBuffer,
+    index: usize,
+) -> usize {
+    catch_panic(0, || {
+        get_text(buffer, index, |buffer| &buffer.choices)
+            .map(|text| text.tags.len())
+            .unwrap_or(0)
+    })
+}
+
+/// Tag of a choice in a buffer, or null if there is no such tag.
+///
+/// # Safety
+/// `buffer` must be null or a valid buffer.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_buffer_choice_tag(
+    buffer: *const InklingBuffer,
+    index: usize,
+    tag_index: usize,
+) -> *const c_char {
+    catch_panic(ptr::null(), || {
+        get_text(buffer, index, |buffer| &buffer.choices)
+            .and_then(|text| text.get_tag(tag_index))
+            .unwrap_or(ptr::null())
+    })
+}
+
+/// Get the value of an integer variable.
+///
+/// # Safety
+/// `story` must be valid, `name` a valid string and `value` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_story_get_int(
+    story: *mut InklingStory,
+    name: *const c_char,
+    value: *mut i32,
+) -> InklingStatus {
+    catch_panic(InklingStatus::Panic, || {
+        get_variable(story, name, value, |variable| match variable {
+            Variable::Int(value) => Some(value),
+            _ => None,
+        })
+    })
+}
+
+/// Get the value of a floating point variable.
+///
+/// # Safety
+/// `story` must be valid, `name` a valid string and `value` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_story_get_float(
+    story: *mut InklingStory,
+    name: *const c_char,
+    value: *mut f32,
+) -> InklingStatus {
+    catch_panic(InklingStatus::Panic, || {
+        get_variable(story, name, value, |variable| match variable {
+            Variable::Float(value) => Some(value),
+            _ => None,
+        })
+    })
+}
+
+/// Get the value of a boolean variable.
+///
+/// # Safety
+/// `story` must be valid, `name` a valid string and `value` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_story_get_bool(
+    story: *mut InklingStory,
+    name: *const c_char,
+    value: *mut bool,
+) -> InklingStatus {
+    catch_panic(InklingStatus::Panic, || {
+        get_variable(story, name, value, |variable| match variable {
+            Variable::Bool(value) => Some(value),
+            _ => None,
+        })
+    })
+}
+
+/// Get the value of a string variable.
+///
+/// The string is owned by the story and valid until the next call with it.
+///
+/// # Safety
+/// `story` must be valid, `name` a valid string and `value` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_story_get_string(
+    story: *mut InklingStory,
+    name: *const c_char,
+    value: *mut *const c_char,
+) -> InklingStatus {
+    catch_panic(InklingStatus::Panic, || {
+        let result = read_variable(story, name, |variable| match variable {
+            Variable::String(value) => Some(to_c_string(&value)),
+            _ => None,
+        })
+        .and_then(|string| {
+            let story = get_story(story)?;
+            let value = value
+                .as_mut()
+                .ok_or_else(|| "no output given".to_string())?;
+
+            *value = story.string_value.insert(string).as_ptr();
+
+            Ok(())
+        });
+
+        into_status(result)
+    })
+}
+
+/// Set the value of an integer variable.
+///
+/// # Safety
+/// `story` must be valid and `name` a valid string.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_story_set_int(
+    story: *mut InklingStory,
+    name: *const c_char,
+    value: i32,
+) -> InklingStatus {
+    catch_panic(InklingStatus::Panic, || {
+        set_variable(story, name, Variable::Int(value))
+    })
+}
+
+/// Set the value of a floating point variable.
+///
+/// # Safety
+/// `story` must be valid and `name` a valid string.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_story_set_float(
+    story: *mut InklingStory,
+    name: *const c_char,
+    value: f32,
+) -> InklingStatus {
+    catch_panic(InklingStatus::Panic, || {
+        set_variable(story, name, Variable::Float(value))
+    })
+}
+
+/// Set the value of a boolean variable.
+///
+/// # Safety
+/// `story` must be valid and `name` a valid string.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_story_set_bool(
+    story: *mut InklingStory,
+    name: *const c_char,
+    value: bool,
+) -> InklingStatus {
+    catch_panic(InklingStatus::Panic, || {
+        set_variable(story, name, Variable::Bool(value))
+    })
+}
+
+/// Set the value of a string variable. The string is copied.
+///
+/// # Safety
+/// `story` must be valid, and `name` and `value` valid strings.
+#[no_mangle]
+pub unsafe extern "C" fn inkling_story_set_string(
+    story: *mut InklingStory,
+    name: *const c_char,
+    value: *const c_char,
+) -> InklingStatus {
+    catch_panic(InklingStatus::Panic, || match read_str(value) {
+        Ok(value) => set_variable(story, name, Variable::String(value.to_string())),
+        Err(error) => into_status(Err(error)),
+    })
+}
+
+impl Text {
+    fn new(text: &str, tags: &[String]) -> Self {
+        Text {
+            text: to_c_string(text),
+            tags: tags.iter().map(|tag| to_c_string(tag)).collect(),
+        }
+    }
+
+    fn from_line(line: &Line) -> Self {
+        Text::new(&line.text, &line.tags)
+    }
+
+    fn get_tag(&self, index: usize) -> Option<*const c_char> {
+        self.tags.get(index).map(|tag| tag.as_ptr())
+    }
+}
+
+/// Copy a string into a C string, removing any null characters.
+fn to_c_string(string: &str) -> CString {
+    CString::new(string.replace('\0', "")).unwrap()
+}
+
+/// Run the body of an exported function, which must not unwind into the caller.
+///
+/// If it panics the message is set as the last error and `on_panic` is returned.
+fn catch_panic<T, F>(on_panic: T, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        set_last_error(format!("panic in inkling: {}", get_panic_message(&payload)));
+        on_panic
+    })
+}
+
+fn get_panic_message(payload: &Box<dyn Any + Send>) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown cause"
+    }
+}
+
+fn set_last_error(message: String) {
+    LAST_ERROR.with(|error| error.borrow_mut().replace(to_c_string(&message)));
+}
+
+fn into_status(result: Result<(), String>) -> InklingStatus {
+    match result {
+        Ok(()) => InklingStatus::Ok,
+        Err(message) => {
+            set_last_error(message);
+            InklingStatus::Error
+        }
+    }
+}
+
+fn into_story_pointer(result: Result<Story, String>) -> *mut InklingStory {
+    match result {
+        Ok(story) => Box::into_raw(Box::new(InklingStory {
+            story,
+            string_value: None,
+        })),
+        Err(message) => {
+            set_last_error(message);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Read a null-terminated UTF-8 string.
+unsafe fn read_str<'a>(string: *const c_char) -> Result<&'a str, String> {
+    if string.is_null() {
+        return Err("no string given".to_string());
+    }
+
+    CStr::from_ptr(string)
+        .to_str()
+        .map_err(|_| "string is not valid UTF-8".to_string())
+}
+
+unsafe fn get_story<'a>(story: *mut InklingStory) -> Result<&'a mut InklingStory, String> {
+    story.as_mut().ok_or_else(|| "no story given".to_string())
+}
+
+unsafe fn get_text<'a, F>(
+    buffer: *const InklingBuffer,
+    index: usize,
+    get_texts: F,
+) -> Option<&'a Text>
+where
+    F: Fn(&'a InklingBuffer) -> &'a Vec<Text>,
+{
+    buffer
+        .as_ref()
+        .and_then(|buffer| get_texts(buffer).get(index))
+}
+
+/// Get a variable and write its value if it has the type that `get_value` accepts.
+unsafe fn get_variable<T, F>(
+    story: *mut InklingStory,
+    name: *const c_char,
+    value: *mut T,
+    get_value: F,
+) -> InklingStatus
+where
+    F: Fn(Variable) -> Option<T>,
+{
+    let result = read_variable(story, name, get_value).and_then(|variable_value| {
+        let value = value
+            .as_mut()
+            .ok_or_else(|| "no output given".to_string())?;
+        *value = variable_value;
+
+        Ok(())
+    });
+
+    into_status(result)
+}
+
+/// Get the value of a variable if it has the type that `get_value` accepts.
+unsafe fn read_variable<T, F>(
+    story: *mut InklingStory,
+    name: *const c_char,
+    get_value: F,
+) -> Result<T, String>
+where
+    F: Fn(Variable) -> Option<T>,
+{
+    let story = get_story(story)?;
+    let name = read_str(name)?;
+
+    let variable = story
+        .story
+        .get_variable(name)
+        .ok_or_else(|| format!("no variable with name '{}'", name))?;
+
+    get_value(variable).ok_or_else(|| format!("variable '{}' has another type", name))
+}
+
+unsafe fn set_variable(
+    story: *mut InklingStory,
+    name: *const c_char,
+    value: Variable,
+) -> InklingStatus {
+    let result = get_story(story).and_then(|story| {
+        let name = read_str(name)?;
+
+        story
+            .story
+            .set_variable(name, value)
+            .map_err(|error| error.to_string())
+    });
+
+    into_status(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = "\
+VAR coins = 3
+VAR name = \"Ali\"
+-> market
+== market ==
+You have {coins} coins. # location: market
+*   Buy an apple. # price: 2
+    You bought an apple.
+*   [Go home] -> home
+- -> home
+== home ==
+Welcome home, {name}.
+-> END
+";
+
+    fn c_string(string: &str) -> CString {
+        CString::new(string).unwrap()
+    }
+
+    unsafe fn read(string: *const c_char) -> Option<&'static str> {
+        string
+            .as_ref()
+            .map(|_| CStr::from_ptr(string).to_str().unwrap())
+    }
+
+    fn last_error() -> String {
+        unsafe { read(inkling_last_error()) }.unwrap().to_string()
+    }
+
+    #[test]
+    fn panics_are_caught_and_set_as_last_error() {
+        let status = catch_panic(InklingStatus::Panic, || -> InklingStatus {
+            panic!("something broke")
+        });
+
+        assert_eq!(status, InklingStatus::Panic);
+        assert_eq!(&last_error(), "panic in inkling: something broke");
+
+        let index = 3;
+        let pointer = catch_panic(ptr::null_mut(), || -> *mut InklingStory {
+            panic!("no item at {}", index)
+        });
+
+        assert!(pointer.is_null());
+        assert_eq!(&last_error(), "panic in inkling: no item at 3");
+    }
+
+    #[test]
+    fn stories_with_errors_are_not_read() {
+        let content = c_string("-> unknown");
+
+        unsafe {
+            assert!(inkling_story_from_string(content.as_ptr()).is_null());
+            assert!(inkling_story_from_string(ptr::null()).is_null());
+        }
+
+        assert!(!last_error().is_empty());
+    }
+
+    #[test]
+    fn story_can_be_played_and_saved_through_the_interface() {
+        let content = c_string(CONTENT);
+        let coins_name = c_string("coins");
+        let name_name = c_string("name");
+        let missing_name = c_string("missing");
+        let new_name = c_string("Bo");
+
+        unsafe {
+            let story = inkling_story_from_string(content.as_ptr());
+            let buffer = inkling_buffer_new();
+            assert!(!story.is_null());
+
+            assert_eq!(
+                inkling_story_set_int(story, coins_name.as_ptr(), 5),
+                InklingStatus::Ok
+            );
+            assert_eq!(
+                inkling_story_set_int(story, missing_name.as_ptr(), 5),
+                InklingStatus::Error
+            );
+            assert!(last_error().contains("'missing'"));
+
+            assert_eq!(inkling_story_resume(story, buffer), InklingPrompt::Choice);
+            assert_eq!(inkling_buffer_num_lines(buffer), 1);
+            assert_eq!(
+                read(inkling_buffer_line_text(buffer, 0)),
+                Some("You have 5 coins.\n")
+            );
+            assert_eq!(inkling_buffer_line_num_tags(buffer, 0), 1);
+            assert_eq!(
+                read(inkling_buffer_line_tag(buffer, 0, 0)),
+                Some("location: market")
+            );
+            assert!(inkling_buffer_line_text(buffer, 1).is_null());
+
+            assert_eq!(inkling_buffer_num_choices(buffer), 2);
+            assert_eq!(read(inkling_buffer_choice_text(buffer, 1)), Some("Go home"));
+            assert_eq!(
+                read(inkling_buffer_choice_tag(buffer, 0, 0)),
+                Some("price: 2")
+            );
+
+            let mut data = ptr::null_mut();
+            let mut length = 0;
+            assert_eq!(
+                inkling_story_save(story, &mut data, &mut length),
+                InklingStatus::Ok
+            );
+            assert!(length > 0);
+
+            assert_eq!(inkling_story_make_choice(story, 5), InklingStatus::Error);
+            assert_eq!(inkling_story_make_choice(story, 0), InklingStatus::Ok);
+
+            inkling_buffer_clear(buffer);
+            assert_eq!(
+                inkling_story_set_string(story, name_name.as_ptr(), new_name.as_ptr()),
+                InklingStatus::Ok
+            );
+            assert_eq!(inkling_story_resume(story, buffer), InklingPrompt::Done);
+            assert_eq!(inkling_buffer_num_lines(buffer), 3);
+            assert_eq!(
+                read(inkling_buffer_line_text(buffer, 0)),
+                Some("Buy an apple.\n")
+            );
+            assert_eq!(
+                read(inkling_buffer_line_text(buffer, 2)),
+                Some("Welcome home, Bo.\n")
+            );
+            assert_eq!(inkling_buffer_num_choices(buffer), 0);
+
+            let mut name = ptr::null();
+            assert_eq!(
+                inkling_story_get_string(story, name_name.as_ptr(), &mut name),
+                InklingStatus::Ok
+            );
+            assert_eq!(read(name), Some("Bo"));
+
+            let mut coins_as_float = 0.0;
+            assert_eq!(
+                inkling_story_get_float(story, coins_name.as_ptr(), &mut coins_as_float),
+                InklingStatus::Error
+            );
+
+            let loaded = inkling_story_load(data, length);
+            inkling_bytes_free(data, length);
+            assert!(!loaded.is_null());
+
+            let mut coins = 0;
+            assert_eq!(
+                inkling_story_get_int(loaded, coins_name.as_ptr(), &mut coins),
+                InklingStatus::Ok
+            );
+            assert_eq!(coins, 5);
+
+            let home = c_string("home");
+            let market = c_string("market");
+            let stall = c_string("stall");
+
+            assert_eq!(
+                inkling_story_move_to(loaded, home.as_ptr(), ptr::null()),
+                InklingStatus::Ok
+            );
+            assert_eq!(
+                inkling_story_move_to(loaded, market.as_ptr(), stall.as_ptr()),
+                InklingStatus::Error
+            );
+
+            inkling_buffer_clear(buffer);
+            assert_eq!(inkling_story_resume(loaded, buffer), InklingPrompt::Done);
+            assert_eq!(
+                read(inkling_buffer_line_text(buffer, 0)),
+                Some("Welcome home, Ali.\n")
+            );
+
+            inkling_story_free(loaded);
+            inkling_story_free(story);
+            inkling_buffer_free(buffer);
+        }
+    }
+}
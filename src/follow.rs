@@ -4,7 +4,14 @@ use crate::{
     error::InklingError,
     knot::Address,
     line::InternalChoice,
-    story::{rng::StoryRng, types::VariableSet},
+    log::LogMessage,
+    story::{
+        coverage::CoverageRecorder,
+        debug::{Debugger, Pause},
+        rng::StoryRng,
+        trace::TraceHook,
+        types::VariableSet,
+    },
 };
 
 #[cfg(feature = "serde_support")]
@@ -27,6 +34,8 @@ pub enum EncounteredEvent {
     Divert(Address),
     /// Finished with the current node or story.
     Done,
+    /// Paused before following a line, to be resumed from that line.
+    Pause(Pause),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -36,6 +45,8 @@ pub struct ChoiceInfo {
     pub num_visited: u32,
     /// Choice data to process before presenting to the user.
     pub choice_data: InternalChoice,
+    /// Identifier of the choice, set from its stitch before it is presented.
+    pub id: String,
 }
 
 impl ChoiceInfo {
@@ -44,6 +55,7 @@ impl ChoiceInfo {
         ChoiceInfo {
             num_visited,
             choice_data: choice.clone(),
+            id: String::new(),
         }
     }
 }
@@ -59,6 +71,25 @@ pub struct FollowData {
     pub variables: VariableSet,
     /// Random number generator
     pub rng: StoryRng,
+    /// Recorder of seen content, if coverage is recorded.
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub coverage: Option<CoverageRecorder>,
+    /// Maximum number of diverts to follow before a choice or end is reached, if limited.
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub divert_limit: Option<usize>,
+    /// Whether the story last stopped at a divert to `END` or `DONE`, rather than by
+    /// running out of content or reaching a choice.
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub reached_end: bool,
+    /// Tracer of events, shared with the user and thus not de/serialized.
+    #[cfg_attr(feature = "serde_support", serde(skip))]
+    pub tracer: TraceHook,
+    /// Messages from following the story, which are moved to its log after the follow.
+    #[cfg_attr(feature = "serde_support", serde(skip))]
+    pub messages: Vec<LogMessage>,
+    /// Breakpoints, watches and the state of stepping through the story.
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub debugger: Debugger,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -80,6 +111,8 @@ pub struct LineText {
     pub glue_end: bool,
     /// Tags associated with the line.
     pub tags: Vec<String>,
+    /// Address of the stitch that the line was followed from, if known.
+    pub address: Option<Address>,
 }
 
 #[cfg(test)]
@@ -91,6 +124,7 @@ pub struct LineTextBuilder {
     pub glue_begin: bool,
     pub glue_end: bool,
     pub tags: Vec<String>,
+    pub address: Option<Address>,
 }
 
 #[cfg(test)]
@@ -101,6 +135,7 @@ impl LineTextBuilder {
             glue_begin: false,
             glue_end: false,
             tags: Vec::new(),
+            address: None,
         }
     }
 
@@ -110,6 +145,7 @@ impl LineTextBuilder {
             glue_begin: self.glue_begin,
             glue_end: self.glue_end,
             tags: self.tags,
+            address: self.address,
         }
     }
 
@@ -127,6 +163,11 @@ impl LineTextBuilder {
         self.tags = tags.to_vec();
         self
     }
+
+    pub fn with_address(mut self, address: &Address) -> Self {
+        self.address.replace(address.clone());
+        self
+    }
 }
 
 #[cfg(test)]
@@ -167,6 +208,12 @@ impl FollowDataBuilder {
             knot_visit_counts: self.knot_visit_counts,
             variables: self.variables,
             rng: self.rng,
+            coverage: None,
+            divert_limit: None,
+            reached_end: false,
+            tracer: TraceHook::default(),
+            messages: Vec::new(),
+            debugger: Debugger::default(),
         }
     }
 }
@@ -16,9 +16,10 @@ use crate::{
     knot::KnotSet,
     log::Logger,
     story::validate::{KnotValidationInfo, ValidateContent, ValidationData},
+    utils::get_closest_name,
 };
 
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
@@ -59,11 +60,13 @@ impl Address {
         root_knot_name: &str,
         knots: &KnotSet,
     ) -> Result<Self, InvalidAddressErrorKind> {
-        let knot = knots
-            .get(root_knot_name)
-            .ok_or(InvalidAddressErrorKind::UnknownKnot {
-                knot_name: root_knot_name.to_string(),
-            })?;
+        let knot =
+            knots
+                .get(root_knot_name)
+                .ok_or_else(|| InvalidAddressErrorKind::UnknownKnot {
+                    knot_name: root_knot_name.to_string(),
+                    suggestion: suggest_name(root_knot_name, knots.keys()),
+                })?;
 
         Ok(Address::Validated(AddressKind::Location {
             knot: root_knot_name.to_string(),
@@ -76,11 +79,13 @@ impl Address {
         location: &Location,
         knots: &KnotSet,
     ) -> Result<Self, InvalidAddressErrorKind> {
-        let knot = knots
-            .get(&location.knot)
-            .ok_or(InvalidAddressErrorKind::UnknownKnot {
-                knot_name: location.knot.to_string(),
-            })?;
+        let knot =
+            knots
+                .get(&location.knot)
+                .ok_or_else(|| InvalidAddressErrorKind::UnknownKnot {
+                    knot_name: location.knot.to_string(),
+                    suggestion: suggest_name(&location.knot, knots.keys()),
+                })?;
 
         let stitch_name = location.stitch.as_ref().unwrap_or(&knot.default_stitch);
 
@@ -93,6 +98,7 @@ impl Address {
             Err(InvalidAddressErrorKind::UnknownStitch {
                 knot_name: location.knot.clone(),
                 stitch_name: stitch_name.clone(),
+                suggestion: suggest_name(stitch_name, knot.stitches.keys()),
             })
         }
     }
@@ -136,20 +142,16 @@ impl Address {
         }
     }
 
-    /// Get a string representation of the address as `Ink` would write it.
-    pub fn to_string(&self) -> String {
-        match &self {
-            Address::Validated(AddressKind::GlobalVariable { name }) => name.clone(),
-            Address::Validated(AddressKind::Location { knot, stitch }) => {
-                if stitch.as_str() == ROOT_KNOT_NAME {
-                    format!("{}", knot)
-                } else {
-                    format!("{}.{}", knot, stitch)
-                }
-            }
-            Address::Raw(content) => content.clone(),
-            Address::End => "END".to_string(),
-        }
+    /// Resolve a divert target as it is written in the story, relative to a location.
+    pub fn from_target(
+        target: &str,
+        current_location: &Address,
+        data: &ValidationData,
+    ) -> Result<Self, InvalidAddressErrorKind> {
+        let mut address = Address::Raw(target.to_string());
+        address.validate_internal(current_location, data)?;
+
+        Ok(address)
     }
 
     /// Validate the `Address` if it is `Raw`.
@@ -178,6 +180,26 @@ impl Address {
     }
 }
 
+impl fmt::Display for Address {
+    /// Write the address as `Ink` would write it.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use AddressKind::*;
+
+        match &self {
+            Address::Validated(GlobalVariable { name }) => write!(f, "{}", name),
+            Address::Validated(Location { knot, stitch }) => {
+                if stitch.as_str() == ROOT_KNOT_NAME {
+                    write!(f, "{}", knot)
+                } else {
+                    write!(f, "{}.{}", knot, stitch)
+                }
+            }
+            Address::Raw(content) => write!(f, "{}", content),
+            Address::End => write!(f, "END"),
+        }
+    }
+}
+
 impl ValidateContent for Address {
     fn validate(
         &mut self,
@@ -236,7 +258,8 @@ fn get_location_from_parts(
     let KnotValidationInfo { stitches, .. } =
         knots
             .get(&knot_name)
-            .ok_or(InvalidAddressErrorKind::UnknownKnot {
+            .ok_or_else(|| InvalidAddressErrorKind::UnknownKnot {
+                suggestion: suggest_name(&knot_name, knots.keys()),
                 knot_name: knot_name.clone(),
             })?;
 
@@ -247,8 +270,9 @@ fn get_location_from_parts(
         })
     } else {
         Err(InvalidAddressErrorKind::UnknownStitch {
-            knot_name: knot_name.clone(),
-            stitch_name: stitch_name.clone(),
+            suggestion: suggest_name(&stitch_name, stitches.keys()),
+            knot_name,
+            stitch_name,
         })
     }
 }
@@ -287,12 +311,30 @@ fn get_address_from_needle(
     } else if matches_variable {
         Ok(AddressKind::GlobalVariable { name: needle })
     } else {
+        let names = current_stitches
+            .iter()
+            .chain(data.knots.keys())
+            .chain(data.follow_data.variables.keys());
+
         Err(InvalidAddressErrorKind::UnknownAddress {
-            name: needle.clone(),
+            suggestion: suggest_name(&needle, names),
+            name: needle,
         })
     }
 }
 
+/// Get the name which is closest to a misspelled name, to suggest in its place.
+///
+/// Root knot and stitch names are internal to `inkling` and never suggested.
+fn suggest_name<'a, I: Iterator<Item = &'a String>>(name: &str, names: I) -> Option<String> {
+    get_closest_name(
+        name,
+        names
+            .map(String::as_str)
+            .filter(|name| *name != ROOT_KNOT_NAME),
+    )
+}
+
 /// Get the knot name and stitches from the given address.
 fn get_knot_name_and_stitches(
     address: &Address,
@@ -722,4 +764,62 @@ You find yourself in Cairo, the capital of Egypt.
             })
         );
     }
+
+    #[test]
+    fn invalid_addresses_suggest_close_knot_stitch_or_variable_names() {
+        let content = "
+== tavern
+You enter the tavern.
+-> END
+
+= cellar
+-> END
+";
+
+        let knots = read_knots_from_string(content).unwrap();
+
+        let variables = &[("coins".to_string(), Variable::Int(0))]
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(i, (name, var))| (name, VariableInfo::new(var, i)))
+            .collect();
+
+        let data = ValidationData::from_data(&knots, variables);
+        let current_address = Address::from_knot("tavern");
+
+        let get_error_kind = |raw: &str| {
+            validate_address(&mut Address::Raw(raw.to_string()), &current_address, &data)
+                .unwrap_err()
+                .kind
+        };
+
+        match get_error_kind("tavren.cellar") {
+            InvalidAddressErrorKind::UnknownKnot { suggestion, .. } => {
+                assert_eq!(suggestion, Some("tavern".to_string()))
+            }
+            other => panic!("expected `UnknownKnot` but got {:?}", other),
+        }
+
+        match get_error_kind("tavern.celar") {
+            InvalidAddressErrorKind::UnknownStitch { suggestion, .. } => {
+                assert_eq!(suggestion, Some("cellar".to_string()))
+            }
+            other => panic!("expected `UnknownStitch` but got {:?}", other),
+        }
+
+        match get_error_kind("coin") {
+            InvalidAddressErrorKind::UnknownAddress { suggestion, .. } => {
+                assert_eq!(suggestion, Some("coins".to_string()))
+            }
+            other => panic!("expected `UnknownAddress` but got {:?}", other),
+        }
+
+        match get_error_kind("harbour") {
+            InvalidAddressErrorKind::UnknownAddress { suggestion, .. } => {
+                assert_eq!(suggestion, None)
+            }
+            other => panic!("expected `UnknownAddress` but got {:?}", other),
+        }
+    }
 }
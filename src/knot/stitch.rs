@@ -72,11 +72,13 @@ pub struct Stitch {
 impl Stitch {
     /// Follow a story while reading every line into a buffer.
     pub fn follow(&mut self, buffer: &mut LineDataBuffer, data: &mut FollowData) -> FollowResult {
-        let result = self.root.follow(&mut self.stack, buffer, data)?;
+        let result = self
+            .root
+            .follow_from_stack(0, &mut self.stack, buffer, data)?;
 
         match &result {
             EncounteredEvent::Done | EncounteredEvent::Divert(..) => self.reset_stack(),
-            EncounteredEvent::BranchingChoice(..) => (),
+            EncounteredEvent::BranchingChoice(..) | EncounteredEvent::Pause(..) => (),
         }
 
         Ok(result)
@@ -227,7 +229,7 @@ mod tests {
             Ok(Stitch {
                 root,
                 stack: vec![0],
-                meta_data: MetaData { line_index: 0 },
+                meta_data: MetaData::from(0),
             })
         }
     }
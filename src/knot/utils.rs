@@ -42,6 +42,21 @@ pub fn get_mut_stitch<'a>(
         )
 }
 
+/// Return a copy of the knots which shares no internal state with the original.
+///
+/// Choices in the knots share their selection text between clones. This copy detaches
+/// them, which ensures that following the copy does not affect the original or vice versa.
+pub fn clone_knots_detached(knots: &KnotSet) -> KnotSet {
+    let mut cloned = knots.clone();
+
+    cloned
+        .values_mut()
+        .flat_map(|knot| knot.stitches.values_mut())
+        .for_each(|stitch| stitch.root.detach_selection_texts());
+
+    cloned
+}
+
 pub fn get_num_visited(address: &Address, data: &FollowData) -> Result<u32, InternalError> {
     let (knot_name, stitch_name) = address.get_knot_and_stitch()?;
 
@@ -79,8 +94,8 @@ pub fn get_empty_knot_counts(knots: &KnotSet) -> HashMap<String, HashMap<String,
         .map(|(knot_name, knot)| {
             let empty = knot
                 .stitches
-                .iter()
-                .map(|(stitch_name, _)| (stitch_name.clone(), 0))
+                .keys()
+                .map(|stitch_name| (stitch_name.clone(), 0))
                 .collect();
 
             (knot_name.clone(), empty)
@@ -25,6 +25,13 @@
 //! If combined with `serde_support`, the random number generator state will be
 //! properly saved and restored along with the rest of the data.
 //!
+//! ## `ffi`
+//! Exposes stories over a C interface, declared in the header `include/inkling.h`
+//! which is generated when building with the feature. Saves are written as JSON,
+//! so this also enables `serde_support` and adds `serde_json` and (for building)
+//! `cbindgen` as dependencies. See the [`ffi`](ffi/index.html) module for how objects
+//! are owned.
+//!
 //! # Contributions
 //! I am a complete novice at designing frameworks which will fit into larger schemes.
 //! As such I have no real idea of best practices for interacting with an engine like this.
@@ -37,6 +44,8 @@
 
 mod consts;
 pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 mod follow;
 mod knot;
 mod line;
@@ -44,12 +53,22 @@ pub mod log;
 mod node;
 mod process;
 mod story;
+pub mod testing;
 mod utils;
 
-pub use error::InklingError;
+pub use error::{InklingError, LocalizationError, ReloadError};
 pub use line::Variable;
 pub use log::Logger;
 pub use story::{
-    copy_lines_into_string, read_story_from_string, Choice, Line, LineBuffer, Location, Prompt,
-    Story,
+    copy_lines_into_string, format_ink, read_story_from_string,
+    read_story_from_string_with_options, Breakpoint, Choice, ChoiceRef, ChoiceSetChange, Coverage,
+    CoverageItem, CoverageKind, CoverageReport, ExploreIssue, ExploreIssueKind, ExploreOptions,
+    ExploreReport, ExploreStrategy, GraphFormat, KnotCoverage, Line, LineBuffer, Location, Pause,
+    PauseReason, Prompt, ReachabilityOptions, ReachabilityReport, ReachedKnot, ReadOptions,
+    SavedPosition, StackFrame, Step, Story, StoryDiff, StoryStats, StringEntry, StringTable,
+    Symbol, SymbolKind, Tag, TraceEvent, Tracer, Transcript, TranscriptEntry, TranscriptEvent,
+    UnseenItem, VariableChange, Watch,
 };
+
+#[cfg(feature = "random")]
+pub use story::RandomSource;
@@ -1,23 +1,26 @@
 //! Content that alternates from a fixed set when processed.
 
 use crate::{
+    consts::{CYCLE_MARKER, ONCE_ONLY_MARKER, SEQUENCE_SEPARATOR, SHUFFLE_MARKER},
     error::{parse::validate::ValidationError, utils::MetaData},
     follow::FollowData,
     knot::Address,
     line::LineChunk,
     log::Logger,
-    story::validate::{ValidateContent, ValidationData},
+    story::{
+        validate::{ValidateContent, ValidationData},
+        TraceEvent,
+    },
 };
 
 #[cfg(not(feature = "random"))]
 use crate::log::Warning;
 
-#[cfg(feature = "random")]
-use rand::seq::SliceRandom;
-
 #[cfg(feature = "serde_support")]
 use serde::{Deserialize, Serialize};
 
+use std::fmt;
+
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
 /// Set of line content which can vary when it is processed.
@@ -40,10 +43,9 @@ pub struct Alternative {
 }
 
 impl Alternative {
-    #[allow(unused_variables)] // `data` only used when the `random` feature is enabled
     /// Get the next item index in the alternative sequence.
     pub fn get_next_index(&mut self, data: &mut FollowData) -> Option<usize> {
-        match self.kind {
+        let index = match self.kind {
             AlternativeKind::OnceOnly => self.active_inds.pop(),
             AlternativeKind::Sequence if self.active_inds.len() > 1 => self.active_inds.pop(),
             AlternativeKind::Sequence => self.active_inds.get(0).cloned(),
@@ -61,12 +63,20 @@ impl Alternative {
 
                 #[cfg(feature = "random")]
                 if self.is_first_item() {
-                    self.active_inds.shuffle(&mut data.rng.gen);
+                    data.rng.shuffle(&mut self.active_inds);
                 }
 
                 self.active_inds.pop()
             }
-        }
+        };
+
+        data.tracer.emit(|| TraceEvent::Alternative {
+            alternative: self.to_string(),
+            index,
+            num_items: self.items.len(),
+        });
+
+        index
     }
 
     #[allow(dead_code)]
@@ -139,6 +149,28 @@ impl ValidateContent for Alternative {
     }
 }
 
+impl fmt::Display for Alternative {
+    /// Write the alternative as `Ink` source, enclosed in braces.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{{")?;
+
+        match self.kind {
+            AlternativeKind::Cycle => write!(f, "{}", CYCLE_MARKER)?,
+            AlternativeKind::OnceOnly => write!(f, "{}", ONCE_ONLY_MARKER)?,
+            AlternativeKind::Sequence => (),
+            AlternativeKind::Shuffle => write!(f, "{}", SHUFFLE_MARKER)?,
+        }
+
+        let items = self
+            .items
+            .iter()
+            .map(|item| item.to_string())
+            .collect::<Vec<_>>();
+
+        write!(f, "{}}}", items.join(SEQUENCE_SEPARATOR))
+    }
+}
+
 /// Builder struct for `Alternative`.
 pub struct AlternativeBuilder {
     kind: AlternativeKind,
@@ -346,6 +378,7 @@ mod tests {
     mod shuffle {
         use super::*;
 
+        use rand::seq::SliceRandom;
         use std::collections::HashMap;
 
         // With 10 items, the probability of drawing a particular sequence is 1 / 10! = 2.75573-07
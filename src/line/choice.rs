@@ -1,14 +1,18 @@
 //! Choice which branches the story.
 
 use crate::{
+    consts::{DIVERT_MARKER, TAG_MARKER},
     error::{parse::validate::ValidationError, utils::MetaData},
     knot::Address,
-    line::{Condition, InternalLine},
+    line::{Condition, InternalLine, LineChunk},
     log::Logger,
     story::validate::{ValidateContent, ValidationData},
 };
 
-use std::sync::{Arc, Mutex};
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
 
 #[cfg(feature = "serde_support")]
 use serde::{Deserialize, Serialize};
@@ -55,6 +59,18 @@ pub struct InternalChoice {
     pub meta_data: MetaData,
 }
 
+impl InternalChoice {
+    /// Replace the shared selection text with an independent copy of its current state.
+    ///
+    /// Cloning an `InternalChoice` shares the selection text with the original. This is
+    /// used when a copy of the choice must not affect the original, such as when a copy
+    /// of the full story state is saved.
+    pub fn detach_selection_text(&mut self) {
+        let line = self.selection_text.lock().unwrap().clone();
+        self.selection_text = Arc::new(Mutex::new(line));
+    }
+}
+
 impl PartialEq for InternalChoice {
     fn eq(&self, rhs: &InternalChoice) -> bool {
         let left_line = (*self.selection_text.lock().unwrap()).clone();
@@ -105,6 +121,83 @@ impl ValidateContent for InternalChoice {
     }
 }
 
+impl fmt::Display for InternalChoice {
+    /// Write the condition and text of the choice as `Ink` source, without choice markers.
+    ///
+    /// Text which is only part of either the selection or display text is written
+    /// inside brackets.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(condition) = &self.condition {
+            write!(f, "{{{}}} ", condition)?;
+        }
+
+        let mut selection_text = self.selection_text.lock().unwrap().clone();
+        selection_text.tags.clear();
+
+        let text = get_choice_text(
+            selection_text.to_string().trim_end(),
+            &self.display_text.to_string(),
+        );
+
+        let text = text.trim_start();
+
+        // Leading braces are read as a condition unless escaped
+        if text.starts_with('{') {
+            write!(f, "\\")?;
+        }
+
+        write!(f, "{}", text)
+    }
+}
+
+/// Combine the selection and display text of a choice into a single line of text.
+///
+/// Text which both lines start with is written first, followed by the rest of the
+/// selection text inside brackets and the rest of the display text.
+fn get_choice_text(selection: &str, display: &str) -> String {
+    let index = get_shared_head_length(selection, display);
+
+    let (head, display_tail) = display.split_at(index);
+    let selection_tail = &selection[index..];
+
+    let is_same_text = selection_tail.is_empty() && {
+        let tail = display_tail.trim_start();
+        tail.is_empty() || tail.starts_with(DIVERT_MARKER) || tail.starts_with(TAG_MARKER)
+    };
+
+    if is_same_text {
+        display.to_string()
+    } else if display_tail.starts_with(DIVERT_MARKER) {
+        format!("{}[{}] {}", head, selection_tail, display_tail)
+    } else {
+        format!("{}[{}]{}", head, selection_tail, display_tail)
+    }
+}
+
+/// Get the byte length of the text which two strings start with, ending outside of braces.
+fn get_shared_head_length(lhs: &str, rhs: &str) -> usize {
+    let mut depth = 0;
+    let mut length = 0;
+
+    for ((i, c), other) in lhs.char_indices().zip(rhs.chars()) {
+        if c != other {
+            break;
+        }
+
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => (),
+        }
+
+        if depth == 0 {
+            length = i + c.len_utf8();
+        }
+    }
+
+    length
+}
+
 /// Builder for constructing an `InternalChoice`.
 ///
 /// For testing purposes this struct implement additional functions when
@@ -120,7 +213,7 @@ pub struct InternalChoiceBuilder {
     condition: Option<Condition>,
     is_fallback: bool,
     is_sticky: bool,
-    tags: Option<Vec<String>>,
+    tags: Option<Vec<LineChunk>>,
 }
 
 impl InternalChoiceBuilder {
@@ -230,7 +323,14 @@ impl InternalChoiceBuilder {
     #[cfg(test)]
     /// Set tags to the choice.
     pub fn with_tags(mut self, tags: &[String]) -> Self {
-        self.tags.replace(tags.to_vec());
+        use crate::line::LineChunkBuilder;
+
+        let tags = tags
+            .iter()
+            .map(|tag| LineChunkBuilder::from_string(tag).build())
+            .collect();
+
+        self.tags.replace(tags);
         self
     }
 }
@@ -36,13 +36,16 @@ use crate::{
         utils::MetaData,
     },
     knot::Address,
-    line::{Expression, Variable},
+    line::{
+        expression::{Operand, Operator},
+        Expression, Variable,
+    },
     log::Logger,
     process::check_condition,
     story::validate::{ValidateContent, ValidationData},
 };
 
-use std::{cmp::Ordering, error::Error};
+use std::{cmp::Ordering, error::Error, fmt};
 
 #[cfg(feature = "serde_support")]
 use crate::utils::OrderingDerive;
@@ -312,6 +315,89 @@ impl ValidateContent for StoryCondition {
     }
 }
 
+impl fmt::Display for Condition {
+    /// Write the condition as `Ink` source, without enclosing braces.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.root)?;
+
+        for item in &self.items {
+            match item {
+                AndOr::And(item) => write!(f, " and {}", item),
+                AndOr::Or(item) => write!(f, " or {}", item),
+            }?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for ConditionItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ConditionKind::Single(StoryCondition::Comparison {
+                lhs_variable,
+                rhs_variable,
+                ordering: Ordering::Equal,
+            }) if self.negate => write!(f, "{} != {}", lhs_variable, rhs_variable),
+            kind => {
+                if self.negate {
+                    write!(f, "not ")?;
+                }
+
+                write!(f, "{}", kind)
+            }
+        }
+    }
+}
+
+impl fmt::Display for ConditionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConditionKind::True => write!(f, "true"),
+            ConditionKind::False => write!(f, "false"),
+            ConditionKind::Nested(condition) => write!(f, "({})", condition),
+            ConditionKind::Single(condition) => write!(f, "{}", condition),
+        }
+    }
+}
+
+impl fmt::Display for StoryCondition {
+    /// Write the condition as `Ink` source.
+    ///
+    /// Conditions read from `<=` and `>=` statements are stored as `<` and `>` comparisons
+    /// with an adjusted right hand side, which is written back as the original statement.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StoryCondition::Comparison {
+                lhs_variable,
+                rhs_variable,
+                ordering,
+            } => {
+                let (operator, adjusted_operator, adjustment) = match ordering {
+                    Ordering::Equal => ("==", "==", 0),
+                    Ordering::Less => ("<", "<=", 1),
+                    Ordering::Greater => (">", ">=", -1),
+                };
+
+                match rhs_variable.tail.split_last() {
+                    Some(((Operator::Add, Operand::Variable(Variable::Int(value))), tail))
+                        if adjustment != 0 && *value == adjustment =>
+                    {
+                        let rhs_variable = Expression {
+                            head: rhs_variable.head.clone(),
+                            tail: tail.to_vec(),
+                        };
+
+                        write!(f, "{} {} {}", lhs_variable, adjusted_operator, rhs_variable)
+                    }
+                    _ => write!(f, "{} {} {}", lhs_variable, operator, rhs_variable),
+                }
+            }
+            StoryCondition::IsTrueLike { variable } => write!(f, "{}", variable.to_ink_source()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
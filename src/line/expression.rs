@@ -16,6 +16,8 @@ use crate::{
 #[cfg(feature = "serde_support")]
 use serde::{Deserialize, Serialize};
 
+use std::fmt;
+
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
 /// Single mathematical expression.
@@ -227,6 +229,68 @@ impl ValidateContent for Operand {
     }
 }
 
+impl fmt::Display for Expression {
+    /// Write the expression as `Ink` source.
+    ///
+    /// Groups of multiplied, divided or remainder terms are nested when the expression
+    /// is parsed. They are written without parenthesis, since the order of operations
+    /// nests them again when the source is parsed. A negated head term is written with
+    /// a leading minus.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let tail = match self.tail.split_first() {
+            Some(((Operator::Multiply, Operand::Variable(Variable::Int(-1))), tail)) => {
+                write!(f, "-{}", self.head)?;
+                tail
+            }
+            _ => {
+                write!(f, "{}", self.head)?;
+                self.tail.as_slice()
+            }
+        };
+
+        for (operator, operand) in tail {
+            write!(f, " {} {}", operator, operand)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operand::Nested(expression) if is_product_group(expression) => {
+                write!(f, "{}", expression)
+            }
+            Operand::Nested(expression) => write!(f, "({})", expression),
+            Operand::Variable(variable) => write!(f, "{}", variable.to_ink_source()),
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let operator = match self {
+            Operator::Add => "+",
+            Operator::Subtract => "-",
+            Operator::Multiply => "*",
+            Operator::Divide => "/",
+            Operator::Remainder => "%",
+        };
+
+        write!(f, "{}", operator)
+    }
+}
+
+/// Check whether an expression is a group of terms nested by the order of operations.
+fn is_product_group(expression: &Expression) -> bool {
+    !expression.tail.is_empty()
+        && expression.tail.iter().all(|(operator, _)| match operator {
+            Operator::Multiply | Operator::Divide | Operator::Remainder => true,
+            Operator::Add | Operator::Subtract => false,
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
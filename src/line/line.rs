@@ -1,6 +1,7 @@
 //! Structures for representing a single, whole line of `Ink` content.
 
 use crate::{
+    consts::{DIVERT_MARKER, GLUE_MARKER, TAG_MARKER},
     error::{parse::validate::ValidationError, utils::MetaData},
     knot::Address,
     line::{Alternative, Condition, Expression},
@@ -11,6 +12,8 @@ use crate::{
 #[cfg(feature = "serde_support")]
 use serde::{Deserialize, Serialize};
 
+use std::fmt::{self, Write};
+
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
 /// Representation of a single line of Ink content.
@@ -20,9 +23,16 @@ use serde::{Deserialize, Serialize};
 pub struct InternalLine {
     /// Root chunk of line content, which may possibly be nested into even finer parts.
     pub chunk: LineChunk,
-    /// Tags associated with the line. Will be given to the user along with the processed
-    /// line content as the story is followed.
-    pub tags: Vec<String>,
+    /// Tags associated with the line. Will be processed and given to the user along with
+    /// the processed line content as the story is followed.
+    ///
+    /// Tags are chunks of line content since they may contain embraced expressions or
+    /// alternatives, which are evaluated at runtime just like the text of the line.
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(deserialize_with = "deserialize_tags")
+    )]
+    pub tags: Vec<LineChunk>,
     /// Whether or not the line is glued to the previous line. Glue prohibits new lines
     /// to be added between lines, which is otherwise the default behavior when following
     /// the story.
@@ -78,6 +88,37 @@ pub enum Content {
     Text(String),
 }
 
+#[cfg(feature = "serde_support")]
+/// Deserialize the tags of a line.
+///
+/// Stories saved before tags could contain embraced content have tags of plain text.
+/// These are read as chunks with the same text.
+fn deserialize_tags<'de, D>(deserializer: D) -> Result<Vec<LineChunk>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum SavedTag {
+        Chunk(Box<LineChunk>),
+        Text(String),
+    }
+
+    let tags = Vec::<SavedTag>::deserialize(deserializer)?
+        .into_iter()
+        .map(|tag| match tag {
+            SavedTag::Chunk(chunk) => *chunk,
+            SavedTag::Text(text) => LineChunk {
+                condition: None,
+                items: vec![Content::Text(text)],
+                else_items: Vec::new(),
+            },
+        })
+        .collect();
+
+    Ok(tags)
+}
+
 impl InternalLine {
     /// Create the line from a finished chunk of line content.
     ///
@@ -128,8 +169,14 @@ impl ValidateContent for InternalLine {
         _: &MetaData,
         data: &ValidationData,
     ) {
+        let meta_data = &self.meta_data;
+
         self.chunk
-            .validate(error, log, current_location, &self.meta_data, data);
+            .validate(error, log, current_location, meta_data, data);
+
+        self.tags
+            .iter_mut()
+            .for_each(|tag| tag.validate(error, log, current_location, meta_data, data));
     }
 }
 
@@ -178,6 +225,94 @@ impl ValidateContent for Content {
     }
 }
 
+impl fmt::Display for InternalLine {
+    /// Write the line as `Ink` source.
+    ///
+    /// Whitespace before a divert at the end of the line is normalized to a single space,
+    /// and tags are written after the divert.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buffer = String::new();
+
+        if self.glue_begin {
+            buffer.push_str(GLUE_MARKER);
+        }
+
+        buffer.push_str(&self.chunk.to_string());
+
+        let ends_with_divert = matches!(self.chunk.items.last(), Some(Content::Divert(..)));
+
+        if self.glue_end && !ends_with_divert {
+            buffer.push_str(GLUE_MARKER);
+        }
+
+        if !self.tags.is_empty() {
+            trim_end(&mut buffer);
+        }
+
+        for tag in &self.tags {
+            write!(buffer, " {} {}", TAG_MARKER, tag)?;
+        }
+
+        write!(f, "{}", buffer)
+    }
+}
+
+impl fmt::Display for LineChunk {
+    /// Write the chunk as `Ink` source.
+    ///
+    /// Chunks with a condition are written as conditional content enclosed in braces.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.condition {
+            Some(condition) if self.else_items.is_empty() => {
+                write!(f, "{{{}:{}}}", condition, write_content(&self.items))
+            }
+            Some(condition) => write!(
+                f,
+                "{{{}:{}|{}}}",
+                condition,
+                write_content(&self.items),
+                write_content(&self.else_items)
+            ),
+            None => write!(f, "{}", write_content(&self.items)),
+        }
+    }
+}
+
+/// Write a set of line content as `Ink` source.
+fn write_content(items: &[Content]) -> String {
+    let mut buffer = String::new();
+
+    for item in items {
+        match item {
+            Content::Alternative(alternative) => write!(buffer, "{}", alternative).unwrap(),
+            Content::Divert(address) => {
+                trim_end(&mut buffer);
+
+                if !buffer.is_empty() {
+                    buffer.push(' ');
+                }
+
+                write!(buffer, "{} {}", DIVERT_MARKER, address).unwrap();
+            }
+            Content::Empty => {
+                if !buffer.is_empty() && !buffer.ends_with(char::is_whitespace) {
+                    buffer.push(' ');
+                }
+            }
+            Content::Expression(expression) => write!(buffer, "{{{}}}", expression).unwrap(),
+            Content::Nested(chunk) => write!(buffer, "{}", chunk).unwrap(),
+            Content::Text(text) => buffer.push_str(text),
+        }
+    }
+
+    buffer
+}
+
+fn trim_end(buffer: &mut String) {
+    let length = buffer.trim_end().len();
+    buffer.truncate(length);
+}
+
 #[cfg(test)]
 pub mod builders {
     //! Builders for line structures.
@@ -187,7 +322,7 @@ pub mod builders {
     /// Builder for constructing an `InternalLine`.
     pub struct InternalLineBuilder {
         chunk: LineChunk,
-        tags: Vec<String>,
+        tags: Vec<LineChunk>,
         glue_begin: bool,
         glue_end: bool,
     }
@@ -210,7 +345,7 @@ pub mod builders {
                 tags: self.tags,
                 glue_begin: self.glue_begin,
                 glue_end: self.glue_end,
-                meta_data: MetaData { line_index: 0 },
+                meta_data: MetaData::from(0),
             }
         }
     }
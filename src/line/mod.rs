@@ -33,5 +33,7 @@ pub(crate) use expression::{evaluate_expression, Expression};
 #[cfg(test)]
 pub(crate) use line::builders::LineChunkBuilder;
 pub(crate) use line::{Content, InternalLine, LineChunk};
-pub(crate) use parse::{parse_line, parse_variable, ParsedLineKind};
+pub(crate) use parse::{
+    parse_condition, parse_expression, parse_line, parse_variable, ParsedLineKind,
+};
 pub use variable::Variable;
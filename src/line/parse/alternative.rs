@@ -129,4 +129,25 @@ mod tests {
     fn only_marker_does_not_fail() {
         assert!(parse_alternative("~").is_ok());
     }
+
+    #[test]
+    fn written_alternatives_parse_into_the_same_alternative() {
+        let alternatives = &[
+            "{One|Two}",
+            "{&One|Two|}",
+            "{!One|{a}}",
+            "{~One|Two -> knot}",
+        ];
+
+        for content in alternatives {
+            let alternative = parse_alternative(&content[1..content.len() - 1]).unwrap();
+            let written = alternative.to_string();
+
+            assert_eq!(&written, content);
+            assert_eq!(
+                parse_alternative(&written[1..written.len() - 1]).unwrap(),
+                alternative
+            );
+        }
+    }
 }
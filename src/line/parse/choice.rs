@@ -40,8 +40,9 @@ fn parse_choice_data(content: &str, meta_data: &MetaData) -> Result<InternalChoi
 
     let (selection_text_line, display_text_line) = parse_choice_line_variants(&buffer)?;
 
+    // The selection text ends before the divert, along with the whitespace before it
     let (without_divert, _) = split_at_divert_marker(&selection_text_line);
-    let selection_text = parse_internal_line(without_divert, meta_data)?;
+    let selection_text = parse_internal_line(without_divert.trim_end(), meta_data)?;
 
     let is_fallback = is_choice_fallback(&selection_text);
 
@@ -362,4 +363,42 @@ pub(crate) mod tests {
     fn parsing_choice_line_variants_return_error_if_brackets_are_reversed() {
         assert!(parse_choice_line_variants("Hello][, World!").is_err());
     }
+
+    #[test]
+    fn written_choices_parse_into_the_same_choice() {
+        let choices = &[
+            "Choice",
+            "[Selection only] Display text.",
+            "Linger[.] for a while.",
+            "Choice -> knot",
+            "[Leave] -> END",
+            "{a > 1} {b} Guarded choice",
+            "\\{a} Escaped braces",
+            "Tagged choice # tag",
+        ];
+
+        for content in choices {
+            let choice = InternalChoice::from_string(content);
+            let written = choice.to_string();
+
+            assert_eq!(InternalChoice::from_string(&written), choice);
+            assert_eq!(InternalChoice::from_string(&written).to_string(), written);
+        }
+    }
+
+    #[test]
+    fn written_choices_keep_their_selection_and_display_text() {
+        assert_eq!(
+            &InternalChoice::from_string("Linger[.] for a while.").to_string(),
+            "Linger[.] for a while."
+        );
+        assert_eq!(
+            &InternalChoice::from_string("[Leave]  ->  END").to_string(),
+            "[Leave] -> END"
+        );
+        assert_eq!(
+            &InternalChoice::from_string("{a > 1}Guarded").to_string(),
+            "{a > 1} Guarded"
+        );
+    }
 }
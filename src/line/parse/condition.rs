@@ -87,7 +87,7 @@ pub fn parse_choice_condition(line: &mut String) -> Result<Option<Condition>, Li
 /// be `&&` or `||` respectively), since chained conditions need them. These splits will
 /// not be done within enclosed parenthesis: all grouped conditions inside those will be
 /// treated as whole.
-fn parse_condition(content: &str) -> Result<Condition, ConditionError> {
+pub fn parse_condition(content: &str) -> Result<Condition, ConditionError> {
     let mut buffer = content.to_string();
 
     let mut items: Vec<(Link, ConditionItem)> = Vec::new();
@@ -1163,4 +1163,25 @@ mod tests {
             validate_items(&[(Link::Blank, ()), (Link::Blank, ()), (Link::Or, ())], "").is_err()
         );
     }
+
+    #[test]
+    fn written_conditions_parse_into_the_same_condition() {
+        let conditions = &[
+            "knot",
+            "not knot",
+            "a == 1 and b != \"word\"",
+            "a < 1 or a <= 2 or a > 3 or a >= 4.5",
+            "(a > 1 or true) and not (b or false)",
+        ];
+
+        for content in conditions {
+            let (condition, _, _) = parse_line_condition(&format!("{}: text", content)).unwrap();
+            let written = condition.to_string();
+
+            assert_eq!(&written, content);
+
+            let (reparsed, _, _) = parse_line_condition(&format!("{}: text", written)).unwrap();
+            assert_eq!(reparsed, condition);
+        }
+    }
 }
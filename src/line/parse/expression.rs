@@ -539,4 +539,24 @@ mod tests {
             &["\"one\" ", "+ word", "-with", "-dash\""]
         );
     }
+
+    #[test]
+    fn written_expressions_parse_into_the_same_expression() {
+        let expressions = &[
+            "1",
+            "-a",
+            "a + 2.5 - \"word\"",
+            "a * 2 + b / 3 - c % 4",
+            "-(a + 1) * b",
+            "(a + b) * (c - 1)",
+        ];
+
+        for content in expressions {
+            let expression = parse_expression(content).unwrap();
+            let written = expression.to_string();
+
+            assert_eq!(&written, content);
+            assert_eq!(parse_expression(&written).unwrap(), expression);
+        }
+    }
 }
@@ -60,10 +60,17 @@ pub fn parse_line(content: &str, meta_data: &MetaData) -> Result<ParsedLineKind,
     } else {
         parse_internal_line(content, meta_data).map(|line| ParsedLineKind::Line(line))
     }
-    .map_err(|kind| LineError {
-        line: content.to_string(),
-        kind,
-        meta_data: meta_data.clone(),
+    .map_err(|kind| {
+        let meta_data = match kind.find_span(content) {
+            Some(span) => meta_data.narrow_to(content, span),
+            None => meta_data.clone(),
+        };
+
+        LineError {
+            line: content.to_string(),
+            kind,
+            meta_data,
+        }
     })
 }
 
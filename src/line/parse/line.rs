@@ -31,7 +31,7 @@ pub fn parse_internal_line(
 ) -> Result<InternalLine, LineErrorKind> {
     let mut buffer = content.to_string();
 
-    let tags = parse_tags(&mut buffer);
+    let tags = parse_tags(&mut buffer)?;
     let divert = split_off_end_divert(&mut buffer)?;
 
     let (glue_begin, glue_end) = parse_line_glue(&mut buffer, divert.is_some());
@@ -155,28 +155,57 @@ fn parse_line_glue(line: &mut String, has_divert: bool) -> (bool, bool) {
 }
 
 /// Split any found tags off the given line and return them separately.
-fn parse_tags(line: &mut String) -> Vec<String> {
-    match line.find(TAG_MARKER) {
-        Some(i) => {
-            let part = line.split_off(i);
-
-            part.trim_matches(TAG_MARKER)
-                .split(TAG_MARKER)
-                .map(|tag| tag.trim().to_string())
-                .collect::<Vec<_>>()
-        }
-        None => Vec::new(),
+///
+/// Tag markers inside embraced content are ignored, which lets tags contain expressions
+/// and alternatives. Whitespace between the line and its tags is removed.
+fn parse_tags(line: &mut String) -> Result<Vec<LineChunk>, LineErrorKind> {
+    let tag_marker = TAG_MARKER.to_string();
+    let head_length = split_line_at_separator_braces(line, &tag_marker, Some(1))?[0].len();
+
+    if head_length == line.len() {
+        return Ok(Vec::new());
     }
+
+    let part = line.split_off(head_length);
+    line.truncate(line.trim_end().len());
+
+    split_line_at_separator_braces(part.trim_matches(TAG_MARKER), &tag_marker, None)?
+        .into_iter()
+        .map(|tag| parse_tag(tag.trim()))
+        .collect()
+}
+
+/// Parse the content of a single tag into a `LineChunk`.
+///
+/// Embraced content is parsed as in regular lines. Unlike lines, text parts of a tag
+/// are kept as they are: they cannot contain diverts or glue.
+fn parse_tag(content: &str) -> Result<LineChunk, LineErrorKind> {
+    let items = split_line_into_groups_braces(content)?
+        .into_iter()
+        .map(|group| match group {
+            LinePart::Text(part) => Ok(Content::Text(part.to_string())),
+            LinePart::Embraced(text) => parse_embraced_line(text),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(LineChunk {
+        condition: None,
+        items,
+        else_items: Vec::new(),
+    })
 }
 
 /// Split diverts off the given line and return it separately if found.
+///
+/// The line is left with a single whitespace at its end, separating it from the content
+/// that it is glued to by the divert.
 fn split_off_end_divert(line: &mut String) -> Result<Option<String>, LineErrorKind> {
     let splits = split_line_at_separator_braces(&line, DIVERT_MARKER, None)?;
 
     match splits.len() {
         0 | 1 => Ok(None),
         2 => {
-            let head_length = splits.get(0).unwrap().len();
+            let head_length = splits.get(0).unwrap().trim_end().len();
 
             let address = validate_address(splits[1].trim())?;
             line.truncate(head_length);
@@ -195,10 +224,9 @@ fn split_off_end_divert(line: &mut String) -> Result<Option<String>, LineErrorKi
 pub fn validate_address(line: &str) -> Result<String, LineErrorKind> {
     if line.contains(|c: char| c.is_whitespace()) {
         let tail = line
-            .splitn(2, |c: char| c.is_whitespace())
-            .skip(1)
-            .next()
+            .split_once(|c: char| c.is_whitespace())
             .unwrap()
+            .1
             .to_string();
 
         Err(LineErrorKind::ExpectedEndOfLine { tail })
@@ -427,19 +455,53 @@ mod tests {
 
     #[test]
     fn tags_are_split_off_from_string_and_added_to_full_line_when_parsed() {
-        let line = parse_internal_line("Hello, World! # tag one # tag two", &().into()).unwrap();
+        let mut line =
+            parse_internal_line("Hello, World! # tag one # tag two", &().into()).unwrap();
 
         assert_eq!(line.tags.len(), 2);
-        assert_eq!(&line.tags[0], "tag one");
-        assert_eq!(&line.tags[1], "tag two");
+        assert_eq!(get_processed_chunk(&mut line.tags[0]), "tag one");
+        assert_eq!(get_processed_chunk(&mut line.tags[1]), "tag two");
 
         assert_eq!(line.chunk.items.len(), 1);
         assert_eq!(
             line.chunk.items[0],
-            Content::Text("Hello, World! ".to_string())
+            Content::Text("Hello, World!".to_string())
         );
     }
 
+    #[test]
+    fn tags_can_contain_embraced_alternatives_and_expressions() {
+        let line = parse_internal_line("Hello! # mood: {&happy|sad} # {name}", &().into()).unwrap();
+
+        assert_eq!(line.tags.len(), 2);
+
+        match &line.tags[0].items[1] {
+            Content::Alternative(..) => (),
+            other => panic!("expected `Content::Alternative` but got {:?}", other),
+        }
+
+        match &line.tags[1].items[0] {
+            Content::Expression(..) => (),
+            other => panic!("expected `Content::Expression` but got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn tag_markers_inside_braces_do_not_split_tags() {
+        let mut line = parse_internal_line("Hello! # {&one#|two}", &().into()).unwrap();
+
+        assert_eq!(line.tags.len(), 1);
+        assert_eq!(get_processed_chunk(&mut line.tags[0]), "one#");
+    }
+
+    #[test]
+    fn text_in_tags_is_not_parsed_for_diverts() {
+        let mut line = parse_internal_line("Hello! # mood -> angry", &().into()).unwrap();
+
+        assert_eq!(line.chunk.items.len(), 1);
+        assert_eq!(get_processed_chunk(&mut line.tags[0]), "mood -> angry");
+    }
+
     #[test]
     fn parse_embraced_line_as_alternative() {
         match parse_embraced_line("One | Two").unwrap() {
@@ -258,7 +258,7 @@ fn get_brace_level_of_line(
             prev.replace(byte);
 
             if open == close {
-                *brace_level = *brace_level % 2;
+                *brace_level %= 2;
             }
 
             Some(Ok(*brace_level))
@@ -508,7 +508,7 @@ mod tests {
 
     #[test]
     fn brace_level_counting_works_for_empty_line() {
-        assert_eq!(get_brace_level_of_line("", '{', '}').unwrap(), &[]);
+        assert_eq!(get_brace_level_of_line("", '{', '}').unwrap(), &[] as &[u8]);
     }
 
     #[test]
@@ -12,8 +12,9 @@ use crate::{
     log::Logger,
     story::{
         validate::{ValidateContent, ValidationData},
-        Location,
+        Location, TraceEvent,
     },
+    utils::get_closest_name,
 };
 
 #[cfg(feature = "serde_support")]
@@ -184,7 +185,7 @@ impl Variable {
     /// ```
     pub fn to_string_unchecked(&self) -> String {
         match &self {
-            Variable::Divert(address) => format!("-> {}", address.to_string()),
+            Variable::Divert(address) => format!("-> {}", address),
             // `Address` variants are fully internal and should not be possible to be operated
             // on by a caller. As a fallback we return the address as a string.
             Variable::Address(address) => address.to_string(),
@@ -192,6 +193,25 @@ impl Variable {
         }
     }
 
+    /// Get a string representation of the variable as it is written in `Ink` source.
+    ///
+    /// Unlike the printed value, strings are quoted and floats always have a decimal point.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::Variable;
+    /// assert_eq!(&Variable::from("Quinn").to_ink_source(), "\"Quinn\"");
+    /// assert_eq!(&Variable::from(3.0).to_ink_source(), "3.0");
+    /// assert_eq!(&Variable::from(true).to_ink_source(), "true");
+    /// ```
+    pub fn to_ink_source(&self) -> String {
+        match &self {
+            Variable::Float(value) => format!("{:?}", value),
+            Variable::String(content) => format!("\"{}\"", content),
+            _ => self.to_string_unchecked(),
+        }
+    }
+
     /// Get the target `Location` of a `Variable::Divert` variant.
     ///
     /// `Variables` which are not of `Divert` type yield `None`.
@@ -238,8 +258,12 @@ impl Variable {
                 Address::Validated(AddressKind::GlobalVariable { name }) => data
                     .variables
                     .get(name)
-                    .ok_or(InklingError::InvalidVariable {
+                    .ok_or_else(|| InklingError::InvalidVariable {
                         name: name.to_string(),
+                        suggestion: get_closest_name(
+                            name,
+                            data.variables.keys().map(String::as_str),
+                        ),
                     })
                     .and_then(|variable_info| variable_info.variable.to_string_internal(data)),
                 other => Err(InternalError::UseOfUnvalidatedAddress {
@@ -269,7 +293,7 @@ impl Variable {
             Variable::Float(value) => format!("{}", value),
             Variable::Int(value) => format!("{}", value),
             Variable::String(string) => format!("\"{}\"", string),
-            Variable::Divert(address) => format!("-> {}", address.to_string()),
+            Variable::Divert(address) => format!("-> {}", address),
         }
     }
 
@@ -292,10 +316,23 @@ impl Variable {
                 Address::Validated(AddressKind::GlobalVariable { name }) => data
                     .variables
                     .get(name)
-                    .ok_or(InklingError::InvalidVariable {
+                    .ok_or_else(|| InklingError::InvalidVariable {
                         name: name.to_string(),
+                        suggestion: get_closest_name(
+                            name,
+                            data.variables.keys().map(String::as_str),
+                        ),
                     })
-                    .and_then(|info| info.variable.as_value(&data)),
+                    .and_then(|info| {
+                        let value = info.variable.as_value(data)?;
+
+                        data.tracer.emit(|| TraceEvent::ReadVariable {
+                            name: name.to_string(),
+                            value: value.clone(),
+                        });
+
+                        Ok(value)
+                    }),
                 other => Err(InternalError::UseOfUnvalidatedAddress {
                     address: other.clone(),
                 }
@@ -1356,4 +1393,27 @@ mod tests {
             .is_none());
         assert!(Variable::Address(address).get_location().is_none());
     }
+
+    #[test]
+    fn variables_are_written_as_ink_source_which_parses_into_the_same_variable() {
+        use crate::line::parse_variable;
+
+        let address = Address::from_parts_unchecked("tripoli", Some("cinema"));
+
+        let variables = vec![
+            Variable::Bool(true),
+            Variable::Float(3.0),
+            Variable::Int(-5),
+            Variable::String("A String".to_string()),
+            Variable::Divert(address),
+        ];
+
+        assert_eq!(&Variable::Float(3.0).to_ink_source(), "3.0");
+        assert_eq!(&Variable::String("A".to_string()).to_ink_source(), "\"A\"");
+
+        for variable in variables {
+            let parsed = parse_variable(&variable.to_ink_source()).unwrap();
+            assert_eq!(parsed.to_ink_source(), variable.to_ink_source());
+        }
+    }
 }
@@ -1,9 +1,11 @@
 use crate::{
     consts::TODO_COMMENT_MARKER,
-    error::MetaData,
-    log::{LogMessage, MessageKind, Warning},
+    error::{Diagnostic, MetaData},
+    log::{LogMessage, MessageKind, RuntimeMessage, Warning},
 };
 
+use std::collections::HashSet;
+
 #[cfg(feature = "serde_support")]
 use serde::{Deserialize, Serialize};
 
@@ -12,9 +14,15 @@ use serde::{Deserialize, Serialize};
 #[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
 /// Log of warnings and to-do comments of the current script.
 ///
-/// Use `Logger::iter()` to iterate over the warning messages. All messages implement `Display`
+/// Use `Logger::iter()` to iterate over the messages. All messages implement `Display`
 /// which means that printing the errors to any sort of string buffer or file is trivial.
 ///
+/// Messages from following the story, such as a fallback choice being taken, are added
+/// to a separate list as the story is resumed. Take them with
+/// [`drain_runtime_messages`][crate::log::Logger::drain_runtime_messages()] to forward
+/// them to a console without losing the warnings from reading the script. They are not
+/// saved with the story.
+///
 /// # Examples
 /// ```
 /// # use inkling::read_story_from_string;
@@ -30,13 +38,54 @@ pub struct Logger {
     pub todo_comments: Vec<LogMessage>,
     /// Non-fatal errors and incompatibilities.
     pub warnings: Vec<LogMessage>,
+    /// Messages from following the story, in the order that they were added.
+    #[cfg_attr(feature = "serde_support", serde(skip))]
+    pub runtime_messages: Vec<LogMessage>,
+    /// Knots or stitches and lines of once-only sequences which have been noted as exhausted.
+    #[cfg_attr(feature = "serde_support", serde(skip))]
+    exhausted_alternatives: HashSet<(String, u32)>,
 }
 
 #[allow(dead_code)]
 impl Logger {
     /// Return whether or not the log has any entries.
     pub fn has_entries(&self) -> bool {
-        !self.todo_comments.is_empty() || !self.warnings.is_empty()
+        !self.todo_comments.is_empty()
+            || !self.warnings.is_empty()
+            || !self.runtime_messages.is_empty()
+    }
+
+    /// Remove and return the messages from following the story, in the order that they
+    /// were added.
+    ///
+    /// To-do comments and warnings from reading the script are kept.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{error::Diagnostic, read_story_from_string};
+    /// let content = "\
+    /// *   [] You wait for something to happen.
+    /// ";
+    ///
+    /// let mut story = read_story_from_string(content).unwrap();
+    /// story.resume(&mut Vec::new()).unwrap();
+    ///
+    /// let codes = story
+    ///     .log
+    ///     .drain_runtime_messages()
+    ///     .map(|message| Diagnostic::from(&message).code)
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(&codes, &["N0002"]);
+    /// assert!(story.log.runtime_messages.is_empty());
+    /// ```
+    pub fn drain_runtime_messages(&mut self) -> std::vec::Drain<'_, LogMessage> {
+        self.runtime_messages.drain(..)
+    }
+
+    /// Get a diagnostic with a stable code for every message, in the order of their lines.
+    pub fn get_diagnostics(&self) -> Vec<Diagnostic> {
+        self.iter().map(Diagnostic::from).collect()
     }
 
     pub(crate) fn add_todo(&mut self, comment: &str, meta_data: &MetaData) {
@@ -51,6 +100,42 @@ impl Logger {
             .push(LogMessage::with_kind(message, meta_data));
     }
 
+    /// Replace the to-do comments and warnings with those from reading a new script.
+    ///
+    /// Runtime messages are kept, followed by any from the other log. Exhausted sequences
+    /// are noted again, since the lines that they were on may have changed.
+    pub(crate) fn replace_script_messages(&mut self, mut other: Logger) {
+        self.todo_comments = other.todo_comments;
+        self.warnings = other.warnings;
+        self.runtime_messages.append(&mut other.runtime_messages);
+        self.exhausted_alternatives.clear();
+    }
+
+    /// Move messages from following the story to the log.
+    ///
+    /// A once-only sequence which has run out of items is only noted the first time, not
+    /// every time that its line is followed after that.
+    pub(crate) fn add_runtime_messages(&mut self, messages: &mut Vec<LogMessage>) {
+        for message in messages.drain(..) {
+            if let MessageKind::Runtime(RuntimeMessage::AlternativeExhausted) = message.message {
+                let location = message
+                    .location
+                    .as_ref()
+                    .map(|location| location.to_string())
+                    .unwrap_or_default();
+
+                if !self
+                    .exhausted_alternatives
+                    .insert((location, message.meta_data.line()))
+                {
+                    continue;
+                }
+            }
+
+            self.runtime_messages.push(message);
+        }
+    }
+
     pub(crate) fn add_warning(&mut self, warning: Warning, meta_data: &MetaData) {
         self.warnings.push(LogMessage::with_kind(
             MessageKind::Warning(warning),
@@ -66,11 +151,14 @@ impl Logger {
 impl Logger {
     /// Iterate over the logged messages.
     ///
-    /// The iterator visits the messages in the order of their line numbers.
-    pub fn iter(&self) -> LoggerIter {
+    /// The iterator visits the to-do comments and warnings in the order of their line
+    /// numbers, followed by the messages from following the story in the order that
+    /// they were added.
+    pub fn iter(&self) -> LoggerIter<'_> {
         LoggerIter {
             todo_comments: self.todo_comments.iter().peekable(),
             warnings: self.warnings.iter().peekable(),
+            runtime_messages: self.runtime_messages.iter(),
         }
     }
 }
@@ -83,6 +171,7 @@ impl IntoIterator for Logger {
         LoggerIntoIter {
             todo_comments: self.todo_comments.into_iter().peekable(),
             warnings: self.warnings.into_iter().peekable(),
+            runtime_messages: self.runtime_messages.into_iter(),
         }
     }
 }
@@ -90,6 +179,7 @@ impl IntoIterator for Logger {
 pub struct LoggerIntoIter {
     todo_comments: std::iter::Peekable<std::vec::IntoIter<LogMessage>>,
     warnings: std::iter::Peekable<std::vec::IntoIter<LogMessage>>,
+    runtime_messages: std::vec::IntoIter<LogMessage>,
 }
 
 impl Iterator for LoggerIntoIter {
@@ -104,7 +194,11 @@ impl Iterator for LoggerIntoIter {
                     self.warnings.next()
                 }
             }
-            _ => self.todo_comments.next().or(self.warnings.next()),
+            _ => self
+                .todo_comments
+                .next()
+                .or_else(|| self.warnings.next())
+                .or_else(|| self.runtime_messages.next()),
         }
     }
 }
@@ -112,6 +206,7 @@ impl Iterator for LoggerIntoIter {
 pub struct LoggerIter<'a> {
     todo_comments: std::iter::Peekable<std::slice::Iter<'a, LogMessage>>,
     warnings: std::iter::Peekable<std::slice::Iter<'a, LogMessage>>,
+    runtime_messages: std::slice::Iter<'a, LogMessage>,
 }
 
 impl<'a> Iterator for LoggerIter<'a> {
@@ -126,7 +221,11 @@ impl<'a> Iterator for LoggerIter<'a> {
                     self.warnings.next()
                 }
             }
-            _ => self.todo_comments.next().or(self.warnings.next()),
+            _ => self
+                .todo_comments
+                .next()
+                .or_else(|| self.warnings.next())
+                .or_else(|| self.runtime_messages.next()),
         }
     }
 }
@@ -135,6 +234,8 @@ impl<'a> Iterator for LoggerIter<'a> {
 mod tests {
     use super::*;
 
+    use crate::story::Location;
+
     #[test]
     fn iterating_through_log_yields_todo_comments_in_order() {
         let mut logger = Logger::default();
@@ -222,4 +323,72 @@ mod tests {
         logger.add_warning(Warning::ShuffleSequenceNoRandom, &MetaData::from(0));
         assert!(logger.has_entries());
     }
+
+    fn get_runtime_message(message: RuntimeMessage, line: usize, location: &str) -> LogMessage {
+        let mut message =
+            LogMessage::with_kind(MessageKind::Runtime(message), &MetaData::from(line));
+        message.location = Some(Location::from(location));
+
+        message
+    }
+
+    #[test]
+    fn iterating_through_log_yields_runtime_messages_after_comments_and_warnings() {
+        let mut logger = Logger::default();
+
+        logger.add_runtime_messages(&mut vec![get_runtime_message(
+            RuntimeMessage::FallbackChoice,
+            0,
+            "tavern",
+        )]);
+        logger.add_todo("Comment 1", &MetaData::from(2));
+        logger.add_warning(Warning::ShuffleSequenceNoRandom, &MetaData::from(1));
+
+        let iter_messages = logger.iter().cloned().collect::<Vec<_>>();
+
+        assert_eq!(iter_messages.len(), 3);
+        assert_eq!(&iter_messages[2], &logger.runtime_messages[0]);
+        assert_eq!(logger.into_iter().collect::<Vec<_>>(), iter_messages);
+    }
+
+    #[test]
+    fn logger_has_entries_if_it_has_runtime_messages_to_iterate_over() {
+        let mut logger = Logger::default();
+
+        logger.add_runtime_messages(&mut vec![get_runtime_message(
+            RuntimeMessage::FallbackChoice,
+            0,
+            "tavern",
+        )]);
+
+        assert!(logger.has_entries());
+        assert_eq!(logger.iter().count(), 1);
+    }
+
+    #[test]
+    fn exhausted_alternatives_are_only_noted_once_per_location_and_line() {
+        let mut logger = Logger::default();
+
+        let mut messages = vec![
+            get_runtime_message(RuntimeMessage::AlternativeExhausted, 3, "tavern"),
+            get_runtime_message(RuntimeMessage::AlternativeExhausted, 3, "tavern"),
+            get_runtime_message(RuntimeMessage::AlternativeExhausted, 4, "tavern"),
+            get_runtime_message(RuntimeMessage::AlternativeExhausted, 3, "tavern.bar"),
+            get_runtime_message(RuntimeMessage::FallbackChoice, 5, "tavern"),
+            get_runtime_message(RuntimeMessage::FallbackChoice, 5, "tavern"),
+        ];
+
+        logger.add_runtime_messages(&mut messages);
+        assert!(messages.is_empty());
+        assert_eq!(logger.runtime_messages.len(), 5);
+
+        logger.drain_runtime_messages();
+        logger.add_runtime_messages(&mut vec![get_runtime_message(
+            RuntimeMessage::AlternativeExhausted,
+            3,
+            "tavern",
+        )]);
+
+        assert!(logger.runtime_messages.is_empty());
+    }
 }
@@ -1,4 +1,7 @@
-use crate::error::utils::MetaData;
+use crate::{
+    error::{utils::MetaData, Severity},
+    story::Location,
+};
 use std::fmt;
 
 #[cfg(feature = "serde_support")]
@@ -13,6 +16,9 @@ pub struct LogMessage {
     pub message: MessageKind,
     /// Information of where the message originated from.
     pub meta_data: MetaData,
+    /// Knot and stitch that the story was in, for messages from following the story.
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub location: Option<Location>,
 }
 
 impl LogMessage {
@@ -20,6 +26,19 @@ impl LogMessage {
         LogMessage {
             message,
             meta_data: meta_data.clone(),
+            location: None,
+        }
+    }
+
+    /// Get the severity of the message.
+    ///
+    /// Warnings are non-fatal errors in the script. To-do comments and messages from
+    /// following the story are notes: they describe expected behavior which may still
+    /// explain content that is not shown.
+    pub fn severity(&self) -> Severity {
+        match &self.message {
+            MessageKind::Warning(..) => Severity::Warning,
+            MessageKind::Todo(..) | MessageKind::Runtime(..) => Severity::Note,
         }
     }
 }
@@ -33,6 +52,8 @@ pub enum MessageKind {
     Todo(String),
     /// Non-fatal error or incompatibility.
     Warning(Warning),
+    /// Event from following the story which may explain unexpected content.
+    Runtime(RuntimeMessage),
 }
 
 #[derive(Clone, Debug)]
@@ -42,6 +63,41 @@ pub enum MessageKind {
 pub enum Warning {
     /// Found a shuffle sequence but the `random` feature is not enabled.
     ShuffleSequenceNoRandom,
+    /// Knot is never diverted to.
+    UnreachableKnot { name: String },
+    /// Stitch is not the default stitch of its knot and is never diverted to.
+    UnreachableStitch { knot: String, stitch: String },
+    /// Global variable is never read in the story.
+    UnusedVariable { name: String },
+    /// Condition does not depend on the story state and always has the same value.
+    ConstantCondition { value: bool },
+    /// Set of choices can be revisited but has no sticky or fallback choices.
+    ///
+    /// Once every choice in the set has been made the story will run out of choices
+    /// when returning to it.
+    ChoicesCanRunOut,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+#[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
+/// Event from following the story which is logged as it happens.
+pub enum RuntimeMessage {
+    /// No regular choices were available, so a fallback choice was followed.
+    FallbackChoice,
+    /// Once-only alternative sequence has shown all of its items and showed nothing.
+    AlternativeExhausted,
+    /// Set of choices which has sticky choices was offered again.
+    StickyChoicesRevisited {
+        /// Number of times that the set has been offered, counted from the choices which
+        /// have been made from it.
+        num_offered: u32,
+    },
+    /// Story was ended by a divert to `END` or `DONE` from inside of a choice.
+    EndInNestedWeave {
+        /// Number of nested choices that the divert was in.
+        depth: usize,
+    },
 }
 
 impl fmt::Display for LogMessage {
@@ -49,9 +105,17 @@ impl fmt::Display for LogMessage {
         let kind = match &self.message {
             MessageKind::Todo(_) => "TODO",
             MessageKind::Warning(_) => "WARNING",
+            MessageKind::Runtime(_) => "NOTE",
         };
 
-        write!(f, "[{}] {}: {}", self.meta_data, kind, self.message)
+        match &self.location {
+            Some(location) => write!(
+                f,
+                "[{} in '{}'] {}: {}",
+                self.meta_data, location, kind, self.message
+            ),
+            None => write!(f, "[{}] {}: {}", self.meta_data, kind, self.message),
+        }
     }
 }
 
@@ -62,6 +126,35 @@ impl fmt::Display for MessageKind {
         match self {
             Todo(comment) => write!(f, "{}", comment),
             Warning(warning) => write!(f, "{}", warning),
+            Runtime(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl fmt::Display for RuntimeMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use RuntimeMessage::*;
+
+        match self {
+            FallbackChoice => write!(
+                f,
+                "no choices were available in the set, so the fallback choice was followed"
+            ),
+            AlternativeExhausted => write!(
+                f,
+                "once-only alternative sequence has shown all of its items and showed nothing"
+            ),
+            StickyChoicesRevisited { num_offered } => write!(
+                f,
+                "set of choices with sticky choices was offered again, {} times in total",
+                num_offered
+            ),
+            EndInNestedWeave { depth } => write!(
+                f,
+                "story was ended by a divert to `END` or `DONE` from inside {} level(s) of \
+                 choices: content gathered after them is not shown",
+                depth
+            ),
         }
     }
 }
@@ -77,6 +170,26 @@ impl fmt::Display for Warning {
                  changed it to a cycle sequence (fix: compile `inkling` with the \
                  `random` feature)"
             ),
+            UnreachableKnot { name } => write!(f, "knot '{}' is never diverted to", name),
+            UnreachableStitch { knot, stitch } => write!(
+                f,
+                "stitch '{}' in knot '{}' is never diverted to",
+                stitch, knot
+            ),
+            UnusedVariable { name } => {
+                write!(f, "global variable '{}' is never read in the story", name)
+            }
+            ConstantCondition { value } => write!(
+                f,
+                "condition does not depend on any variable or visit count and is always {}",
+                value
+            ),
+            ChoicesCanRunOut => write!(
+                f,
+                "set of choices can be returned to but has no sticky or fallback choices: \
+                 the story runs out of choices once all have been made (fix: make a choice \
+                 sticky with `+` or add a fallback choice)"
+            ),
         }
     }
 }
@@ -92,6 +205,20 @@ mod tests {
         assert!(format!("{}", message).contains("TODO"));
     }
 
+    #[test]
+    fn runtime_messages_are_printed_as_notes_with_location() {
+        let mut message = LogMessage::with_kind(
+            MessageKind::Runtime(RuntimeMessage::FallbackChoice),
+            &MetaData::from(2),
+        );
+        message
+            .location
+            .replace(Location::with_stitch("tavern", "bar"));
+
+        assert_eq!(message.severity(), Severity::Note);
+        assert!(format!("{}", message).starts_with("[line 3 in 'tavern.bar'] NOTE: "));
+    }
+
     #[test]
     fn warning_messages_are_printed_with_marker() {
         let warning = Warning::ShuffleSequenceNoRandom;
@@ -40,4 +40,4 @@ mod logger;
 mod message;
 
 pub use logger::Logger;
-pub use message::{LogMessage, MessageKind, Warning};
+pub use message::{LogMessage, MessageKind, RuntimeMessage, Warning};
@@ -3,9 +3,11 @@
 use crate::{
     error::{runtime::internal::IncorrectNodeStackError, InternalError},
     follow::{ChoiceInfo, EncounteredEvent, FollowData, FollowResult, LineDataBuffer},
-    knot::increment_num_visited,
+    knot::{increment_num_visited, Address},
+    log::{LogMessage, MessageKind, RuntimeMessage},
     node::{Branch, NodeItem, RootNode},
-    process::process_line,
+    process::{process_line, process_selected_choice_tags},
+    story::{location_from_address, CoverageKind, TraceEvent},
 };
 
 use std::{fmt, slice::IterMut};
@@ -69,6 +71,13 @@ pub trait Follow: FollowInternal {
         buffer: &mut LineDataBuffer,
         data: &mut FollowData,
     ) -> FollowResult {
+        // Number of choices made to reach this node
+        let depth = stack.len() / 2;
+
+        // The node that the story paused in has already been visited and has checked
+        // the line that it paused at
+        let mut is_resuming = data.debugger.take_resuming();
+
         let at_index = stack
             .last_mut()
             .ok_or(InternalError::from(IncorrectNodeStackError::EmptyStack))?;
@@ -80,7 +89,7 @@ pub trait Follow: FollowInternal {
                 num_items: self.get_num_items(),
             })
             .into());
-        } else if *at_index == 0 {
+        } else if *at_index == 0 && !is_resuming {
             self.increment_num_visited(data)?;
         }
 
@@ -89,11 +98,32 @@ pub trait Follow: FollowInternal {
 
             match item {
                 NodeItem::Line(line) => {
+                    if !std::mem::take(&mut is_resuming) {
+                        if let Some(pause) = data.debugger.check_line(&line.meta_data) {
+                            *at_index -= 1;
+
+                            return Ok(EncounteredEvent::Pause(pause));
+                        }
+                    }
+
+                    if let Some(recorder) = data.coverage.as_mut() {
+                        recorder.add(CoverageKind::Line, &line.meta_data);
+                    }
+
                     let result =
                         process_line(line, buffer, data).map_err(|err| InternalError::from(err))?;
 
-                    if let EncounteredEvent::Divert(..) = result {
-                        return Ok(result);
+                    match result {
+                        EncounteredEvent::Divert(Address::End) if depth > 0 => {
+                            data.messages.push(LogMessage::with_kind(
+                                MessageKind::Runtime(RuntimeMessage::EndInNestedWeave { depth }),
+                                &line.meta_data,
+                            ));
+
+                            return Ok(result);
+                        }
+                        EncounteredEvent::Divert(..) => return Ok(result),
+                        _ => (),
                     }
                 }
                 NodeItem::BranchingPoint(branches) => {
@@ -141,6 +171,13 @@ pub trait Follow: FollowInternal {
         } else {
             let selected_branch = self.get_selected_branch(selection, stack_index, stack)?;
 
+            if let Some(recorder) = data.coverage.as_mut() {
+                recorder.add(CoverageKind::Choice, &selected_branch.choice.meta_data);
+            }
+
+            process_selected_choice_tags(&selected_branch.choice, data)
+                .map_err(InternalError::from)?;
+
             stack.extend_from_slice(&[selection, 0]);
 
             selected_branch.follow(stack, buffer, data)
@@ -149,7 +186,44 @@ pub trait Follow: FollowInternal {
         match result {
             EncounteredEvent::Done => {
                 stack.truncate(stack_index + 1);
-                stack.last_mut().map(|i| *i += 1);
+
+                if let Some(i) = stack.last_mut() {
+                    *i += 1;
+                }
+
+                self.follow(stack, buffer, data)
+            }
+            other => Ok(other),
+        }
+    }
+
+    /// Resume the follow of content in the tree from the deepest position in the `Stack`.
+    ///
+    /// Like `follow_with_choice` this moves through the stack to the deepest level, but
+    /// follows that node from its current item instead of selecting a branch. This resumes
+    /// a follow which paused inside a branch. If the stack has a single level this is
+    /// the same as `follow`.
+    fn follow_from_stack(
+        &mut self,
+        stack_index: usize,
+        stack: &mut Stack,
+        buffer: &mut LineDataBuffer,
+        data: &mut FollowData,
+    ) -> FollowResult {
+        let result = match self.get_next_level_branch(stack_index, stack)? {
+            Some(next_branch) => {
+                next_branch.follow_from_stack(stack_index + 2, stack, buffer, data)
+            }
+            None => return self.follow(stack, buffer, data),
+        }?;
+
+        match result {
+            EncounteredEvent::Done => {
+                stack.truncate(stack_index + 1);
+
+                if let Some(i) = stack.last_mut() {
+                    *i += 1;
+                }
 
                 self.follow(stack, buffer, data)
             }
@@ -257,7 +331,7 @@ pub trait FollowInternal: fmt::Debug {
     fn get_item_mut(&mut self, index: usize) -> Option<&mut NodeItem>;
     fn get_num_items(&self) -> usize;
     fn increment_num_visited(&mut self, data: &mut FollowData) -> Result<(), InternalError>;
-    fn iter_mut_items(&mut self) -> IterMut<NodeItem>;
+    fn iter_mut_items(&mut self) -> IterMut<'_, NodeItem>;
 }
 
 impl FollowInternal for RootNode {
@@ -274,10 +348,16 @@ impl FollowInternal for RootNode {
     }
 
     fn increment_num_visited(&mut self, data: &mut FollowData) -> Result<(), InternalError> {
-        increment_num_visited(&self.address, data)
+        increment_num_visited(&self.address, data)?;
+
+        data.tracer.emit(|| TraceEvent::Enter {
+            location: location_from_address(&self.address),
+        });
+
+        Ok(())
     }
 
-    fn iter_mut_items(&mut self) -> IterMut<NodeItem> {
+    fn iter_mut_items(&mut self) -> IterMut<'_, NodeItem> {
         self.items.iter_mut()
     }
 }
@@ -301,7 +381,7 @@ impl FollowInternal for Branch {
         Ok(())
     }
 
-    fn iter_mut_items(&mut self) -> IterMut<NodeItem> {
+    fn iter_mut_items(&mut self) -> IterMut<'_, NodeItem> {
         self.items.iter_mut()
     }
 }
@@ -314,6 +394,42 @@ fn get_choices_from_branching_set(branches: &[Branch]) -> Vec<ChoiceInfo> {
         .collect::<Vec<_>>()
 }
 
+/// Get the line number of the current item at every level of the stack.
+///
+/// Levels are ordered from the root node to the deepest branch. Levels which have been
+/// left by making a choice yield the line of that choice, a set of choices yields the
+/// line of its first choice. Levels which have run out of items yield `None`.
+pub fn get_stack_lines(root: &RootNode, stack: &Stack) -> Vec<Option<u32>> {
+    let mut lines = Vec::new();
+    let mut node: &dyn FollowInternal = root;
+
+    for (stack_index, item_index) in stack.iter().enumerate().step_by(2) {
+        let branch_index = stack.get(stack_index + 1);
+
+        match (node.get_item(*item_index), branch_index) {
+            (Some(NodeItem::BranchingPoint(branches)), Some(i)) => match branches.get(*i) {
+                Some(branch) => {
+                    lines.push(Some(branch.choice.meta_data.line()));
+                    node = branch;
+                }
+                None => {
+                    lines.push(None);
+                    break;
+                }
+            },
+            (Some(NodeItem::BranchingPoint(branches)), None) => lines.push(
+                branches
+                    .first()
+                    .map(|branch| branch.choice.meta_data.line()),
+            ),
+            (Some(NodeItem::Line(line)), _) => lines.push(Some(line.meta_data.line())),
+            (None, _) => lines.push(None),
+        }
+    }
+
+    lines
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
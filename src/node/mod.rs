@@ -9,7 +9,8 @@ mod follow;
 mod node;
 mod parse;
 
-pub use follow::{Follow, Stack};
+pub use follow::{get_stack_lines, Follow, Stack};
 pub(self) use node::builders;
 pub use node::{builders::RootNodeBuilder, Branch, NodeItem, RootNode};
+pub(crate) use node::{get_content_indent, get_marked_line};
 pub use parse::parse_root_node;
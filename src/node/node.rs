@@ -1,6 +1,7 @@
 //! Node tree structure for branching content.
 
 use crate::{
+    consts::{CHOICE_MARKER, GATHER_MARKER, STICKY_CHOICE_MARKER},
     error::{parse::validate::ValidationError, utils::MetaData},
     knot::Address,
     line::{InternalChoice, InternalLine},
@@ -11,6 +12,8 @@ use crate::{
 #[cfg(feature = "serde_support")]
 use serde::{Deserialize, Serialize};
 
+use std::fmt;
+
 #[derive(Clone, Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
@@ -66,6 +69,37 @@ impl NodeItem {
     }
 }
 
+impl RootNode {
+    /// Replace all shared choice selection texts in the node with independent copies.
+    pub fn detach_selection_texts(&mut self) {
+        self.items
+            .iter_mut()
+            .for_each(|item| item.detach_selection_texts());
+    }
+}
+
+impl Branch {
+    /// Replace all shared choice selection texts in the branch with independent copies.
+    pub fn detach_selection_texts(&mut self) {
+        self.choice.detach_selection_text();
+
+        self.items
+            .iter_mut()
+            .for_each(|item| item.detach_selection_texts());
+    }
+}
+
+impl NodeItem {
+    /// Replace all shared choice selection texts in the item with independent copies.
+    pub fn detach_selection_texts(&mut self) {
+        if let NodeItem::BranchingPoint(branches) = self {
+            branches
+                .iter_mut()
+                .for_each(|branch| branch.detach_selection_texts());
+        }
+    }
+}
+
 impl ValidateContent for RootNode {
     fn validate(
         &mut self,
@@ -129,6 +163,95 @@ impl ValidateContent for NodeItem {
     }
 }
 
+impl fmt::Display for RootNode {
+    /// Write the content of the node as `Ink` source.
+    ///
+    /// Choices and gathers are written with a marker for every level they are nested at.
+    /// Content is indented to line up with the text of the choice it belongs to.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_node_items(&self.items, 0, f)
+    }
+}
+
+/// Write the items of a node or branch which is nested under choices of the given level.
+///
+/// Sets of choices which directly follow each other are each written one level deeper
+/// than the next, which separates them when the source is read again.
+fn write_node_items(items: &[NodeItem], level: usize, f: &mut fmt::Formatter) -> fmt::Result {
+    let indent = " ".repeat(get_content_indent(level));
+
+    for (i, item) in items.iter().enumerate() {
+        let follows_choices = matches!(
+            i.checked_sub(1).map(|j| &items[j]),
+            Some(NodeItem::BranchingPoint(..))
+        );
+
+        match item {
+            NodeItem::Line(line) if follows_choices => {
+                writeln!(
+                    f,
+                    "{}",
+                    get_marked_line(GATHER_MARKER, level + 1, &line.to_string())
+                )?;
+            }
+            NodeItem::Line(line) => writeln!(f, "{}", format!("{}{}", indent, line).trim_end())?,
+            NodeItem::BranchingPoint(branches) => {
+                let num_following_sets = items[i + 1..]
+                    .iter()
+                    .take_while(|item| match item {
+                        NodeItem::BranchingPoint(..) => true,
+                        NodeItem::Line(..) => false,
+                    })
+                    .count();
+
+                let choice_level = level + 1 + num_following_sets;
+
+                for branch in branches {
+                    let marker = if branch.choice.is_sticky {
+                        STICKY_CHOICE_MARKER
+                    } else {
+                        CHOICE_MARKER
+                    };
+
+                    writeln!(
+                        f,
+                        "{}",
+                        get_marked_line(marker, choice_level, &branch.choice.to_string())
+                    )?;
+
+                    // The first item of a branch is a copy of the choice display text
+                    let items = branch.items.get(1..).unwrap_or(&[]);
+                    write_node_items(items, choice_level, f)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Get a choice or gather line with one marker for every level it is nested at.
+pub(crate) fn get_marked_line(marker: char, level: usize, text: &str) -> String {
+    let indent = " ".repeat(4 * (level - 1));
+    let markers = vec![marker.to_string(); level].join(" ");
+
+    let line = format!("{}{:width$}{}", indent, markers, text, width = 4 * level);
+
+    line.trim_end().to_string()
+}
+
+/// Get the indentation of content nested under choices of the given level.
+///
+/// The content lines up with the text of the choices, which are indented by four spaces
+/// for every level and written with their markers padded to four spaces per level.
+pub(crate) fn get_content_indent(level: usize) -> usize {
+    if level == 0 {
+        0
+    } else {
+        8 * level - 4
+    }
+}
+
 pub mod builders {
     //! Builders for constructing nodes.
     //!
@@ -2,11 +2,19 @@
 
 use crate::{
     follow::{LineDataBuffer, LineText},
+    knot::Address,
     story::{Line, LineBuffer},
 };
 
 /// Process internal lines to a user-ready state.
-pub fn process_buffer(into_buffer: &mut LineBuffer, from_buffer: LineDataBuffer) {
+///
+/// Returns the addresses that the added lines were followed from, in the same order.
+pub fn process_buffer(
+    into_buffer: &mut LineBuffer,
+    from_buffer: LineDataBuffer,
+) -> Vec<Option<Address>> {
+    let mut addresses = Vec::new();
+
     let mut iter = from_buffer
         .into_iter()
         .filter(|line| !line.text.trim().is_empty())
@@ -22,7 +30,11 @@ pub fn process_buffer(into_buffer: &mut LineBuffer, from_buffer: LineDataBuffer)
             text: line.text,
             tags: line.tags,
         });
+
+        addresses.push(line.address);
     }
+
+    addresses
 }
 
 /// Check whether the line is glued to the next and if so whether it ends with a blank space.
@@ -217,6 +229,24 @@ mod tests {
         assert_eq!(processed[0].tags, tags);
     }
 
+    #[test]
+    fn processing_line_buffer_returns_addresses_of_added_lines() {
+        let address = Address::End;
+
+        let buffer = vec![
+            LineTextBuilder::from_string("").build(),
+            LineTextBuilder::from_string("Mr. and Mrs. Doubtfire")
+                .with_address(&address)
+                .build(),
+            LineTextBuilder::from_string("Hello, World!").build(),
+        ];
+
+        let mut processed = Vec::new();
+        let addresses = process_buffer(&mut processed, buffer);
+
+        assert_eq!(addresses, vec![Some(address), None]);
+    }
+
     #[test]
     fn only_single_whitespaces_are_left_between_words_after_processing() {
         let text = "A line    with   just    enough   whitespace";
@@ -1,10 +1,12 @@
 //! Process and filter choices to present to the user.
 
 use crate::{
-    error::{InklingError, InternalError},
+    error::{runtime::internal::ProcessError, InklingError, InternalError},
     follow::{ChoiceInfo, FollowData},
-    line::InternalLine,
-    process::{check_condition, process_line},
+    knot::Address,
+    line::{InternalChoice, InternalLine},
+    node::{NodeItem, RootNode},
+    process::{check_condition, process_line, process_tags},
     story::Choice,
 };
 
@@ -33,6 +35,71 @@ pub fn get_fallback_choices(
     get_available_choices(choices, data, true)
 }
 
+/// Set the identifiers of choices from the address and root node of their stitch.
+///
+/// A choice is identified by its stitch and a label made from its text as written in
+/// the script. If earlier choices in the stitch have the same label the number of the
+/// choice among them is appended, so that every choice in the stitch has its own id.
+pub fn set_choice_ids(choices: &mut [ChoiceInfo], address: &Address, root: &RootNode) {
+    let mut labels = Vec::new();
+    collect_choice_labels(&root.items, &mut labels);
+
+    for choice in choices.iter_mut() {
+        let line = choice.choice_data.meta_data.line();
+        let label = get_choice_label(&choice.choice_data);
+
+        let num_before = labels
+            .iter()
+            .take_while(|(other_line, _)| *other_line != line)
+            .filter(|(_, other_label)| other_label == &label)
+            .count();
+
+        choice.id = if num_before == 0 {
+            format!("{}:{}", address, label)
+        } else {
+            format!("{}:{}:{}", address, label, num_before + 1)
+        };
+    }
+}
+
+/// Collect the line and label of all choices in the items, in the order of the script.
+fn collect_choice_labels(items: &[NodeItem], labels: &mut Vec<(u32, String)>) {
+    for item in items {
+        if let NodeItem::BranchingPoint(branches) = item {
+            for branch in branches {
+                labels.push((
+                    branch.choice.meta_data.line(),
+                    get_choice_label(&branch.choice),
+                ));
+
+                collect_choice_labels(&branch.items, labels);
+            }
+        }
+    }
+}
+
+/// Get a label for the choice from its selection text as written in the script.
+///
+/// Words are written in lower case and joined by underscores.
+fn get_choice_label(choice: &InternalChoice) -> String {
+    let mut selection_text = choice.selection_text.lock().unwrap().clone();
+    selection_text.tags.clear();
+
+    let label = selection_text
+        .to_string()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+
+    if label.is_empty() {
+        "choice".to_string()
+    } else {
+        label
+    }
+}
+
 /// Return the currently available choices in the set.
 ///
 /// Filters choices which do not fulfil the conditions to be active. These can for example
@@ -68,30 +135,45 @@ fn zip_choices_with_filter_values(
         .iter()
         .zip(checked_choices.into_iter())
         .enumerate()
-        .map(|(i, (ChoiceInfo { choice_data, .. }, keep))| {
-            let (text, tags) = if keep {
-                process_choice_text_and_tags(choice_data.selection_text.clone(), data)
-            } else {
-                // If we are filtering the choice we do not want it's processed selection
-                // text to update their state. Instead, we clone the data and process that.
-
-                let independent_text = choice_data.selection_text.lock().unwrap().clone();
-                process_choice_text_and_tags(Arc::new(Mutex::new(independent_text)), data)
-            }?;
-
-            Ok((
-                keep,
-                Choice {
-                    text,
-                    tags,
-                    index: i,
-                },
-            ))
-        })
+        .map(
+            |(
+                i,
+                (
+                    ChoiceInfo {
+                        choice_data, id, ..
+                    },
+                    keep,
+                ),
+            )| {
+                let (text, tags) = if keep {
+                    process_choice_text_and_tags(choice_data.selection_text.clone(), data)
+                } else {
+                    // If we are filtering the choice we do not want it's processed selection
+                    // text to update their state. Instead, we clone the data and process that.
+
+                    let independent_text = choice_data.selection_text.lock().unwrap().clone();
+                    process_choice_text_and_tags(Arc::new(Mutex::new(independent_text)), data)
+                }?;
+
+                Ok((
+                    keep,
+                    Choice {
+                        text,
+                        tags,
+                        id: id.clone(),
+                        index: i,
+                    },
+                ))
+            },
+        )
         .collect()
 }
 
 /// Process a line into a string and return it with its tags.
+///
+/// The tags are evaluated from a copy of those in the line, so that presenting a choice
+/// does not advance alternatives in them. They are advanced once when the choice is
+/// selected, by [`process_selected_choice_tags`][crate::process::process_selected_choice_tags].
 fn process_choice_text_and_tags(
     choice_line: Arc<Mutex<InternalLine>>,
     data: &mut FollowData,
@@ -99,9 +181,13 @@ fn process_choice_text_and_tags(
     let mut data_buffer = Vec::new();
 
     let mut line = choice_line.lock().unwrap();
-    let mut line = line.deref_mut();
+    let line = line.deref_mut();
 
-    process_line(&mut line, &mut data_buffer, data).map_err(|err| InternalError::from(err))?;
+    let tags = std::mem::take(&mut line.tags);
+    let result = process_line(line, &mut data_buffer, data);
+    line.tags = tags;
+
+    result.map_err(InternalError::from)?;
 
     let mut buffer = String::new();
 
@@ -109,7 +195,23 @@ fn process_choice_text_and_tags(
         buffer.push_str(&data.text);
     }
 
-    Ok((buffer.trim().to_string(), line.tags.clone()))
+    let tags = process_tags(&mut line.tags.clone(), data).map_err(InternalError::from)?;
+
+    Ok((buffer.trim().to_string(), tags))
+}
+
+/// Evaluate the tags of a selected choice.
+///
+/// This advances any alternatives in them, so that the choice presents the next item
+/// of a sequence in its tags after it has been selected.
+pub fn process_selected_choice_tags(
+    choice: &InternalChoice,
+    data: &mut FollowData,
+) -> Result<(), ProcessError> {
+    let mut line = choice.selection_text.lock().unwrap();
+    process_tags(&mut line.tags, data)?;
+
+    Ok(())
 }
 
 /// Return a list of whether choices fulfil their conditions.
@@ -123,6 +225,7 @@ fn check_choices_for_conditions(
     for ChoiceInfo {
         num_visited,
         choice_data,
+        ..
     } in choices.iter()
     {
         let mut keep = choice_data
@@ -160,6 +263,7 @@ mod tests {
         ChoiceInfo {
             num_visited,
             choice_data,
+            id: String::new(),
         }
     }
 
@@ -7,12 +7,30 @@ use crate::{
     },
     follow::FollowData,
     line::{expression::evaluate_expression, Condition, StoryCondition, Variable},
+    story::TraceEvent,
 };
 
 use std::cmp::Ordering;
 
 /// Check whether a single condition is fulfilled.
+///
+/// The result is reported to the tracer of the story, as a condition that was checked
+/// while following it.
 pub fn check_condition(condition: &Condition, data: &FollowData) -> Result<bool, InklingError> {
+    let result = evaluate_condition(condition, data);
+
+    if let Ok(result) = result {
+        data.tracer.emit(|| TraceEvent::Condition {
+            condition: condition.to_string(),
+            result,
+        });
+    }
+
+    result
+}
+
+/// Evaluate whether a single condition is fulfilled, without reporting it to the tracer.
+pub fn evaluate_condition(condition: &Condition, data: &FollowData) -> Result<bool, InklingError> {
     let evaluator = |kind: &StoryCondition| match kind {
         StoryCondition::Comparison {
             lhs_variable,
@@ -33,7 +51,7 @@ pub fn check_condition(condition: &Condition, data: &FollowData) -> Result<bool,
             Variable::Bool(value) => Ok(value),
             Variable::Float(value) => Ok(value != 0.0),
             Variable::Int(value) => Ok(value != 0),
-            Variable::String(s) => Ok(s.len() > 0),
+            Variable::String(s) => Ok(!s.is_empty()),
             Variable::Divert(..) => Err(VariableError::from_kind(
                 variable.clone(),
                 VariableErrorKind::InvalidComparison {
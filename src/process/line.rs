@@ -2,8 +2,10 @@
 
 use crate::{
     error::runtime::internal::{ProcessError, ProcessErrorKind},
+    error::utils::MetaData,
     follow::{EncounteredEvent, FollowData, LineDataBuffer, LineText},
-    line::{evaluate_expression, Alternative, Content, InternalLine, LineChunk},
+    line::{evaluate_expression, Alternative, AlternativeKind, Content, InternalLine, LineChunk},
+    log::{LogMessage, MessageKind, RuntimeMessage},
     process::check_condition,
 };
 
@@ -14,14 +16,31 @@ pub fn process_line(
     data: &mut FollowData,
 ) -> Result<EncounteredEvent, ProcessError> {
     let mut text_buffer = String::new();
+    let num_messages = data.messages.len();
+
+    if let Some(recorder) = data.coverage.as_mut() {
+        recorder.begin_line(&line.meta_data);
+    }
 
     let result = process_chunk(&mut line.chunk, &mut text_buffer, data);
 
+    if let Some(recorder) = data.coverage.as_mut() {
+        recorder.end_line();
+    }
+
+    let tags = process_tags(&mut line.tags, data)?;
+
+    // Messages from the content of the line originate from it
+    data.messages[num_messages..]
+        .iter_mut()
+        .for_each(|message| message.meta_data = line.meta_data.clone());
+
     let line_text = LineText {
         text: text_buffer,
         glue_begin: line.glue_begin,
         glue_end: line.glue_end,
-        tags: line.tags.clone(),
+        tags,
+        address: None,
     };
 
     buffer.push(line_text);
@@ -29,6 +48,23 @@ pub fn process_line(
     result
 }
 
+/// Process the tags of a line into their final text.
+///
+/// Tags cannot divert the story, so any divert that is encountered in them is ignored.
+pub fn process_tags(
+    tags: &mut [LineChunk],
+    data: &mut FollowData,
+) -> Result<Vec<String>, ProcessError> {
+    tags.iter_mut()
+        .map(|tag| {
+            let mut buffer = String::new();
+            process_chunk(tag, &mut buffer, data)?;
+
+            Ok(buffer.trim().to_string())
+        })
+        .collect()
+}
+
 /// Process and add the content of a `LineChunk` to a string buffer.
 ///
 /// If a condition is set to the chunk, it will be evaluated. If it evaluates to true,
@@ -39,23 +75,46 @@ fn process_chunk(
     buffer: &mut String,
     data: &mut FollowData,
 ) -> Result<EncounteredEvent, ProcessError> {
-    let items = match &chunk.condition {
+    let (items, branch) = match &chunk.condition {
         Some(ref condition) => {
-            if check_condition(condition, data)? {
-                chunk.items.iter_mut()
+            let value = check_condition(condition, data)?;
+
+            if let Some(recorder) = data.coverage.as_mut() {
+                recorder.add_conditional_branch(value);
+            }
+
+            if value {
+                (chunk.items.iter_mut(), Some(0))
             } else {
-                chunk.else_items.iter_mut()
+                (chunk.else_items.iter_mut(), Some(1))
             }
         }
-        None => chunk.items.iter_mut(),
+        None => (chunk.items.iter_mut(), None),
     };
 
-    for item in items {
+    // Coverage paths are not left when returning early, since the line is done then
+    if let (Some(recorder), Some(index)) = (data.coverage.as_mut(), branch) {
+        recorder.enter(index);
+    }
+
+    for (i, item) in items.enumerate() {
+        if let Some(recorder) = data.coverage.as_mut() {
+            recorder.enter(i);
+        }
+
         let result = process_content(item, buffer, data)?;
 
         if let EncounteredEvent::Divert(..) = result {
             return Ok(result);
         }
+
+        if let Some(recorder) = data.coverage.as_mut() {
+            recorder.leave();
+        }
+    }
+
+    if let (Some(recorder), Some(..)) = (data.coverage.as_mut(), branch) {
+        recorder.leave();
     }
 
     Ok(EncounteredEvent::Done)
@@ -102,9 +161,30 @@ fn process_alternative(
                     kind: ProcessErrorKind::InvalidAlternativeIndex,
                 })?;
 
-            process_chunk(item, buffer, data)
+            if let Some(recorder) = data.coverage.as_mut() {
+                recorder.add_alternative_branch(index);
+                recorder.enter(index);
+            }
+
+            let result = process_chunk(item, buffer, data)?;
+
+            if let Some(recorder) = data.coverage.as_mut() {
+                recorder.leave();
+            }
+
+            Ok(result)
+        }
+        None => {
+            // The message is given the meta data of its line in `process_line`
+            if alternative.kind == AlternativeKind::OnceOnly {
+                data.messages.push(LogMessage::with_kind(
+                    MessageKind::Runtime(RuntimeMessage::AlternativeExhausted),
+                    &MetaData::from(0),
+                ));
+            }
+
+            Ok(EncounteredEvent::Done)
         }
-        None => Ok(EncounteredEvent::Done),
     }
 }
 
@@ -172,7 +252,10 @@ pub mod tests {
     #[test]
     fn full_line_processing_retains_tags() {
         let mut line = parse_internal_line("A test string", &().into()).unwrap();
-        line.tags = vec!["tag 1".to_string(), "tag 2".to_string()];
+        line.tags = vec![
+            LineChunkBuilder::from_string("tag 1").build(),
+            LineChunkBuilder::from_string("tag 2").build(),
+        ];
 
         let mut buffer = Vec::new();
         let mut data = mock_data_with_single_stitch("", "", 0);
@@ -180,7 +263,24 @@ pub mod tests {
         process_line(&mut line, &mut buffer, &mut data).unwrap();
 
         let result = &buffer[0];
-        assert_eq!(result.tags, line.tags);
+        assert_eq!(result.tags, vec!["tag 1".to_string(), "tag 2".to_string()]);
+    }
+
+    #[test]
+    fn full_line_processing_evaluates_embraced_content_in_tags() {
+        let mut line =
+            parse_internal_line("A test string # mood: {&happy|sad}", &().into()).unwrap();
+
+        let mut buffer = Vec::new();
+        let mut data = mock_data_with_single_stitch("", "", 0);
+
+        process_line(&mut line, &mut buffer, &mut data).unwrap();
+        process_line(&mut line, &mut buffer, &mut data).unwrap();
+        process_line(&mut line, &mut buffer, &mut data).unwrap();
+
+        assert_eq!(&buffer[0].tags, &["mood: happy".to_string()]);
+        assert_eq!(&buffer[1].tags, &["mood: sad".to_string()]);
+        assert_eq!(&buffer[2].tags, &["mood: happy".to_string()]);
     }
 
     #[test]
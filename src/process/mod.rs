@@ -6,6 +6,8 @@ mod condition;
 pub(crate) mod line;
 
 pub use buffer::process_buffer;
-pub use choice::{get_fallback_choices, prepare_choices_for_user};
-pub use condition::check_condition;
-pub use line::process_line;
+pub use choice::{
+    get_fallback_choices, prepare_choices_for_user, process_selected_choice_tags, set_choice_ids,
+};
+pub use condition::{check_condition, evaluate_condition};
+pub use line::{process_line, process_tags};
@@ -0,0 +1,566 @@
+//! Record of which content in a story has been seen while following it.
+//!
+//! Coverage is recorded for every line in the story, every choice, every item of
+//! alternative sequences and both branches of conditional content. Items are identified
+//! by the [`MetaData`][crate::error::MetaData] of their origin in the story, so coverage
+//! from several playthroughs of the same story can be merged.
+
+use crate::{
+    consts::ROOT_KNOT_NAME,
+    error::utils::MetaData,
+    knot::KnotSet,
+    line::{Content, InternalLine, LineChunk},
+    node::NodeItem,
+    story::utils::get_chunk_text,
+};
+
+#[cfg(feature = "serde_support")]
+use serde::{Deserialize, Serialize};
+
+use std::{
+    collections::{hash_set, HashSet},
+    fmt,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
+/// Content item in a story which can be covered.
+pub struct CoverageItem {
+    /// Information about the origin of the item.
+    ///
+    /// For alternative and conditional branches this is the origin of their line.
+    pub meta_data: MetaData,
+    /// Kind of item.
+    pub kind: CoverageKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
+/// Kind of content item in a story which can be covered.
+///
+/// Branches are identified by a path of item indices from the start of their line down
+/// to the alternative sequence or conditional content that they belong to.
+pub enum CoverageKind {
+    /// Line of text.
+    Line,
+    /// Choice which was made.
+    Choice,
+    /// Item with the given index in an alternative sequence.
+    AlternativeBranch { path: Vec<usize>, index: usize },
+    /// Branch of conditional content which is used when the condition has the given value.
+    ConditionalBranch { path: Vec<usize>, value: bool },
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
+/// Set of content items which have been seen in a story.
+///
+/// Started with [`record_coverage`][crate::story::Story::record_coverage()]. Coverage
+/// from several sessions of the same story can be merged and used to get
+/// a [report][crate::story::Story::get_coverage_report()] of unseen content.
+///
+/// # Examples
+/// ```
+/// # use inkling::{read_story_from_string, ChoiceRef};
+/// let content = "\
+/// The ferry was about to leave.
+/// *   I ran to the gangway.
+/// *   I let it go.
+/// ";
+///
+/// let mut first = read_story_from_string(content).unwrap();
+/// first.record_coverage();
+/// first.replay(&[ChoiceRef::Index(0)], &mut Vec::new()).unwrap();
+///
+/// let mut second = read_story_from_string(content).unwrap();
+/// second.record_coverage();
+/// second.replay(&[ChoiceRef::Index(1)], &mut Vec::new()).unwrap();
+///
+/// let mut coverage = first.take_coverage().unwrap();
+/// coverage.merge(&second.take_coverage().unwrap());
+///
+/// let report = first.get_coverage_report(&coverage);
+/// assert_eq!(report.num_seen(), report.num_items());
+/// ```
+pub struct Coverage {
+    items: HashSet<CoverageItem>,
+}
+
+impl Coverage {
+    /// Add all items from another coverage record to this one.
+    pub fn merge(&mut self, other: &Coverage) {
+        self.items.extend(other.items.iter().cloned());
+    }
+
+    /// Whether an item has been seen.
+    pub fn contains(&self, item: &CoverageItem) -> bool {
+        self.items.contains(item)
+    }
+
+    /// Iterate over all seen items, in arbitrary order.
+    pub fn iter(&self) -> hash_set::Iter<'_, CoverageItem> {
+        self.items.iter()
+    }
+
+    /// Number of seen items.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether no items have been seen.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    fn add(&mut self, kind: CoverageKind, meta_data: &MetaData) {
+        self.items.insert(CoverageItem {
+            meta_data: meta_data.clone(),
+            kind,
+        });
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(test, derive(PartialEq))]
+#[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
+/// Recorder of coverage while content is processed.
+///
+/// Keeps track of the line that is being processed and the path to the current item
+/// in it, to identify the branches of alternatives and conditional content.
+pub struct CoverageRecorder {
+    /// Recorded coverage.
+    pub coverage: Coverage,
+    /// Origin of the line which is being processed, if any.
+    #[cfg_attr(feature = "serde_support", serde(skip))]
+    line: Option<MetaData>,
+    /// Path of item indices to the current item in the line.
+    #[cfg_attr(feature = "serde_support", serde(skip))]
+    path: Vec<usize>,
+}
+
+impl CoverageRecorder {
+    /// Add an item which is identified by its own origin.
+    pub fn add(&mut self, kind: CoverageKind, meta_data: &MetaData) {
+        self.coverage.add(kind, meta_data);
+    }
+
+    /// Start processing a line, after which branches in it are recorded.
+    pub fn begin_line(&mut self, meta_data: &MetaData) {
+        self.line.replace(meta_data.clone());
+        self.path.clear();
+    }
+
+    /// Stop recording branches until the next line is processed.
+    pub fn end_line(&mut self) {
+        self.line = None;
+    }
+
+    /// Enter the item with the given index in the current set of items.
+    pub fn enter(&mut self, index: usize) {
+        self.path.push(index);
+    }
+
+    /// Leave the current item.
+    pub fn leave(&mut self) {
+        self.path.pop();
+    }
+
+    /// Add the branch of an alternative sequence at the current path.
+    pub fn add_alternative_branch(&mut self, index: usize) {
+        if let Some(meta_data) = &self.line {
+            let kind = CoverageKind::AlternativeBranch {
+                path: self.path.clone(),
+                index,
+            };
+
+            self.coverage.add(kind, meta_data);
+        }
+    }
+
+    /// Add the branch of conditional content at the current path.
+    pub fn add_conditional_branch(&mut self, value: bool) {
+        if let Some(meta_data) = &self.line {
+            let kind = CoverageKind::ConditionalBranch {
+                path: self.path.clone(),
+                value,
+            };
+
+            self.coverage.add(kind, meta_data);
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Report of which content in a story has not been seen, per knot.
+///
+/// Created with [`get_coverage_report`][crate::story::Story::get_coverage_report()].
+/// Printing the report lists every unseen item with its line number and text.
+pub struct CoverageReport {
+    /// Coverage of every knot, starting with the content before the first knot
+    /// and then in order of knot name.
+    pub knots: Vec<KnotCoverage>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Coverage of the content in a single knot.
+pub struct KnotCoverage {
+    /// Name of knot, or `None` for the content before the first knot in the story.
+    pub name: Option<String>,
+    /// Number of items in the knot.
+    pub num_items: usize,
+    /// Number of items in the knot that have been seen.
+    pub num_seen: usize,
+    /// Items which have not been seen, ordered by their position in the story.
+    pub unseen: Vec<UnseenItem>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Content item which has not been seen.
+pub struct UnseenItem {
+    /// Item which has not been seen.
+    pub item: CoverageItem,
+    /// Text of the item, without any alternative or conditional content.
+    pub text: String,
+}
+
+impl CoverageReport {
+    /// Number of items in the story.
+    pub fn num_items(&self) -> usize {
+        self.knots.iter().map(|knot| knot.num_items).sum()
+    }
+
+    /// Number of items in the story which have been seen.
+    pub fn num_seen(&self) -> usize {
+        self.knots.iter().map(|knot| knot.num_seen).sum()
+    }
+}
+
+/// Get a report of the content in a set of knots that is not in the coverage record.
+pub fn get_coverage_report(knots: &KnotSet, coverage: &Coverage) -> CoverageReport {
+    let mut knot_names = knots.keys().collect::<Vec<_>>();
+    knot_names.sort_by_key(|name| (name.as_str() != ROOT_KNOT_NAME, name.as_str()));
+
+    let knots = knot_names
+        .into_iter()
+        .map(|name| {
+            let mut items = Vec::new();
+
+            for stitch in knots[name].stitches.values() {
+                add_node_items(&stitch.root.items, &mut items);
+            }
+
+            items.sort_by_key(|(item, _)| {
+                (item.meta_data.line(), item.meta_data.column_range().start)
+            });
+
+            let num_items = items.len();
+
+            let unseen = items
+                .into_iter()
+                .filter(|(item, _)| !coverage.contains(item))
+                .map(|(item, text)| UnseenItem { item, text })
+                .collect::<Vec<_>>();
+
+            KnotCoverage {
+                name: Some(name.clone()).filter(|name| name != ROOT_KNOT_NAME),
+                num_items,
+                num_seen: num_items - unseen.len(),
+                unseen,
+            }
+        })
+        .collect();
+
+    CoverageReport { knots }
+}
+
+/// Collect all coverable items with their text from a list of node items.
+///
+/// Branches are given the same paths as they are recorded with in `process_chunk`.
+fn add_node_items(items: &[NodeItem], buffer: &mut Vec<(CoverageItem, String)>) {
+    for item in items {
+        match item {
+            NodeItem::Line(line) => {
+                add_item(CoverageKind::Line, &line.meta_data, &line.chunk, buffer);
+                add_line_branches(line, buffer);
+            }
+            NodeItem::BranchingPoint(branches) => {
+                for branch in branches {
+                    let selection_text = branch.choice.selection_text.lock().unwrap();
+
+                    let meta_data = &branch.choice.meta_data;
+                    add_item(
+                        CoverageKind::Choice,
+                        meta_data,
+                        &selection_text.chunk,
+                        buffer,
+                    );
+                    add_line_branches(&selection_text, buffer);
+
+                    add_node_items(&branch.items, buffer);
+                }
+            }
+        }
+    }
+}
+
+fn add_item(
+    kind: CoverageKind,
+    meta_data: &MetaData,
+    chunk: &LineChunk,
+    buffer: &mut Vec<(CoverageItem, String)>,
+) {
+    let item = CoverageItem {
+        meta_data: meta_data.clone(),
+        kind,
+    };
+
+    if !buffer.iter().any(|(existing, _)| existing == &item) {
+        buffer.push((item, get_chunk_text(chunk)));
+    }
+}
+
+fn add_line_branches(line: &InternalLine, buffer: &mut Vec<(CoverageItem, String)>) {
+    add_chunk_branches(&line.chunk, &line.meta_data, &mut Vec::new(), buffer);
+}
+
+fn add_chunk_branches(
+    chunk: &LineChunk,
+    meta_data: &MetaData,
+    path: &mut Vec<usize>,
+    buffer: &mut Vec<(CoverageItem, String)>,
+) {
+    match &chunk.condition {
+        Some(..) => {
+            for (selector, (value, items)) in [(true, &chunk.items), (false, &chunk.else_items)]
+                .iter()
+                .enumerate()
+            {
+                let kind = CoverageKind::ConditionalBranch {
+                    path: path.clone(),
+                    value: *value,
+                };
+
+                let text = LineChunk {
+                    condition: None,
+                    items: items.to_vec(),
+                    else_items: Vec::new(),
+                };
+
+                add_item(kind, meta_data, &text, buffer);
+
+                path.push(selector);
+                add_content_branches(items, meta_data, path, buffer);
+                path.pop();
+            }
+        }
+        None => add_content_branches(&chunk.items, meta_data, path, buffer),
+    }
+}
+
+fn add_content_branches(
+    items: &[Content],
+    meta_data: &MetaData,
+    path: &mut Vec<usize>,
+    buffer: &mut Vec<(CoverageItem, String)>,
+) {
+    for (i, item) in items.iter().enumerate() {
+        path.push(i);
+
+        match item {
+            Content::Alternative(alternative) => {
+                for (index, chunk) in alternative.items.iter().enumerate() {
+                    let kind = CoverageKind::AlternativeBranch {
+                        path: path.clone(),
+                        index,
+                    };
+
+                    add_item(kind, meta_data, chunk, buffer);
+
+                    path.push(index);
+                    add_chunk_branches(chunk, meta_data, path, buffer);
+                    path.pop();
+                }
+            }
+            Content::Nested(chunk) => add_chunk_branches(chunk, meta_data, path, buffer),
+            Content::Divert(..) | Content::Empty | Content::Expression(..) | Content::Text(..) => {}
+        }
+
+        path.pop();
+    }
+}
+
+impl fmt::Display for CoverageReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for knot in &self.knots {
+            writeln!(
+                f,
+                "{}: {}/{} items seen",
+                knot.name.as_deref().unwrap_or("(start)"),
+                knot.num_seen,
+                knot.num_items
+            )?;
+
+            for unseen in &knot.unseen {
+                writeln!(
+                    f,
+                    "    ({}) {}: {}",
+                    unseen.item.meta_data, unseen.item.kind, unseen.text
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for CoverageKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use CoverageKind::*;
+
+        match self {
+            Line => write!(f, "line"),
+            Choice => write!(f, "choice"),
+            AlternativeBranch { index, .. } => write!(f, "alternative {}", index + 1),
+            ConditionalBranch { value: true, .. } => write!(f, "condition true"),
+            ConditionalBranch { value: false, .. } => write!(f, "condition false"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::story::{read_story_from_string, ChoiceRef, Story};
+
+    fn play(content: &str, choices: &[usize]) -> Story {
+        let choices = choices
+            .iter()
+            .map(|&index| ChoiceRef::from(index))
+            .collect::<Vec<_>>();
+
+        let mut story = read_story_from_string(content).unwrap();
+        story.record_coverage();
+        story.replay(&choices, &mut Vec::new()).unwrap();
+
+        story
+    }
+
+    fn get_unseen(story: &Story, coverage: &Coverage) -> Vec<(u32, String)> {
+        story
+            .get_coverage_report(coverage)
+            .knots
+            .into_iter()
+            .flat_map(|knot| knot.unseen)
+            .map(|unseen| (unseen.item.meta_data.line(), unseen.item.kind.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn lines_and_choices_which_were_not_followed_are_unseen() {
+        let content = "\
+Hello.
+*   Left
+    Went left.
+*   Right
+    Went right.
+-   Done.
+";
+
+        let mut story = play(content, &[0]);
+        let coverage = story.take_coverage().unwrap();
+
+        assert_eq!(
+            get_unseen(&story, &coverage),
+            &[
+                (4, "choice".to_string()),
+                (4, "line".to_string()),
+                (5, "line".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn unseen_alternative_and_conditional_branches_are_reported() {
+        let content = "\
+VAR rich = false
+{&Hi|Hello} {rich: sir|friend}.
+";
+
+        let mut story = play(content, &[]);
+        let coverage = story.take_coverage().unwrap();
+
+        assert_eq!(
+            get_unseen(&story, &coverage),
+            &[
+                (2, "alternative 2".to_string()),
+                (2, "condition true".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn merged_coverage_contains_items_from_both_sessions() {
+        let content = "\
+{&Hi|Hello}.
+*   Left
+*   Right
+";
+
+        let mut first = play(content, &[0]);
+        let mut second = play(content, &[1]);
+
+        let mut coverage = first.take_coverage().unwrap();
+        coverage.merge(&second.take_coverage().unwrap());
+
+        assert_eq!(
+            get_unseen(&first, &coverage),
+            &[(1, "alternative 2".to_string())]
+        );
+    }
+
+    #[test]
+    fn rewinding_keeps_recorded_coverage() {
+        let content = "\
+*   Left
+    Went left.
+*   Right
+";
+
+        let mut story = read_story_from_string(content).unwrap();
+        story.record_coverage();
+        story.set_history_limit(1);
+        story
+            .replay(&[ChoiceRef::from(0)], &mut Vec::new())
+            .unwrap();
+        story.rewind(1).unwrap();
+
+        let coverage = story.take_coverage().unwrap();
+
+        assert!(get_unseen(&story, &coverage)
+            .iter()
+            .all(|(line, _)| *line == 3));
+    }
+
+    #[test]
+    fn report_is_printed_per_knot_with_unseen_text() {
+        let content = "\
+-> tavern
+== tavern
+Dim light.
+{&Quiet|Loud} music.
+";
+
+        let mut story = play(content, &[]);
+        let coverage = story.take_coverage().unwrap();
+
+        assert_eq!(
+            story.get_coverage_report(&coverage).to_string(),
+            "\
+(start): 1/1 items seen
+tavern: 3/4 items seen
+    (line 4) alternative 2: Loud
+"
+        );
+    }
+}
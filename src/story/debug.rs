@@ -0,0 +1,391 @@
+//! Breakpoints, stepping and watches to debug a story while it is followed.
+//!
+//! The [`Debugger`] is carried with the follow data and checked before every line
+//! that is followed. When it asks to pause, the story returns
+//! [`Prompt::Breakpoint`][crate::story::Prompt::Breakpoint] with a [`Pause`] and keeps
+//! its position in the node stack, so that it can be resumed from the paused line.
+
+use crate::{
+    error::{utils::MetaData, InklingError},
+    follow::FollowData,
+    knot::Address,
+    line::{evaluate_expression, Expression, Variable},
+    story::{location_from_address, types::Location},
+};
+
+#[cfg(feature = "serde_support")]
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
+/// Position in a story to pause at.
+pub enum Breakpoint {
+    /// Pause before the first line that is followed after the knot or stitch is entered
+    /// from its beginning.
+    ///
+    /// A knot without a stitch refers to its default stitch, as for
+    /// [`move_to`][crate::story::Story::move_to()].
+    Location(Location),
+    /// Pause before the line with this number in the script is followed.
+    ///
+    /// Line numbers start from 1. Lines without content to follow, such as comments
+    /// or knot headers, never pause the story.
+    Line(u32),
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Breakpoint::Location(location) => write!(f, "'{}'", location),
+            Breakpoint::Line(line) => write!(f, "line {}", line),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
+/// How far to follow the story with [`step`][crate::story::Story::step()].
+pub enum Step {
+    /// Pause before the next line that is followed, including lines in knots and
+    /// stitches that the story diverts to.
+    Into,
+    /// Pause before the next line that is followed in the current knot or stitch.
+    ///
+    /// Lines in other knots and stitches are followed without pausing, until the story
+    /// returns or reaches a choice or an end.
+    Over,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
+/// Reason that the story paused.
+pub enum PauseReason {
+    /// A breakpoint was reached.
+    Breakpoint(Breakpoint),
+    /// A step was completed.
+    Step,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
+/// Position that the story paused at, before following a line.
+pub struct Pause {
+    /// Reason that the story paused.
+    pub reason: PauseReason,
+    /// Knot and stitch of the line.
+    pub location: Location,
+    /// Number of the line in the script, starting from 1.
+    pub line: u32,
+}
+
+impl fmt::Display for Pause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.reason {
+            PauseReason::Breakpoint(breakpoint) => write!(f, "Breakpoint at {}", breakpoint)?,
+            PauseReason::Step => write!(f, "Step")?,
+        }
+
+        write!(
+            f,
+            ": paused before line {} in '{}'",
+            self.line, self.location
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
+/// Level of nested choices in the node stack of the current knot or stitch.
+///
+/// See [`get_node_stack`][crate::story::Story::get_node_stack()].
+pub struct StackFrame {
+    /// Knot and stitch of the node.
+    pub location: Location,
+    /// Number of choices that were made in the knot or stitch to reach the node.
+    pub depth: usize,
+    /// Number of the line in the script that the node is at, starting from 1.
+    ///
+    /// This is the line which is followed next, the choice which was made to reach
+    /// the next level, or the first choice of a set which is presented. `None` if the
+    /// node has run out of content.
+    pub line: Option<u32>,
+}
+
+#[derive(Clone, Debug)]
+/// Watched expression with its value in the current state of the story.
+///
+/// See [`add_watch`][crate::story::Story::add_watch()].
+pub struct Watch {
+    /// Expression as it was added.
+    pub expression: String,
+    /// Value of the expression, or the error from evaluating it.
+    pub value: Result<Variable, InklingError>,
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(test, derive(PartialEq))]
+#[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
+/// Breakpoints, watches and the state of stepping through a story.
+pub struct Debugger {
+    /// Breakpoints to pause at, with the address of the stitch for location breakpoints.
+    breakpoints: Vec<(Breakpoint, Option<Address>)>,
+    /// Watched expressions as they were added, along with their parsed form.
+    watches: Vec<(String, Expression)>,
+    /// Active step and the address that it was made from.
+    step: Option<(Step, Address)>,
+    /// Address of the stitch which is currently followed, if the debugger is active.
+    address: Option<Address>,
+    /// Whether the current stitch was entered from its beginning and has not followed
+    /// a line since.
+    entered: bool,
+    /// Whether the story is paused.
+    paused: bool,
+    /// Whether the story is resuming from a pause and has not yet followed the line
+    /// that it paused at.
+    resuming: bool,
+}
+
+impl Debugger {
+    /// Whether lines have to be checked for pauses.
+    fn is_active(&self) -> bool {
+        !self.breakpoints.is_empty() || self.step.is_some()
+    }
+
+    /// Get the set breakpoints in the order that they were added.
+    pub fn get_breakpoints(&self) -> Vec<Breakpoint> {
+        self.breakpoints
+            .iter()
+            .map(|(breakpoint, _)| breakpoint.clone())
+            .collect()
+    }
+
+    /// Add a breakpoint, unless it has already been added.
+    ///
+    /// Location breakpoints need the address of the stitch that their location refers to.
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint, address: Option<Address>) {
+        if !self
+            .breakpoints
+            .iter()
+            .any(|(other, _)| other == &breakpoint)
+        {
+            self.breakpoints.push((breakpoint, address));
+        }
+    }
+
+    /// Remove a breakpoint. Returns whether it had been added.
+    pub fn remove_breakpoint(&mut self, breakpoint: &Breakpoint) -> bool {
+        let num_breakpoints = self.breakpoints.len();
+        self.breakpoints.retain(|(other, _)| other != breakpoint);
+
+        self.breakpoints.len() < num_breakpoints
+    }
+
+    /// Remove all breakpoints.
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    /// Get the watched expressions in the order that they were added.
+    pub fn get_watches(&self) -> &[(String, Expression)] {
+        &self.watches
+    }
+
+    /// Add a parsed expression to watch, unless it is already watched.
+    pub fn add_watch(&mut self, content: &str, expression: Expression) {
+        if !self.watches.iter().any(|(other, _)| other == content) {
+            self.watches.push((content.to_string(), expression));
+        }
+    }
+
+    /// Remove a watched expression. Returns whether it was watched.
+    pub fn remove_watch(&mut self, content: &str) -> bool {
+        let num_watches = self.watches.len();
+        self.watches.retain(|(other, _)| other != content);
+
+        self.watches.len() < num_watches
+    }
+
+    /// Whether the story is paused at a line.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Mark the story as paused after following it returned a `Pause`.
+    pub fn set_paused(&mut self) {
+        self.paused = true;
+    }
+
+    /// Stop the pause without following the paused line, for example after a move.
+    pub fn clear_pause(&mut self) {
+        self.paused = false;
+        self.resuming = false;
+    }
+
+    /// Prepare to resume the story, from the paused line if it is paused.
+    pub fn resume(&mut self) {
+        self.resuming = std::mem::take(&mut self.paused);
+    }
+
+    /// Take whether the story is resuming from a pause.
+    ///
+    /// Called when a node starts to follow its content. The node that the story paused
+    /// in is followed first, so it alone skips counting a visit and checking the paused
+    /// line again.
+    pub fn take_resuming(&mut self) -> bool {
+        std::mem::take(&mut self.resuming)
+    }
+
+    /// Start a step from the given address.
+    pub fn set_step(&mut self, step: Step, address: &Address) {
+        self.step.replace((step, address.clone()));
+    }
+
+    /// Stop an active step.
+    pub fn clear_step(&mut self) {
+        self.step = None;
+    }
+
+    /// Set the stitch which is about to be followed.
+    ///
+    /// `is_entry` marks that the stitch is followed from its beginning, which location
+    /// breakpoints pause at.
+    pub fn set_address(&mut self, address: &Address, is_entry: bool) {
+        if self.is_active() {
+            self.address.replace(address.clone());
+            self.entered = is_entry && !self.resuming;
+        }
+    }
+
+    /// Check whether to pause before following a line.
+    ///
+    /// If so, the active step is completed and the pause is returned.
+    pub fn check_line(&mut self, meta_data: &MetaData) -> Option<Pause> {
+        if !self.is_active() {
+            return None;
+        }
+
+        let entered = std::mem::take(&mut self.entered);
+        let address = self.address.as_ref()?;
+        let location = location_from_address(address);
+
+        let reason = match &self.step {
+            Some((Step::Into, _)) => Some(PauseReason::Step),
+            Some((Step::Over, from)) if from == address => Some(PauseReason::Step),
+            _ => None,
+        }
+        .or_else(|| {
+            self.breakpoints
+                .iter()
+                .find(|(breakpoint, breakpoint_address)| match breakpoint {
+                    Breakpoint::Location(..) => {
+                        entered && breakpoint_address.as_ref() == Some(address)
+                    }
+                    Breakpoint::Line(line) => *line == meta_data.line(),
+                })
+                .map(|(breakpoint, _)| PauseReason::Breakpoint(breakpoint.clone()))
+        })?;
+
+        self.step = None;
+
+        Some(Pause {
+            reason,
+            location,
+            line: meta_data.line(),
+        })
+    }
+}
+
+/// Evaluate the watched expressions with the current data of the story.
+pub(crate) fn evaluate_watches(watches: &[(String, Expression)], data: &FollowData) -> Vec<Watch> {
+    watches
+        .iter()
+        .map(|(content, expression)| Watch {
+            expression: content.clone(),
+            value: evaluate_expression(expression, data),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{consts::ROOT_KNOT_NAME, knot::AddressKind};
+
+    fn get_address(knot: &str, stitch: &str) -> Address {
+        Address::Validated(AddressKind::Location {
+            knot: knot.to_string(),
+            stitch: stitch.to_string(),
+        })
+    }
+
+    #[test]
+    fn inactive_debugger_never_pauses() {
+        let mut debugger = Debugger::default();
+        debugger.set_address(&get_address("tavern", "bar"), true);
+
+        assert!(debugger.check_line(&MetaData::from(0)).is_none());
+    }
+
+    #[test]
+    fn line_breakpoints_pause_at_their_line_number() {
+        let mut debugger = Debugger::default();
+        debugger.add_breakpoint(Breakpoint::Line(3), None);
+        debugger.set_address(&get_address("tavern", "bar"), false);
+
+        assert!(debugger.check_line(&MetaData::from(1)).is_none());
+
+        let pause = debugger.check_line(&MetaData::from(2)).unwrap();
+
+        assert_eq!(pause.reason, PauseReason::Breakpoint(Breakpoint::Line(3)));
+        assert_eq!(pause.location, Location::with_stitch("tavern", "bar"));
+        assert_eq!(pause.line, 3);
+    }
+
+    #[test]
+    fn location_breakpoints_only_pause_at_the_first_line_after_entering() {
+        let breakpoint = Breakpoint::Location(Location::with_stitch("tavern", "bar"));
+
+        let mut debugger = Debugger::default();
+        debugger.add_breakpoint(breakpoint, Some(get_address("tavern", "bar")));
+
+        debugger.set_address(&get_address("tavern", "bar"), false);
+        assert!(debugger.check_line(&MetaData::from(0)).is_none());
+
+        debugger.set_address(&get_address("tavern", "bar"), true);
+        assert!(debugger.check_line(&MetaData::from(0)).is_some());
+        assert!(debugger.check_line(&MetaData::from(1)).is_none());
+    }
+
+    #[test]
+    fn stepping_over_only_pauses_in_the_stitch_it_was_made_from() {
+        let mut debugger = Debugger::default();
+        debugger.set_step(Step::Over, &get_address("tavern", "bar"));
+
+        debugger.set_address(&get_address("street", ROOT_KNOT_NAME), true);
+        assert!(debugger.check_line(&MetaData::from(0)).is_none());
+
+        debugger.set_address(&get_address("tavern", "bar"), false);
+        let pause = debugger.check_line(&MetaData::from(1)).unwrap();
+
+        assert_eq!(pause.reason, PauseReason::Step);
+        assert!(debugger.check_line(&MetaData::from(2)).is_none());
+    }
+
+    #[test]
+    fn resuming_is_only_taken_once_after_a_pause() {
+        let mut debugger = Debugger::default();
+
+        debugger.resume();
+        assert!(!debugger.take_resuming());
+
+        debugger.set_paused();
+        debugger.resume();
+
+        assert!(!debugger.is_paused());
+        assert!(debugger.take_resuming());
+        assert!(!debugger.take_resuming());
+    }
+}
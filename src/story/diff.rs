@@ -0,0 +1,752 @@
+//! Structural differences between two versions of a story.
+
+use crate::{
+    consts::{CHOICE_MARKER, ROOT_KNOT_NAME, STICKY_CHOICE_MARKER},
+    knot::{Address, AddressKind, KnotSet},
+    line::Variable,
+    node::{Branch, NodeItem},
+    story::{
+        format::write_knot_content,
+        types::{Location, VariableSet},
+    },
+};
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    mem::discriminant,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+/// Differences in structure between a story and a new version of it.
+///
+/// Retrieved with [`diff`][crate::story::Story::diff()]. All lists are sorted by name.
+///
+/// A knot or stitch which was removed while another with the same content was added
+/// is considered renamed. References from a knot or stitch to itself are compared as if
+/// they had the same name, so a knot which diverts to itself can also be renamed.
+pub struct StoryDiff {
+    /// Named knots which are only in the new version.
+    pub added_knots: Vec<String>,
+    /// Named knots which are only in the old version.
+    pub removed_knots: Vec<String>,
+    /// Knots which have new names, as pairs of old and new names.
+    pub renamed_knots: Vec<(String, String)>,
+    /// Named stitches which are only in the new version, in knots which are in both.
+    pub added_stitches: Vec<Location>,
+    /// Named stitches which are only in the old version, in knots which are in both.
+    pub removed_stitches: Vec<Location>,
+    /// Stitches which have new names in the same knot, as pairs of old and new locations.
+    pub renamed_stitches: Vec<(Location, Location)>,
+    /// Global variables which are only in the new version.
+    pub added_variables: Vec<String>,
+    /// Global variables which are only in the old version.
+    pub removed_variables: Vec<String>,
+    /// Global variables which are declared with a different value or type.
+    pub changed_variables: Vec<VariableChange>,
+    /// Sets of choices which changed in stitches that are in both versions.
+    pub changed_choices: Vec<ChoiceSetChange>,
+    /// Whether the position of the old story can be resumed from in the new version.
+    pub saved_position: SavedPosition,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Global variable which is declared with different values in two versions of a story.
+pub struct VariableChange {
+    /// Name of the variable.
+    pub name: String,
+    /// Declared value in the old version.
+    pub old: Variable,
+    /// Declared value in the new version.
+    pub new: Variable,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Set of choices which is different, or only exists, in one version of a story.
+pub struct ChoiceSetChange {
+    /// Name of knot, or `None` for the content before the first knot in the story.
+    pub knot: Option<String>,
+    /// Name of stitch, or `None` for the content before the first stitch in the knot.
+    pub stitch: Option<String>,
+    /// Position of the set in the stitch, as the stack of choices which leads to it.
+    pub stack: Vec<usize>,
+    /// Choices in the old version. Empty if the set is new.
+    ///
+    /// Choices are written with their marker followed by their selection text in brackets,
+    /// as in `* [Board the ship]`. Text which is only shown after selecting a choice
+    /// is not part of the set.
+    pub old_choices: Vec<String>,
+    /// Choices in the new version. Empty if the set was removed.
+    pub new_choices: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Whether a story can continue from where it is in a new version of it.
+pub enum SavedPosition {
+    /// The current stitch exists and the choices made in it lead to the same sets
+    /// of choices in the new version.
+    Valid,
+    /// The current knot or stitch does not exist in the new version.
+    MissingLocation(Location),
+    /// The choices made in the current stitch do not lead to the same sets of choices
+    /// in the new version.
+    ChangedStack {
+        /// Location of the current stitch.
+        location: Location,
+        /// Stack of choices made in the stitch.
+        stack: Vec<usize>,
+    },
+}
+
+impl VariableChange {
+    /// Whether the variable has a different type in the new version.
+    pub fn is_type_change(&self) -> bool {
+        discriminant(&self.old) != discriminant(&self.new)
+    }
+}
+
+impl StoryDiff {
+    /// Whether the two versions have the same knots, stitches, variables and choices.
+    ///
+    /// The saved position is not considered.
+    pub fn is_empty(&self) -> bool {
+        self.added_knots.is_empty()
+            && self.removed_knots.is_empty()
+            && self.renamed_knots.is_empty()
+            && self.added_stitches.is_empty()
+            && self.removed_stitches.is_empty()
+            && self.renamed_stitches.is_empty()
+            && self.added_variables.is_empty()
+            && self.removed_variables.is_empty()
+            && self.changed_variables.is_empty()
+            && self.changed_choices.is_empty()
+    }
+}
+
+/// Compare the knots and variables of a story to those of a new version.
+///
+/// The position of the old story is its current address and the stack of its stitch.
+pub fn get_story_diff(
+    old_knots: &KnotSet,
+    old_variables: &VariableSet,
+    current_address: &Address,
+    new_knots: &KnotSet,
+    new_variables: &VariableSet,
+) -> StoryDiff {
+    let (added_knots, removed_knots, renamed_knots) = get_name_changes(
+        get_knot_names(old_knots),
+        get_knot_names(new_knots),
+        |name| normalize_self_references(&write_knot_content(&old_knots[name]), name),
+        |name| normalize_self_references(&write_knot_content(&new_knots[name]), name),
+    );
+
+    let mut added_stitches = Vec::new();
+    let mut removed_stitches = Vec::new();
+    let mut renamed_stitches = Vec::new();
+    let mut changed_choices = Vec::new();
+
+    let mut common_knots = old_knots
+        .keys()
+        .filter(|name| new_knots.contains_key(*name))
+        .collect::<Vec<_>>();
+    common_knots.sort();
+
+    for knot_name in common_knots {
+        let old_knot = &old_knots[knot_name];
+        let new_knot = &new_knots[knot_name];
+
+        let (added, removed, renamed) = get_name_changes(
+            get_stitch_names(old_knot.stitches.keys()),
+            get_stitch_names(new_knot.stitches.keys()),
+            |name| {
+                let content = old_knot.stitches[name].root.to_string();
+                normalize_self_references(&content, &format!("{}.{}", knot_name, name))
+            },
+            |name| {
+                let content = new_knot.stitches[name].root.to_string();
+                normalize_self_references(&content, &format!("{}.{}", knot_name, name))
+            },
+        );
+
+        let location = |stitch: String| Location::with_stitch(knot_name.clone(), stitch);
+
+        added_stitches.extend(added.into_iter().map(location));
+        removed_stitches.extend(removed.into_iter().map(location));
+        renamed_stitches.extend(
+            renamed
+                .into_iter()
+                .map(|(old, new)| (location(old), location(new))),
+        );
+
+        let mut common_stitches = old_knot
+            .stitches
+            .keys()
+            .filter(|name| new_knot.stitches.contains_key(*name))
+            .collect::<Vec<_>>();
+        common_stitches.sort();
+
+        for stitch_name in common_stitches {
+            let old_sets = get_choice_sets(&old_knot.stitches[stitch_name].root.items);
+            let new_sets = get_choice_sets(&new_knot.stitches[stitch_name].root.items);
+
+            let stacks = old_sets
+                .keys()
+                .chain(new_sets.keys())
+                .collect::<BTreeSet<_>>();
+
+            for stack in stacks {
+                let old_choices = old_sets.get(stack).cloned().unwrap_or_default();
+                let new_choices = new_sets.get(stack).cloned().unwrap_or_default();
+
+                if old_choices != new_choices {
+                    changed_choices.push(ChoiceSetChange {
+                        knot: Some(knot_name.clone()).filter(|name| name != ROOT_KNOT_NAME),
+                        stitch: Some(stitch_name.clone()).filter(|name| name != ROOT_KNOT_NAME),
+                        stack: stack.clone(),
+                        old_choices,
+                        new_choices,
+                    });
+                }
+            }
+        }
+    }
+
+    let (added_variables, removed_variables) = get_added_and_removed(
+        old_variables.keys().cloned().collect(),
+        new_variables.keys().cloned().collect(),
+    );
+
+    let mut changed_variables = old_variables
+        .iter()
+        .filter_map(|(name, old)| {
+            new_variables
+                .get(name)
+                .filter(|new| new.get_declared() != old.get_declared())
+                .map(|new| VariableChange {
+                    name: name.clone(),
+                    old: old.get_declared().clone(),
+                    new: new.get_declared().clone(),
+                })
+        })
+        .collect::<Vec<_>>();
+    changed_variables.sort_by(|a, b| a.name.cmp(&b.name));
+
+    StoryDiff {
+        added_knots,
+        removed_knots,
+        renamed_knots,
+        added_stitches,
+        removed_stitches,
+        renamed_stitches,
+        added_variables,
+        removed_variables,
+        changed_variables,
+        changed_choices,
+        saved_position: get_saved_position(current_address, old_knots, new_knots),
+    }
+}
+
+fn get_knot_names(knots: &KnotSet) -> Vec<String> {
+    get_stitch_names(knots.keys())
+}
+
+/// Get the names which are not the unnamed content at the start of the story or a knot.
+fn get_stitch_names<'a, I: Iterator<Item = &'a String>>(names: I) -> Vec<String> {
+    names
+        .filter(|name| name.as_str() != ROOT_KNOT_NAME)
+        .cloned()
+        .collect()
+}
+
+/// Get the sorted names which were added and removed between two sets of names.
+fn get_added_and_removed(old: Vec<String>, new: Vec<String>) -> (Vec<String>, Vec<String>) {
+    let mut added = new
+        .iter()
+        .filter(|name| !old.contains(name))
+        .cloned()
+        .collect::<Vec<_>>();
+    let mut removed = old
+        .iter()
+        .filter(|name| !new.contains(name))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    added.sort();
+    removed.sort();
+
+    (added, removed)
+}
+
+/// Get the names which were added, removed and renamed between two sets of names.
+///
+/// A removed name is renamed to the first added name with the same content.
+fn get_name_changes<F, G>(
+    old: Vec<String>,
+    new: Vec<String>,
+    get_old_content: F,
+    get_new_content: G,
+) -> (Vec<String>, Vec<String>, Vec<(String, String)>)
+where
+    F: Fn(&str) -> String,
+    G: Fn(&str) -> String,
+{
+    let (mut added, mut removed) = get_added_and_removed(old, new);
+    let mut renamed = Vec::new();
+
+    removed.retain(|old_name| {
+        let content = get_old_content(old_name);
+
+        match added
+            .iter()
+            .position(|new_name| get_new_content(new_name) == content)
+        {
+            Some(i) => {
+                renamed.push((old_name.clone(), added.remove(i)));
+                false
+            }
+            None => true,
+        }
+    });
+
+    (added, removed, renamed)
+}
+
+/// Replace the address of a knot or stitch in its written content with a placeholder.
+///
+/// Content is written with full addresses, so a knot or stitch which refers to itself
+/// would not match its content under a new name. The address is replaced where it is
+/// a whole word or the knot of a longer address.
+fn normalize_self_references(content: &str, address: &str) -> String {
+    let is_name_char = |c: char| c.is_alphanumeric() || c == '_';
+
+    let mut normalized = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(i) = rest.find(address) {
+        let (head, tail) = rest.split_at(i);
+        let after = &tail[address.len()..];
+
+        let is_whole_address = !head.ends_with(|c: char| is_name_char(c) || c == '.')
+            && !after.starts_with(is_name_char);
+
+        normalized.push_str(head);
+
+        if is_whole_address {
+            normalized.push('\0');
+        } else {
+            normalized.push_str(address);
+        }
+
+        rest = after;
+    }
+
+    normalized.push_str(rest);
+    normalized
+}
+
+/// Get the choices of every set in a stitch by the stack which leads to the set.
+fn get_choice_sets(items: &[NodeItem]) -> HashMap<Vec<usize>, Vec<String>> {
+    let mut sets = HashMap::new();
+    add_choice_sets(items, &mut Vec::new(), &mut sets);
+
+    sets
+}
+
+fn add_choice_sets(
+    items: &[NodeItem],
+    stack: &mut Vec<usize>,
+    sets: &mut HashMap<Vec<usize>, Vec<String>>,
+) {
+    for (i, item) in items.iter().enumerate() {
+        if let NodeItem::BranchingPoint(branches) = item {
+            stack.push(i);
+            sets.insert(stack.clone(), get_choice_texts(branches));
+
+            for (j, branch) in branches.iter().enumerate() {
+                stack.push(j);
+                add_choice_sets(&branch.items, stack, sets);
+                stack.pop();
+            }
+
+            stack.pop();
+        }
+    }
+}
+
+/// Write the choices of a set by their markers and selection texts.
+fn get_choice_texts(branches: &[Branch]) -> Vec<String> {
+    branches
+        .iter()
+        .map(|branch| {
+            let marker = if branch.choice.is_sticky {
+                STICKY_CHOICE_MARKER
+            } else {
+                CHOICE_MARKER
+            };
+
+            let selection_text = branch.choice.selection_text.lock().unwrap();
+            format!("{} [{}]", marker, selection_text.to_string().trim())
+        })
+        .collect()
+}
+
+/// Check whether the current stitch and its stack resolve to the same content in the
+/// new version.
+fn get_saved_position(address: &Address, old: &KnotSet, new: &KnotSet) -> SavedPosition {
+    get_position(address, old, new, get_choice_texts)
+}
+
+/// Check whether the current stitch and its stack resolve to the same structure in the
+/// new version.
+///
+/// Only the markers of the choices in every set are compared, so choices may have new text.
+pub(crate) fn get_reload_position(
+    address: &Address,
+    old: &KnotSet,
+    new: &KnotSet,
+) -> SavedPosition {
+    get_position(address, old, new, get_choice_markers)
+}
+
+/// Check whether the stack of the current stitch passes through sets of choices which
+/// are the same in both versions, when compared by the given function.
+fn get_position<T, F>(address: &Address, old: &KnotSet, new: &KnotSet, get_set: F) -> SavedPosition
+where
+    T: PartialEq,
+    F: Fn(&[Branch]) -> T,
+{
+    let (knot, stitch) = match address {
+        Address::Validated(AddressKind::Location { knot, stitch }) => (knot, stitch),
+        _ => return SavedPosition::Valid,
+    };
+
+    let location = Location::new(
+        knot.as_str(),
+        Some(stitch.as_str()).filter(|name| *name != ROOT_KNOT_NAME),
+    );
+
+    let old_stitch = old.get(knot).and_then(|knot| knot.stitches.get(stitch));
+    let new_stitch = new.get(knot).and_then(|knot| knot.stitches.get(stitch));
+
+    match (old_stitch, new_stitch) {
+        (Some(old_stitch), Some(new_stitch)) => {
+            let stack = &old_stitch.stack;
+            let old_sets = resolve_stack(stack, &old_stitch.root.items, &get_set);
+
+            if old_sets.is_some()
+                && old_sets == resolve_stack(stack, &new_stitch.root.items, &get_set)
+            {
+                SavedPosition::Valid
+            } else {
+                SavedPosition::ChangedStack {
+                    location,
+                    stack: stack.clone(),
+                }
+            }
+        }
+        _ => SavedPosition::MissingLocation(location),
+    }
+}
+
+/// Write the markers of the choices in a set.
+fn get_choice_markers(branches: &[Branch]) -> Vec<char> {
+    branches
+        .iter()
+        .map(|branch| {
+            if branch.choice.is_sticky {
+                STICKY_CHOICE_MARKER
+            } else {
+                CHOICE_MARKER
+            }
+        })
+        .collect()
+}
+
+/// Get every set of choices that a stack passes through, or `None` if it cannot be
+/// followed through the items.
+///
+/// The stack alternates between the index of an item and the index of a branch in it,
+/// ending with the index of the item that the story is at.
+fn resolve_stack<T, F>(stack: &[usize], items: &[NodeItem], get_set: &F) -> Option<Vec<T>>
+where
+    F: Fn(&[Branch]) -> T,
+{
+    let mut items = items;
+    let mut sets = Vec::new();
+    let mut iter = stack.iter();
+
+    while let Some(&i) = iter.next() {
+        match (items.get(i), iter.next()) {
+            (Some(NodeItem::BranchingPoint(branches)), branch_index) => {
+                sets.push(get_set(branches));
+
+                match branch_index {
+                    Some(&j) => items = &branches.get(j)?.items,
+                    None => break,
+                }
+            }
+            (Some(NodeItem::Line(..)), None) => (),
+            (None, None) if i == items.len() => (),
+            _ => return None,
+        }
+    }
+
+    Some(sets)
+}
+
+/// Get the branches of the set of choices that a stack ends at, or `None` if it does
+/// not end at one.
+pub(crate) fn get_branches_at_stack<'a>(
+    stack: &[usize],
+    items: &'a [NodeItem],
+) -> Option<&'a [Branch]> {
+    let (&last, path) = stack.split_last()?;
+    let mut items = items;
+
+    for pair in path.chunks(2) {
+        match (items.get(pair[0]), pair.get(1)) {
+            (Some(NodeItem::BranchingPoint(branches)), Some(&j)) => items = &branches.get(j)?.items,
+            _ => return None,
+        }
+    }
+
+    match items.get(last) {
+        Some(NodeItem::BranchingPoint(branches)) => Some(branches),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::story::{read_story_from_string, Story};
+
+    const CONTENT: &str = "\
+VAR gold = 3
+VAR name = \"Ali\"
+-> market
+
+== market
+The market is busy.
+*   [Buy fish] -> stall
+*   [Leave] -> END
+
+= stall
+You look at the fish.
+*   [Haggle] -> END
+*   [Pay] -> END
+
+== harbor
+The boats are in.
+-> END
+";
+
+    fn diff(old: &str, new: &str) -> StoryDiff {
+        let old = read_story_from_string(old).unwrap();
+        let new = read_story_from_string(new).unwrap();
+
+        old.diff(&new)
+    }
+
+    fn resumed(content: &str, choices: &[usize]) -> Story {
+        let mut story = read_story_from_string(content).unwrap();
+        story.resume(&mut Vec::new()).unwrap();
+
+        for &choice in choices {
+            story.make_choice(choice).unwrap();
+            story.resume(&mut Vec::new()).unwrap();
+        }
+
+        story
+    }
+
+    #[test]
+    fn equal_stories_have_an_empty_diff() {
+        let diff = diff(CONTENT, CONTENT);
+
+        assert!(diff.is_empty());
+        assert_eq!(diff.saved_position, SavedPosition::Valid);
+    }
+
+    #[test]
+    fn knots_with_the_same_content_under_a_new_name_are_renamed() {
+        let new = CONTENT
+            .replace("== harbor", "== docks")
+            .replace("= stall", "= counter")
+            .replace("-> stall", "-> counter")
+            .replace("The market is busy.", "The market is quiet.");
+
+        let diff = diff(CONTENT, &format!("{}\n== square\nEmpty.\n-> END", new));
+
+        assert_eq!(
+            diff.renamed_knots,
+            &[("harbor".to_string(), "docks".to_string())]
+        );
+        assert_eq!(diff.added_knots, &["square"]);
+        assert!(diff.removed_knots.is_empty());
+
+        assert_eq!(
+            diff.renamed_stitches,
+            &[(
+                Location::with_stitch("market", "stall"),
+                Location::with_stitch("market", "counter")
+            )]
+        );
+    }
+
+    #[test]
+    fn knots_and_stitches_which_divert_to_themselves_can_be_renamed() {
+        let content = "\
+-> loop
+== loop
+Again?
++   [Yes] -> loop
++   [Inner] -> inner
++   [No] -> END
+= inner
++   [Again] -> inner
++   [Back] -> loop
+";
+
+        let knot_diff = diff(content, &content.replace("loop", "cycle"));
+
+        assert_eq!(
+            knot_diff.renamed_knots,
+            &[("loop".to_string(), "cycle".to_string())]
+        );
+        assert!(knot_diff.added_knots.is_empty());
+        assert!(knot_diff.removed_knots.is_empty());
+
+        let stitches = "\
+== loop
+-> inner
+= inner
++   [Again] -> inner
++   [Back] -> END
+";
+
+        let stitch_diff = diff(stitches, &stitches.replace("inner", "nested"));
+
+        assert_eq!(
+            stitch_diff.renamed_stitches,
+            &[(
+                Location::with_stitch("loop", "inner"),
+                Location::with_stitch("loop", "nested")
+            )]
+        );
+    }
+
+    #[test]
+    fn references_to_other_knots_are_not_normalized() {
+        let content = "\
+== loop
++   [Again] -> loop
++   [Leave] -> loop_end
+== loop_end
+-> END
+";
+
+        assert_eq!(
+            normalize_self_references("+   [Again] -> loop\n+   [Leave] -> loop_end", "loop"),
+            "+   [Again] -> \0\n+   [Leave] -> loop_end"
+        );
+
+        let new = content
+            .replace("== loop\n", "== cycle\n")
+            .replace("-> loop\n", "-> cycle\n")
+            .replace("loop_end", "finish");
+
+        let diff = diff(content, &new);
+
+        assert_eq!(diff.removed_knots, &["loop"]);
+        assert_eq!(
+            diff.renamed_knots,
+            &[("loop_end".to_string(), "finish".to_string())]
+        );
+    }
+
+    #[test]
+    fn variables_are_compared_by_their_declared_values() {
+        let mut old = read_story_from_string(CONTENT).unwrap();
+        old.set_variable("gold", 10).unwrap();
+
+        let new = read_story_from_string(CONTENT).unwrap();
+        assert!(old.diff(&new).changed_variables.is_empty());
+
+        let new = read_story_from_string(&CONTENT.replace("VAR gold = 3", "VAR gold = 4")).unwrap();
+        let changes = old.diff(&new).changed_variables;
+
+        assert_eq!(
+            changes,
+            &[VariableChange {
+                name: "gold".to_string(),
+                old: Variable::Int(3),
+                new: Variable::Int(4),
+            }]
+        );
+    }
+
+    #[test]
+    fn variables_with_new_values_or_types_are_changed() {
+        let new = CONTENT
+            .replace("VAR gold = 3", "VAR gold = 3.0")
+            .replace("VAR name = \"Ali\"", "VAR title = \"Ali\"");
+
+        let diff = diff(CONTENT, &new);
+
+        assert_eq!(diff.added_variables, &["title"]);
+        assert_eq!(diff.removed_variables, &["name"]);
+        assert_eq!(diff.changed_variables.len(), 1);
+        assert!(diff.changed_variables[0].is_type_change());
+    }
+
+    #[test]
+    fn changed_choice_sets_are_found_by_their_stack() {
+        let new = CONTENT.replace("*   [Pay] -> END", "*   [Pay] -> END\n*   [Steal] -> END");
+        let diff = diff(CONTENT, &new);
+
+        assert_eq!(diff.changed_choices.len(), 1);
+
+        let change = &diff.changed_choices[0];
+        assert_eq!(change.knot.as_deref(), Some("market"));
+        assert_eq!(change.stitch.as_deref(), Some("stall"));
+        assert_eq!(change.stack, &[1]);
+        assert_eq!(change.old_choices.len(), 2);
+        assert_eq!(change.new_choices[2], "* [Steal]");
+    }
+
+    #[test]
+    fn saved_position_is_valid_if_the_stack_leads_to_the_same_choices() {
+        let story = resumed(CONTENT, &[0]);
+
+        let appended = format!("{}\n== square\nEmpty.\n-> END", CONTENT);
+        let new_line = CONTENT.replace("You look at the fish.", "You look.\nFish!");
+        let new_choice = CONTENT.replace("*   [Pay] -> END", "*   [Pay] -> END\n*   [Go] -> END");
+        let removed = CONTENT
+            .replace("= stall", "= counter")
+            .replace("-> stall", "-> counter");
+
+        let position = |content: &str| {
+            story
+                .diff(&read_story_from_string(content).unwrap())
+                .saved_position
+        };
+
+        assert_eq!(position(&appended), SavedPosition::Valid);
+        assert_eq!(
+            position(&new_line),
+            SavedPosition::ChangedStack {
+                location: Location::with_stitch("market", "stall"),
+                stack: vec![1]
+            }
+        );
+        assert!(matches!(
+            position(&new_choice),
+            SavedPosition::ChangedStack { .. }
+        ));
+        assert_eq!(
+            position(&removed),
+            SavedPosition::MissingLocation(Location::with_stitch("market", "stall"))
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Reading expressions and conditions which are given to a story from outside its script.
+//!
+//! These are parsed with the same functions as lines in the script and validated against
+//! the knots and variables of the story, so that they can be evaluated with its follow data.
+
+use crate::{
+    error::{
+        parse::validate::ValidationError, utils::MetaData, InklingError, InvalidExpressionKind,
+    },
+    knot::{Address, KnotSet},
+    line::{parse_condition, parse_expression, Condition, Expression},
+    log::Logger,
+    story::{
+        types::VariableSet,
+        validate::{ValidateContent, ValidationData},
+    },
+};
+
+/// Parse an expression given to the story and validate it against its content.
+///
+/// Names of knots and stitches are resolved from the given address, as they would be
+/// in the script at that address.
+pub(crate) fn parse_story_expression(
+    content: &str,
+    current_address: &Address,
+    knots: &KnotSet,
+    variables: &VariableSet,
+) -> Result<Expression, InklingError> {
+    let mut expression = parse_expression(content)
+        .map_err(|err| get_error(content, InvalidExpressionKind::Parse(Box::new(err))))?;
+
+    validate_story_item(&mut expression, content, current_address, knots, variables)?;
+
+    Ok(expression)
+}
+
+/// Parse a condition given to the story and validate it against its content.
+///
+/// The condition is written as inside the braces of a conditional line in the script,
+/// without the braces. Names are resolved as for
+/// [`parse_story_expression`][crate::story::evaluate::parse_story_expression].
+pub(crate) fn parse_story_condition(
+    content: &str,
+    current_address: &Address,
+    knots: &KnotSet,
+    variables: &VariableSet,
+) -> Result<Condition, InklingError> {
+    let mut condition = parse_condition(content).map_err(|err| {
+        get_error(
+            content,
+            InvalidExpressionKind::ParseCondition(Box::new(err)),
+        )
+    })?;
+
+    validate_story_item(&mut condition, content, current_address, knots, variables)?;
+
+    Ok(condition)
+}
+
+/// Validate a parsed item against the knots and variables of the story.
+///
+/// Notes from validating are discarded, since the item is not part of the script.
+fn validate_story_item<T: ValidateContent>(
+    item: &mut T,
+    content: &str,
+    current_address: &Address,
+    knots: &KnotSet,
+    variables: &VariableSet,
+) -> Result<(), InklingError> {
+    let mut error = ValidationError::new();
+
+    item.validate(
+        &mut error,
+        &mut Logger::default(),
+        current_address,
+        &MetaData::from(0),
+        &ValidationData::from_data(knots, variables),
+    );
+
+    if error.is_empty() {
+        Ok(())
+    } else {
+        Err(get_error(
+            content,
+            InvalidExpressionKind::Validation(Box::new(error)),
+        ))
+    }
+}
+
+fn get_error(content: &str, kind: InvalidExpressionKind) -> InklingError {
+    InklingError::InvalidExpression {
+        expression: content.to_string(),
+        kind,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        knot::AddressKind, line::ConditionKind, story::parse::read_story_content_from_string,
+    };
+
+    fn get_story_data() -> (Address, KnotSet, VariableSet) {
+        let content = "\
+VAR coins = 2
+
+== tavern
+= bar
+You have {coins} coins.
+";
+
+        let (knots, variables, _) =
+            read_story_content_from_string(content, &mut Logger::default()).unwrap();
+
+        let address = Address::Validated(AddressKind::Location {
+            knot: "tavern".to_string(),
+            stitch: "bar".to_string(),
+        });
+
+        (address, knots, variables)
+    }
+
+    #[test]
+    fn expressions_and_conditions_with_known_names_are_parsed() {
+        let (address, knots, variables) = get_story_data();
+
+        assert!(parse_story_expression("coins * 2 + bar", &address, &knots, &variables).is_ok());
+
+        let condition =
+            parse_story_condition("coins > 1 and tavern", &address, &knots, &variables).unwrap();
+
+        match condition.root.kind {
+            ConditionKind::Single(..) => assert_eq!(condition.items.len(), 1),
+            other => panic!("expected a single condition, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn conditions_which_cannot_be_parsed_yield_condition_errors() {
+        let (address, knots, variables) = get_story_data();
+
+        match parse_story_condition("coins > 1 and", &address, &knots, &variables) {
+            Err(InklingError::InvalidExpression {
+                expression,
+                kind: InvalidExpressionKind::ParseCondition(..),
+            }) => assert_eq!(&expression, "coins > 1 and"),
+            other => panic!("expected a condition error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unknown_names_yield_validation_errors() {
+        let (address, knots, variables) = get_story_data();
+
+        match parse_story_condition("gold > 1", &address, &knots, &variables) {
+            Err(InklingError::InvalidExpression {
+                kind: InvalidExpressionKind::Validation(error),
+                ..
+            }) => assert!(!error.is_empty()),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+
+        assert!(parse_story_expression("gold + 1", &address, &knots, &variables).is_err());
+    }
+}
@@ -0,0 +1,666 @@
+//! Automated playthroughs of a story to find runtime errors and dead ends.
+
+use crate::{
+    error::InklingError,
+    story::{
+        story::Story,
+        types::{ChoiceRef, LineBuffer, Location, Prompt},
+    },
+};
+
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// How choices are picked when exploring a story.
+pub enum ExploreStrategy {
+    /// Pick choices at random, with random numbers drawn from the given seed.
+    ///
+    /// Every playthrough starts from the beginning and picks new choices. Shuffle sequences
+    /// in the story are also seeded from this, so the same seed always yields the same
+    /// playthroughs.
+    Random { seed: u64 },
+    /// Try every combination of choices, in order of choice index.
+    Exhaustive,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Options for [`explore`][crate::story::Story::explore()].
+pub struct ExploreOptions {
+    /// How to pick choices.
+    pub strategy: ExploreStrategy,
+    /// Maximum number of playthroughs to run.
+    pub max_playthroughs: usize,
+    /// Maximum number of choices to make in a playthrough before it is stopped.
+    pub max_depth: usize,
+    /// Maximum number of diverts to follow without reaching a choice or an end, after
+    /// which the story is considered to be stuck in a loop.
+    pub divert_limit: usize,
+}
+
+impl Default for ExploreOptions {
+    fn default() -> Self {
+        ExploreOptions {
+            strategy: ExploreStrategy::Random { seed: 0 },
+            max_playthroughs: 100,
+            max_depth: 50,
+            divert_limit: 1000,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Result of exploring a story.
+pub struct ExploreReport {
+    /// Number of playthroughs that were run.
+    pub num_playthroughs: usize,
+    /// Number of playthroughs which were stopped after making the maximum number of choices.
+    pub num_stopped_at_max_depth: usize,
+    /// Issues that were found, each with the shortest found list of choices that leads to it.
+    ///
+    /// Issues of the same kind at the same location are only reported once.
+    pub issues: Vec<ExploreIssue>,
+}
+
+#[derive(Clone, Debug)]
+/// Issue found while exploring a story.
+pub struct ExploreIssue {
+    /// Kind of issue.
+    pub kind: ExploreIssueKind,
+    /// Location of the story when the issue was found.
+    pub location: Location,
+    /// Indices of the choices to make from the start to reproduce the issue.
+    pub choices: Vec<usize>,
+    /// Seed that the random number generator of the story was set to for the playthrough.
+    ///
+    /// Only set with the random strategy when the `random` feature is enabled, since the
+    /// story is then reseeded for every playthrough.
+    pub seed: Option<u64>,
+}
+
+#[derive(Clone, Debug)]
+/// Kind of issue found while exploring a story.
+pub enum ExploreIssueKind {
+    /// An error was returned from the story.
+    Error(InklingError),
+    /// The story kept diverting without reaching a choice or an end.
+    LoopWithoutChoices,
+    /// The story ran out of content without diverting to `END` or `DONE`.
+    NoEnd,
+}
+
+impl ExploreReport {
+    /// Whether no issues were found.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Add an issue if one of the same kind has not been found at the location, or replace
+    /// it if the new issue is reached with fewer choices.
+    fn add_issue(&mut self, issue: ExploreIssue) {
+        let key = issue.get_key();
+
+        match self
+            .issues
+            .iter_mut()
+            .find(|existing| existing.get_key() == key)
+        {
+            Some(existing) if issue.choices.len() < existing.choices.len() => *existing = issue,
+            Some(..) => (),
+            None => self.issues.push(issue),
+        }
+    }
+}
+
+impl ExploreIssue {
+    /// Reproduce the issue on a clone of the explored story.
+    ///
+    /// The story is reseeded with the seed of the playthrough, if it has one, before
+    /// the choices are replayed with [`replay`][crate::story::Story::replay()]. Loops
+    /// without choices are only returned as an error if a divert limit has been set
+    /// with [`set_divert_limit`][crate::story::Story::set_divert_limit()].
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{read_story_from_string, ExploreOptions};
+    /// let content = "\
+    /// *   Left -> END
+    /// *   Right
+    ///     Nothing more.
+    /// ";
+    ///
+    /// let story = read_story_from_string(content).unwrap();
+    /// let report = story.explore(&ExploreOptions::default());
+    ///
+    /// let mut line_buffer = Vec::new();
+    /// report.issues[0].replay(&mut story.clone(), &mut line_buffer).unwrap();
+    ///
+    /// assert_eq!(&line_buffer.last().unwrap().text, "Nothing more.\n");
+    /// ```
+    ///
+    /// # Errors
+    /// Any error from [`replay`][crate::story::Story::replay()].
+    pub fn replay(
+        &self,
+        story: &mut Story,
+        line_buffer: &mut LineBuffer,
+    ) -> Result<Prompt, InklingError> {
+        #[cfg(feature = "random")]
+        if let Some(seed) = self.seed {
+            story.set_seed(seed);
+        }
+
+        let choices = self
+            .choices
+            .iter()
+            .map(|&index| ChoiceRef::from(index))
+            .collect::<Vec<_>>();
+
+        story.replay(&choices, line_buffer)
+    }
+
+    /// Get a key which identifies the kind and location of the issue.
+    fn get_key(&self) -> (String, Location) {
+        let kind = match &self.kind {
+            ExploreIssueKind::Error(err) => err.to_string(),
+            other => format!("{:?}", other),
+        };
+
+        (kind, self.location.clone())
+    }
+}
+
+/// Result of following a story from a branching point.
+enum Step {
+    /// Choices were presented, with the number of them.
+    Choice(usize),
+    /// The playthrough is over.
+    Stopped,
+}
+
+/// Explore a story by following it through many playthroughs.
+///
+/// Every playthrough starts from a clone of the story in its current state, without
+/// its history, coverage, transcript or random source. With the random strategy the
+/// generator of the story is reseeded from the explore seed for every playthrough.
+pub fn explore_story(story: &Story, options: &ExploreOptions) -> ExploreReport {
+    let mut report = ExploreReport {
+        num_playthroughs: 0,
+        num_stopped_at_max_depth: 0,
+        issues: Vec::new(),
+    };
+
+    let mut start = story.clone();
+    start.clear_tracer();
+    start.clear_breakpoints();
+    start.set_divert_limit(Some(options.divert_limit));
+
+    // Playthroughs are never rewound or recorded, and must not draw numbers from
+    // a source which is shared with the caller
+    start.set_history_limit(0);
+    start.take_coverage();
+    start.take_transcript();
+
+    #[cfg(feature = "random")]
+    start.clear_random_source();
+
+    match options.strategy {
+        ExploreStrategy::Random { seed } => {
+            let mut rng = SplitMix64(seed);
+
+            while report.num_playthroughs < options.max_playthroughs {
+                #[cfg_attr(not(feature = "random"), allow(unused_mut))]
+                let mut story = start.clone();
+
+                #[cfg(feature = "random")]
+                let seed = {
+                    let seed = rng.next();
+                    story.set_seed(seed);
+                    Some(seed)
+                };
+
+                #[cfg(not(feature = "random"))]
+                let seed = None;
+
+                explore_random(story, seed, options, &mut rng, &mut report);
+            }
+        }
+        ExploreStrategy::Exhaustive => {
+            explore_exhaustive(start, &mut Vec::new(), options, &mut report);
+        }
+    }
+
+    report
+}
+
+/// Run a single playthrough with random choices, from a story with the given seed.
+fn explore_random(
+    mut story: Story,
+    seed: Option<u64>,
+    options: &ExploreOptions,
+    rng: &mut SplitMix64,
+    report: &mut ExploreReport,
+) {
+    let mut choices = Vec::new();
+
+    while let Step::Choice(num_choices) = follow(&mut story, &choices, seed, options, report) {
+        let selection = (rng.next() % num_choices as u64) as usize;
+
+        if let Err(err) = make_choice(&mut story, selection, &mut choices) {
+            let (kind, location) = get_error_issue(err, &story);
+            report.add_issue(ExploreIssue {
+                kind,
+                location,
+                choices,
+                seed,
+            });
+
+            report.num_playthroughs += 1;
+            break;
+        }
+    }
+}
+
+/// Follow every choice from the current state of a story, depth first.
+fn explore_exhaustive(
+    mut story: Story,
+    choices: &mut Vec<usize>,
+    options: &ExploreOptions,
+    report: &mut ExploreReport,
+) {
+    if let Step::Choice(num_choices) = follow(&mut story, choices, None, options, report) {
+        for selection in 0..num_choices {
+            if report.num_playthroughs >= options.max_playthroughs {
+                return;
+            }
+
+            let mut branch = story.clone();
+
+            match make_choice(&mut branch, selection, choices) {
+                Ok(()) => explore_exhaustive(branch, choices, options, report),
+                Err(err) => {
+                    let (kind, location) = get_error_issue(err, &branch);
+                    report.add_issue(ExploreIssue {
+                        kind,
+                        location,
+                        choices: choices.clone(),
+                        seed: None,
+                    });
+
+                    report.num_playthroughs += 1;
+                }
+            }
+
+            choices.pop();
+        }
+    }
+}
+
+/// Resume the story and return the number of presented choices, or record how the
+/// playthrough ended.
+fn follow(
+    story: &mut Story,
+    choices: &[usize],
+    seed: Option<u64>,
+    options: &ExploreOptions,
+    report: &mut ExploreReport,
+) -> Step {
+    let result = story.resume(&mut Vec::new());
+
+    let issue = match result {
+        Ok(Prompt::Choice(presented)) if choices.len() < options.max_depth => {
+            return Step::Choice(presented.len());
+        }
+        Ok(Prompt::Choice(..)) => {
+            report.num_stopped_at_max_depth += 1;
+            None
+        }
+        Ok(Prompt::Done) if story.has_reached_end() => None,
+        Ok(Prompt::Done) => Some((ExploreIssueKind::NoEnd, story.get_current_location())),
+        Ok(Prompt::Breakpoint(..)) => unreachable!("breakpoints are cleared before exploring"),
+        Err(InklingError::DivertLimitExceeded { location, .. }) => {
+            Some((ExploreIssueKind::LoopWithoutChoices, location))
+        }
+        Err(err) => Some(get_error_issue(err, story)),
+    };
+
+    if let Some((kind, location)) = issue {
+        report.add_issue(ExploreIssue {
+            kind,
+            location,
+            choices: choices.to_vec(),
+            seed,
+        });
+    }
+
+    report.num_playthroughs += 1;
+
+    Step::Stopped
+}
+
+fn make_choice(
+    story: &mut Story,
+    selection: usize,
+    choices: &mut Vec<usize>,
+) -> Result<(), InklingError> {
+    choices.push(selection);
+    story.make_choice(selection)
+}
+
+/// Get the issue kind of an error with the location of the story when it was returned.
+///
+/// The location of the story is not updated when an error is returned, so the location
+/// of the error is used if it has one.
+fn get_error_issue(err: InklingError, story: &Story) -> (ExploreIssueKind, Location) {
+    let location = match &err {
+        InklingError::OutOfChoices { location } => location.clone(),
+        _ => story.get_current_location(),
+    };
+
+    (ExploreIssueKind::Error(err), location)
+}
+
+/// Small random number generator for picking choices, independent of the story's own.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+impl fmt::Display for ExploreReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} playthrough(s), {} stopped at maximum depth, {} issue(s)",
+            self.num_playthroughs,
+            self.num_stopped_at_max_depth,
+            self.issues.len()
+        )?;
+
+        for issue in &self.issues {
+            writeln!(f, "{}", issue)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for ExploreIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ExploreIssueKind::Error(err) => write!(f, "error: {}", err)?,
+            ExploreIssueKind::LoopWithoutChoices => {
+                write!(f, "story loops without presenting choices")?
+            }
+            ExploreIssueKind::NoEnd => {
+                write!(f, "story runs out of content without reaching END or DONE")?
+            }
+        }
+
+        match &self.location.stitch {
+            Some(stitch) => write!(f, " (knot: {}, stitch: {}", self.location.knot, stitch)?,
+            None => write!(f, " (knot: {}", self.location.knot)?,
+        }
+
+        write!(f, ", choices: {:?}", self.choices)?;
+
+        match self.seed {
+            Some(seed) => write!(f, ", seed: {})", seed),
+            None => write!(f, ")"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::story::read_story_from_string;
+
+    fn explore(content: &str, strategy: ExploreStrategy) -> ExploreReport {
+        let options = ExploreOptions {
+            strategy,
+            max_playthroughs: 20,
+            max_depth: 10,
+            divert_limit: 100,
+        };
+
+        read_story_from_string(content).unwrap().explore(&options)
+    }
+
+    #[test]
+    fn story_which_always_ends_has_no_issues() {
+        let content = "\
+*   Left -> END
+*   Right
+    -> DONE
+";
+
+        let report = explore(content, ExploreStrategy::Exhaustive);
+
+        assert!(report.is_ok());
+        assert_eq!(report.num_playthroughs, 2);
+    }
+
+    #[test]
+    fn running_out_of_content_without_end_is_reported_with_its_choices() {
+        let content = "\
+*   Left -> END
+*   Right
+    Nothing more.
+";
+
+        let report = explore(content, ExploreStrategy::Exhaustive);
+
+        assert_eq!(report.issues.len(), 1);
+        assert!(matches!(report.issues[0].kind, ExploreIssueKind::NoEnd));
+        assert_eq!(report.issues[0].choices, &[1]);
+    }
+
+    #[test]
+    fn loops_without_choices_are_reported() {
+        let content = "\
+*   Stay -> END
+*   Wander -> circle
+== circle
+Round.
+-> circle
+";
+
+        let report = explore(content, ExploreStrategy::Exhaustive);
+
+        assert_eq!(report.issues.len(), 1);
+        assert!(matches!(
+            report.issues[0].kind,
+            ExploreIssueKind::LoopWithoutChoices
+        ));
+        assert_eq!(report.issues[0].location, Location::from("circle"));
+    }
+
+    #[test]
+    fn runtime_errors_are_reported_with_the_choices_that_reproduce_them() {
+        let content = "\
+-> hub
+== hub
+*   Once -> hub
+*   Twice -> hub
+";
+
+        let report = explore(content, ExploreStrategy::Exhaustive);
+
+        assert_eq!(report.issues.len(), 1);
+
+        match &report.issues[0].kind {
+            ExploreIssueKind::Error(InklingError::OutOfChoices { .. }) => (),
+            other => panic!("expected `OutOfChoices` error but got {:?}", other),
+        }
+
+        let mut story = read_story_from_string(content).unwrap();
+
+        assert_eq!(report.issues[0].seed, None);
+        assert!(report.issues[0]
+            .replay(&mut story, &mut Vec::new())
+            .is_err());
+    }
+
+    #[test]
+    fn random_exploration_runs_the_given_number_of_playthroughs_and_finds_issues() {
+        let content = "\
+-> hub
+== hub
+*   Once -> hub
++   Again -> hub
+*   Leave
+    Nothing more.
+";
+
+        let report = explore(content, ExploreStrategy::Random { seed: 5 });
+
+        assert_eq!(report.num_playthroughs, 20);
+        assert!(report
+            .issues
+            .iter()
+            .any(|issue| matches!(issue.kind, ExploreIssueKind::NoEnd)));
+    }
+
+    #[test]
+    fn random_exploration_with_same_seed_yields_same_issues() {
+        let content = "\
+-> hub
+== hub
++   Stay -> hub
++   Leave
+    {&-> END|Nothing.}
+";
+
+        let first = explore(content, ExploreStrategy::Random { seed: 1 });
+        let second = explore(content, ExploreStrategy::Random { seed: 1 });
+
+        assert_eq!(first.to_string(), second.to_string());
+    }
+
+    #[cfg(feature = "random")]
+    #[test]
+    fn random_exploration_does_not_depend_on_the_seed_of_the_story() {
+        let content = "\
+-> hub
+== hub
++   Stay -> hub
++   Leave
+    {~-> END|Nothing.}
+";
+
+        let options = ExploreOptions {
+            strategy: ExploreStrategy::Random { seed: 1 },
+            ..ExploreOptions::default()
+        };
+
+        let mut story = read_story_from_string(content).unwrap();
+        let mut other = read_story_from_string(content).unwrap();
+
+        story.set_seed(1);
+        other.set_seed(2);
+
+        assert_eq!(
+            story.explore(&options).to_string(),
+            other.explore(&options).to_string()
+        );
+    }
+
+    #[cfg(feature = "random")]
+    #[test]
+    fn issues_from_random_exploration_are_replayed_with_the_seed_of_their_playthrough() {
+        let content = "\
+*   Leave
+    {~-> END|Nothing more.}
+";
+
+        let report = explore(content, ExploreStrategy::Random { seed: 3 });
+
+        assert!(!report.issues.is_empty());
+
+        for issue in report.issues.iter() {
+            assert!(issue.seed.is_some());
+
+            let mut story = read_story_from_string(content).unwrap();
+            let mut line_buffer = Vec::new();
+
+            issue.replay(&mut story, &mut line_buffer).unwrap();
+
+            assert_eq!(&line_buffer.last().unwrap().text, "Nothing more.\n");
+            assert!(story.resume(&mut line_buffer).is_err());
+        }
+    }
+
+    #[cfg(feature = "random")]
+    #[test]
+    fn exploring_does_not_change_the_random_numbers_of_the_story() {
+        use crate::story::rng::RandomSource;
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Debug)]
+        struct Counter(u64);
+
+        impl RandomSource for Counter {
+            fn next_u64(&mut self) -> u64 {
+                self.0 += 1;
+                self.0
+            }
+        }
+
+        let content = "\
+-> roll
+== roll
+{~1|2|3|4|5|6}
++   Roll again -> roll
+";
+
+        let mut story = read_story_from_string(content).unwrap();
+        let mut other = read_story_from_string(content).unwrap();
+
+        story.set_seed(10);
+        other.set_seed(10);
+
+        let counter = Arc::new(Mutex::new(Counter(0)));
+        story.set_random_source(counter.clone());
+
+        story.explore(&ExploreOptions::default());
+
+        assert_eq!(counter.lock().unwrap().0, 0);
+
+        story.clear_random_source();
+
+        let mut line_buffer = Vec::new();
+        let mut other_buffer = Vec::new();
+
+        for _ in 0..10 {
+            story.resume(&mut line_buffer).unwrap();
+            story.make_choice(0).unwrap();
+
+            other.resume(&mut other_buffer).unwrap();
+            other.make_choice(0).unwrap();
+        }
+
+        assert_eq!(line_buffer, other_buffer);
+    }
+
+    #[test]
+    fn playthroughs_are_stopped_at_max_depth() {
+        let content = "\
+-> hub
+== hub
++   Stay -> hub
+";
+
+        let report = explore(content, ExploreStrategy::Random { seed: 0 });
+
+        assert!(report.is_ok());
+        assert_eq!(report.num_stopped_at_max_depth, 20);
+    }
+}
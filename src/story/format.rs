@@ -0,0 +1,187 @@
+//! Writing a story back to `Ink` source.
+
+use crate::{
+    consts::{
+        CHOICE_MARKER, CONST_MARKER, GATHER_MARKER, ROOT_KNOT_NAME, STICKY_CHOICE_MARKER,
+        STITCH_MARKER, TAG_MARKER, VARIABLE_MARKER,
+    },
+    error::{utils::MetaData, ReadError},
+    knot::{read_knot_name, read_stitch_name, Knot},
+    line::{parse_line, ParsedLineKind},
+    node::{get_content_indent, get_marked_line},
+    story::{
+        parse::{
+            is_metadata_line, parse_tag_from_line, parse_variable_info_from_line, split_off_comment,
+        },
+        read_story_from_string,
+    },
+};
+
+/// Format the `Ink` source of a story.
+///
+/// The story is read to check that it has no errors, after which every line of the source
+/// is written in a normalized form: choices and gathers are indented by their nesting
+/// level, content is indented to line up with the choice it belongs to, diverts are
+/// separated from text by a single space and tags are written at the end of their line.
+///
+/// Everything else is kept as written. Comments and empty lines are kept in place,
+/// although runs of empty lines are collapsed into one, diverts are not resolved to
+/// their full address and variables are written with their declared values.
+///
+/// # Examples
+/// ```
+/// # use inkling::format_ink;
+/// let content = "\
+/// *   Board the ferry.->ferry // The only way across
+/// *   Stay ashore.# calm
+///
+/// ==   ferry
+/// The engines roar.
+/// ";
+///
+/// assert_eq!(
+///     &format_ink(content).unwrap(),
+///     "\
+/// *   Board the ferry. -> ferry // The only way across
+/// *   Stay ashore. # calm
+///
+/// === ferry ===
+/// The engines roar.
+/// "
+/// );
+/// ```
+pub fn format_ink(content: &str) -> Result<String, ReadError> {
+    read_story_from_string(content)?;
+
+    let mut lines: Vec<String> = Vec::new();
+
+    let mut is_prelude = true;
+    let mut level = 0;
+
+    for (line_index, line) in content.lines().enumerate() {
+        let (code, comment) = split_off_comment(line);
+        let code = code.trim();
+
+        if is_prelude && !is_metadata_line(code) {
+            is_prelude = false;
+        }
+
+        let formatted = if code.is_empty() {
+            " ".repeat(get_content_indent(level))
+        } else if let Ok(name) = read_knot_name(code) {
+            level = 0;
+            format!("=== {} ===", name)
+        } else if let Ok(name) = read_stitch_name(code) {
+            level = 0;
+            format!("{} {}", STITCH_MARKER, name)
+        } else if is_prelude {
+            format_metadata_line(code)
+        } else {
+            format_content_line(code, line_index, &mut level)
+        };
+
+        let formatted = match comment {
+            Some(comment) if code.is_empty() => format!("{}{}", formatted, comment.trim_end()),
+            Some(comment) => format!("{} {}", formatted, comment.trim_end()),
+            None => formatted,
+        };
+
+        let formatted = formatted.trim_end();
+
+        // Runs of empty lines are collapsed into one and dropped from the start of the file
+        let is_after_empty = match lines.last() {
+            Some(line) => line.is_empty(),
+            None => true,
+        };
+
+        if !(formatted.is_empty() && is_after_empty) {
+            lines.push(formatted.to_string());
+        }
+    }
+
+    while let Some(true) = lines.last().map(|line| line.is_empty()) {
+        lines.pop();
+    }
+
+    let mut buffer = lines.join("\n");
+    buffer.push('\n');
+
+    Ok(buffer)
+}
+
+/// Format a line of global tags, variables or other metadata in the prelude.
+///
+/// Variables are written with the value that they are declared with. `INCLUDE` and
+/// `EXTERNAL` lines are kept as written.
+fn format_metadata_line(line: &str) -> String {
+    if line.starts_with(TAG_MARKER) {
+        format!("{} {}", TAG_MARKER, parse_tag_from_line(line))
+    } else if let Ok((name, info)) = parse_variable_info_from_line(line, &MetaData::from(0)) {
+        let marker = if info.is_const {
+            CONST_MARKER
+        } else {
+            VARIABLE_MARKER
+        };
+
+        format!("{} {} = {}", marker, name, info.variable.to_ink_source())
+    } else {
+        line.to_string()
+    }
+}
+
+/// Format a line of content in a knot or stitch.
+///
+/// The level is the nesting level of the choice that the content belongs to. It is set
+/// by choice and gather lines, which begin a new level.
+fn format_content_line(line: &str, line_index: usize, level: &mut usize) -> String {
+    match parse_line(line, &MetaData::from(line_index)) {
+        Ok(ParsedLineKind::Choice {
+            level: choice_level,
+            choice_data,
+        }) => {
+            *level = choice_level as usize;
+
+            let marker = if choice_data.is_sticky {
+                STICKY_CHOICE_MARKER
+            } else {
+                CHOICE_MARKER
+            };
+
+            get_marked_line(marker, *level, &choice_data.to_string())
+        }
+        Ok(ParsedLineKind::Gather {
+            level: gather_level,
+            line,
+        }) => {
+            *level = gather_level as usize - 1;
+            get_marked_line(GATHER_MARKER, gather_level as usize, &line.to_string())
+        }
+        Ok(ParsedLineKind::Line(line)) => format!(
+            "{}{}",
+            " ".repeat(get_content_indent(*level)),
+            line.to_string().trim()
+        ),
+        // The story has been read without errors, but keep the line as written if it fails
+        Err(_) => line.to_string(),
+    }
+}
+
+/// Write the stitches of a knot, separated by empty lines.
+///
+/// The unnamed stitch at the start of the knot is written without a name.
+pub(crate) fn write_knot_content(knot: &Knot) -> String {
+    let mut stitches = knot.stitches.iter().collect::<Vec<_>>();
+    stitches.sort_by_key(|(_, stitch)| stitch.meta_data.line());
+
+    stitches
+        .into_iter()
+        .map(|(name, stitch)| {
+            if name == ROOT_KNOT_NAME {
+                stitch.root.to_string()
+            } else {
+                format!("= {}\n{}", name, stitch.root)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
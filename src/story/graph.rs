@@ -0,0 +1,568 @@
+//! Export of the branching structure of a story as a graph.
+
+use crate::{
+    consts::ROOT_KNOT_NAME,
+    knot::{Address, AddressKind, KnotSet},
+    line::{Content, InternalLine, LineChunk},
+    node::{Branch, NodeItem},
+    story::utils::get_chunk_text,
+};
+
+use std::{collections::HashMap, fmt::Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Format to export a story graph in.
+///
+/// Used with [`export_graph`][crate::story::Story::export_graph()].
+pub enum GraphFormat {
+    /// Graphviz DOT, which can be rendered with eg. `dot -Tsvg story.dot`.
+    Dot,
+    /// Mermaid flowchart, which can be rendered in Markdown documents.
+    Mermaid,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Kind of node in the graph.
+enum NodeKind {
+    Stitch,
+    ChoicePoint,
+    End,
+}
+
+#[derive(Clone, Debug)]
+struct GraphNode {
+    kind: NodeKind,
+    label: String,
+    /// Name of knot that the node is drawn inside, if any.
+    knot: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+struct GraphEdge {
+    from: usize,
+    to: usize,
+    label: Option<String>,
+}
+
+#[derive(Default)]
+/// Nodes and edges of a story, with nodes identified by their index.
+struct Graph {
+    nodes: Vec<GraphNode>,
+    edges: Vec<GraphEdge>,
+    /// Node index of every knot and stitch.
+    stitches: HashMap<(String, String), usize>,
+    /// Node index of the end of the story, if it is diverted to.
+    end: Option<usize>,
+}
+
+/// Node that the story can continue from, with a label for the edge which leaves it.
+///
+/// The label is set after choices and conditions and used by the next edge from the node.
+type OpenEnd = (usize, Option<String>);
+
+/// Export the knots, stitches and choices of a story as a graph.
+///
+/// Nodes are knots, stitches and the points where choices are presented. Edges are
+/// diverts and choice branches, labelled with the choice text and conditions they
+/// were made under. Stitches are drawn inside their knots.
+///
+/// Diverts to divert variables are not included, since their target is only known
+/// when the story is followed.
+pub fn export_story_graph(knots: &KnotSet, format: GraphFormat) -> String {
+    let graph = build_graph(knots);
+
+    match format {
+        GraphFormat::Dot => write_dot(&graph),
+        GraphFormat::Mermaid => write_mermaid(&graph),
+    }
+}
+
+fn build_graph(knots: &KnotSet) -> Graph {
+    let mut graph = Graph::default();
+
+    let locations = get_sorted_locations(knots);
+
+    for (knot, stitch) in &locations {
+        let (label, knot_name) = match (knot.as_str(), stitch.as_str()) {
+            (ROOT_KNOT_NAME, _) => ("start".to_string(), None),
+            (_, ROOT_KNOT_NAME) => (knot.clone(), Some(knot.clone())),
+            _ => (format!("{}.{}", knot, stitch), Some(knot.clone())),
+        };
+
+        let index = graph.add_node(NodeKind::Stitch, label, knot_name);
+        graph.stitches.insert((knot.clone(), stitch.clone()), index);
+    }
+
+    for (knot, stitch) in &locations {
+        let items = &knots[knot].stitches[stitch].root.items;
+        let knot_name = Some(knot.as_str()).filter(|name| *name != ROOT_KNOT_NAME);
+
+        let from = graph.stitches[&(knot.clone(), stitch.clone())];
+        add_node_items(items, vec![(from, None)], knot_name, &mut graph);
+    }
+
+    graph
+}
+
+/// Get the knot and stitch names of all stitches, sorted with the root knot and default
+/// stitches first.
+fn get_sorted_locations(knots: &KnotSet) -> Vec<(String, String)> {
+    let mut locations = knots
+        .iter()
+        .flat_map(|(knot_name, knot)| {
+            knot.stitches
+                .keys()
+                .map(move |stitch_name| (knot_name.clone(), stitch_name.clone()))
+        })
+        .collect::<Vec<_>>();
+
+    locations.sort_by_key(|(knot, stitch)| {
+        (
+            knot != ROOT_KNOT_NAME,
+            knot.clone(),
+            stitch != &knots[knot].default_stitch,
+            stitch.clone(),
+        )
+    });
+
+    locations
+}
+
+/// Add edges and choice points from a list of items and return the ends that the story
+/// continues from after them.
+fn add_node_items(
+    items: &[NodeItem],
+    mut ends: Vec<OpenEnd>,
+    knot: Option<&str>,
+    graph: &mut Graph,
+) -> Vec<OpenEnd> {
+    for item in items {
+        match item {
+            NodeItem::Line(line) => {
+                if add_line(line, &ends, graph) {
+                    ends.clear();
+                }
+            }
+            NodeItem::BranchingPoint(branches) => {
+                ends = add_branching_point(branches, &ends, knot, graph);
+            }
+        }
+    }
+
+    ends
+}
+
+fn add_branching_point(
+    branches: &[Branch],
+    ends: &[OpenEnd],
+    knot: Option<&str>,
+    graph: &mut Graph,
+) -> Vec<OpenEnd> {
+    let choice_point = graph.add_node(
+        NodeKind::ChoicePoint,
+        String::new(),
+        knot.map(str::to_string),
+    );
+    graph.add_edges(ends, choice_point);
+
+    branches
+        .iter()
+        .flat_map(|branch| {
+            let label = get_choice_label(branch);
+            add_node_items(
+                &branch.items,
+                vec![(choice_point, Some(label))],
+                knot,
+                graph,
+            )
+        })
+        .collect()
+}
+
+/// Add edges from diverts in a line and return whether the line always diverts.
+fn add_line(line: &InternalLine, ends: &[OpenEnd], graph: &mut Graph) -> bool {
+    add_chunk(&line.chunk, ends, graph)
+}
+
+/// Add edges from diverts in a chunk and return whether the chunk always diverts.
+///
+/// Diverts in conditional content get the condition added to their edge label. If the
+/// content does not divert the story continues with the original labels.
+fn add_chunk(chunk: &LineChunk, ends: &[OpenEnd], graph: &mut Graph) -> bool {
+    match &chunk.condition {
+        Some(condition) => {
+            let true_ends = add_label_to_ends(ends, &format!("{{{}}}", condition));
+            let false_ends = add_label_to_ends(ends, &format!("{{not ({})}}", condition));
+
+            let true_diverts = add_content(&chunk.items, &true_ends, graph);
+            let false_diverts = add_content(&chunk.else_items, &false_ends, graph);
+
+            true_diverts && false_diverts
+        }
+        None => add_content(&chunk.items, ends, graph),
+    }
+}
+
+fn add_content(items: &[Content], ends: &[OpenEnd], graph: &mut Graph) -> bool {
+    for item in items {
+        let always_diverts = match item {
+            Content::Divert(address) => {
+                if let Some(target) = graph.get_target(address) {
+                    graph.add_edges(ends, target);
+                }
+
+                true
+            }
+            Content::Nested(chunk) => add_chunk(chunk, ends, graph),
+            Content::Alternative(alternative) => alternative
+                .items
+                .iter()
+                .fold(!alternative.items.is_empty(), |acc, chunk| {
+                    add_chunk(chunk, ends, graph) && acc
+                }),
+            Content::Empty | Content::Expression(..) | Content::Text(..) => false,
+        };
+
+        if always_diverts {
+            return true;
+        }
+    }
+
+    false
+}
+
+fn add_label_to_ends(ends: &[OpenEnd], label: &str) -> Vec<OpenEnd> {
+    ends.iter()
+        .map(|(index, existing)| {
+            let label = match existing {
+                Some(existing) => format!("{} {}", existing, label),
+                None => label.to_string(),
+            };
+
+            (*index, Some(label))
+        })
+        .collect()
+}
+
+impl Graph {
+    fn add_node(&mut self, kind: NodeKind, label: String, knot: Option<String>) -> usize {
+        self.nodes.push(GraphNode { kind, label, knot });
+        self.nodes.len() - 1
+    }
+
+    fn add_edges(&mut self, ends: &[OpenEnd], to: usize) {
+        for (from, label) in ends {
+            self.edges.push(GraphEdge {
+                from: *from,
+                to,
+                label: label.clone(),
+            });
+        }
+    }
+
+    /// Get the node index of a divert target, adding the end node if it is the target.
+    fn get_target(&mut self, address: &Address) -> Option<usize> {
+        match address {
+            Address::Validated(AddressKind::Location { knot, stitch }) => {
+                self.stitches.get(&(knot.clone(), stitch.clone())).cloned()
+            }
+            Address::End => match self.end {
+                Some(index) => Some(index),
+                None => {
+                    let index = self.add_node(NodeKind::End, "END".to_string(), None);
+                    self.end.replace(index);
+
+                    Some(index)
+                }
+            },
+            Address::Validated(AddressKind::GlobalVariable { .. }) | Address::Raw(..) => None,
+        }
+    }
+
+    /// Get the names of knots with nodes in the graph, in the order they were added.
+    fn get_knot_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+
+        for name in self.nodes.iter().filter_map(|node| node.knot.as_deref()) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+
+        names
+    }
+}
+
+/// Get the label of a choice: its condition and selection text.
+fn get_choice_label(branch: &Branch) -> String {
+    let choice = &branch.choice;
+    let selection_text = choice.selection_text.lock().unwrap();
+
+    let mut text = get_chunk_text(&selection_text.chunk);
+
+    if text.is_empty() && choice.is_fallback {
+        text.push_str("(fallback)");
+    }
+
+    match &choice.condition {
+        Some(condition) => format!("{{{}}} {}", condition, text).trim_end().to_string(),
+        None => text,
+    }
+}
+
+/// Write the graph in Graphviz DOT format.
+fn write_dot(graph: &Graph) -> String {
+    let mut buffer = String::from("digraph story {\n");
+
+    let write_node = |buffer: &mut String, index: usize, indent: &str| {
+        let node = &graph.nodes[index];
+
+        let shape = match node.kind {
+            NodeKind::Stitch => "box",
+            NodeKind::ChoicePoint => "diamond",
+            NodeKind::End => "doublecircle",
+        };
+
+        writeln!(
+            buffer,
+            "{}n{} [label=\"{}\", shape={}];",
+            indent,
+            index,
+            escape_dot(&node.label),
+            shape
+        )
+        .unwrap();
+    };
+
+    for (index, _) in graph
+        .nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, n)| n.knot.is_none())
+    {
+        write_node(&mut buffer, index, "    ");
+    }
+
+    for knot in graph.get_knot_names() {
+        writeln!(buffer, "    subgraph \"cluster_{}\" {{", escape_dot(knot)).unwrap();
+        writeln!(buffer, "        label=\"{}\";", escape_dot(knot)).unwrap();
+
+        for (index, _) in graph
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.knot.as_deref() == Some(knot))
+        {
+            write_node(&mut buffer, index, "        ");
+        }
+
+        buffer.push_str("    }\n");
+    }
+
+    for edge in &graph.edges {
+        match &edge.label {
+            Some(label) => writeln!(
+                buffer,
+                "    n{} -> n{} [label=\"{}\"];",
+                edge.from,
+                edge.to,
+                escape_dot(label)
+            ),
+            None => writeln!(buffer, "    n{} -> n{};", edge.from, edge.to),
+        }
+        .unwrap();
+    }
+
+    buffer.push_str("}\n");
+    buffer
+}
+
+/// Write the graph as a Mermaid flowchart.
+fn write_mermaid(graph: &Graph) -> String {
+    let mut buffer = String::from("flowchart TD\n");
+
+    let write_node = |buffer: &mut String, index: usize, indent: &str| {
+        let node = &graph.nodes[index];
+        let label = escape_mermaid(&node.label);
+
+        match node.kind {
+            NodeKind::Stitch => writeln!(buffer, "{}n{}[\"{}\"]", indent, index, label),
+            NodeKind::ChoicePoint => writeln!(buffer, "{}n{}{{\" \"}}", indent, index),
+            NodeKind::End => writeln!(buffer, "{}n{}([\"{}\"])", indent, index, label),
+        }
+        .unwrap();
+    };
+
+    for (index, _) in graph
+        .nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, n)| n.knot.is_none())
+    {
+        write_node(&mut buffer, index, "    ");
+    }
+
+    for (i, knot) in graph.get_knot_names().into_iter().enumerate() {
+        writeln!(buffer, "    subgraph k{}[\"{}\"]", i, escape_mermaid(knot)).unwrap();
+
+        for (index, _) in graph
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.knot.as_deref() == Some(knot))
+        {
+            write_node(&mut buffer, index, "        ");
+        }
+
+        buffer.push_str("    end\n");
+    }
+
+    for edge in &graph.edges {
+        match &edge.label {
+            Some(label) => writeln!(
+                buffer,
+                "    n{} -->|\"{}\"| n{}",
+                edge.from,
+                escape_mermaid(label),
+                edge.to
+            ),
+            None => writeln!(buffer, "    n{} --> n{}", edge.from, edge.to),
+        }
+        .unwrap();
+    }
+
+    buffer
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::story::read_story_from_string;
+
+    fn export(content: &str, format: GraphFormat) -> String {
+        read_story_from_string(content)
+            .unwrap()
+            .export_graph(format)
+    }
+
+    #[test]
+    fn knots_and_stitches_are_nodes_and_diverts_are_edges() {
+        let content = "\
+-> tavern
+== tavern
+Dim light.
+-> tavern.cellar
+= cellar
+Barrels.
+-> END
+";
+
+        assert_eq!(
+            export(content, GraphFormat::Dot),
+            "\
+digraph story {
+    n0 [label=\"start\", shape=box];
+    n3 [label=\"END\", shape=doublecircle];
+    subgraph \"cluster_tavern\" {
+        label=\"tavern\";
+        n1 [label=\"tavern\", shape=box];
+        n2 [label=\"tavern.cellar\", shape=box];
+    }
+    n0 -> n1;
+    n1 -> n2;
+    n2 -> n3;
+}
+"
+        );
+    }
+
+    #[test]
+    fn choices_are_edges_from_choice_points_labelled_with_text_and_conditions() {
+        let content = "\
+VAR has_key = false
+*   {has_key} Open the door[.] carefully. -> room
+*   Leave -> END
+== room
+Dusty.
+-> END
+";
+
+        let dot = export(content, GraphFormat::Dot);
+
+        assert!(dot.contains("n2 [label=\"\", shape=diamond];"));
+        assert!(dot.contains("n0 -> n2;"));
+        assert!(dot.contains("n2 -> n1 [label=\"{has_key} Open the door.\"];"));
+        assert!(dot.contains("n2 -> n3 [label=\"Leave\"];"));
+    }
+
+    #[test]
+    fn conditional_diverts_are_labelled_with_their_condition() {
+        let content = "\
+VAR coins = 0
+{coins > 5: -> shop | -> street}
+== shop
+Shop.
+-> END
+== street
+Street.
+-> END
+";
+
+        let dot = export(content, GraphFormat::Dot);
+
+        assert!(dot.contains("n0 -> n1 [label=\"{coins > 5}\"];"));
+        assert!(dot.contains("n0 -> n2 [label=\"{not (coins > 5)}\"];"));
+    }
+
+    #[test]
+    fn choices_which_fall_through_continue_from_the_gather() {
+        let content = "\
+*   Wave.
+*   Nod.
+-   -> next
+== next
+Done.
+-> END
+";
+
+        let dot = export(content, GraphFormat::Dot);
+
+        assert!(dot.contains("n2 -> n1 [label=\"Wave.\"];"));
+        assert!(dot.contains("n2 -> n1 [label=\"Nod.\"];"));
+    }
+
+    #[test]
+    fn mermaid_flowchart_has_knot_subgraphs_and_labelled_edges() {
+        let content = "\
+*   Say \"hi\" -> greeting
+== greeting
+Hello.
+-> END
+";
+
+        assert_eq!(
+            export(content, GraphFormat::Mermaid),
+            "\
+flowchart TD
+    n0[\"start\"]
+    n2{\" \"}
+    n3([\"END\"])
+    subgraph k0[\"greeting\"]
+        n1[\"greeting\"]
+    end
+    n0 --> n2
+    n2 -->|\"Say #quot;hi#quot;\"| n1
+    n1 --> n3
+"
+        );
+    }
+}
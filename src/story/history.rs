@@ -0,0 +1,223 @@
+//! History of story states which can be returned to.
+//!
+//! Every time a choice is made in the story a [`Snapshot`] of the state at that branching
+//! point is recorded. These are kept in a bounded [`History`], from which the story can
+//! be rewound to an earlier choice.
+//!
+//! Much of the story state lives inside the knots themselves: the positions in stitches
+//! and the states of alternative sequences. Snapshots thus keep full copies of the knots,
+//! made using [`clone_knots_detached`][crate::knot::clone_knots_detached] to ensure that
+//! they share no state with the running story. Of the follow data only the variables,
+//! visit counts and random number generator are kept, since the rest belongs to the caller.
+
+use crate::{
+    knot::{clone_knots_detached, Address, KnotSet},
+    story::{
+        rng::StoryRng,
+        types::{Choice, VariableSet},
+    },
+};
+
+#[cfg(feature = "serde_support")]
+use serde::{de::IgnoredAny, Deserialize, Deserializer, Serialize};
+
+use std::collections::{HashMap, VecDeque};
+
+/// Default number of snapshots that are kept in the history.
+///
+/// Snapshots copy the whole story, so none are kept unless rewinding is enabled.
+pub const DEFAULT_HISTORY_LIMIT: usize = 0;
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+/// State of a story at a branching choice.
+pub struct Snapshot {
+    /// Address of the story at the branching point.
+    pub current_address: Address,
+    /// Copy of all knots, with their stacks and alternative sequence states.
+    pub knots: KnotSet,
+    /// Number of times each knot and stitch had been visited.
+    pub knot_visit_counts: HashMap<String, HashMap<String, u32>>,
+    /// Global variables of the story.
+    pub variables: VariableSet,
+    /// Random number generator, without any external source.
+    pub rng: StoryRng,
+    /// Choices which were presented to the user at the branching point.
+    pub last_choices: Vec<Choice>,
+}
+
+impl Clone for Snapshot {
+    fn clone(&self) -> Self {
+        Snapshot {
+            current_address: self.current_address.clone(),
+            knots: clone_knots_detached(&self.knots),
+            knot_visit_counts: self.knot_visit_counts.clone(),
+            variables: self.variables.clone(),
+            rng: self.rng.clone(),
+            last_choices: self.last_choices.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+#[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde_support", serde(bound = ""))]
+/// Bounded stack of recorded items, where the oldest items are dropped first.
+///
+/// Only the limit is de/serialized: recorded items are not saved with the story.
+pub struct History<T> {
+    /// Recorded items, with the most recent last.
+    #[cfg_attr(feature = "serde_support", serde(skip))]
+    items: VecDeque<T>,
+    /// Maximum number of items to keep.
+    limit: usize,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        History::with_limit(DEFAULT_HISTORY_LIMIT)
+    }
+}
+
+impl<T> History<T> {
+    /// Create an empty history which keeps at most `limit` items.
+    pub fn with_limit(limit: usize) -> Self {
+        History {
+            items: VecDeque::new(),
+            limit,
+        }
+    }
+
+    /// Number of recorded items.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Record an item, dropping the oldest if the limit is reached.
+    pub fn push(&mut self, item: T) {
+        self.items.push_back(item);
+        self.truncate();
+    }
+
+    /// Remove the given number of the most recent items and return the last one removed.
+    ///
+    /// Returns `None` and leaves the history unchanged if `steps` is zero or larger than
+    /// the number of recorded items.
+    pub fn rewind(&mut self, steps: usize) -> Option<T> {
+        if steps == 0 || steps > self.items.len() {
+            return None;
+        }
+
+        let index = self.items.len() - steps;
+
+        self.items.drain(index..).next()
+    }
+
+    /// Remove all recorded items.
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Set the maximum number of items to keep, dropping the oldest items if needed.
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.truncate();
+    }
+
+    /// Drop the oldest items until the limit is fulfilled.
+    fn truncate(&mut self) {
+        while self.items.len() > self.limit {
+            self.items.pop_front();
+        }
+    }
+}
+
+#[cfg(feature = "serde_support")]
+/// Deserialize a history, or an empty one from a story saved before rewinding was added.
+///
+/// Such stories saved a list of visited addresses in place of the history, which
+/// is read and discarded.
+pub fn deserialize_history<'de, D, T>(deserializer: D) -> Result<History<T>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    #[serde(bound = "", untagged)]
+    enum SavedHistory<T> {
+        History(History<T>),
+        Addresses(Vec<IgnoredAny>),
+    }
+
+    match SavedHistory::deserialize(deserializer)? {
+        SavedHistory::History(history) => Ok(history),
+        SavedHistory::Addresses(..) => Ok(History::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pushing_to_full_history_drops_the_oldest_item() {
+        let mut history = History::with_limit(2);
+
+        history.push(1);
+        history.push(2);
+        history.push(3);
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.rewind(2), Some(2));
+    }
+
+    #[test]
+    fn history_with_zero_limit_records_nothing() {
+        let mut history = History::with_limit(0);
+
+        history.push(1);
+
+        assert_eq!(history.len(), 0);
+    }
+
+    #[test]
+    fn rewinding_removes_and_returns_the_item_the_given_number_of_steps_back() {
+        let mut history = History::with_limit(5);
+
+        history.push(1);
+        history.push(2);
+        history.push(3);
+
+        assert_eq!(history.rewind(2), Some(2));
+        assert_eq!(history.len(), 1);
+        assert_eq!(history.rewind(1), Some(1));
+        assert_eq!(history.len(), 0);
+    }
+
+    #[test]
+    fn rewinding_zero_steps_or_past_the_history_changes_nothing() {
+        let mut history = History::with_limit(5);
+
+        history.push(1);
+        history.push(2);
+
+        assert_eq!(history.rewind(0), None);
+        assert_eq!(history.rewind(3), None);
+        assert_eq!(history.len(), 2);
+    }
+
+    #[test]
+    fn lowering_the_limit_drops_the_oldest_items() {
+        let mut history = History::with_limit(5);
+
+        history.push(1);
+        history.push(2);
+        history.push(3);
+
+        history.set_limit(1);
+
+        assert_eq!(history.len(), 1);
+        assert_eq!(history.rewind(1), Some(3));
+    }
+}
@@ -0,0 +1,211 @@
+//! Reading and writing string tables as CSV files.
+//!
+//! The file has a header row with the columns `id`, `source` and `translation`, followed
+//! by a row for every entry. Fields are quoted as in RFC 4180. An empty translation marks
+//! an untranslated entry.
+
+use crate::{
+    error::LocalizationError,
+    story::localization::table::{StringEntry, StringTable},
+};
+
+const FORMAT: &str = "CSV";
+
+impl StringTable {
+    /// Write the table as a CSV file.
+    pub fn to_csv(&self) -> String {
+        let mut buffer = String::from("id,source,translation\n");
+
+        for entry in &self.entries {
+            buffer.push_str(&format!(
+                "{},{},{}\n",
+                quote(&entry.id),
+                quote(&entry.source),
+                quote(entry.translation.as_deref().unwrap_or(""))
+            ));
+        }
+
+        buffer
+    }
+
+    /// Read a table from a CSV file.
+    ///
+    /// The columns are found by their names in the header row, in any order. The `id`
+    /// and `translation` columns are required.
+    pub fn from_csv(content: &str) -> Result<Self, LocalizationError> {
+        let mut records = read_records(content)?.into_iter();
+
+        let (_, header) = records
+            .next()
+            .ok_or_else(|| LocalizationError::InvalidTable {
+                format: FORMAT,
+                line: 1,
+                message: "missing header".to_string(),
+            })?;
+
+        let get_column = |name: &str| {
+            header
+                .iter()
+                .position(|column| column.trim() == name)
+                .ok_or_else(|| LocalizationError::InvalidTable {
+                    format: FORMAT,
+                    line: 1,
+                    message: format!("missing column `{}`", name),
+                })
+        };
+
+        let id_column = get_column("id")?;
+        let translation_column = get_column("translation")?;
+        let source_column = get_column("source").ok();
+
+        records
+            .filter(|(_, fields)| fields.iter().any(|field| !field.is_empty()))
+            .map(|(line, mut fields)| {
+                if fields.len() != header.len() {
+                    return Err(LocalizationError::InvalidTable {
+                        format: FORMAT,
+                        line,
+                        message: format!(
+                            "expected {} fields but found {}",
+                            header.len(),
+                            fields.len()
+                        ),
+                    });
+                }
+
+                let source = source_column
+                    .map(|column| std::mem::take(&mut fields[column]))
+                    .unwrap_or_default();
+
+                Ok(StringEntry {
+                    id: std::mem::take(&mut fields[id_column]),
+                    source,
+                    translation: Some(std::mem::take(&mut fields[translation_column]))
+                        .filter(|translation| !translation.is_empty()),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(|entries| StringTable { entries })
+    }
+}
+
+/// Quote a field if it contains separators, quotes, line breaks or surrounding whitespace.
+fn quote(field: &str) -> String {
+    let needs_quotes = field.contains(&[',', '"', '\n', '\r'][..]) || field.trim() != field;
+
+    if needs_quotes {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Split the content into records of fields, along with the line that each record starts at.
+fn read_records(content: &str) -> Result<Vec<(usize, Vec<String>)>, LocalizationError> {
+    let mut records = Vec::new();
+
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if field.is_empty() => in_quotes = true,
+            '\n' if in_quotes => {
+                line += 1;
+                field.push(c);
+            }
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes && chars.peek() == Some(&'\n') => (),
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut fields)));
+
+                line += 1;
+                record_line = line;
+            }
+            other => field.push(other),
+        }
+    }
+
+    if in_quotes {
+        return Err(LocalizationError::InvalidTable {
+            format: FORMAT,
+            line: record_line,
+            message: "quoted field is not closed".to_string(),
+        });
+    }
+
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((record_line, fields));
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_written_to_csv_is_read_back() {
+        let table = StringTable {
+            entries: vec![
+                StringEntry {
+                    id: "tavern:3".to_string(),
+                    source: "\"Welcome,\" said {1}.\nCome in.".to_string(),
+                    translation: Some("« Bienvenue », dit {1}.\nEntrez.".to_string()),
+                },
+                StringEntry {
+                    id: "tavern:4/selection".to_string(),
+                    source: "Sit down ".to_string(),
+                    translation: None,
+                },
+            ],
+        };
+
+        let csv = table.to_csv();
+
+        assert!(csv.starts_with("id,source,translation\n"));
+        assert!(csv.contains("tavern:4/selection,\"Sit down \",\n"));
+        assert_eq!(StringTable::from_csv(&csv).unwrap(), table);
+    }
+
+    #[test]
+    fn csv_columns_are_found_by_name() {
+        let content = "translation,id\r\nBonjour,greeting\r\n";
+        let table = StringTable::from_csv(content).unwrap();
+
+        assert_eq!(
+            table.entries,
+            vec![StringEntry {
+                id: "greeting".to_string(),
+                source: String::new(),
+                translation: Some("Bonjour".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn csv_rows_with_wrong_number_of_fields_are_errors_with_their_line_number() {
+        let content = "id,source,translation\na,\"One\nTwo\",Un\nb,Three\n";
+
+        match StringTable::from_csv(content) {
+            Err(LocalizationError::InvalidTable { format, line, .. }) => {
+                assert_eq!(format, "CSV");
+                assert_eq!(line, 4);
+            }
+            other => panic!("expected an error but got {:?}", other),
+        }
+    }
+}
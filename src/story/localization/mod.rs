@@ -0,0 +1,14 @@
+//! Translation of the text in stories.
+//!
+//! All text in a story can be extracted into a [`StringTable`][crate::story::StringTable]
+//! with identifiers that are stable between runs. The table is written to and read from
+//! PO, XLIFF or CSV files, and a translated table is loaded into a story to replace its text.
+
+mod csv;
+mod po;
+mod table;
+mod translate;
+mod xliff;
+
+pub use table::{StringEntry, StringTable};
+pub(crate) use translate::{get_string_table, translate_knots};
@@ -0,0 +1,226 @@
+//! Reading and writing string tables as gettext PO files.
+//!
+//! Every entry is written with its identifier as `msgctxt`, the source text as `msgid`
+//! and the translation as `msgstr`. An empty `msgstr` marks an untranslated entry.
+
+use crate::{
+    error::LocalizationError,
+    story::localization::table::{StringEntry, StringTable},
+};
+
+const FORMAT: &str = "PO";
+
+impl StringTable {
+    /// Write the table as a PO file.
+    pub fn to_po(&self) -> String {
+        let mut buffer = String::from(
+            "msgid \"\"\nmsgstr \"\"\n\"Content-Type: text/plain; charset=UTF-8\\n\"\n",
+        );
+
+        for entry in &self.entries {
+            buffer.push_str(&format!(
+                "\nmsgctxt \"{}\"\nmsgid \"{}\"\nmsgstr \"{}\"\n",
+                escape(&entry.id),
+                escape(&entry.source),
+                escape(entry.translation.as_deref().unwrap_or(""))
+            ));
+        }
+
+        buffer
+    }
+
+    /// Read a table from a PO file.
+    ///
+    /// Entries without a `msgctxt`, such as the header, are skipped. Plural forms
+    /// are not supported.
+    pub fn from_po(content: &str) -> Result<Self, LocalizationError> {
+        let mut entries = Vec::new();
+        let mut entry = PoEntry::default();
+        let mut field = None;
+
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            let invalid = |message: &str| LocalizationError::InvalidTable {
+                format: FORMAT,
+                line: i + 1,
+                message: message.to_string(),
+            };
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (keyword, rest) = match line.find(char::is_whitespace) {
+                Some(index) if !line.starts_with('"') => (&line[..index], line[index..].trim()),
+                _ => ("", line),
+            };
+
+            let value = unescape(rest).ok_or_else(|| invalid("invalid quoted string"))?;
+
+            match keyword {
+                "msgctxt" | "msgid" if entry.msgstr.is_some() => {
+                    entries.extend(entry.into_string_entry());
+                    entry = PoEntry::default();
+                }
+                _ => (),
+            }
+
+            let target = match keyword {
+                "msgctxt" => &mut entry.msgctxt,
+                "msgid" => &mut entry.msgid,
+                "msgstr" => &mut entry.msgstr,
+                "" => match field {
+                    Some(Field::Context) => &mut entry.msgctxt,
+                    Some(Field::Id) => &mut entry.msgid,
+                    Some(Field::Str) => &mut entry.msgstr,
+                    None => return Err(invalid("string without a keyword")),
+                },
+                other => return Err(invalid(&format!("unsupported keyword `{}`", other))),
+            };
+
+            target.get_or_insert_with(String::new).push_str(&value);
+
+            field = match keyword {
+                "msgctxt" => Some(Field::Context),
+                "msgid" => Some(Field::Id),
+                "msgstr" => Some(Field::Str),
+                _ => field,
+            };
+        }
+
+        entries.extend(entry.into_string_entry());
+
+        Ok(StringTable { entries })
+    }
+}
+
+#[derive(Clone, Copy)]
+/// Field of a PO entry that continued strings are added to.
+enum Field {
+    Context,
+    Id,
+    Str,
+}
+
+#[derive(Default)]
+struct PoEntry {
+    msgctxt: Option<String>,
+    msgid: Option<String>,
+    msgstr: Option<String>,
+}
+
+impl PoEntry {
+    fn into_string_entry(self) -> Option<StringEntry> {
+        let id = self.msgctxt?;
+
+        Some(StringEntry {
+            id,
+            source: self.msgid.unwrap_or_default(),
+            translation: self.msgstr.filter(|translation| !translation.is_empty()),
+        })
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
+
+/// Read the content of a quoted string. Returns `None` if it is not properly quoted.
+fn unescape(quoted: &str) -> Option<String> {
+    let inner = quoted.strip_prefix('"')?.strip_suffix('"')?;
+
+    let mut text = String::new();
+    let mut chars = inner.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                'n' => text.push('\n'),
+                't' => text.push('\t'),
+                other => text.push(other),
+            },
+            '"' => return None,
+            other => text.push(other),
+        }
+    }
+
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_table() -> StringTable {
+        StringTable {
+            entries: vec![
+                StringEntry {
+                    id: "tavern:3".to_string(),
+                    source: "\"Welcome,\" said {1}.\n".to_string(),
+                    translation: Some("« Bienvenue », dit {1}.\n".to_string()),
+                },
+                StringEntry {
+                    id: "tavern:4/selection".to_string(),
+                    source: "Sit down".to_string(),
+                    translation: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn table_written_to_po_is_read_back() {
+        let table = get_table();
+        assert_eq!(StringTable::from_po(&table.to_po()).unwrap(), table);
+    }
+
+    #[test]
+    fn po_entries_are_written_with_context_and_escaped_strings() {
+        let po = get_table().to_po();
+
+        assert!(po.contains(
+            "msgctxt \"tavern:3\"\nmsgid \"\\\"Welcome,\\\" said {1}.\\n\"\nmsgstr \"« Bienvenue », dit {1}.\\n\"\n"
+        ));
+        assert!(po.contains("msgctxt \"tavern:4/selection\"\nmsgid \"Sit down\"\nmsgstr \"\"\n"));
+    }
+
+    #[test]
+    fn po_strings_can_continue_over_several_lines() {
+        let content = "\
+# Translator comment
+msgctxt \"id\"
+msgid \"\"
+\"Hello, \"
+\"world\"
+msgstr \"Bonjour, \"
+\"le monde\"
+";
+
+        let table = StringTable::from_po(content).unwrap();
+
+        assert_eq!(
+            table.entries,
+            vec![StringEntry {
+                id: "id".to_string(),
+                source: "Hello, world".to_string(),
+                translation: Some("Bonjour, le monde".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn invalid_po_lines_are_errors_with_their_line_number() {
+        let content = "msgctxt \"id\"\nmsgid \"unclosed\nmsgstr \"\"\n";
+
+        match StringTable::from_po(content) {
+            Err(LocalizationError::InvalidTable { format, line, .. }) => {
+                assert_eq!(format, "PO");
+                assert_eq!(line, 2);
+            }
+            other => panic!("expected an error but got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! Table of translatable text.
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// Table of translatable text in a story.
+///
+/// Get the table of a story with [`get_string_table`][crate::story::Story::get_string_table()]
+/// and write it to a file for translators in one of the supported formats:
+///
+/// *   PO, with [`to_po`][StringTable::to_po()] and [`from_po`][StringTable::from_po()]
+/// *   XLIFF 1.2, with [`to_xliff`][StringTable::to_xliff()] and
+///     [`from_xliff`][StringTable::from_xliff()]
+/// *   CSV, with [`to_csv`][StringTable::to_csv()] and [`from_csv`][StringTable::from_csv()]
+///
+/// A translated table is loaded into a story with
+/// [`load_string_table`][crate::story::Story::load_string_table()].
+///
+/// # Placeholders
+/// Variables, alternatives, conditional content and diverts in the text are written as
+/// placeholders with their index in the line: `{0}`, `{1}` and so on. Their own text is
+/// translated in separate entries. Placeholders can be moved around in a translation, but
+/// every one must be kept exactly once. Literal braces are written as `{{` and `}}`.
+pub struct StringTable {
+    /// Entries of text in the table.
+    pub entries: Vec<StringEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Translatable text in a story.
+pub struct StringEntry {
+    /// Identifier of the text.
+    ///
+    /// Formed from the knot, stitch and line number of the text, along with where in
+    /// the line it is: `knot.stitch:line/path`. It does not change between runs of the
+    /// story, but will change if the script is edited around the text.
+    pub id: String,
+    /// Text in the story, with placeholders for content which is not text.
+    pub source: String,
+    /// Translation of the text, if it has been translated.
+    pub translation: Option<String>,
+}
+
+impl StringTable {
+    /// Get the entry with the given identifier.
+    pub fn get(&self, id: &str) -> Option<&StringEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    /// Get a mutable reference to the entry with the given identifier.
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut StringEntry> {
+        self.entries.iter_mut().find(|entry| entry.id == id)
+    }
+}
@@ -0,0 +1,492 @@
+//! Extraction of translatable text from stories and loading of translations into them.
+//!
+//! Text is extracted per list of items in a `LineChunk`. Text items are kept as they are
+//! while all other items are replaced by placeholders with their index in the list: `{0}`,
+//! `{1}` and so on. Translating the text replaces the text items but keeps every other
+//! item, possibly in a new order. Variables, alternatives, conditions and diverts are thus
+//! the same in every language.
+//!
+//! Identifiers are formed from the address of the knot or stitch that the text is in and
+//! the index of its line or choice among all lines and choices in that knot or stitch.
+//! They are thus kept when lines are added or removed in other knots and stitches, or
+//! when the script is reformatted. Nested chunks add the index of their item to the
+//! identifier of their parent, and alternatives the index of their item in the
+//! alternative. Text which is shown when a condition is false adds `/else`.
+
+use crate::{
+    error::LocalizationError,
+    knot::{Address, KnotSet},
+    line::{Content, InternalLine, LineChunk},
+    node::NodeItem,
+    story::localization::table::{StringEntry, StringTable},
+};
+
+use std::{
+    collections::{HashMap, HashSet},
+    convert::Infallible,
+};
+
+/// Get all translatable text in a set of knots, in the order it appears in the script.
+///
+/// Text which is identical for the selection and display parts of a choice is only
+/// added once, as its selection text.
+pub fn get_string_table(knots: &mut KnotSet) -> StringTable {
+    let mut table = StringTable::default();
+    let mut seen = HashSet::new();
+
+    let result: Result<(), Infallible> = for_each_line(knots, &mut |id, line| {
+        add_chunk_entries(&line.chunk, id, &mut table.entries, &mut seen);
+        Ok(())
+    });
+
+    result.unwrap();
+
+    table
+}
+
+/// Replace text in a set of knots with its translation from a table.
+///
+/// Entries without a translation or whose source text does not match the text in the knots
+/// are not loaded. The identifiers of all text which was not translated are returned.
+pub fn translate_knots(
+    knots: &mut KnotSet,
+    table: &StringTable,
+) -> Result<Vec<String>, LocalizationError> {
+    let entries = table
+        .entries
+        .iter()
+        .map(|entry| (entry.id.as_str(), entry))
+        .collect::<HashMap<_, _>>();
+
+    let mut untranslated = Vec::new();
+
+    for_each_line(knots, &mut |id, line| {
+        translate_chunk(&mut line.chunk, id, &entries, &mut untranslated)
+    })?;
+
+    // The display text of choices is translated both in the choice and its branch
+    let mut seen = HashSet::new();
+    untranslated.retain(|id| seen.insert(id.clone()));
+
+    Ok(untranslated)
+}
+
+/// Call a function with every line, choice selection text and choice display text.
+///
+/// Knots and stitches are visited in the order they appear in the script.
+fn for_each_line<F, E>(knots: &mut KnotSet, f: &mut F) -> Result<(), E>
+where
+    F: FnMut(&str, &mut InternalLine) -> Result<(), E>,
+{
+    let mut knots = knots.values_mut().collect::<Vec<_>>();
+    knots.sort_by_key(|knot| knot.meta_data.line());
+
+    for knot in knots {
+        let mut stitches = knot.stitches.values_mut().collect::<Vec<_>>();
+        stitches.sort_by_key(|stitch| stitch.meta_data.line());
+
+        for stitch in stitches {
+            let mut index = 0;
+            for_each_node_line(&mut stitch.root.items, &stitch.root.address, &mut index, f)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Call a function with every line and choice in a list of node items.
+///
+/// The index counts the lines and choices which have been visited so far in the stitch.
+fn for_each_node_line<F, E>(
+    items: &mut [NodeItem],
+    address: &Address,
+    index: &mut usize,
+    f: &mut F,
+) -> Result<(), E>
+where
+    F: FnMut(&str, &mut InternalLine) -> Result<(), E>,
+{
+    for item in items {
+        match item {
+            NodeItem::Line(line) => {
+                let id = format!("{}:{}", address, index);
+                *index += 1;
+
+                f(&id, line)?;
+            }
+            NodeItem::BranchingPoint(branches) => {
+                for branch in branches {
+                    let choice = &mut branch.choice;
+                    let id = format!("{}:{}", address, index);
+                    *index += 1;
+
+                    let selection_id = format!("{}/selection", id);
+                    let mut selection_text = choice.selection_text.lock().unwrap();
+
+                    let display_id = if choice.display_text.chunk == selection_text.chunk {
+                        selection_id.clone()
+                    } else {
+                        format!("{}/display", id)
+                    };
+
+                    f(&selection_id, &mut selection_text)?;
+                    f(&display_id, &mut choice.display_text)?;
+
+                    drop(selection_text);
+
+                    // The first line in a branch is the display text of its choice, which
+                    // is the line that is added to the buffer when the choice is made
+                    let items = match branch.items.split_first_mut() {
+                        Some((NodeItem::Line(line), rest)) => {
+                            f(&display_id, line)?;
+                            rest
+                        }
+                        _ => &mut branch.items,
+                    };
+
+                    for_each_node_line(items, address, index, f)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Add entries for the text of a chunk and all chunks nested in it.
+fn add_chunk_entries(
+    chunk: &LineChunk,
+    id: &str,
+    entries: &mut Vec<StringEntry>,
+    seen: &mut HashSet<String>,
+) {
+    add_items_entries(&chunk.items, id, entries, seen);
+
+    if chunk.condition.is_some() {
+        add_items_entries(&chunk.else_items, &format!("{}/else", id), entries, seen);
+    }
+}
+
+fn add_items_entries(
+    items: &[Content],
+    id: &str,
+    entries: &mut Vec<StringEntry>,
+    seen: &mut HashSet<String>,
+) {
+    if let Some(source) = get_source_text(items) {
+        if seen.insert(id.to_string()) {
+            entries.push(StringEntry {
+                id: id.to_string(),
+                source,
+                translation: None,
+            });
+        }
+    }
+
+    for (i, item) in items.iter().enumerate() {
+        match item {
+            Content::Nested(chunk) => {
+                add_chunk_entries(chunk, &format!("{}/{}", id, i), entries, seen);
+            }
+            Content::Alternative(alternative) => {
+                for (j, chunk) in alternative.items.iter().enumerate() {
+                    add_chunk_entries(chunk, &format!("{}/{}/{}", id, i, j), entries, seen);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Translate the text of a chunk and all chunks nested in it.
+///
+/// Nested chunks are translated first, since their identifiers are formed from the
+/// indices of their items before the parent text is translated and its items reordered.
+fn translate_chunk(
+    chunk: &mut LineChunk,
+    id: &str,
+    entries: &HashMap<&str, &StringEntry>,
+    untranslated: &mut Vec<String>,
+) -> Result<(), LocalizationError> {
+    translate_items(&mut chunk.items, id, entries, untranslated)?;
+
+    if chunk.condition.is_some() {
+        let else_id = format!("{}/else", id);
+        translate_items(&mut chunk.else_items, &else_id, entries, untranslated)?;
+    }
+
+    Ok(())
+}
+
+fn translate_items(
+    items: &mut Vec<Content>,
+    id: &str,
+    entries: &HashMap<&str, &StringEntry>,
+    untranslated: &mut Vec<String>,
+) -> Result<(), LocalizationError> {
+    for (i, item) in items.iter_mut().enumerate() {
+        match item {
+            Content::Nested(chunk) => {
+                translate_chunk(chunk, &format!("{}/{}", id, i), entries, untranslated)?;
+            }
+            Content::Alternative(alternative) => {
+                for (j, chunk) in alternative.items.iter_mut().enumerate() {
+                    let item_id = format!("{}/{}/{}", id, i, j);
+                    translate_chunk(chunk, &item_id, entries, untranslated)?;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    let source = match get_source_text(items) {
+        Some(source) => source,
+        None => return Ok(()),
+    };
+
+    let translation = entries
+        .get(id)
+        .filter(|entry| entry.source == source)
+        .and_then(|entry| entry.translation.as_ref());
+
+    match translation {
+        Some(translation) => apply_translation(items, id, translation),
+        None => {
+            untranslated.push(id.to_string());
+            Ok(())
+        }
+    }
+}
+
+/// Get the text of a list of items with placeholders for all items which are not text.
+///
+/// Returns `None` if the items contain no text other than whitespace.
+fn get_source_text(items: &[Content]) -> Option<String> {
+    let has_text = items.iter().any(|item| match item {
+        Content::Text(text) => !text.trim().is_empty(),
+        _ => false,
+    });
+
+    if !has_text {
+        return None;
+    }
+
+    let text = items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| match item {
+            Content::Text(text) => text.replace('{', "{{").replace('}', "}}"),
+            _ => format!("{{{}}}", i),
+        })
+        .collect();
+
+    Some(text)
+}
+
+#[derive(Debug, PartialEq)]
+/// Part of a translated text.
+enum Segment {
+    Text(String),
+    Placeholder(usize),
+}
+
+/// Replace the text items in a list with a translation.
+///
+/// The translation must contain the placeholder of every item which is not text exactly
+/// once, with no text after diverts. The items are left untouched if it does not.
+fn apply_translation(
+    items: &mut Vec<Content>,
+    id: &str,
+    translation: &str,
+) -> Result<(), LocalizationError> {
+    let invalid_placeholders = || LocalizationError::InvalidPlaceholders {
+        id: id.to_string(),
+        translation: translation.to_string(),
+    };
+
+    let segments = parse_translation(translation).ok_or_else(invalid_placeholders)?;
+
+    let mut expected = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| !matches!(item, Content::Text(..)))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+
+    let mut found = segments
+        .iter()
+        .filter_map(|segment| match segment {
+            Segment::Placeholder(i) => Some(*i),
+            Segment::Text(..) => None,
+        })
+        .collect::<Vec<_>>();
+
+    expected.sort();
+    found.sort();
+
+    if expected != found || has_text_after_divert(&segments, items) {
+        return Err(invalid_placeholders());
+    }
+
+    let mut slots = items
+        .drain(..)
+        .map(|item| match item {
+            Content::Text(..) => None,
+            other => Some(other),
+        })
+        .collect::<Vec<_>>();
+
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => items.push(Content::Text(text)),
+            Segment::Placeholder(i) => items.extend(slots[i].take()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether any text follows a divert, which would never be shown since the divert is
+/// followed before it is processed.
+fn has_text_after_divert(segments: &[Segment], items: &[Content]) -> bool {
+    segments
+        .iter()
+        .skip_while(|segment| match segment {
+            Segment::Placeholder(i) => !matches!(items[*i], Content::Divert(..)),
+            Segment::Text(..) => true,
+        })
+        .any(|segment| match segment {
+            Segment::Text(text) => !text.trim().is_empty(),
+            Segment::Placeholder(..) => false,
+        })
+}
+
+/// Split a translated text into text and placeholders.
+///
+/// Literal braces are written as `{{` and `}}`. Returns `None` if a brace is not part
+/// of a placeholder or an escaped brace.
+fn parse_translation(translation: &str) -> Option<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut chars = translation.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut index = String::new();
+
+                loop {
+                    match chars.next()? {
+                        '}' => break,
+                        digit if digit.is_ascii_digit() => index.push(digit),
+                        _ => return None,
+                    }
+                }
+
+                if !text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut text)));
+                }
+
+                segments.push(Segment::Placeholder(index.parse().ok()?));
+            }
+            '}' => return None,
+            other => text.push(other),
+        }
+    }
+
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+
+    Some(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::line::parse::parse_internal_line;
+
+    fn get_items(line: &str) -> Vec<Content> {
+        parse_internal_line(line, &().into()).unwrap().chunk.items
+    }
+
+    #[test]
+    fn source_text_has_placeholders_for_items_which_are_not_text() {
+        let items = get_items("Hello, {name}! {&Hi|Bye}");
+        assert_eq!(get_source_text(&items).unwrap(), "Hello, {1}! {3}");
+    }
+
+    #[test]
+    fn source_text_escapes_braces_and_is_none_for_items_without_text() {
+        let items = vec![Content::Text("{ and }".to_string())];
+        assert_eq!(get_source_text(&items).unwrap(), "{{ and }}");
+
+        let items = get_items("{name}");
+        assert_eq!(get_source_text(&items), None);
+    }
+
+    #[test]
+    fn translation_replaces_text_and_may_reorder_placeholders() {
+        let mut items = vec![
+            Content::Text("Give ".to_string()),
+            Content::Empty,
+            Content::Text(" to ".to_string()),
+            Content::Divert(Address::End),
+        ];
+
+        apply_translation(&mut items, "id", "À {{}} donner: {1} {3}").unwrap();
+
+        assert_eq!(
+            items,
+            vec![
+                Content::Text("À {} donner: ".to_string()),
+                Content::Empty,
+                Content::Text(" ".to_string()),
+                Content::Divert(Address::End),
+            ]
+        );
+    }
+
+    #[test]
+    fn translation_with_text_after_a_divert_is_an_error() {
+        let mut items = vec![
+            Content::Text("Leave ".to_string()),
+            Content::Divert(Address::End),
+        ];
+
+        assert!(apply_translation(&mut items, "id", "{1} Partir").is_err());
+        assert!(apply_translation(&mut items, "id", "Partir {1} ").is_ok());
+    }
+
+    #[test]
+    fn translation_without_every_placeholder_exactly_once_is_an_error() {
+        let items = vec![
+            Content::Text("Give ".to_string()),
+            Content::Empty,
+            Content::Text(" away".to_string()),
+        ];
+
+        for translation in &["Donner", "Donner {1} {1}", "Donner {2}", "Donner {1", "{x}"] {
+            let mut translated = items.clone();
+
+            match apply_translation(&mut translated, "id", translation) {
+                Err(LocalizationError::InvalidPlaceholders { id, .. }) => assert_eq!(id, "id"),
+                other => panic!(
+                    "expected an error for '{}' but got {:?}",
+                    translation, other
+                ),
+            }
+
+            assert_eq!(translated, items);
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//! Reading and writing string tables as XLIFF 1.2 files.
+//!
+//! Every entry is written as a `trans-unit` with its identifier as `id`, along with its
+//! `source` text and `target` translation. Untranslated entries have no `target`.
+//!
+//! Only the `trans-unit` elements and their `source` and `target` elements are read.
+//! Any other content of the file is ignored.
+
+use crate::{
+    error::LocalizationError,
+    story::localization::table::{StringEntry, StringTable},
+};
+
+const FORMAT: &str = "XLIFF";
+
+impl StringTable {
+    /// Write the table as an XLIFF 1.2 file.
+    ///
+    /// The languages are written as the `source-language` and `target-language`
+    /// of the file, for example `en` and `fr`. The target language is left out if it
+    /// is not given, as for a table which has not yet been translated.
+    pub fn to_xliff(&self, source_language: &str, target_language: Option<&str>) -> String {
+        let target = target_language
+            .map(|language| format!(" target-language=\"{}\"", escape(language)))
+            .unwrap_or_default();
+
+        let mut buffer = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<xliff version=\"1.2\" xmlns=\"urn:oasis:names:tc:xliff:document:1.2\">
+  <file original=\"story\" datatype=\"plaintext\" source-language=\"{}\"{}>
+    <body>
+",
+            escape(source_language),
+            target
+        );
+
+        for entry in &self.entries {
+            buffer.push_str(&format!(
+                "      <trans-unit id=\"{}\" xml:space=\"preserve\">\n",
+                escape(&entry.id)
+            ));
+            buffer.push_str(&format!(
+                "        <source>{}</source>\n",
+                escape(&entry.source)
+            ));
+
+            if let Some(translation) = &entry.translation {
+                buffer.push_str(&format!(
+                    "        <target>{}</target>\n",
+                    escape(translation)
+                ));
+            }
+
+            buffer.push_str("      </trans-unit>\n");
+        }
+
+        buffer.push_str("    </body>\n  </file>\n</xliff>\n");
+
+        buffer
+    }
+
+    /// Read a table from an XLIFF 1.2 file.
+    ///
+    /// Empty `target` elements mark untranslated entries.
+    pub fn from_xliff(content: &str) -> Result<Self, LocalizationError> {
+        let mut entries = Vec::new();
+        let mut position = 0;
+
+        while let Some(offset) = content[position..].find("<trans-unit") {
+            let start = position + offset;
+            let invalid = |message: &str| LocalizationError::InvalidTable {
+                format: FORMAT,
+                line: content[..start].matches('\n').count() + 1,
+                message: message.to_string(),
+            };
+
+            let end = content[start..]
+                .find("</trans-unit>")
+                .map(|offset| start + offset)
+                .ok_or_else(|| invalid("`trans-unit` is not closed"))?;
+
+            let unit = &content[start..end];
+            let tag_end = unit
+                .find('>')
+                .ok_or_else(|| invalid("`trans-unit` tag is not closed"))?;
+
+            let id = get_attribute(&unit[..tag_end], "id")
+                .ok_or_else(|| invalid("`trans-unit` has no `id`"))?;
+
+            let source = get_element(unit, "source")
+                .ok_or_else(|| invalid("`trans-unit` has no `source`"))?;
+
+            let translation = get_element(unit, "target");
+
+            entries.push(StringEntry {
+                id: unescape(id).ok_or_else(|| invalid("invalid character reference"))?,
+                source: unescape(source).ok_or_else(|| invalid("invalid character reference"))?,
+                translation: translation
+                    .map(|text| {
+                        unescape(text).ok_or_else(|| invalid("invalid character reference"))
+                    })
+                    .transpose()?
+                    .filter(|text| !text.is_empty()),
+            });
+
+            position = end;
+        }
+
+        Ok(StringTable { entries })
+    }
+}
+
+/// Get the value of an attribute in an opening tag.
+fn get_attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    ['"', '\''].iter().find_map(|quote| {
+        let pattern = format!(" {}={}", name, quote);
+        let start = tag.find(&pattern)? + pattern.len();
+        let length = tag[start..].find(*quote)?;
+
+        Some(&tag[start..start + length])
+    })
+}
+
+/// Get the content of the first element with a name.
+///
+/// Empty elements such as `<target/>` have empty content.
+fn get_element<'a>(content: &'a str, name: &str) -> Option<&'a str> {
+    let open = format!("<{}", name);
+
+    let start = content.match_indices(&open).find_map(|(index, _)| {
+        match content[index + open.len()..].chars().next()? {
+            '>' | ' ' | '/' | '\t' | '\n' | '\r' => Some(index),
+            _ => None,
+        }
+    })?;
+
+    let tag_end = start + content[start..].find('>')?;
+
+    if content[..tag_end].ends_with('/') {
+        return Some("");
+    }
+
+    let close = format!("</{}>", name);
+    let length = content[tag_end + 1..].find(&close)?;
+
+    Some(&content[tag_end + 1..tag_end + 1 + length])
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Replace entity and character references with their characters.
+///
+/// Returns `None` if a reference is not closed or unknown.
+fn unescape(text: &str) -> Option<String> {
+    let mut buffer = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        buffer.push_str(&rest[..start]);
+
+        let length = rest[start..].find(';')?;
+        let reference = &rest[start + 1..start + length];
+
+        let c = match reference {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = match reference.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => reference.strip_prefix('#')?.parse().ok()?,
+                };
+
+                std::char::from_u32(code)?
+            }
+        };
+
+        buffer.push(c);
+        rest = &rest[start + length + 1..];
+    }
+
+    buffer.push_str(rest);
+
+    Some(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_written_to_xliff_is_read_back() {
+        let table = StringTable {
+            entries: vec![
+                StringEntry {
+                    id: "tavern:3".to_string(),
+                    source: "<\"Welcome\" & 'hello'> {1}".to_string(),
+                    translation: Some("« Bienvenue » {1}".to_string()),
+                },
+                StringEntry {
+                    id: "tavern:4/selection".to_string(),
+                    source: "Sit down ".to_string(),
+                    translation: None,
+                },
+            ],
+        };
+
+        let xliff = table.to_xliff("en", Some("fr"));
+
+        assert!(xliff.contains("source-language=\"en\" target-language=\"fr\">"));
+        assert!(table
+            .to_xliff("en", None)
+            .contains("source-language=\"en\">"));
+        assert!(xliff
+            .contains("<source>&lt;&quot;Welcome&quot; &amp; &apos;hello&apos;&gt; {1}</source>"));
+        assert_eq!(StringTable::from_xliff(&xliff).unwrap(), table);
+    }
+
+    #[test]
+    fn xliff_units_with_empty_or_character_reference_targets_are_read() {
+        let content = "\
+<xliff version='1.2'>
+  <file>
+    <body>
+      <trans-unit id='a'>
+        <source>One</source>
+        <target state=\"new\"/>
+      </trans-unit>
+      <trans-unit id=\"b\">
+        <source>Two</source>
+        <target state=\"translated\">Deux &#233;&#xE9;</target>
+      </trans-unit>
+    </body>
+  </file>
+</xliff>
+";
+
+        let table = StringTable::from_xliff(content).unwrap();
+
+        assert_eq!(table.get("a").unwrap().translation, None);
+        assert_eq!(
+            table.get("b").unwrap().translation.as_deref(),
+            Some("Deux éé")
+        );
+    }
+
+    #[test]
+    fn xliff_unit_without_source_is_an_error_with_its_line_number() {
+        let content = "<xliff>\n<trans-unit id=\"a\">\n<target>Un</target>\n</trans-unit>\n";
+
+        match StringTable::from_xliff(content) {
+            Err(LocalizationError::InvalidTable { format, line, .. }) => {
+                assert_eq!(format, "XLIFF");
+                assert_eq!(line, 2);
+            }
+            other => panic!("expected an error but got {:?}", other),
+        }
+    }
+}
@@ -11,14 +11,46 @@
 //! Most of the rest of this module deals with processing internal data into a form
 //! presented to the user, or validating the content of the story as it is being accessed.
 
+pub(crate) mod coverage;
+pub(crate) mod debug;
+mod diff;
+mod evaluate;
+mod explore;
+mod format;
+mod graph;
+pub(crate) mod history;
+mod localization;
 pub(crate) mod parse;
+mod reachability;
 pub(crate) mod rng;
+mod stats;
 mod story;
+mod symbols;
+pub(crate) mod trace;
+mod transcript;
 pub(crate) mod types;
 mod utils;
 pub(crate) mod validate;
 
+pub use coverage::{
+    Coverage, CoverageItem, CoverageKind, CoverageReport, KnotCoverage, UnseenItem,
+};
+pub use debug::{Breakpoint, Pause, PauseReason, StackFrame, Step, Watch};
+pub use diff::{ChoiceSetChange, SavedPosition, StoryDiff, VariableChange};
+pub use explore::{ExploreIssue, ExploreIssueKind, ExploreOptions, ExploreReport, ExploreStrategy};
+pub use format::format_ink;
+pub use graph::GraphFormat;
+pub use localization::{StringEntry, StringTable};
 pub use parse::read_story_content_from_string;
-pub use story::{read_story_from_string, Story};
-pub use types::{Choice, Line, LineBuffer, Location, Prompt};
+pub use reachability::{ReachabilityOptions, ReachabilityReport, ReachedKnot};
+pub use stats::StoryStats;
+pub(crate) use story::location_from_address;
+pub use story::{read_story_from_string, read_story_from_string_with_options, Story};
+pub use symbols::{Symbol, SymbolKind};
+pub use trace::{TraceEvent, Tracer};
+pub use transcript::{Transcript, TranscriptEntry, TranscriptEvent};
+pub use types::{Choice, ChoiceRef, Line, LineBuffer, Location, Prompt, ReadOptions, Tag};
 pub use utils::copy_lines_into_string;
+
+#[cfg(feature = "random")]
+pub use rng::RandomSource;
@@ -53,6 +53,7 @@ pub fn read_story_content_from_string(
         Err(ParseError {
             knot_errors,
             prelude_errors,
+            source: None,
         }
         .into())
     }
@@ -68,12 +69,34 @@ fn process_file_content_into_lines_and_metadata<'a>(
 ) -> Vec<(&'a str, MetaData)> {
     content
         .lines()
-        .zip(0..)
-        .map(|(line, line_index)| (line, MetaData::from(line_index)))
-        .map(|(line, meta_data)| (trim_comment(line, log, &meta_data).trim(), meta_data))
+        .enumerate()
+        .map(|(line_index, line)| {
+            let line_offset = get_byte_offset(content, line);
+            let line_meta_data = MetaData::with_span(0, line_index, line_offset, 0, line);
+
+            let trimmed = trim_comment(line, log, &line_meta_data).trim();
+            let leading_whitespace = get_byte_offset(line, trimmed);
+
+            let meta_data = MetaData::with_span(
+                0,
+                line_index,
+                line_offset + leading_whitespace,
+                line[..leading_whitespace].chars().count(),
+                trimmed,
+            );
+
+            (trimmed, meta_data)
+        })
         .collect()
 }
 
+/// Get the byte offset of a string slice from the start of the string it was sliced from.
+fn get_byte_offset(content: &str, slice: &str) -> usize {
+    (slice.as_ptr() as usize)
+        .saturating_sub(content.as_ptr() as usize)
+        .min(content.len())
+}
+
 /// Split off lines until the first named knot then parse its content and root knot.
 ///
 /// After this function has been called, the given set of lines starts at the first named
@@ -199,21 +222,20 @@ fn get_knot_from_lines(lines: Vec<(&str, MetaData)>) -> Result<(String, Knot), K
     let (default_stitch, stitches, stitch_errors) = get_stitches_from_lines(tail, &knot_name);
     line_errors.extend(stitch_errors);
 
-    if default_stitch.is_some() && line_errors.is_empty() {
-        Ok((
+    match default_stitch {
+        Some(default_stitch) if line_errors.is_empty() => Ok((
             knot_name,
             Knot {
-                default_stitch: default_stitch.unwrap(),
+                default_stitch,
                 stitches,
                 tags,
                 meta_data: knot_meta_data.clone(),
             },
-        ))
-    } else {
-        Err(KnotError {
+        )),
+        _ => Err(KnotError {
             knot_meta_data: knot_meta_data.clone(),
             line_errors,
-        })
+        }),
     }
 }
 
@@ -319,8 +341,8 @@ fn get_stitch_name(
         Err(KnotNameError::Empty) => Ok(None),
         Err(kind) => Err(KnotErrorKind::InvalidName {
             line: first_line.to_string(),
+            meta_data: get_invalid_name_meta_data(first_line, &kind, meta_data),
             kind,
-            meta_data: meta_data.clone(),
         }),
     }
 }
@@ -335,13 +357,37 @@ fn get_invalid_name_error(
 
     let error = KnotErrorKind::InvalidName {
         line: line.to_string(),
+        meta_data: get_invalid_name_meta_data(line, &kind, meta_data),
         kind,
-        meta_data: meta_data.clone(),
     };
 
     (invalid_name, error)
 }
 
+/// Narrow the meta data of a knot or stitch line to the part of the name that is invalid.
+fn get_invalid_name_meta_data(line: &str, kind: &KnotNameError, meta_data: &MetaData) -> MetaData {
+    let name = line
+        .trim_start()
+        .trim_start_matches(STITCH_MARKER)
+        .trim_end()
+        .trim_end_matches(STITCH_MARKER)
+        .trim();
+
+    match kind {
+        KnotNameError::ContainsInvalidCharacter(c) => match line.rfind(name) {
+            Some(i) => {
+                let j = i + name.find(*c).unwrap_or(0);
+                meta_data.narrow_to(line, j..j + c.len_utf8())
+            }
+            None => meta_data.clone(),
+        },
+        KnotNameError::ContainsWhitespace | KnotNameError::ReservedKeyword { .. } => {
+            meta_data.narrow_to_str(line, name)
+        }
+        KnotNameError::Empty => meta_data.clone(),
+    }
+}
+
 /// Get a verified name for a stitch.
 ///
 /// Stitches are name spaced under their parent knot. If the given stitch has no read name
@@ -403,20 +449,35 @@ fn divide_lines_at_marker<'a>(
 ///
 /// Assumes that the line only contains a single tag, i.e. this is not suitable for
 /// line tags.
-fn parse_tag_from_line(line: &str) -> String {
+pub(crate) fn parse_tag_from_line(line: &str) -> String {
     line.trim_start_matches(|c: char| c.is_whitespace() || c == TAG_MARKER)
         .to_string()
 }
 
 /// Trim TODO and line comments from a line.
 fn trim_comment<'a>(line: &'a str, log: &mut Logger, meta_data: &MetaData) -> &'a str {
+    let (content, comment) = split_off_comment(line);
+
+    if comment
+        .filter(|comment| comment.starts_with(TODO_COMMENT_MARKER))
+        .is_some()
+    {
+        log.add_todo(line, meta_data);
+    }
+
+    content
+}
+
+/// Split a line into its content and its line or TODO comment, if it has one.
+///
+/// Lines which begin with a TODO comment are comments in their entirety.
+pub(crate) fn split_off_comment(line: &str) -> (&str, Option<&str>) {
     if let Some(i) = line.find(LINE_COMMENT_MARKER) {
-        line.get(..i).unwrap()
+        (&line[..i], Some(&line[i..]))
     } else if line.trim_start().starts_with(TODO_COMMENT_MARKER) {
-        log.add_todo(line, meta_data);
-        ""
+        ("", Some(line.trim_start()))
     } else {
-        line
+        (line, None)
     }
 }
 
@@ -441,6 +502,16 @@ fn split_off_prelude_lines<'a>(lines: &mut Vec<(&'a str, MetaData)>) -> Vec<(&'a
 fn split_prelude_into_metadata_and_text<'a>(
     lines: &[(&'a str, MetaData)],
 ) -> (Vec<(&'a str, MetaData)>, Vec<(&'a str, MetaData)>) {
+    if let Some(i) = lines.iter().position(|(line, _)| !is_metadata_line(line)) {
+        let (metadata, text) = lines.split_at(i);
+        (metadata.to_vec(), text.to_vec())
+    } else {
+        (lines.to_vec(), Vec::new())
+    }
+}
+
+/// Check whether a line in the prelude is metadata, a comment or empty.
+pub(crate) fn is_metadata_line(line: &str) -> bool {
     // Add spaces after all keywords (except line comment) to search for whole words.
     let metadata_keywords = &[
         format!("{} ", CONST_MARKER),
@@ -453,20 +524,11 @@ fn split_prelude_into_metadata_and_text<'a>(
 
     const METADATA_CHARS: &[char] = &[TAG_MARKER];
 
-    if let Some(i) = lines
-        .iter()
-        .map(|(line, _)| line.trim_start())
-        .position(|line| {
-            metadata_keywords.iter().all(|key| !line.starts_with(key))
-                && METADATA_CHARS.iter().all(|&c| !line.starts_with(c))
-                && !line.is_empty()
-        })
-    {
-        let (metadata, text) = lines.split_at(i);
-        (metadata.to_vec(), text.to_vec())
-    } else {
-        (lines.to_vec(), Vec::new())
-    }
+    let line = line.trim_start();
+
+    metadata_keywords.iter().any(|key| line.starts_with(key))
+        || METADATA_CHARS.iter().any(|&c| line.starts_with(c))
+        || line.is_empty()
 }
 
 /// Parse global tags from a set of metadata lines in the prelude.
@@ -497,10 +559,15 @@ fn parse_global_variables(lines: &[(&str, MetaData)]) -> (VariableSet, Vec<Prelu
                 }
             })
         {
+            let meta_data = match &kind {
+                PreludeErrorKind::DuplicateVariable { name } => meta_data.narrow_to_str(line, name),
+                _ => meta_data.clone(),
+            };
+
             errors.push(PreludeError {
                 line: line.to_string(),
                 kind,
-                meta_data: meta_data.clone(),
+                meta_data,
             });
         }
     }
@@ -512,7 +579,7 @@ fn parse_global_variables(lines: &[(&str, MetaData)]) -> (VariableSet, Vec<Prelu
 ///
 /// Variable lines are on the form `VAR variable_name = initial_value` and constant variables
 /// on the form `CONST variable_name = constant_value`.
-fn parse_variable_info_from_line(
+pub(crate) fn parse_variable_info_from_line(
     line: &str,
     meta_data: &MetaData,
 ) -> Result<(String, VariableInfo), PreludeErrorKind> {
@@ -527,6 +594,7 @@ fn parse_variable_info_from_line(
             name,
             VariableInfo {
                 is_const,
+                declared: Some(variable.clone()),
                 variable,
                 meta_data: meta_data.clone(),
             },
@@ -925,6 +993,21 @@ Second line.
         assert_eq!(knot_lines[2][..], content[5..]);
     }
 
+    #[test]
+    fn initial_processing_sets_span_of_trimmed_content_in_meta_data() {
+        let content = "Line one\n  \tåäö line // Comment\n";
+
+        let mut log = Logger::default();
+        let lines = process_file_content_into_lines_and_metadata(content, &mut log);
+
+        assert_eq!(lines[0].1.byte_range(), 0..8);
+        assert_eq!(lines[0].1.column_range(), 0..8);
+
+        assert_eq!(lines[1].0, "åäö line");
+        assert_eq!(&content[lines[1].1.byte_range()], "åäö line");
+        assert_eq!(lines[1].1.column_range(), 3..11);
+    }
+
     #[test]
     fn comment_lines_are_trimmed_by_initial_processing() {
         let content_lines = vec![
@@ -937,13 +1020,16 @@ Second line.
         let content = content_lines.join("\n");
 
         let mut log = Logger::default();
-        let lines = process_file_content_into_lines_and_metadata(&content, &mut log);
+        let lines = process_file_content_into_lines_and_metadata(&content, &mut log)
+            .into_iter()
+            .map(|(line, meta_data)| (line, meta_data.line_index))
+            .collect::<Vec<_>>();
 
         assert_eq!(lines.len(), 4);
-        assert_eq!(lines[0], (content_lines[0], MetaData::from(0)));
-        assert_eq!(lines[1], ("", MetaData::from(1)));
-        assert_eq!(lines[2], ("", MetaData::from(2)));
-        assert_eq!(lines[3], (content_lines[3], MetaData::from(3)));
+        assert_eq!(lines[0], (content_lines[0], 0));
+        assert_eq!(lines[1], ("", 1));
+        assert_eq!(lines[2], ("", 2));
+        assert_eq!(lines[3], (content_lines[3], 3));
     }
 
     #[test]
@@ -957,12 +1043,15 @@ Second line.
         let content = content_lines.join("\n");
 
         let mut log = Logger::default();
-        let lines = process_file_content_into_lines_and_metadata(&content, &mut log);
+        let lines = process_file_content_into_lines_and_metadata(&content, &mut log)
+            .into_iter()
+            .map(|(line, meta_data)| (line, meta_data.line_index))
+            .collect::<Vec<_>>();
 
         assert_eq!(lines.len(), 3);
-        assert_eq!(lines[0], (content_lines[0], MetaData::from(0)));
-        assert_eq!(lines[1], ("Line before comment marker", MetaData::from(1)));
-        assert_eq!(lines[2], (content_lines[2], MetaData::from(2)));
+        assert_eq!(lines[0], (content_lines[0], 0));
+        assert_eq!(lines[1], ("Line before comment marker", 1));
+        assert_eq!(lines[2], (content_lines[2], 2));
     }
 
     #[test]
@@ -977,12 +1066,15 @@ Second line.
         let content = content_lines.join("\n");
 
         let mut log = Logger::default();
-        let lines = process_file_content_into_lines_and_metadata(&content, &mut log);
+        let lines = process_file_content_into_lines_and_metadata(&content, &mut log)
+            .into_iter()
+            .map(|(line, meta_data)| (line, meta_data.line_index))
+            .collect::<Vec<_>>();
 
-        assert_eq!(lines[0], (content_lines[0], MetaData::from(0)));
-        assert_eq!(lines[1], ("", MetaData::from(1)));
-        assert_eq!(lines[2], ("", MetaData::from(2)));
-        assert_eq!(lines[3], (content_lines[3], MetaData::from(3)));
+        assert_eq!(lines[0], (content_lines[0], 0));
+        assert_eq!(lines[1], ("", 1));
+        assert_eq!(lines[2], ("", 2));
+        assert_eq!(lines[3], (content_lines[3], 3));
     }
 
     #[test]
@@ -999,15 +1091,18 @@ Second line.
         let content = content_lines.join("\n");
 
         let mut log = Logger::default();
-        let lines = process_file_content_into_lines_and_metadata(&content, &mut log);
+        let lines = process_file_content_into_lines_and_metadata(&content, &mut log)
+            .into_iter()
+            .map(|(line, meta_data)| (line, meta_data.line_index))
+            .collect::<Vec<_>>();
 
         assert_eq!(lines.len(), 6);
-        assert_eq!(lines[0], ("Initial", MetaData::from(0)));
-        assert_eq!(lines[1], ("End", MetaData::from(1)));
-        assert_eq!(lines[2], ("", MetaData::from(2)));
-        assert_eq!(lines[3], ("After comment", MetaData::from(3)));
-        assert_eq!(lines[4], ("", MetaData::from(4)));
-        assert_eq!(lines[5], ("Both", MetaData::from(5)));
+        assert_eq!(lines[0], ("Initial", 0));
+        assert_eq!(lines[1], ("End", 1));
+        assert_eq!(lines[2], ("", 2));
+        assert_eq!(lines[3], ("After comment", 3));
+        assert_eq!(lines[4], ("", 4));
+        assert_eq!(lines[5], ("Both", 5));
     }
 
     #[test]
@@ -1017,14 +1112,17 @@ Second line.
         let content = content_lines.join("\n");
 
         let mut log = Logger::default();
-        let lines = process_file_content_into_lines_and_metadata(&content, &mut log);
+        let lines = process_file_content_into_lines_and_metadata(&content, &mut log)
+            .into_iter()
+            .map(|(line, meta_data)| (line, meta_data.line_index))
+            .collect::<Vec<_>>();
 
         assert_eq!(lines.len(), 5);
-        assert_eq!(lines[0], (content_lines[0], MetaData::from(0)));
-        assert_eq!(lines[1], (content_lines[1], MetaData::from(1)));
-        assert_eq!(lines[2], (content_lines[2], MetaData::from(2)));
-        assert_eq!(lines[3], ("", MetaData::from(3)));
-        assert_eq!(lines[4], (content_lines[4], MetaData::from(4)));
+        assert_eq!(lines[0], (content_lines[0], 0));
+        assert_eq!(lines[1], (content_lines[1], 1));
+        assert_eq!(lines[2], (content_lines[2], 2));
+        assert_eq!(lines[3], ("", 3));
+        assert_eq!(lines[4], (content_lines[4], 4));
     }
 
     #[test]
@@ -1456,10 +1554,35 @@ Line two.
 
         let mut log = Logger::default();
 
+        // Meta data spans differ with the whitespace, so compare names and tags directly
+        let (knots, variables, tags) =
+            read_story_content_from_string(&content_nowhitespace, &mut log).unwrap();
+        let (knots_whitespace, variables_whitespace, tags_whitespace) =
+            read_story_content_from_string(&content_whitespace, &mut log).unwrap();
+
+        assert_eq!(tags, tags_whitespace);
+
+        assert_eq!(variables.len(), variables_whitespace.len());
         assert_eq!(
-            read_story_content_from_string(&content_nowhitespace, &mut log).unwrap(),
-            read_story_content_from_string(&content_whitespace, &mut log).unwrap()
+            variables.get("trimmed").unwrap().variable,
+            variables_whitespace.get("trimmed").unwrap().variable
         );
+
+        assert_eq!(knots.len(), knots_whitespace.len());
+
+        for (name, knot) in knots {
+            let knot_whitespace = knots_whitespace.get(&name).unwrap();
+
+            assert_eq!(knot.tags, knot_whitespace.tags);
+
+            let mut stitch_names = knot.stitches.keys().collect::<Vec<_>>();
+            let mut stitch_names_whitespace = knot_whitespace.stitches.keys().collect::<Vec<_>>();
+
+            stitch_names.sort();
+            stitch_names_whitespace.sort();
+
+            assert_eq!(stitch_names, stitch_names_whitespace);
+        }
     }
 
     #[test]
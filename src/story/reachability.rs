@@ -0,0 +1,551 @@
+//! Breadth-first enumeration of the states a story can reach through its choices.
+
+use crate::{
+    consts::ROOT_KNOT_NAME,
+    line::Variable,
+    story::{
+        story::Story,
+        types::{Choice, Location, Prompt},
+    },
+};
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Options for [`get_reachability_report`][crate::story::Story::get_reachability_report()].
+pub struct ReachabilityOptions {
+    /// Maximum number of choices to make from the starting state.
+    pub max_depth: usize,
+    /// Maximum number of distinct states to enumerate.
+    pub max_states: usize,
+    /// Maximum number of diverts to follow without reaching a choice or an end, after
+    /// which the story is considered to be stuck in a loop.
+    pub divert_limit: usize,
+}
+
+impl Default for ReachabilityOptions {
+    fn default() -> Self {
+        ReachabilityOptions {
+            max_depth: 20,
+            max_states: 10_000,
+            divert_limit: 1000,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Knots which can be reached in a story and how to reach them.
+pub struct ReachabilityReport {
+    /// Number of distinct states that were enumerated.
+    ///
+    /// A state is the location at which the story stopped and the choices it presented,
+    /// along with the values of its global variables and which knots and stitches have
+    /// been visited.
+    pub num_states: usize,
+    /// Number of times the story returned an error. No states are enumerated past errors.
+    pub num_errors: usize,
+    /// Whether every state was enumerated, or if the maximum depth or number of states
+    /// stopped the enumeration early.
+    pub is_complete: bool,
+    /// Knots which were reached, in the order they were first reached.
+    pub reached: Vec<ReachedKnot>,
+    /// Names of knots which were never reached.
+    pub unreached: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Knot which was reached while enumerating the states of a story.
+pub struct ReachedKnot {
+    /// Name of knot, or `None` for the content at the start of the story before any knot.
+    pub name: Option<String>,
+    /// Indices of the fewest choices to make from the start to reach the knot.
+    pub choices: Vec<usize>,
+    /// Distinct values of the non-constant global variables, sorted by name, when the
+    /// story stopped at a choice or end after having visited the knot.
+    pub variable_values: Vec<Vec<(String, Variable)>>,
+}
+
+impl ReachabilityReport {
+    /// Get the reached knot with the given name, or `None` for the start of the story.
+    pub fn get_knot(&self, name: Option<&str>) -> Option<&ReachedKnot> {
+        self.reached
+            .iter()
+            .find(|knot| knot.name.as_deref() == name)
+    }
+}
+
+/// Visit counts summed over the stitches of every knot.
+type KnotCounts = HashMap<String, u32>;
+
+#[derive(PartialEq, Eq, Hash)]
+/// Location, presented choices, global variables and visited knots and stitches of a story.
+///
+/// Other state, such as how far alternative sequences have progressed or how many times
+/// knots and stitches have been visited, is not included. Stories which differ only in
+/// that state are considered to be the same, which lets loops end in a state that has
+/// already been followed. Content behind conditions on exact visit counts, such as
+/// `{hub > 2}`, may thus not be reached.
+struct StateKey {
+    /// Knot and stitch that the story stopped at.
+    location: (String, Option<String>),
+    /// Identifiers of the presented choices.
+    choices: Vec<String>,
+    /// Names and values of the non-constant global variables, sorted by name.
+    ///
+    /// `Variable` cannot derive `Hash` since it may contain a float, so the values
+    /// are written in their debug representation.
+    variables: Vec<(String, String)>,
+    /// Knots and stitches which have been visited, sorted by knot and stitch.
+    visited: Vec<(String, String)>,
+}
+
+/// Enumerate the states that a story can reach from its current state, breadth first.
+///
+/// Every state is only followed once. Since states are enumerated in order of the number
+/// of choices it takes to reach them, the first path to a knot is the shortest.
+pub fn get_reachability(story: &Story, options: &ReachabilityOptions) -> ReachabilityReport {
+    let mut start = story.clone();
+    start.clear_tracer();
+    start.clear_breakpoints();
+    start.set_divert_limit(Some(options.divert_limit));
+
+    // States are never rewound or recorded, and must not draw numbers from a source
+    // which is shared with the caller
+    start.set_history_limit(0);
+    start.take_coverage();
+    start.take_transcript();
+
+    #[cfg(feature = "random")]
+    start.clear_random_source();
+
+    let initial_counts = get_knot_counts(&start);
+
+    let mut report = ReachabilityReport {
+        num_states: 0,
+        num_errors: 0,
+        is_complete: true,
+        reached: Vec::new(),
+        unreached: Vec::new(),
+    };
+
+    let mut seen_states = HashSet::new();
+    let mut queue = VecDeque::new();
+    queue.push_back((start, Vec::new()));
+
+    while let Some((mut story, choices)) = queue.pop_front() {
+        let counts_before = get_knot_counts(&story);
+
+        let presented = match story.resume(&mut Vec::new()) {
+            Ok(Prompt::Choice(presented)) => presented,
+            Ok(Prompt::Done) => Vec::new(),
+            Ok(Prompt::Breakpoint(..)) => {
+                unreachable!("breakpoints are cleared before exploring")
+            }
+            Err(..) => {
+                report.num_errors += 1;
+                continue;
+            }
+        };
+
+        add_reached_knots(&story, &counts_before, &choices, &mut report);
+
+        if !seen_states.insert(get_state_key(&story, &presented)) {
+            continue;
+        }
+
+        if report.num_states >= options.max_states {
+            report.is_complete = false;
+            break;
+        }
+
+        report.num_states += 1;
+
+        if !presented.is_empty() && choices.len() >= options.max_depth {
+            report.is_complete = false;
+            continue;
+        }
+
+        for selection in 0..presented.len() {
+            let mut branch = story.clone();
+
+            match branch.make_choice(selection) {
+                Ok(()) => {
+                    let mut branch_choices = choices.clone();
+                    branch_choices.push(selection);
+
+                    queue.push_back((branch, branch_choices));
+                }
+                Err(..) => report.num_errors += 1,
+            }
+        }
+    }
+
+    let mut unreached = initial_counts
+        .keys()
+        .filter(|name| name.as_str() != ROOT_KNOT_NAME)
+        .filter(|name| report.get_knot(Some(name)).is_none())
+        .cloned()
+        .collect::<Vec<_>>();
+
+    unreached.sort();
+    report.unreached = unreached;
+
+    report
+}
+
+/// Add the knots whose visit counts increased since the last state to the report.
+fn add_reached_knots(
+    story: &Story,
+    counts_before: &KnotCounts,
+    choices: &[usize],
+    report: &mut ReachabilityReport,
+) {
+    let counts_after = get_knot_counts(story);
+
+    let mut visited = counts_after
+        .iter()
+        .filter(|(name, &count)| count > counts_before.get(*name).cloned().unwrap_or(0))
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>();
+
+    visited.sort();
+
+    let variables = story.get_mutable_variables();
+
+    for name in visited {
+        let name = if name == ROOT_KNOT_NAME {
+            None
+        } else {
+            Some(name.clone())
+        };
+
+        match report.reached.iter_mut().find(|knot| knot.name == name) {
+            Some(knot) => {
+                if !knot.variable_values.contains(&variables) {
+                    knot.variable_values.push(variables.clone());
+                }
+            }
+            None => report.reached.push(ReachedKnot {
+                name,
+                choices: choices.to_vec(),
+                variable_values: vec![variables.clone()],
+            }),
+        }
+    }
+}
+
+fn get_knot_counts(story: &Story) -> KnotCounts {
+    story
+        .get_knot_visit_counts()
+        .iter()
+        .map(|(knot, stitches)| (knot.clone(), stitches.values().sum()))
+        .collect()
+}
+
+/// Get the key which identifies the state of a story that presented the given choices.
+fn get_state_key(story: &Story, presented: &[Choice]) -> StateKey {
+    let Location { knot, stitch } = story.get_current_location();
+
+    let variables = story
+        .get_mutable_variables()
+        .into_iter()
+        .map(|(name, variable)| (name, format!("{:?}", variable)))
+        .collect();
+
+    let mut visited = story
+        .get_knot_visit_counts()
+        .iter()
+        .flat_map(|(knot, stitches)| {
+            stitches
+                .iter()
+                .filter(|(_, &count)| count > 0)
+                .map(move |(stitch, _)| (knot.clone(), stitch.clone()))
+        })
+        .collect::<Vec<_>>();
+
+    visited.sort();
+
+    StateKey {
+        location: (knot, stitch),
+        choices: presented.iter().map(|choice| choice.id.clone()).collect(),
+        variables,
+        visited,
+    }
+}
+
+impl fmt::Display for ReachabilityReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} state(s){}, {} error(s)",
+            self.num_states,
+            if self.is_complete {
+                ""
+            } else {
+                " (incomplete)"
+            },
+            self.num_errors
+        )?;
+
+        for knot in &self.reached {
+            writeln!(
+                f,
+                "{}: reached with choices {:?}",
+                knot.name.as_deref().unwrap_or("(start)"),
+                knot.choices
+            )?;
+
+            for values in knot
+                .variable_values
+                .iter()
+                .filter(|values| !values.is_empty())
+            {
+                let values = values
+                    .iter()
+                    .map(|(name, variable)| {
+                        format!("{} = {}", name, variable.to_string_unchecked())
+                    })
+                    .collect::<Vec<_>>();
+
+                writeln!(f, "    {}", values.join(", "))?;
+            }
+        }
+
+        for name in &self.unreached {
+            writeln!(f, "{}: never reached", name)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::story::read_story_from_string;
+
+    fn get_report(content: &str, max_depth: usize) -> ReachabilityReport {
+        let options = ReachabilityOptions {
+            max_depth,
+            ..ReachabilityOptions::default()
+        };
+
+        read_story_from_string(content)
+            .unwrap()
+            .get_reachability_report(&options)
+    }
+
+    #[test]
+    fn knots_are_reached_with_the_fewest_choices() {
+        let content = "\
+*   Long way -> detour
+*   Short way -> goal
+== detour
+*   Onwards -> goal
+== goal
+Made it.
+-> END
+== nowhere
+-> END
+";
+
+        let report = get_report(content, 10);
+
+        assert!(report.is_complete);
+        assert_eq!(report.get_knot(None).unwrap().choices, &[] as &[usize]);
+        assert_eq!(report.get_knot(Some("detour")).unwrap().choices, &[0]);
+        assert_eq!(report.get_knot(Some("goal")).unwrap().choices, &[1]);
+        assert_eq!(report.unreached, &["nowhere"]);
+    }
+
+    #[test]
+    fn variable_values_are_recorded_for_reached_knots() {
+        let content = "\
+VAR gold = 0
+CONST price = 5
+*   {gold >= price} Buy -> market
+*   Leave -> END
+== market
+-> END
+";
+
+        let mut story = read_story_from_string(content).unwrap();
+        let options = ReachabilityOptions::default();
+
+        let report = story.get_reachability_report(&options);
+        assert_eq!(report.unreached, &["market"]);
+
+        story.set_variable("gold", 10).unwrap();
+
+        let report = story.get_reachability_report(&options);
+        let market = report.get_knot(Some("market")).unwrap();
+
+        assert_eq!(
+            market.variable_values,
+            vec![vec![("gold".to_string(), Variable::Int(10))]]
+        );
+    }
+
+    #[test]
+    fn paths_which_lead_to_the_same_state_are_only_followed_once() {
+        let content = "\
+*   Left -> hub
+*   Right -> hub
+== hub
+*   Leave -> END
+";
+
+        let report = get_report(content, 10);
+
+        assert!(report.is_complete);
+        assert_eq!(report.num_states, 3);
+        assert_eq!(report.get_knot(Some("hub")).unwrap().choices, &[0]);
+    }
+
+    #[test]
+    fn enumeration_is_incomplete_if_stopped_at_max_depth() {
+        let content = "\
+-> one
+== one
++   Onwards -> two
+== two
++   Onwards -> three
+== three
++   Onwards -> four
+== four
++   Onwards -> END
+";
+
+        let report = get_report(content, 3);
+
+        assert!(!report.is_complete);
+        assert_eq!(report.num_states, 4);
+    }
+
+    #[test]
+    fn loops_end_in_states_which_have_been_followed() {
+        let content = "\
+-> hub
+== hub
++   Again -> hub
++   {hub} Rest -> camp
+== camp
++   Back -> hub
+";
+
+        let report = get_report(content, 100);
+
+        assert!(report.is_complete);
+        assert_eq!(report.num_states, 3);
+        assert_eq!(report.get_knot(Some("camp")).unwrap().choices, &[1]);
+    }
+
+    #[test]
+    fn state_keys_are_equal_only_for_equal_states() {
+        let content = "\
+VAR gold = 0
+*   Leave -> END
+";
+
+        let mut story = read_story_from_string(content).unwrap();
+        let mut line_buffer = Vec::new();
+
+        let choices = match story.resume(&mut line_buffer).unwrap() {
+            Prompt::Choice(choices) => choices,
+            other => panic!("expected a choice, got {:?}", other),
+        };
+
+        let other = story.clone();
+        assert!(get_state_key(&story, &choices) == get_state_key(&other, &choices));
+
+        story.set_variable("gold", 1).unwrap();
+        assert!(get_state_key(&story, &choices) != get_state_key(&other, &choices));
+        assert!(get_state_key(&other, &choices) != get_state_key(&other, &[]));
+    }
+
+    #[test]
+    fn state_keys_include_whether_knots_were_visited_but_not_how_many_times() {
+        let content = "\
+-> hub
+== hub
++   Again -> hub
++   Rest -> camp
+== camp
++   Back -> hub
+";
+
+        let mut story = read_story_from_string(content).unwrap();
+        let mut line_buffer = Vec::new();
+
+        let choices = match story.resume(&mut line_buffer).unwrap() {
+            Prompt::Choice(choices) => choices,
+            other => panic!("expected a choice, got {:?}", other),
+        };
+
+        let first = get_state_key(&story, &choices);
+
+        story.make_choice(0).unwrap();
+        story.resume(&mut line_buffer).unwrap();
+
+        assert!(get_state_key(&story, &choices) == first);
+
+        story.make_choice(1).unwrap();
+        story.resume(&mut line_buffer).unwrap();
+        story.make_choice(0).unwrap();
+        story.resume(&mut line_buffer).unwrap();
+
+        assert!(get_state_key(&story, &choices) != first);
+    }
+
+    #[cfg(feature = "random")]
+    #[test]
+    fn enumerating_does_not_draw_from_the_random_source_of_the_story() {
+        use crate::story::rng::RandomSource;
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Debug)]
+        struct Counter(u64);
+
+        impl RandomSource for Counter {
+            fn next_u64(&mut self) -> u64 {
+                self.0 += 1;
+                self.0
+            }
+        }
+
+        let content = "\
+-> roll
+== roll
+{~1|2|3|4|5|6}
++   Roll again -> roll
+";
+
+        let mut story = read_story_from_string(content).unwrap();
+
+        let counter = Arc::new(Mutex::new(Counter(0)));
+        story.set_random_source(counter.clone());
+
+        story.get_reachability_report(&ReachabilityOptions::default());
+
+        assert_eq!(counter.lock().unwrap().0, 0);
+    }
+
+    #[test]
+    fn errors_are_counted_and_not_followed() {
+        let content = "\
+*   Fine -> END
+*   Loop -> circle
+== circle
+-> circle
+";
+
+        let report = get_report(content, 10);
+
+        assert_eq!(report.num_errors, 1);
+        assert!(report.get_knot(Some("circle")).is_none());
+    }
+}
@@ -15,6 +15,9 @@
 // from the module.
 pub use feature_wrapper::StoryRng;
 
+#[cfg(feature = "random")]
+pub use feature_wrapper::RandomSource;
+
 #[cfg(not(feature = "random"))]
 mod feature_wrapper {
     #[cfg(feature = "serde_support")]
@@ -32,20 +35,64 @@ mod feature_wrapper {
     /// If you are reading this text, the `random` feature is **not**
     /// currently enabled.
     pub struct StoryRng;
+
+    impl StoryRng {
+        /// Use the same external source as another generator. Does nothing since there
+        /// are no sources without the `random` feature.
+        pub fn set_source_from(&mut self, _: &StoryRng) {}
+    }
 }
 
 #[cfg(feature = "random")]
 mod feature_wrapper {
-    use rand::{RngCore, SeedableRng};
+    use rand::{seq::SliceRandom, RngCore, SeedableRng};
     use rand_chacha::ChaCha8Rng;
 
+    use std::{
+        fmt,
+        sync::{Arc, Mutex},
+    };
+
     #[cfg(feature = "serde_support")]
     use serde::{
         de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor},
         ser::{Serialize, SerializeStruct, Serializer},
     };
 
-    #[derive(Clone, Debug)]
+    /// Source of random numbers which a [`Story`][crate::story::Story] can use instead
+    /// of its own generator.
+    ///
+    /// Implement this for a generator to share it between a game and its story, which
+    /// is set with [`set_random_source`][crate::story::Story::set_random_source()].
+    /// The story draws numbers from it for all shuffle sequences.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{read_story_from_string, RandomSource};
+    /// # use std::sync::{Arc, Mutex};
+    /// #[derive(Debug)]
+    /// struct Counter(u64);
+    ///
+    /// impl RandomSource for Counter {
+    ///     fn next_u64(&mut self) -> u64 {
+    ///         self.0 += 1;
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let mut story = read_story_from_string("{~Heads|Tails}").unwrap();
+    /// let counter = Arc::new(Mutex::new(Counter(0)));
+    ///
+    /// story.set_random_source(counter.clone());
+    /// story.resume(&mut Vec::new()).unwrap();
+    ///
+    /// assert!(counter.lock().unwrap().0 > 0);
+    /// ```
+    pub trait RandomSource: Send {
+        /// Return the next random number.
+        fn next_u64(&mut self) -> u64;
+    }
+
     /// Random number generator for the [`Story`][crate::story::Story].
     ///
     /// We use `ChaChaRng` due to it being seedable and with the ability to get and set
@@ -54,11 +101,27 @@ mod feature_wrapper {
     /// If the `serde_support` feature is enabled, we manually derived `Deserialize`
     /// and `Serialize` below. This is due to the generator itself not having either
     /// derived.
+    ///
+    /// An external [`RandomSource`] can be set to use instead of the generator. It is shared
+    /// with the user and thus neither copied when the generator is cloned, nor de/serialized.
     pub struct StoryRng {
         /// Random number generator.
         pub gen: ChaCha8Rng,
         /// Seed for the generator.
         seed: u64,
+        /// External source of random numbers, used instead of `gen` if set.
+        source: Option<Arc<Mutex<dyn RandomSource>>>,
+    }
+
+    impl Clone for StoryRng {
+        /// Clone the generator without the external source, which belongs to the user.
+        fn clone(&self) -> Self {
+            StoryRng {
+                gen: self.gen.clone(),
+                seed: self.seed,
+                source: None,
+            }
+        }
     }
 
     impl Default for StoryRng {
@@ -68,15 +131,63 @@ mod feature_wrapper {
         }
     }
 
+    impl fmt::Debug for StoryRng {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.debug_struct("StoryRng")
+                .field("gen", &self.gen)
+                .field("seed", &self.seed)
+                .field("source", &self.source.as_ref().map(|_| "RandomSource"))
+                .finish()
+        }
+    }
+
     impl StoryRng {
         /// Initiate the random number generator with a seed.
-        fn with_seed(seed: u64) -> Self {
+        pub fn with_seed(seed: u64) -> Self {
             let mut gen = ChaCha8Rng::seed_from_u64(seed);
 
             // `get_word_pos()` will panic unless we set the stream to 0
             gen.set_word_pos(0);
 
-            StoryRng { gen, seed }
+            StoryRng {
+                gen,
+                seed,
+                source: None,
+            }
+        }
+
+        /// Restart the generator from a new seed. Any set external source is kept.
+        pub fn reseed(&mut self, seed: u64) {
+            let source = self.source.take();
+
+            *self = StoryRng::with_seed(seed);
+            self.source = source;
+        }
+
+        /// Get the seed that the generator was started from.
+        pub fn get_seed(&self) -> u64 {
+            self.seed
+        }
+
+        /// Set or remove an external source of random numbers to use instead of the generator.
+        pub fn set_source(&mut self, source: Option<Arc<Mutex<dyn RandomSource>>>) {
+            self.source = source;
+        }
+
+        /// Use the same external source as another generator, or none if it has none.
+        pub fn set_source_from(&mut self, other: &StoryRng) {
+            self.source = other.source.clone();
+        }
+
+        /// Shuffle a slice of items in place.
+        pub fn shuffle<T>(&mut self, items: &mut [T]) {
+            match &self.source {
+                Some(source) => {
+                    let mut source = source.lock().unwrap();
+                    items.shuffle(&mut SourceRng(&mut *source));
+                }
+                None => items.shuffle(&mut self.gen),
+            }
         }
 
         #[cfg(feature = "serde_support")]
@@ -89,6 +200,31 @@ mod feature_wrapper {
         }
     }
 
+    /// Wrapper which lets a `RandomSource` be used with the `rand` utilities.
+    struct SourceRng<'a>(&'a mut dyn RandomSource);
+
+    impl RngCore for SourceRng<'_> {
+        fn next_u32(&mut self) -> u32 {
+            (self.0.next_u64() >> 32) as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0.next_u64()
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for chunk in dest.chunks_mut(8) {
+                let bytes = self.0.next_u64().to_le_bytes();
+                chunk.copy_from_slice(&bytes[..chunk.len()]);
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
     #[cfg(feature = "serde_support")]
     impl Serialize for StoryRng {
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -224,9 +360,88 @@ mod feature_wrapper {
         }
     }
 
-    #[cfg(all(test, feature = "serde_support"))]
+    #[cfg(test)]
     mod tests {
         use super::*;
+
+        #[derive(Debug)]
+        struct Counter(u64);
+
+        impl RandomSource for Counter {
+            fn next_u64(&mut self) -> u64 {
+                self.0 += 1;
+                self.0
+            }
+        }
+
+        #[test]
+        fn generators_with_same_seed_shuffle_identically() {
+            let mut items = (0..10).collect::<Vec<_>>();
+            let mut other_items = items.clone();
+
+            StoryRng::with_seed(5).shuffle(&mut items);
+            StoryRng::with_seed(5).shuffle(&mut other_items);
+
+            assert_eq!(items, other_items);
+        }
+
+        #[test]
+        fn reseeding_restarts_generator_from_new_seed() {
+            let mut rng = StoryRng::with_seed(5);
+            rng.gen.next_u64();
+
+            rng.reseed(10);
+
+            assert_eq!(rng.get_seed(), 10);
+            assert_eq!(rng, StoryRng::with_seed(10));
+        }
+
+        #[test]
+        fn shuffling_draws_numbers_from_external_source_if_set() {
+            let counter = Arc::new(Mutex::new(Counter(0)));
+
+            let mut rng = StoryRng::with_seed(5);
+            rng.set_source(Some(counter.clone()));
+
+            let mut items = (0..10).collect::<Vec<_>>();
+            rng.shuffle(&mut items);
+
+            assert!(counter.lock().unwrap().0 > 0);
+            assert_eq!(rng.gen.get_word_pos(), 0);
+        }
+
+        #[test]
+        fn cloning_does_not_copy_external_source() {
+            let counter = Arc::new(Mutex::new(Counter(0)));
+
+            let mut rng = StoryRng::with_seed(5);
+            rng.set_source(Some(counter.clone()));
+
+            let mut clone = rng.clone();
+            clone.shuffle(&mut [0, 1, 2]);
+
+            assert_eq!(counter.lock().unwrap().0, 0);
+            assert_eq!(Arc::strong_count(&counter), 2);
+            assert!(clone.gen.get_word_pos() > 0);
+        }
+
+        #[test]
+        fn reseeding_keeps_external_source() {
+            let counter = Arc::new(Mutex::new(Counter(0)));
+
+            let mut rng = StoryRng::with_seed(5);
+            rng.set_source(Some(counter.clone()));
+            rng.reseed(10);
+
+            rng.shuffle(&mut [0, 1, 2]);
+
+            assert!(counter.lock().unwrap().0 > 0);
+        }
+    }
+
+    #[cfg(all(test, feature = "serde_support"))]
+    mod serde_tests {
+        use super::*;
         use serde_test::*;
 
         #[test]
@@ -0,0 +1,174 @@
+//! Summary counts of the content in a story.
+
+use crate::{
+    consts::ROOT_KNOT_NAME,
+    knot::KnotSet,
+    line::{Content, InternalLine, LineChunk},
+    node::NodeItem,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+/// Number of knots, stitches, choices and words in a story.
+///
+/// Retrieved with [`get_stats`][crate::story::Story::get_stats()].
+///
+/// Words are counted in all text content: every item of alternative sequences and both
+/// branches of conditional content are included. Words which are both in the selection
+/// and display text of a choice are counted once.
+///
+/// # Examples
+/// ```
+/// # use inkling::read_story_from_string;
+/// let content = "\
+/// The ferry was about to leave.
+/// *   I ran to the gangway.
+/// *   I let it go.
+/// ";
+///
+/// let stats = read_story_from_string(content).unwrap().get_stats();
+///
+/// assert_eq!(stats.num_choices, 2);
+/// assert_eq!(stats.num_words, 15);
+/// ```
+pub struct StoryStats {
+    /// Number of named knots.
+    pub num_knots: usize,
+    /// Number of named stitches in all knots.
+    pub num_stitches: usize,
+    /// Number of choices.
+    pub num_choices: usize,
+    /// Number of words in lines and choices.
+    pub num_words: usize,
+}
+
+/// Count the content in a set of knots.
+pub fn get_story_stats(knots: &KnotSet) -> StoryStats {
+    let mut stats = StoryStats::default();
+
+    for (knot_name, knot) in knots {
+        if knot_name != ROOT_KNOT_NAME {
+            stats.num_knots += 1;
+        }
+
+        for (stitch_name, stitch) in &knot.stitches {
+            if stitch_name != ROOT_KNOT_NAME {
+                stats.num_stitches += 1;
+            }
+
+            add_node_items_to_stats(&stitch.root.items, &mut stats);
+        }
+    }
+
+    stats
+}
+
+/// Add the counts of a set of node items and all branches below them.
+fn add_node_items_to_stats(items: &[NodeItem], stats: &mut StoryStats) {
+    for item in items {
+        match item {
+            NodeItem::Line(line) => stats.num_words += count_words_in_line(line),
+            NodeItem::BranchingPoint(branches) => {
+                for branch in branches {
+                    let choice = &branch.choice;
+                    let selection_text = choice.selection_text.lock().unwrap();
+
+                    // The display text is the first line of the branch items, so only
+                    // words which are exclusive to the selection text are added here
+                    let num_selection_words = count_words_in_line(&selection_text);
+                    let num_display_words = count_words_in_line(&choice.display_text);
+
+                    stats.num_choices += 1;
+                    stats.num_words += num_selection_words.saturating_sub(num_display_words);
+
+                    add_node_items_to_stats(&branch.items, stats);
+                }
+            }
+        }
+    }
+}
+
+fn count_words_in_line(line: &InternalLine) -> usize {
+    count_words_in_chunk(&line.chunk)
+}
+
+fn count_words_in_chunk(chunk: &LineChunk) -> usize {
+    chunk
+        .items
+        .iter()
+        .chain(chunk.else_items.iter())
+        .map(|item| match item {
+            Content::Text(text) => text.split_whitespace().count(),
+            Content::Nested(chunk) => count_words_in_chunk(chunk),
+            Content::Alternative(alternative) => {
+                alternative.items.iter().map(count_words_in_chunk).sum()
+            }
+            Content::Divert(..) | Content::Empty | Content::Expression(..) => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::story::parse::read_story_content_from_string;
+
+    fn get_stats(content: &str) -> StoryStats {
+        let (knots, _, _) =
+            read_story_content_from_string(content, &mut Default::default()).unwrap();
+        get_story_stats(&knots)
+    }
+
+    #[test]
+    fn root_knot_and_default_stitches_are_not_counted() {
+        let content = "
+Root content.
+== knot ==
+Knot content.
+= stitch
+Stitch content.
+= other_stitch
+Stitch content.
+== other_knot ==
+= stitch
+Stitch content.
+";
+
+        let stats = get_stats(content);
+
+        assert_eq!(stats.num_knots, 2);
+        assert_eq!(stats.num_stitches, 3);
+    }
+
+    #[test]
+    fn choices_in_nested_branches_are_counted() {
+        let content = "
+*   Choice one
+    **  Nested choice
+    **  Other nested choice
+*   Choice two
+";
+
+        assert_eq!(get_stats(content).num_choices, 4);
+    }
+
+    #[test]
+    fn words_in_alternatives_and_conditional_content_are_counted() {
+        let content = "
+VAR flag = true
+One {&two|three four} {flag: five|six seven}
+";
+
+        assert_eq!(get_stats(content).num_words, 7);
+    }
+
+    #[test]
+    fn choice_words_are_counted_once() {
+        let content = "
+*   Go home.
+*   [Stay]
+";
+
+        assert_eq!(get_stats(content).num_words, 3);
+    }
+}
@@ -2,24 +2,62 @@
 
 use crate::{
     consts::ROOT_KNOT_NAME,
-    error::{InklingError, ReadError},
+    error::{
+        parse::address::InvalidAddressErrorKind, InklingError, LocalizationError, ReadError,
+        ReloadError,
+    },
     follow::{ChoiceInfo, EncounteredEvent, FollowData, LineDataBuffer},
-    knot::{get_empty_knot_counts, get_mut_stitch, get_num_visited, Address, KnotSet},
-    line::Variable,
-    log::Logger,
-    process::{get_fallback_choices, prepare_choices_for_user, process_buffer},
+    knot::{
+        clone_knots_detached, get_empty_knot_counts, get_mut_stitch, get_num_visited, get_stitch,
+        Address, KnotSet,
+    },
+    line::{evaluate_expression, Variable},
+    log::{LogMessage, Logger, MessageKind, RuntimeMessage},
+    node::get_stack_lines,
+    process::{
+        evaluate_condition, get_fallback_choices, prepare_choices_for_user, process_buffer,
+        set_choice_ids,
+    },
     story::{
+        coverage::{get_coverage_report, Coverage, CoverageReport},
+        debug::{evaluate_watches, Breakpoint, Debugger, StackFrame, Step, Watch},
+        diff::{
+            get_branches_at_stack, get_reload_position, get_story_diff, SavedPosition, StoryDiff,
+        },
+        evaluate::{parse_story_condition, parse_story_expression},
+        explore::{explore_story, ExploreOptions, ExploreReport},
+        graph::{export_story_graph, GraphFormat},
+        history::{History, Snapshot},
+        localization::{get_string_table, translate_knots, StringTable},
         parse::read_story_content_from_string,
+        reachability::{get_reachability, ReachabilityOptions, ReachabilityReport},
         rng::StoryRng,
-        types::{Choice, LineBuffer, Location, Prompt},
-        validate::validate_story_content,
+        stats::{get_story_stats, StoryStats},
+        symbols::{get_symbols, resolve_address, Symbol},
+        trace::{TraceEvent, TraceHook, Tracer},
+        transcript::Transcript,
+        types::{Choice, ChoiceRef, LineBuffer, Location, Prompt, ReadOptions},
+        validate::{lint_story_content, validate_story_content, ValidationData},
     },
+    utils::get_closest_name,
+};
+
+use std::{
+    collections::HashMap,
+    mem::discriminant,
+    sync::{Arc, Mutex},
 };
 
+#[cfg(feature = "random")]
+use crate::story::rng::RandomSource;
+
+#[cfg(feature = "serde_support")]
+use crate::story::history::deserialize_history;
+
 #[cfg(feature = "serde_support")]
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
 /// Story with knots, diverts, choices and possibly lots of text.
@@ -28,8 +66,12 @@ pub struct Story {
     current_address: Address,
     /// Collection of `Knot`s which make up the story.
     knots: KnotSet,
-    /// History of visited addresses.
-    history: Vec<Address>,
+    /// Snapshots of the story state at previously made choices.
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(default, deserialize_with = "deserialize_history")
+    )]
+    history: History<Snapshot>,
     /// Internal data for the story.
     data: FollowData,
     /// Global tags associated with the story.
@@ -38,10 +80,31 @@ pub struct Story {
     last_choices: Option<Vec<Choice>>,
     /// Choice that has been set to resume the story with.
     selected_choice: Option<usize>,
+    /// Transcript of lines and choices, if it is being recorded.
+    transcript: Option<Transcript>,
     /// Log of warnings and to-do comments encountered when parsing the `Story` from the script.
     pub log: Logger,
 }
 
+impl Clone for Story {
+    /// Clone the story.
+    ///
+    /// The clone shares no state with the original, so both can be followed independently.
+    fn clone(&self) -> Self {
+        Story {
+            current_address: self.current_address.clone(),
+            knots: clone_knots_detached(&self.knots),
+            history: self.history.clone(),
+            data: self.data.clone(),
+            tags: self.tags.clone(),
+            last_choices: self.last_choices.clone(),
+            selected_choice: self.selected_choice,
+            transcript: self.transcript.clone(),
+            log: self.log.clone(),
+        }
+    }
+}
+
 impl Story {
     /// Resume the story text flow while reading all encountered lines into the supplied buffer.
     ///
@@ -50,7 +113,8 @@ impl Story {
     ///
     /// Returns either when the story reaches an end or when a set of choices is encountered,
     /// which requires the user to select one. Make a choice by calling
-    /// [`make_choice`][crate::story::Story::make_choice()].
+    /// [`make_choice`][crate::story::Story::make_choice()]. If the story is debugged it
+    /// also returns when it pauses at a line, and continues from that line when resumed.
     ///
     /// # Notes
     /// This method does not clear the input `line_buffer` vector before reading more lines
@@ -102,108 +166,1161 @@ impl Story {
     /// assert_eq!(&line_buffer[0].text, "Rabat!\n");
     /// ```
     ///
-    /// ## Moving to a new knot
-    /// ```
-    /// # use inkling::{read_story_from_string, Location};
-    /// # let content = "\
-    /// # Sam was in real trouble now. The fleet footed criminals were just about to corner her.
-    /// #
-    /// # *   [Climb the fire escape]
-    /// #     She clattered up the rackety fire escape.
-    /// # *   [Bluff]
-    /// #     To hell with running. Sam turned around with a cocksure smirk on her lips.
-    /// #
-    /// # === mirandas_den ===
-    /// # = bar
-    /// # The room was thick with smoke and the smell of noir.
-    /// #
-    /// # = meeting
-    /// # Miranda was waiting in her office.
-    /// # She had questions and Sam for once had answers.
-    /// # ";
-    /// # let mut story = read_story_from_string(content).unwrap();
-    /// # let mut line_buffer = Vec::new();
-    /// # story.resume(&mut line_buffer).unwrap();
-    /// let location = Location {
-    ///     knot: "mirandas_den".to_string(),
-    ///     stitch: Some("meeting".to_string()),
-    /// };
+    /// ## Moving to a new knot
+    /// ```
+    /// # use inkling::{read_story_from_string, Location};
+    /// # let content = "\
+    /// # Sam was in real trouble now. The fleet footed criminals were just about to corner her.
+    /// #
+    /// # *   [Climb the fire escape]
+    /// #     She clattered up the rackety fire escape.
+    /// # *   [Bluff]
+    /// #     To hell with running. Sam turned around with a cocksure smirk on her lips.
+    /// #
+    /// # === mirandas_den ===
+    /// # = bar
+    /// # The room was thick with smoke and the smell of noir.
+    /// #
+    /// # = meeting
+    /// # Miranda was waiting in her office.
+    /// # She had questions and Sam for once had answers.
+    /// # ";
+    /// # let mut story = read_story_from_string(content).unwrap();
+    /// # let mut line_buffer = Vec::new();
+    /// # story.resume(&mut line_buffer).unwrap();
+    /// let location = Location {
+    ///     knot: "mirandas_den".to_string(),
+    ///     stitch: Some("meeting".to_string()),
+    /// };
+    ///
+    /// story.move_to(&location).unwrap();
+    /// # line_buffer.clear();
+    /// story.resume(&mut line_buffer).unwrap();
+    ///
+    /// assert_eq!(&line_buffer[0].text, "Miranda was waiting in her office.\n");
+    /// ```
+    pub fn resume(&mut self, line_buffer: &mut LineBuffer) -> Result<Prompt, InklingError> {
+        // Break early if we are at a choice but no choice has yet been made
+        match (self.selected_choice, self.last_choices.as_ref()) {
+            (None, Some(choices)) => return Ok(Prompt::Choice(choices.clone())),
+            _ => (),
+        }
+
+        let selection = self.selected_choice.take();
+        self.data.debugger.resume();
+
+        self.follow_story_wrapper(selection, line_buffer)
+    }
+
+    /// Make a choice from a given set of options.
+    ///
+    /// The `selection` index corresponds to the index in the list of choices that was
+    /// previously returned when the branching point was reached. This list can be retrieved
+    /// again by calling [`resume`][crate::story::Story::resume()] on the story before making
+    /// a choice: once a choice has been successfully made, a call to `resume` will continue
+    /// the text flow from that branch.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{read_story_from_string, Prompt};
+    /// let content = "\
+    /// Just as Nancy picked the old diary up from the table she heard
+    /// the door behind her creak open. Someone’s coming!
+    ///
+    /// *   She spun around to face the danger head on.
+    ///     Her heart was racing as the door slowly swung open and the black
+    ///     cat from the garden swept in.
+    ///     “Miao!”
+    /// *   In one smooth motion she hid behind the large curtain.
+    ///     A familiar “meow” coming from the room filled her with relief.
+    ///     That barely lasted a moment before the dusty curtains made her
+    ///     sneeze, awakening the house keeper sleeping in the neighbouring room.
+    /// ";
+    ///
+    /// // ... setup
+    /// # let mut story = read_story_from_string(content).unwrap();
+    /// # let mut line_buffer = Vec::new();
+    ///
+    /// if let Prompt::Choice(choices) = story.resume(&mut line_buffer).unwrap() {
+    ///     story.make_choice(0).unwrap();
+    ///     story.resume(&mut line_buffer);
+    /// }
+    ///
+    /// assert_eq!(line_buffer.last().unwrap().text, "“Miao!”\n");
+    /// ```
+    ///
+    /// # Errors
+    /// *   [`MadeChoiceWithoutChoice`][crate::error::InklingError::MadeChoiceWithoutChoice]:
+    ///     if the story is not currently at a branching point.
+    pub fn make_choice(&mut self, selection: usize) -> Result<(), InklingError> {
+        let choice = self
+            .last_choices
+            .as_ref()
+            .ok_or(InklingError::MadeChoiceWithoutChoice)
+            .and_then(|last_choices| {
+                last_choices
+                    .get(selection)
+                    .cloned()
+                    .ok_or(InklingError::InvalidChoice {
+                        selection,
+                        presented_choices: last_choices.clone(),
+                    })
+            })?;
+
+        let index = choice.index;
+
+        self.data.tracer.emit(|| TraceEvent::SelectChoice {
+            location: location_from_address(&self.current_address),
+            choice: choice.clone(),
+            is_fallback: false,
+        });
+
+        if let Some(last_choices) = self.last_choices.take() {
+            self.history.push(Snapshot {
+                current_address: self.current_address.clone(),
+                knots: clone_knots_detached(&self.knots),
+                knot_visit_counts: self.data.knot_visit_counts.clone(),
+                variables: self.data.variables.clone(),
+                rng: self.data.rng.clone(),
+                last_choices,
+            });
+        }
+
+        if let Some(transcript) = self.transcript.as_mut() {
+            let location = location_from_address(&self.current_address);
+            transcript.add_choice(choice, location);
+        }
+
+        self.selected_choice.replace(index);
+
+        Ok(())
+    }
+
+    /// Replay a list of choices, following the story through them.
+    ///
+    /// Starting from the current state of the story, this calls
+    /// [`resume`][crate::story::Story::resume()] and makes each choice in turn as it is
+    /// presented. After the last choice has been made the story is resumed once more and
+    /// the resulting `Prompt` is returned. All lines that are encountered are added to the
+    /// buffer.
+    ///
+    /// Choices can be referred to by their index, text or identifier: see
+    /// [`ChoiceRef`][crate::story::ChoiceRef]. Identifiers and texts are more robust than
+    /// indices if the script has changed since the choices were recorded, since they
+    /// only change if the text of the choice itself is edited. See
+    /// [`Choice::id`][crate::story::Choice::id] for how identifiers are formed.
+    ///
+    /// For stories with shuffle sequences the same random seed must be used as when the
+    /// choices were recorded for the replay to be reproducible.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{read_story_from_string, ChoiceRef, Prompt};
+    /// let content = "\
+    /// -> crossroads
+    /// === crossroads ===
+    /// The road splits in two.
+    /// +   [Go left] -> forest
+    /// +   [Go right] -> village
+    /// === forest ===
+    /// Trees surround you. -> crossroads
+    /// === village ===
+    /// You arrive at the village.
+    /// ";
+    ///
+    /// let mut story = read_story_from_string(content).unwrap();
+    /// let mut line_buffer = Vec::new();
+    ///
+    /// let choices = [ChoiceRef::Index(0), ChoiceRef::from("Go right")];
+    /// let prompt = story.replay(&choices, &mut line_buffer).unwrap();
+    ///
+    /// assert!(prompt.get_choices().is_none());
+    /// assert_eq!(line_buffer.last().unwrap().text, "You arrive at the village.\n");
+    /// ```
+    ///
+    /// # Errors
+    /// *   [`ReplayDiverged`][crate::error::InklingError::ReplayDiverged]: if a choice
+    ///     was not presented, or the story ended before all choices were made. The error
+    ///     contains the step in the list, the location of the story and the choices
+    ///     which were presented at that point.
+    /// *   Any error from [`resume`][crate::story::Story::resume()] or
+    ///     [`make_choice`][crate::story::Story::make_choice()].
+    pub fn replay(
+        &mut self,
+        choices: &[ChoiceRef],
+        line_buffer: &mut LineBuffer,
+    ) -> Result<Prompt, InklingError> {
+        for (step, choice) in choices.iter().enumerate() {
+            let presented_choices = self.resume(line_buffer)?.get_choices().unwrap_or_default();
+
+            let selection =
+                choice
+                    .find_in(&presented_choices)
+                    .ok_or_else(|| InklingError::ReplayDiverged {
+                        step,
+                        choice: choice.clone(),
+                        location: self.get_current_location(),
+                        presented_choices: presented_choices.clone(),
+                    })?;
+
+            self.make_choice(selection)?;
+        }
+
+        self.resume(line_buffer)
+    }
+
+    /// Rewind the story to the branching choice made the given number of choices ago.
+    ///
+    /// Every time a choice is made with [`make_choice`][crate::story::Story::make_choice()]
+    /// the state of the story is recorded. Rewinding restores the story to the state it was
+    /// in when the choice was presented: variables, visit counts, alternative sequences,
+    /// the random number generator and the location are all reset. After rewinding,
+    /// [`resume`][crate::story::Story::resume()] returns the choices from that branching
+    /// point again, and a new choice can be made.
+    ///
+    /// A `steps` value of 1 undoes the last made choice, 2 the one before it, and so on.
+    /// Rewinding zero steps does nothing.
+    ///
+    /// No states are recorded unless a limit is set with
+    /// [`set_history_limit`][crate::story::Story::set_history_limit()] before the
+    /// choices are made. The number of steps that can currently be rewound is returned by
+    /// [`get_num_rewind_steps`][crate::story::Story::get_num_rewind_steps()].
+    ///
+    /// # Notes
+    /// Recorded states are not de/serialized with the story when the `serde_support`
+    /// feature is enabled. A deserialized story cannot be rewound to choices made
+    /// before it was serialized.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{read_story_from_string, Prompt};
+    /// let content = "\
+    /// You stop at the market stall.
+    /// *   Buy an apple.
+    ///     You have an apple now.
+    /// *   Walk on.
+    ///     You keep walking.
+    /// ";
+    ///
+    /// let mut story = read_story_from_string(content).unwrap();
+    /// let mut line_buffer = Vec::new();
+    ///
+    /// story.set_history_limit(10);
+    ///
+    /// story.resume(&mut line_buffer).unwrap();
+    /// story.make_choice(0).unwrap();
+    /// story.resume(&mut line_buffer).unwrap();
+    ///
+    /// // Take back buying the apple
+    /// story.rewind(1).unwrap();
+    /// line_buffer.clear();
+    ///
+    /// match story.resume(&mut line_buffer).unwrap() {
+    ///     Prompt::Choice(choices) => assert_eq!(choices[0].text, "Buy an apple."),
+    ///     _ => unreachable!(),
+    /// }
+    ///
+    /// story.make_choice(1).unwrap();
+    /// story.resume(&mut line_buffer).unwrap();
+    ///
+    /// assert_eq!(line_buffer.last().unwrap().text, "You keep walking.\n");
+    /// ```
+    ///
+    /// # Errors
+    /// *   [`InvalidRewind`][crate::error::InklingError::InvalidRewind]: if more steps
+    ///     are given than there are recorded choices.
+    pub fn rewind(&mut self, steps: usize) -> Result<(), InklingError> {
+        if steps == 0 {
+            return Ok(());
+        }
+
+        let num_available = self.history.len();
+
+        let snapshot = self
+            .history
+            .rewind(steps)
+            .ok_or(InklingError::InvalidRewind {
+                steps,
+                num_available,
+            })?;
+
+        self.current_address = snapshot.current_address;
+        self.knots = snapshot.knots;
+        self.data.knot_visit_counts = snapshot.knot_visit_counts;
+        self.data.variables = snapshot.variables;
+        self.data.reached_end = false;
+        self.data.debugger.clear_pause();
+
+        // The random source belongs to the caller and is kept
+        let mut rng = snapshot.rng;
+        rng.set_source_from(&self.data.rng);
+        self.data.rng = rng;
+
+        self.last_choices.replace(snapshot.last_choices);
+        self.selected_choice = None;
+
+        if let Some(transcript) = self.transcript.as_mut() {
+            transcript.rewind(steps);
+        }
+
+        Ok(())
+    }
+
+    /// Get the number of choices that the story can currently be rewound.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::read_story_from_string;
+    /// let content = "\
+    /// *   Knock on the door.
+    /// *   Ring the bell.
+    /// ";
+    ///
+    /// let mut story = read_story_from_string(content).unwrap();
+    /// let mut line_buffer = Vec::new();
+    ///
+    /// story.set_history_limit(10);
+    ///
+    /// story.resume(&mut line_buffer).unwrap();
+    /// assert_eq!(story.get_num_rewind_steps(), 0);
+    ///
+    /// story.make_choice(1).unwrap();
+    /// assert_eq!(story.get_num_rewind_steps(), 1);
+    /// ```
+    pub fn get_num_rewind_steps(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Set the maximum number of choices that the story keeps states for to rewind to.
+    ///
+    /// Every recorded state contains a full copy of the story content, which may use a lot
+    /// of memory for large stories. By default no states are kept and the story cannot
+    /// be rewound. If the new limit is lower than the number of currently recorded states,
+    /// the oldest are dropped. Setting the limit to 0 disables rewinding again.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::read_story_from_string;
+    /// # let content = "\
+    /// # *   Knock on the door.
+    /// # *   Ring the bell.
+    /// # ";
+    /// let mut story = read_story_from_string(content).unwrap();
+    /// let mut line_buffer = Vec::new();
+    ///
+    /// story.set_history_limit(1);
+    ///
+    /// story.resume(&mut line_buffer).unwrap();
+    /// story.make_choice(1).unwrap();
+    /// assert_eq!(story.get_num_rewind_steps(), 1);
+    ///
+    /// story.set_history_limit(0);
+    /// assert!(story.rewind(1).is_err());
+    /// ```
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.history.set_limit(limit);
+    }
+
+    /// Start recording a transcript of all lines and made choices.
+    ///
+    /// Lines are recorded as they are returned by [`resume`][crate::story::Story::resume()]
+    /// and choices as they are made with
+    /// [`make_choice`][crate::story::Story::make_choice()]. If a transcript is already
+    /// being recorded this does nothing. Rewinding the story also removes the rewound
+    /// content from the transcript.
+    ///
+    /// See [`Transcript`][crate::story::Transcript] for an example.
+    pub fn record_transcript(&mut self) {
+        self.transcript.get_or_insert_with(Transcript::default);
+    }
+
+    /// Get the transcript of the story, if one is being recorded.
+    ///
+    /// Returns `None` if [`record_transcript`][crate::story::Story::record_transcript()]
+    /// has not been called.
+    pub fn transcript(&self) -> Option<&Transcript> {
+        self.transcript.as_ref()
+    }
+
+    /// Stop recording the transcript and return it.
+    ///
+    /// Returns `None` if no transcript was being recorded.
+    pub fn take_transcript(&mut self) -> Option<Transcript> {
+        self.transcript.take()
+    }
+
+    /// Restart the random number generator of the story from a seed.
+    ///
+    /// Shuffle sequences draw from this generator. Two stories with the same content
+    /// and seed will thus select the same items when followed through the same choices.
+    /// A random source set with [`set_random_source`][crate::story::Story::set_random_source()]
+    /// is still used instead of the generator.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::read_story_from_string;
+    /// let content = "{~Heads|Tails}, I win.";
+    ///
+    /// let mut story = read_story_from_string(content).unwrap();
+    /// let mut other = read_story_from_string(content).unwrap();
+    ///
+    /// story.set_seed(1234);
+    /// other.set_seed(1234);
+    ///
+    /// let mut line_buffer = Vec::new();
+    /// let mut other_buffer = Vec::new();
+    ///
+    /// story.resume(&mut line_buffer).unwrap();
+    /// other.resume(&mut other_buffer).unwrap();
+    ///
+    /// assert_eq!(line_buffer, other_buffer);
+    /// ```
+    #[cfg(feature = "random")]
+    pub fn set_seed(&mut self, seed: u64) {
+        self.data.rng.reseed(seed);
+    }
+
+    /// Get the seed that the random number generator of the story was started from.
+    ///
+    /// Unless set, the seed is drawn from system entropy when the story is read.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::read_story_from_string;
+    /// let mut story = read_story_from_string("{~Heads|Tails}").unwrap();
+    ///
+    /// story.set_seed(1234);
+    /// assert_eq!(story.get_seed(), 1234);
+    /// ```
+    #[cfg(feature = "random")]
+    pub fn get_seed(&self) -> u64 {
+        self.data.rng.get_seed()
+    }
+
+    /// Set a source of random numbers to use instead of the generator of the story.
+    ///
+    /// The source is shared with the caller, which lets a game and its story draw from
+    /// the same deterministic generator. Since the source is owned by the caller it is not
+    /// saved with the story, nor restored when rewinding. See
+    /// [`RandomSource`][crate::story::RandomSource] for an example.
+    #[cfg(feature = "random")]
+    pub fn set_random_source(&mut self, source: Arc<Mutex<dyn RandomSource>>) {
+        self.data.rng.set_source(Some(source));
+    }
+
+    /// Stop using a set source of random numbers and return to the generator of the story.
+    #[cfg(feature = "random")]
+    pub fn clear_random_source(&mut self) {
+        self.data.rng.set_source(None);
+    }
+
+    /// Set a tracer which receives events as the story is followed.
+    ///
+    /// Events are sent for knots and stitches that are entered, diverts, evaluated
+    /// conditions, alternative sequences, presented and selected choices, and variables
+    /// which are read or set. This shows why a line was or was not shown. The tracer is
+    /// shared with the caller and thus neither saved with the story, nor restored when
+    /// rewinding. See [`Tracer`][crate::story::Tracer] for an example.
+    ///
+    /// Stories which are explored with [`explore`][crate::story::Story::explore()] or
+    /// [`get_reachability_report`][crate::story::Story::get_reachability_report()] do
+    /// not send events.
+    pub fn set_tracer(&mut self, tracer: Arc<Mutex<dyn Tracer>>) {
+        self.data.tracer.set(Some(tracer));
+    }
+
+    /// Stop sending events to a set tracer.
+    pub fn clear_tracer(&mut self) {
+        self.data.tracer.set(None);
+    }
+
+    /// Add a breakpoint to pause the story at.
+    ///
+    /// When the story reaches the breakpoint, [`resume`][crate::story::Story::resume()]
+    /// returns [`Prompt::Breakpoint`][crate::story::Prompt::Breakpoint] before following
+    /// the line. Lines which were followed before it are added to the buffer as usual.
+    /// Resume the story again to continue from the line.
+    ///
+    /// Breakpoints are saved with the story, but neither restored when rewinding nor
+    /// used by [`explore`][crate::story::Story::explore()] and
+    /// [`get_reachability_report`][crate::story::Story::get_reachability_report()].
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{read_story_from_string, Breakpoint, Location, Prompt};
+    /// let content = "\
+    /// The guard waved us through.
+    /// -> courtyard
+    ///
+    /// == courtyard
+    /// A fountain splashed in the middle of the courtyard.
+    /// ";
+    ///
+    /// let mut story = read_story_from_string(content).unwrap();
+    /// let mut line_buffer = Vec::new();
+    ///
+    /// story.add_breakpoint(Breakpoint::Location("courtyard".into())).unwrap();
+    ///
+    /// match story.resume(&mut line_buffer).unwrap() {
+    ///     Prompt::Breakpoint(pause) => assert_eq!(pause.line, 5),
+    ///     _ => unreachable!(),
+    /// }
+    ///
+    /// assert_eq!(line_buffer.len(), 1);
+    ///
+    /// story.resume(&mut line_buffer).unwrap();
+    /// assert_eq!(&line_buffer[1].text, "A fountain splashed in the middle of the courtyard.\n");
+    /// ```
+    ///
+    /// # Errors
+    /// *   [`InvalidAddress`][crate::error::InklingError::InvalidAddress]: if the location
+    ///     of a breakpoint does not exist in the story.
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> Result<(), InklingError> {
+        let address = match &breakpoint {
+            Breakpoint::Location(location) => Some(self.get_location_address(location)?),
+            Breakpoint::Line(..) => None,
+        };
+
+        self.data.debugger.add_breakpoint(breakpoint, address);
+
+        Ok(())
+    }
+
+    /// Remove a breakpoint. Returns whether it had been added.
+    pub fn remove_breakpoint(&mut self, breakpoint: &Breakpoint) -> bool {
+        self.data.debugger.remove_breakpoint(breakpoint)
+    }
+
+    /// Remove all breakpoints.
+    pub fn clear_breakpoints(&mut self) {
+        self.data.debugger.clear_breakpoints();
+    }
+
+    /// Get the set breakpoints in the order that they were added.
+    pub fn get_breakpoints(&self) -> Vec<Breakpoint> {
+        self.data.debugger.get_breakpoints()
+    }
+
+    /// Resume the story until it has followed a single line.
+    ///
+    /// The story pauses before the next line, as set by [`Step`][crate::story::Step],
+    /// and returns [`Prompt::Breakpoint`][crate::story::Prompt::Breakpoint]. Breakpoints
+    /// which are reached first pause the story as well. If a set of choices or the end is
+    /// reached first, that is returned as from [`resume`][crate::story::Story::resume()].
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{read_story_from_string, Prompt, Step};
+    /// let content = "\
+    /// Captain’s log, day one.
+    /// The sea was calm.
+    /// The crew was not.
+    /// ";
+    ///
+    /// let mut story = read_story_from_string(content).unwrap();
+    /// let mut line_buffer = Vec::new();
+    ///
+    /// if let Prompt::Breakpoint(pause) = story.step(Step::Into, &mut line_buffer).unwrap() {
+    ///     assert_eq!(pause.line, 1);
+    /// }
+    ///
+    /// if let Prompt::Breakpoint(pause) = story.step(Step::Over, &mut line_buffer).unwrap() {
+    ///     assert_eq!(pause.line, 2);
+    /// }
+    ///
+    /// assert_eq!(&line_buffer[0].text, "Captain’s log, day one.\n");
+    /// ```
+    ///
+    /// # Errors
+    /// Any error from [`resume`][crate::story::Story::resume()].
+    pub fn step(
+        &mut self,
+        step: Step,
+        line_buffer: &mut LineBuffer,
+    ) -> Result<Prompt, InklingError> {
+        self.data.debugger.set_step(step, &self.current_address);
+        let result = self.resume(line_buffer);
+        self.data.debugger.clear_step();
+
+        result
+    }
+
+    /// Get the stack of nested choices in the current knot or stitch.
+    ///
+    /// The first frame is the deepest level, which the story is paused or presenting
+    /// choices at. Every following frame is the level that the choice of the previous
+    /// frame was made from. Diverts leave the knot or stitch and are not part of the stack.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{read_story_from_string, Breakpoint, Prompt};
+    /// let content = "\
+    /// *   Open the chest.
+    ///     It is full of coins.
+    /// *   Leave.
+    /// ";
+    ///
+    /// let mut story = read_story_from_string(content).unwrap();
+    /// let mut line_buffer = Vec::new();
+    ///
+    /// story.add_breakpoint(Breakpoint::Line(2)).unwrap();
+    /// story.resume(&mut line_buffer).unwrap();
+    /// story.make_choice(0).unwrap();
+    /// story.resume(&mut line_buffer).unwrap();
+    ///
+    /// let stack = story.get_node_stack();
+    ///
+    /// assert_eq!(stack.len(), 2);
+    /// assert_eq!((stack[0].depth, stack[0].line), (1, Some(2)));
+    /// assert_eq!((stack[1].depth, stack[1].line), (0, Some(1)));
+    /// ```
+    pub fn get_node_stack(&self) -> Vec<StackFrame> {
+        let location = location_from_address(&self.current_address);
+
+        let lines = match get_stitch(&self.current_address, &self.knots) {
+            Ok(stitch) => get_stack_lines(&stitch.root, &stitch.stack),
+            Err(..) => Vec::new(),
+        };
+
+        lines
+            .into_iter()
+            .enumerate()
+            .rev()
+            .map(|(depth, line)| StackFrame {
+                location: location.clone(),
+                depth,
+                line,
+            })
+            .collect()
+    }
+
+    /// Add an expression to watch while debugging the story.
+    ///
+    /// The expression is written as in the script and may use global variables, visit
+    /// counts of knots and stitches and mathematical operations. Names of stitches are
+    /// resolved from the current location of the story. Get the values of all watched
+    /// expressions with [`get_watches`][crate::story::Story::get_watches()].
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{read_story_from_string, Variable};
+    /// let content = "\
+    /// VAR coins = 3
+    /// VAR price = 2
+    /// ";
+    ///
+    /// let mut story = read_story_from_string(content).unwrap();
+    /// story.add_watch("coins - price").unwrap();
+    ///
+    /// let watches = story.get_watches();
+    ///
+    /// assert_eq!(&watches[0].expression, "coins - price");
+    /// assert_eq!(watches[0].value.as_ref().unwrap(), &Variable::Int(1));
+    /// ```
+    ///
+    /// # Errors
+    /// *   [`InvalidExpression`][crate::error::InklingError::InvalidExpression]: if the
+    ///     expression cannot be parsed, refers to names which do not exist in the story
+    ///     or operates on variables of incompatible types.
+    pub fn add_watch(&mut self, expression: &str) -> Result<(), InklingError> {
+        let parsed = parse_story_expression(
+            expression,
+            &self.current_address,
+            &self.knots,
+            &self.data.variables,
+        )?;
+
+        self.data.debugger.add_watch(expression, parsed);
+
+        Ok(())
+    }
+
+    /// Remove a watched expression. Returns whether it was watched.
+    pub fn remove_watch(&mut self, expression: &str) -> bool {
+        self.data.debugger.remove_watch(expression)
+    }
+
+    /// Get the watched expressions with their values in the current state of the story.
+    pub fn get_watches(&self) -> Vec<Watch> {
+        evaluate_watches(self.data.debugger.get_watches(), &self.data)
+    }
+
+    /// Limit the number of diverts that the story follows without reaching a choice or end.
+    ///
+    /// A story that diverts in a loop without ever presenting a choice would otherwise
+    /// never return from [`resume`][crate::story::Story::resume()]. With a limit set,
+    /// a [`DivertLimitExceeded`][crate::error::InklingError::DivertLimitExceeded] error
+    /// is returned instead once more diverts than the limit have been followed. There
+    /// is no limit by default.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{read_story_from_string, InklingError};
+    /// let content = "\
+    /// -> loop
+    /// == loop
+    /// Round and round.
+    /// -> loop
+    /// ";
+    ///
+    /// let mut story = read_story_from_string(content).unwrap();
+    /// story.set_divert_limit(Some(100));
+    ///
+    /// match story.resume(&mut Vec::new()) {
+    ///     Err(InklingError::DivertLimitExceeded { limit, .. }) => assert_eq!(limit, 100),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn set_divert_limit(&mut self, limit: Option<usize>) {
+        self.data.divert_limit = limit;
+    }
+
+    /// Explore the story through many automated playthroughs.
+    ///
+    /// Playthroughs start from a clone of the story in its current state and pick choices
+    /// at random or try all of them, as set in the options. Every error that is returned
+    /// from the story is reported, as well as loops which never reach a choice and
+    /// playthroughs which run out of content without reaching `END` or `DONE`. Each issue
+    /// comes with the list of choices that reproduces it.
+    ///
+    /// The story itself is not changed and its random number generator or source is not
+    /// drawn from.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{read_story_from_string, ExploreIssueKind, ExploreOptions};
+    /// let content = "\
+    /// *   Go home. -> END
+    /// *   Keep walking.
+    ///     The road goes on.
+    /// ";
+    ///
+    /// let story = read_story_from_string(content).unwrap();
+    /// let report = story.explore(&ExploreOptions::default());
+    ///
+    /// assert_eq!(report.issues.len(), 1);
+    /// assert_eq!(report.issues[0].choices, &[1]);
+    /// ```
+    pub fn explore(&self, options: &ExploreOptions) -> ExploreReport {
+        explore_story(self, options)
+    }
+
+    /// Enumerate every state the story can reach from its current state.
+    ///
+    /// All choices are followed breadth first from a clone of the story. A state is the
+    /// location where the story stops at a choice or end, along with the values of global
+    /// variables and which knots and stitches have been visited, and is only followed once.
+    /// The report lists which knots can be reached, the values of global variables they
+    /// are reached with, and the fewest choices to make to reach each of them.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{read_story_from_string, ReachabilityOptions};
+    /// let content = "\
+    /// *   Take the stairs. -> roof
+    /// *   Wait for the lift.
+    ///     *   *   Give up. -> END
+    /// == roof
+    /// -> END
+    /// == basement
+    /// -> END
+    /// ";
+    ///
+    /// let story = read_story_from_string(content).unwrap();
+    /// let report = story.get_reachability_report(&ReachabilityOptions::default());
+    ///
+    /// assert_eq!(report.get_knot(Some("roof")).unwrap().choices, &[0]);
+    /// assert_eq!(report.unreached, &["basement"]);
+    /// ```
+    pub fn get_reachability_report(&self, options: &ReachabilityOptions) -> ReachabilityReport {
+        get_reachability(self, options)
+    }
+
+    /// Whether the story last stopped at a divert to `END` or `DONE`.
+    pub(crate) fn has_reached_end(&self) -> bool {
+        self.data.reached_end
+    }
+
+    /// Get the number of visits to every knot and stitch in the story.
+    pub(crate) fn get_knot_visit_counts(&self) -> &HashMap<String, HashMap<String, u32>> {
+        &self.data.knot_visit_counts
+    }
+
+    /// Get the values of all non-constant global variables, sorted by name.
+    pub(crate) fn get_mutable_variables(&self) -> Vec<(String, Variable)> {
+        let mut variables = self
+            .data
+            .variables
+            .iter()
+            .filter(|(_, info)| !info.is_const)
+            .map(|(name, info)| (name.clone(), info.variable.clone()))
+            .collect::<Vec<_>>();
+
+        variables.sort_by(|a, b| a.0.cmp(&b.0));
+        variables
+    }
+
+    /// Start recording which content in the story is seen.
+    ///
+    /// Every line, made choice and branch of alternative sequences and conditional content
+    /// that is followed from this point is added to a [`Coverage`][crate::story::Coverage]
+    /// record. If coverage is already being recorded, this does nothing.
+    pub fn record_coverage(&mut self) {
+        self.data.coverage.get_or_insert_with(Default::default);
+    }
+
+    /// Get the coverage of the story, if it is being recorded.
+    ///
+    /// Returns `None` if [`record_coverage`][crate::story::Story::record_coverage()]
+    /// has not been called.
+    pub fn coverage(&self) -> Option<&Coverage> {
+        self.data
+            .coverage
+            .as_ref()
+            .map(|recorder| &recorder.coverage)
+    }
+
+    /// Stop recording coverage and return it.
+    ///
+    /// Returns `None` if no coverage was being recorded.
+    pub fn take_coverage(&mut self) -> Option<Coverage> {
+        self.data.coverage.take().map(|recorder| recorder.coverage)
+    }
+
+    /// Get a report of the content in the story which is not in a coverage record.
+    ///
+    /// The coverage can be merged from several sessions of the story with
+    /// [`merge`][crate::story::Coverage::merge()]. The report lists the unseen content
+    /// in every knot.
+    pub fn get_coverage_report(&self, coverage: &Coverage) -> CoverageReport {
+        get_coverage_report(&self.knots, coverage)
+    }
+
+    /// Compare the structure of the story to a new version of it.
+    ///
+    /// Lists the knots, stitches and global variables which were added, removed or renamed,
+    /// the variables with different values and the sets of choices which changed. Variables
+    /// are compared by the values that they are declared with in the scripts, so values
+    /// which were set while playing are not reported.
+    ///
+    /// The report also tells whether the story can continue from its current position in
+    /// the new version: the current stitch must exist and the choices made in it must lead
+    /// to the same sets of choices. Call this on a loaded save to check if it is still valid.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{read_story_from_string, Location, SavedPosition};
+    /// let old = "\
+    /// == harbor
+    /// *   [Board the ship] -> END
+    /// *   [Stay] -> END
+    /// ";
+    ///
+    /// let new = "\
+    /// == docks
+    /// *   [Board the ship] -> END
+    /// *   [Stay] -> END
+    /// ";
+    ///
+    /// let mut story = read_story_from_string(old).unwrap();
+    /// story.move_to(&Location::from("harbor")).unwrap();
+    /// story.resume(&mut Vec::new()).unwrap();
+    ///
+    /// let diff = story.diff(&read_story_from_string(new).unwrap());
+    ///
+    /// assert_eq!(diff.renamed_knots, &[("harbor".to_string(), "docks".to_string())]);
+    /// assert!(matches!(diff.saved_position, SavedPosition::MissingLocation(..)));
+    /// ```
+    pub fn diff(&self, new: &Story) -> StoryDiff {
+        get_story_diff(
+            &self.knots,
+            &self.data.variables,
+            &self.current_address,
+            &new.knots,
+            &new.data.variables,
+        )
+    }
+
+    /// Get the number of knots, stitches, choices and words in the story.
+    ///
+    /// See [`StoryStats`][crate::story::StoryStats] for how the content is counted.
+    pub fn get_stats(&self) -> StoryStats {
+        get_story_stats(&self.knots)
+    }
+
+    /// Get the named knots, stitches and global variables of the story.
+    ///
+    /// The symbols are ordered by the line that they are defined on. See
+    /// [`Symbol`][crate::story::Symbol] for an example.
+    pub fn get_symbols(&self) -> Vec<Symbol> {
+        get_symbols(&ValidationData::from_data(
+            &self.knots,
+            &self.data.variables,
+        ))
+    }
+
+    /// Find the knot, stitch or variable that a divert target refers to.
+    ///
+    /// The target is resolved as if it was written in a divert at the given location,
+    /// so a stitch name refers to a stitch in the location's knot before it refers to
+    /// a knot. A location of `None` is the content before the first knot. Returns `None`
+    /// if the target does not resolve, or is `END` or `DONE`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{read_story_from_string, Location};
+    /// let content = "\
+    /// -> market
+    ///
+    /// == market
+    /// -> stall
+    ///
+    /// = stall
+    /// Fish for sale.
+    /// -> END
+    /// ";
+    ///
+    /// let story = read_story_from_string(content).unwrap();
+    ///
+    /// let stall = story.resolve_address("stall", Some(&Location::from("market"))).unwrap();
+    /// assert_eq!(stall.meta_data.line(), 6);
+    ///
+    /// assert!(story.resolve_address("stall", None).is_none());
+    /// ```
+    pub fn resolve_address(&self, target: &str, location: Option<&Location>) -> Option<Symbol> {
+        let data = ValidationData::from_data(&self.knots, &self.data.variables);
+        resolve_address(target, location, &data)
+    }
+
+    /// Export the knots, stitches and choices of the story as a graph.
+    ///
+    /// Nodes are knots, stitches and the points where choices are presented. Edges are
+    /// diverts and choice branches, labelled with the choice text and the conditions they
+    /// are taken under. Stitches are drawn inside the knot they belong to.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{read_story_from_string, GraphFormat};
+    /// let content = "\
+    /// *   Board the ferry. -> ferry
+    /// *   Stay ashore. -> END
+    /// == ferry
+    /// The engines roar.
+    /// -> END
+    /// ";
+    ///
+    /// let story = read_story_from_string(content).unwrap();
+    /// let dot = story.export_graph(GraphFormat::Dot);
+    ///
+    /// assert!(dot.starts_with("digraph story {"));
+    /// assert!(dot.contains("[label=\"Board the ferry.\"]"));
+    /// ```
+    pub fn export_graph(&self, format: GraphFormat) -> String {
+        export_story_graph(&self.knots, format)
+    }
+
+    /// Get all translatable text in the story.
+    ///
+    /// Every line, choice, alternative item and conditional text is an entry in the table,
+    /// with an identifier that is stable between runs. Write the table to a file for
+    /// translation with one of the formats of [`StringTable`][crate::story::StringTable].
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::read_story_from_string;
+    /// let content = "\
+    /// VAR name = \"Ishmael\"
+    /// Call me {name}.
+    /// *   [Some years ago] Never mind how long precisely.
+    /// ";
+    ///
+    /// let story = read_story_from_string(content).unwrap();
+    /// let table = story.get_string_table();
+    ///
+    /// assert_eq!(table.get("$ROOT$:0").unwrap().source, "Call me {1}.");
+    /// assert_eq!(table.get("$ROOT$:1/selection").unwrap().source, "Some years ago");
+    /// assert_eq!(
+    ///     table.get("$ROOT$:1/display").unwrap().source,
+    ///     " Never mind how long precisely."
+    /// );
+    /// ```
+    pub fn get_string_table(&self) -> StringTable {
+        get_string_table(&mut clone_knots_detached(&self.knots))
+    }
+
+    /// Replace the text in the story with translations from a table.
+    ///
+    /// Only text is replaced: variables, alternatives, conditions, diverts, glue and tags
+    /// are kept, so the story branches the same way in every language. Entries without
+    /// a translation, or whose source text does not match the text in the story since
+    /// the script has been changed, are not loaded. The identifiers of all text in the
+    /// story which was not translated are returned.
+    ///
+    /// The table should be loaded before the story is started. Text which has already
+    /// been processed, such as choices which are currently presented, is not translated.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{read_story_from_string, Prompt};
+    /// let content = "\
+    /// VAR name = \"Ishmael\"
+    /// Call me {name}.
+    /// ";
+    ///
+    /// let mut story = read_story_from_string(content).unwrap();
+    ///
+    /// let mut table = story.get_string_table();
+    /// table.entries[0].translation = Some("Appelez-moi {1}.".to_string());
+    ///
+    /// let untranslated = story.load_string_table(&table).unwrap();
+    /// assert!(untranslated.is_empty());
     ///
-    /// story.move_to(&location).unwrap();
-    /// # line_buffer.clear();
+    /// let mut line_buffer = Vec::new();
     /// story.resume(&mut line_buffer).unwrap();
     ///
-    /// assert_eq!(&line_buffer[0].text, "Miranda was waiting in her office.\n");
+    /// assert_eq!(line_buffer[0].text, "Appelez-moi Ishmael.\n");
     /// ```
-    pub fn resume(&mut self, line_buffer: &mut LineBuffer) -> Result<Prompt, InklingError> {
-        // Break early if we are at a choice but no choice has yet been made
-        match (self.selected_choice, self.last_choices.as_ref()) {
-            (None, Some(choices)) => return Ok(Prompt::Choice(choices.clone())),
-            _ => (),
-        }
+    ///
+    /// # Errors
+    /// *   [`InvalidPlaceholders`][crate::error::LocalizationError::InvalidPlaceholders]:
+    ///     if a translation does not contain every placeholder of its source text exactly
+    ///     once. No text is replaced if this is returned.
+    pub fn load_string_table(
+        &mut self,
+        table: &StringTable,
+    ) -> Result<Vec<String>, LocalizationError> {
+        let mut knots = clone_knots_detached(&self.knots);
+        let untranslated = translate_knots(&mut knots, table)?;
 
-        let selection = self.selected_choice.take();
+        self.knots = knots;
 
-        self.follow_story_wrapper(selection, line_buffer)
+        Ok(untranslated)
     }
 
-    /// Make a choice from a given set of options.
+    /// Replace the script of the story with a modified version while keeping its state.
     ///
-    /// The `selection` index corresponds to the index in the list of choices that was
-    /// previously returned when the branching point was reached. This list can be retrieved
-    /// again by calling [`resume`][crate::story::Story::resume()] on the story before making
-    /// a choice: once a choice has been successfully made, a call to `resume` will continue
-    /// the text flow from that branch.
+    /// The new script is read and validated like with
+    /// [`read_story_from_string`][crate::story::read_story_from_string()]. The story then
+    /// continues from the same knot and stitch, with the choices made in it, in the new
+    /// script. It keeps the random number generator, the number of visits to knots and
+    /// stitches which still exist and the values of variables which still exist with
+    /// the same type. Constants and variables which changed type get their new values.
+    ///
+    /// The choices made in the current stitch only have to lead to sets with the same number
+    /// and kinds of choices, so the text of choices can be changed. If the story is waiting
+    /// for a choice to be made, the choices are presented again from the new script.
+    /// Warnings and to-do comments in the log are replaced by those of the new script,
+    /// while messages from following the story are kept.
+    ///
+    /// Alternative sequences and once-only choices start over and any loaded string
+    /// table has to be loaded again. The story cannot be rewound past the reload.
     ///
     /// # Examples
     /// ```
-    /// # use inkling::{read_story_from_string, Prompt};
+    /// # use inkling::{read_story_from_string, Variable};
     /// let content = "\
-    /// Just as Nancy picked the old diary up from the table she heard
-    /// the door behind her creak open. Someone’s coming!
-    ///
-    /// *   She spun around to face the danger head on.
-    ///     Her heart was racing as the door slowly swung open and the black
-    ///     cat from the garden swept in.
-    ///     “Miao!”
-    /// *   In one smooth motion she hid behind the large curtain.
-    ///     A familiar “meow” coming from the room filled her with relief.
-    ///     That barely lasted a moment before the dusty curtains made her
-    ///     sneeze, awakening the house keeper sleeping in the neighbouring room.
+    /// VAR coins = 3
+    /// == market
+    /// You have {coins} coins.
+    /// +   [Buy] -> market
     /// ";
     ///
-    /// // ... setup
-    /// # let mut story = read_story_from_string(content).unwrap();
-    /// # let mut line_buffer = Vec::new();
+    /// let mut story = read_story_from_string(content).unwrap();
+    /// story.move_to(&"market".into()).unwrap();
+    /// story.set_variable("coins", 2).unwrap();
     ///
-    /// if let Prompt::Choice(choices) = story.resume(&mut line_buffer).unwrap() {
-    ///     story.make_choice(0).unwrap();
-    ///     story.resume(&mut line_buffer);
-    /// }
+    /// let mut line_buffer = Vec::new();
+    /// story.resume(&mut line_buffer).unwrap();
     ///
-    /// assert_eq!(line_buffer.last().unwrap().text, "“Miao!”\n");
+    /// let edited = content.replace("You have", "Your purse holds");
+    /// story.reload_from_string(&edited).unwrap();
+    ///
+    /// line_buffer.clear();
+    /// story.make_choice(0).unwrap();
+    /// story.resume(&mut line_buffer).unwrap();
+    ///
+    /// assert_eq!(&line_buffer[0].text, "Your purse holds 2 coins.\n");
     /// ```
     ///
     /// # Errors
-    /// *   [`MadeChoiceWithoutChoice`][crate::error::InklingError::MadeChoiceWithoutChoice]:
-    ///     if the story is not currently at a branching point.
-    pub fn make_choice(&mut self, selection: usize) -> Result<(), InklingError> {
-        let index = self
-            .last_choices
-            .as_ref()
-            .ok_or(InklingError::MadeChoiceWithoutChoice)
-            .and_then(|last_choices| {
-                last_choices
-                    .get(selection)
-                    .ok_or(InklingError::InvalidChoice {
-                        selection,
-                        presented_choices: last_choices.clone(),
-                    })
-                    .map(|choice| choice.index)
-            })?;
+    /// *   [`Read`][crate::error::ReloadError::Read]: if the new script could not be read.
+    /// *   [`MissingLocation`][crate::error::ReloadError::MissingLocation]: if the current
+    ///     knot or stitch does not exist in the new script.
+    /// *   [`ChangedStack`][crate::error::ReloadError::ChangedStack]: if the choices made
+    ///     in the current stitch do not lead to the same sets of choices in the new script.
+    /// *   [`Choices`][crate::error::ReloadError::Choices]: if the choices that the story
+    ///     is waiting on could not be prepared from the new script.
+    ///
+    /// The story is left unchanged if an error is returned.
+    pub fn reload_from_string(&mut self, content: &str) -> Result<(), ReloadError> {
+        let mut story = read_story_from_string(content)?;
+
+        match get_reload_position(&self.current_address, &self.knots, &story.knots) {
+            SavedPosition::Valid => (),
+            SavedPosition::MissingLocation(location) => {
+                return Err(ReloadError::MissingLocation { location })
+            }
+            SavedPosition::ChangedStack { location, stack } => {
+                return Err(ReloadError::ChangedStack { location, stack })
+            }
+        }
 
-        self.selected_choice.replace(index);
-        self.last_choices = None;
+        // The position has been checked to resolve, so the choices made in the current
+        // stitch are followed the same way in the new script
+        if let (Ok(old), Ok(new)) = (
+            get_stitch(&self.current_address, &self.knots),
+            get_mut_stitch(&self.current_address, &mut story.knots),
+        ) {
+            new.stack = old.stack.clone();
+        }
+
+        for (knot, counts) in story.data.knot_visit_counts.iter_mut() {
+            for (stitch, count) in counts.iter_mut() {
+                if let Some(old_count) = self
+                    .data
+                    .knot_visit_counts
+                    .get(knot)
+                    .and_then(|counts| counts.get(stitch))
+                {
+                    *count = *old_count;
+                }
+            }
+        }
+
+        for (name, info) in story.data.variables.iter_mut() {
+            match self.data.variables.get(name) {
+                Some(old)
+                    if !info.is_const
+                        && !old.is_const
+                        && discriminant(&old.variable) == discriminant(&info.variable) =>
+                {
+                    info.variable = old.variable.clone();
+                }
+                _ => (),
+            }
+        }
+
+        // Choices which are waiting to be made are presented again from the new script,
+        // with the state and random numbers of this story
+        story.data.rng = self.data.rng.clone();
+        story.data.rng.set_source_from(&self.data.rng);
+
+        let last_choices = match self.last_choices {
+            Some(..) => Some(
+                get_choices_at_stack(&self.current_address, &story.knots, &mut story.data)
+                    .map_err(ReloadError::Choices)?,
+            ),
+            None => None,
+        };
+
+        self.knots = story.knots;
+        self.tags = story.tags;
+        self.log.replace_script_messages(story.log);
+        self.last_choices = last_choices;
+        self.data.knot_visit_counts = story.data.knot_visit_counts;
+        self.data.variables = story.data.variables;
+        self.data.rng = story.data.rng;
+        self.history.clear();
 
         Ok(())
     }
@@ -265,16 +1382,13 @@ impl Story {
     /// *   [`InvalidAddress`][crate::error::InklingError::InvalidAddress]: if the given
     ///     location does not exist in the story.
     pub fn move_to(&mut self, location: &Location) -> Result<(), InklingError> {
-        let to_address = Address::from_location(location, &self.knots).map_err(|_| {
-            InklingError::InvalidAddress {
-                location: location.clone(),
-            }
-        })?;
+        let to_address = self.get_location_address(location)?;
 
         self.update_last_stack(&to_address);
 
         self.last_choices = None;
         self.selected_choice = None;
+        self.data.debugger.clear_pause();
 
         Ok(())
     }
@@ -308,19 +1422,7 @@ impl Story {
     /// assert_eq!(story.get_current_location(), location);
     /// ```
     pub fn get_current_location(&self) -> Location {
-        let (knot, stitch) = match self.current_address.get_knot_and_stitch() {
-            Ok(result) => result,
-            Err(_) => {
-                eprintln!("`inkling` encountered an error: the current location in the story is a variable, which should not happen");
-                (ROOT_KNOT_NAME, ROOT_KNOT_NAME)
-            }
-        };
-
-        if stitch == ROOT_KNOT_NAME {
-            Location::from(knot)
-        } else {
-            Location::with_stitch(knot, stitch)
-        }
+        location_from_address(&self.current_address)
     }
 
     /// Get the tags associated with the given knot.
@@ -502,13 +1604,99 @@ impl Story {
         name: &str,
         value: T,
     ) -> Result<(), InklingError> {
-        self.data
-            .variables
-            .get_mut(name)
-            .ok_or(InklingError::InvalidVariable {
+        match self.data.variables.get_mut(name) {
+            Some(variable_info) => {
+                let old_value = variable_info.variable.clone();
+                variable_info.assign(value.into(), name)?;
+
+                self.data.tracer.emit(|| TraceEvent::WriteVariable {
+                    name: name.to_string(),
+                    old_value,
+                    new_value: variable_info.variable.clone(),
+                });
+
+                Ok(())
+            }
+            None => Err(InklingError::InvalidVariable {
                 name: name.to_string(),
-            })
-            .and_then(|variable_info| variable_info.assign(value.into(), name))
+                suggestion: get_closest_name(name, self.data.variables.keys().map(String::as_str)),
+            }),
+        }
+    }
+
+    /// Evaluate an expression with the current variables and visit counts of the story.
+    ///
+    /// The expression is written as in the script and may use global variables, visit
+    /// counts of knots and stitches and mathematical operations. Names of stitches are
+    /// resolved from the current location of the story. The story is not changed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{read_story_from_string, Variable};
+    /// let content = "\
+    /// VAR coins = 6
+    /// VAR bonus = 3
+    /// ";
+    ///
+    /// let story = read_story_from_string(content).unwrap();
+    ///
+    /// assert_eq!(story.evaluate_expression("coins * 2 + bonus").unwrap(), Variable::Int(15));
+    /// ```
+    ///
+    /// # Errors
+    /// *   [`InvalidExpression`][crate::error::InklingError::InvalidExpression]: if the
+    ///     expression cannot be parsed, refers to names which do not exist in the story
+    ///     or operates on variables of incompatible types.
+    pub fn evaluate_expression(&self, expression: &str) -> Result<Variable, InklingError> {
+        let expression = parse_story_expression(
+            expression,
+            &self.current_address,
+            &self.knots,
+            &self.data.variables,
+        )?;
+
+        evaluate_expression(&expression, &self.data)
+    }
+
+    /// Evaluate a condition with the current variables and visit counts of the story.
+    ///
+    /// The condition is written as inside the braces of conditional content in the script,
+    /// with `and`, `or`, `not` and comparisons of expressions. A knot or stitch name on
+    /// its own checks whether it has been visited. The story is not changed and the
+    /// condition is not reported to a [tracer][crate::story::Story::set_tracer()].
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::read_story_from_string;
+    /// let content = "\
+    /// VAR coins = 12
+    /// -> tavern
+    ///
+    /// == tavern
+    /// The tavern was loud.
+    /// -> END
+    /// ";
+    ///
+    /// let mut story = read_story_from_string(content).unwrap();
+    /// assert!(!story.evaluate_condition("tavern and coins > 10").unwrap());
+    ///
+    /// story.resume(&mut Vec::new()).unwrap();
+    /// assert!(story.evaluate_condition("tavern and coins > 10").unwrap());
+    /// ```
+    ///
+    /// # Errors
+    /// *   [`InvalidExpression`][crate::error::InklingError::InvalidExpression]: if the
+    ///     condition cannot be parsed, refers to names which do not exist in the story
+    ///     or compares variables of incompatible types.
+    pub fn evaluate_condition(&self, condition: &str) -> Result<bool, InklingError> {
+        let condition = parse_story_condition(
+            condition,
+            &self.current_address,
+            &self.knots,
+            &self.data.variables,
+        )?;
+
+        evaluate_condition(&condition, &self.data)
     }
 
     /// Wrapper for calling `follow_story` with a prepared internal buffer.
@@ -522,15 +1710,26 @@ impl Story {
     ) -> Result<Prompt, InklingError> {
         let mut internal_buffer = Vec::new();
 
-        let (result, last_address) = follow_story(
+        let result = follow_story(
             &self.current_address,
             &mut internal_buffer,
             selection,
             &mut self.knots,
             &mut self.data,
-        )?;
+        );
+
+        self.log.add_runtime_messages(&mut self.data.messages);
+        let (result, last_address) = result?;
 
-        process_buffer(line_buffer, internal_buffer);
+        let num_lines = line_buffer.len();
+        let line_addresses = process_buffer(line_buffer, internal_buffer);
+
+        if let Some(transcript) = self.transcript.as_mut() {
+            for (line, address) in line_buffer[num_lines..].iter().zip(line_addresses) {
+                let location = location_from_address(address.as_ref().unwrap_or(&last_address));
+                transcript.add_line(line.clone(), location);
+            }
+        }
 
         self.update_last_stack(&last_address);
 
@@ -540,6 +1739,11 @@ impl Story {
 
                 Ok(Prompt::Choice(choices))
             }
+            Prompt::Breakpoint(pause) => {
+                self.data.debugger.set_paused();
+
+                Ok(Prompt::Breakpoint(pause))
+            }
             other => Ok(other),
         }
     }
@@ -548,6 +1752,69 @@ impl Story {
     fn update_last_stack(&mut self, address: &Address) {
         self.current_address = address.clone();
     }
+
+    /// Get the address of a location in the story, with a suggestion if it does not exist.
+    fn get_location_address(&self, location: &Location) -> Result<Address, InklingError> {
+        Address::from_location(location, &self.knots).map_err(|err| {
+            let suggestion = match err {
+                InvalidAddressErrorKind::UnknownKnot { suggestion, .. } => suggestion,
+                InvalidAddressErrorKind::UnknownStitch {
+                    knot_name,
+                    suggestion,
+                    ..
+                } => suggestion.map(|stitch| format!("{}.{}", knot_name, stitch)),
+                _ => None,
+            };
+
+            InklingError::InvalidAddress {
+                location: location.clone(),
+                suggestion,
+            }
+        })
+    }
+}
+
+/// Prepare the choices of the set that the stack of the current stitch ends at.
+///
+/// The choices are presented as they are when the story follows the content, but no
+/// messages are logged since they were when the choices were first presented.
+fn get_choices_at_stack(
+    address: &Address,
+    knots: &KnotSet,
+    data: &mut FollowData,
+) -> Result<Vec<Choice>, InklingError> {
+    let stitch = get_stitch(address, knots)?;
+
+    let mut choice_set = get_branches_at_stack(&stitch.stack, &stitch.root.items)
+        .unwrap_or(&[])
+        .iter()
+        .map(|branch| ChoiceInfo::from_choice(&branch.choice, branch.num_visited))
+        .collect::<Vec<_>>();
+
+    set_choice_ids(&mut choice_set, address, &stitch.root);
+
+    let num_messages = data.messages.len();
+    let choices = prepare_choices_for_user(&choice_set, data);
+    data.messages.truncate(num_messages);
+
+    choices
+}
+
+/// Get the `Location` that corresponds to an address in the story.
+pub(crate) fn location_from_address(address: &Address) -> Location {
+    let (knot, stitch) = match address.get_knot_and_stitch() {
+        Ok(result) => result,
+        Err(_) => {
+            eprintln!("`inkling` encountered an error: the current location in the story is a variable, which should not happen");
+            (ROOT_KNOT_NAME, ROOT_KNOT_NAME)
+        }
+    };
+
+    if stitch == ROOT_KNOT_NAME {
+        Location::from(knot)
+    } else {
+        Location::with_stitch(knot, stitch)
+    }
 }
 
 /// Read a `Story` by parsing an input string.
@@ -563,16 +1830,49 @@ impl Story {
 /// let story: Story = read_story_from_string(content).unwrap();
 /// ```
 pub fn read_story_from_string(string: &str) -> Result<Story, ReadError> {
+    read_story_from_string_with_options(string, &ReadOptions::default())
+}
+
+/// Read a `Story` by parsing an input string, with options for how it is set up.
+///
+/// # Examples
+/// ```
+/// # use inkling::{read_story_from_string_with_options, ReadOptions};
+/// let content = "\
+/// {~Heads|Tails}, I win.
+/// ";
+///
+/// let options = ReadOptions {
+///     seed: Some(1234),
+///     ..Default::default()
+/// };
+///
+/// let story = read_story_from_string_with_options(content, &options).unwrap();
+/// ```
+pub fn read_story_from_string_with_options(
+    string: &str,
+    options: &ReadOptions,
+) -> Result<Story, ReadError> {
     let mut log = Logger::default();
-    let (mut knots, variables, tags) = read_story_content_from_string(string, &mut log)?;
+    let (mut knots, variables, tags) =
+        read_story_content_from_string(string, &mut log).map_err(|err| err.with_source(string))?;
 
     let data = FollowData {
         knot_visit_counts: get_empty_knot_counts(&knots),
         variables,
-        rng: StoryRng::default(),
+        rng: get_story_rng(options),
+        coverage: None,
+        divert_limit: None,
+        reached_end: false,
+        tracer: TraceHook::default(),
+        messages: Vec::new(),
+        debugger: Debugger::default(),
     };
 
-    validate_story_content(&mut knots, &data, &mut log)?;
+    validate_story_content(&mut knots, &data, &mut log)
+        .map_err(|err| ReadError::from(err).with_source(string))?;
+
+    lint_story_content(&knots, &data, string, &mut log);
 
     let root_address = Address::from_root_knot(ROOT_KNOT_NAME, &knots).expect(
         "After successfully creating all knots, the root knot name that was returned from \
@@ -583,15 +1883,28 @@ pub fn read_story_from_string(string: &str) -> Result<Story, ReadError> {
     Ok(Story {
         current_address: root_address,
         knots,
-        history: Vec::new(),
+        history: History::default(),
         data,
         tags,
         last_choices: None,
         selected_choice: None,
+        transcript: None,
         log,
     })
 }
 
+/// Create the random number generator for a story, from a seed if one is given.
+#[cfg(feature = "random")]
+fn get_story_rng(options: &ReadOptions) -> StoryRng {
+    options.seed.map(StoryRng::with_seed).unwrap_or_default()
+}
+
+/// Create the random number generator for a story.
+#[cfg(not(feature = "random"))]
+fn get_story_rng(_: &ReadOptions) -> StoryRng {
+    StoryRng::default()
+}
+
 /// Follow the nodes in a story with selected branch index if supplied.
 ///
 /// When an event that triggers a `Prompt` is encountered it will be returned along with
@@ -608,13 +1921,38 @@ fn follow_story(
         follow_knot(current_address, internal_buffer, selection, knots, data)?;
 
     match event {
-        EncounteredEvent::BranchingChoice(choice_set) => {
+        EncounteredEvent::BranchingChoice(mut choice_set) => {
+            let root = &get_stitch(&last_address, knots)?.root;
+            set_choice_ids(&mut choice_set, &last_address, root);
+
+            let num_messages = data.messages.len();
             let user_choice_lines = prepare_choices_for_user(&choice_set, data)?;
+
             if !user_choice_lines.is_empty() {
+                add_revisited_sticky_choices_message(&choice_set, data);
+                set_message_locations(&mut data.messages[num_messages..], &last_address);
+
+                data.tracer.emit(|| TraceEvent::PresentChoices {
+                    location: location_from_address(&last_address),
+                    choices: user_choice_lines.clone(),
+                });
+
                 Ok((Prompt::Choice(user_choice_lines), last_address))
             } else {
                 let choice = get_fallback_choice(&choice_set, &last_address, data)?;
 
+                data.messages.push(LogMessage::with_kind(
+                    MessageKind::Runtime(RuntimeMessage::FallbackChoice),
+                    &choice_set[choice.index].choice_data.meta_data,
+                ));
+                set_message_locations(&mut data.messages[num_messages..], &last_address);
+
+                data.tracer.emit(|| TraceEvent::SelectChoice {
+                    location: location_from_address(&last_address),
+                    choice: choice.clone(),
+                    is_fallback: true,
+                });
+
                 follow_story(
                     &last_address,
                     internal_buffer,
@@ -625,6 +1963,7 @@ fn follow_story(
             }
         }
         EncounteredEvent::Done => Ok((Prompt::Done, last_address)),
+        EncounteredEvent::Pause(pause) => Ok((Prompt::Breakpoint(pause), last_address)),
         EncounteredEvent::Divert(..) => unreachable!("diverts are treated in `follow_knot`"),
     }
 }
@@ -644,27 +1983,87 @@ fn follow_knot(
     data: &mut FollowData,
 ) -> Result<(Address, EncounteredEvent), InklingError> {
     let mut current_address = address.clone();
+    let mut num_diverts = 0;
 
     let event = loop {
         let current_stitch = get_mut_stitch(&current_address, knots)?;
+        let num_lines = internal_buffer.len();
+        let num_messages = data.messages.len();
+
+        let is_entry = selection.is_none() && current_stitch.stack == [0];
+        data.debugger.set_address(&current_address, is_entry);
 
         let result = match selection.take() {
             Some(i) => current_stitch.follow_with_choice(i, internal_buffer, data),
             None => current_stitch.follow(internal_buffer, data),
         }?;
 
+        internal_buffer[num_lines..]
+            .iter_mut()
+            .for_each(|line| line.address = Some(current_address.clone()));
+
+        set_message_locations(&mut data.messages[num_messages..], &current_address);
+
         match result {
-            EncounteredEvent::Divert(Address::End) => break EncounteredEvent::Done,
+            EncounteredEvent::Divert(Address::End) => {
+                data.tracer.emit(|| TraceEvent::End {
+                    location: location_from_address(&current_address),
+                });
+
+                data.reached_end = true;
+                break EncounteredEvent::Done;
+            }
             EncounteredEvent::Divert(to_address) => {
+                num_diverts += 1;
+
+                if let Some(limit) = data.divert_limit.filter(|&limit| num_diverts > limit) {
+                    return Err(InklingError::DivertLimitExceeded {
+                        location: location_from_address(&current_address),
+                        limit,
+                    });
+                }
+
+                data.tracer.emit(|| TraceEvent::Divert {
+                    from: location_from_address(&current_address),
+                    to: location_from_address(&to_address),
+                });
+
                 current_address = to_address;
             }
-            _ => break result,
+            _ => {
+                data.reached_end = false;
+                break result;
+            }
         }
     };
 
     Ok((current_address, event))
 }
 
+/// Log that a set of choices with sticky choices is offered again.
+///
+/// The number of times that the set has been offered is counted from the choices
+/// that have been made from it, since that is what the nodes keep track of.
+fn add_revisited_sticky_choices_message(choice_set: &[ChoiceInfo], data: &mut FollowData) {
+    let has_sticky_choices = choice_set.iter().any(|info| info.choice_data.is_sticky);
+    let num_offered = choice_set.iter().map(|info| info.num_visited).sum::<u32>() + 1;
+
+    if let (true, Some(first)) = (has_sticky_choices && num_offered > 1, choice_set.first()) {
+        data.messages.push(LogMessage::with_kind(
+            MessageKind::Runtime(RuntimeMessage::StickyChoicesRevisited { num_offered }),
+            &first.choice_data.meta_data,
+        ));
+    }
+}
+
+/// Set the location of messages from following the story which do not yet have one.
+fn set_message_locations(messages: &mut [LogMessage], address: &Address) {
+    messages
+        .iter_mut()
+        .filter(|message| message.location.is_none())
+        .for_each(|message| message.location = Some(location_from_address(address)));
+}
+
 /// Return the first available fallback choice from the given set of choices.
 ///
 /// Choices are filtered as usual by conditions and visits.
@@ -696,6 +2095,7 @@ mod tests {
             .map(|(text, index)| Choice {
                 text: text.to_string(),
                 tags: Vec::new(),
+                id: String::new(),
                 index: *index,
             })
             .collect()
@@ -1620,6 +3020,55 @@ Once back home we feasted on cheese.
         assert!(story.move_to(&location).is_err());
     }
 
+    #[test]
+    fn move_to_and_set_variable_errors_suggest_close_names() {
+        let content = "
+
+VAR coins = 0
+
+== tavern
+You enter the tavern.
+-> END
+
+= cellar
+It is dark.
+-> END
+
+";
+
+        let mut story = read_story_from_string(content).unwrap();
+
+        match story.move_to(&"tavren".into()) {
+            Err(InklingError::InvalidAddress { suggestion, .. }) => {
+                assert_eq!(suggestion, Some("tavern".to_string()))
+            }
+            other => panic!(
+                "expected `InklingError::InvalidAddress` but got {:?}",
+                other
+            ),
+        }
+
+        match story.move_to(&Location::with_stitch("tavern", "celar")) {
+            Err(InklingError::InvalidAddress { suggestion, .. }) => {
+                assert_eq!(suggestion, Some("tavern.cellar".to_string()))
+            }
+            other => panic!(
+                "expected `InklingError::InvalidAddress` but got {:?}",
+                other
+            ),
+        }
+
+        match story.set_variable("coin", 5) {
+            Err(err @ InklingError::InvalidVariable { .. }) => {
+                assert!(format!("{}", err).ends_with("did you mean 'coins'?"))
+            }
+            other => panic!(
+                "expected `InklingError::InvalidVariable` but got {:?}",
+                other
+            ),
+        }
+    }
+
     #[test]
     fn get_knot_tags_from_knot_name() {
         let content = "
@@ -0,0 +1,273 @@
+//! Definitions of knots, stitches and variables in a story, for editor tooling.
+
+use crate::{
+    consts::ROOT_KNOT_NAME,
+    error::utils::MetaData,
+    knot::{Address, AddressKind},
+    line::Variable,
+    story::{types::Location, validate::ValidationData},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+/// Definition of a named knot, stitch or global variable in a story.
+///
+/// Retrieved with [`get_symbols`][crate::story::Story::get_symbols()] and
+/// [`resolve_address`][crate::story::Story::resolve_address()].
+///
+/// # Examples
+/// ```
+/// # use inkling::{read_story_from_string, SymbolKind};
+/// let content = "\
+/// VAR gold = 3
+/// -> market
+///
+/// == market
+/// = stall
+/// Fish for sale.
+/// -> END
+/// ";
+///
+/// let story = read_story_from_string(content).unwrap();
+/// let symbols = story.get_symbols();
+///
+/// assert_eq!(&symbols[0].name, "gold");
+/// assert_eq!(&symbols[1].name, "market");
+/// assert_eq!(symbols[2].kind, SymbolKind::Stitch { knot: "market".to_string() });
+/// assert_eq!(symbols[2].meta_data.line(), 5);
+/// ```
+pub struct Symbol {
+    /// Name of the knot, stitch or variable.
+    ///
+    /// Stitch names are not prefixed with the name of their knot.
+    pub name: String,
+    /// Kind of definition.
+    pub kind: SymbolKind,
+    /// Information about the line which defines the symbol.
+    pub meta_data: MetaData,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Kind of a named definition in a story.
+pub enum SymbolKind {
+    /// Knot, defined by a `== name` line.
+    Knot,
+    /// Stitch in a knot, defined by a `= name` line.
+    Stitch { knot: String },
+    /// Global variable or constant, defined by a `VAR` or `CONST` line.
+    Variable {
+        /// Value that the variable had when the story was read.
+        value: Variable,
+        /// Whether the variable is a constant.
+        is_const: bool,
+    },
+}
+
+/// Get all named knots, stitches and global variables, ordered by the line they are defined on.
+pub fn get_symbols(data: &ValidationData) -> Vec<Symbol> {
+    let mut symbols = Vec::new();
+
+    for (name, info) in &data.follow_data.variables {
+        symbols.push(Symbol {
+            name: name.clone(),
+            kind: SymbolKind::Variable {
+                value: info.variable.clone(),
+                is_const: info.is_const,
+            },
+            meta_data: info.meta_data.clone(),
+        });
+    }
+
+    for (knot_name, knot_info) in &data.knots {
+        if knot_name != ROOT_KNOT_NAME {
+            symbols.push(get_knot_symbol(knot_name, data));
+        }
+
+        for (stitch_name, stitch_info) in &knot_info.stitches {
+            if stitch_name != ROOT_KNOT_NAME {
+                symbols.push(Symbol {
+                    name: stitch_name.clone(),
+                    kind: SymbolKind::Stitch {
+                        knot: knot_name.clone(),
+                    },
+                    meta_data: stitch_info.meta_data.clone(),
+                });
+            }
+        }
+    }
+
+    symbols.sort_by_key(|symbol| (symbol.meta_data.line(), symbol.meta_data.column()));
+
+    symbols
+}
+
+/// Find the definition that a divert target resolves to when written at a location.
+///
+/// A location of `None` is the content before the first knot. Targets are resolved like
+/// diverts are when the story is read: a single name is first looked for among the stitches
+/// of the current knot, then among knots and last among global variables. Targets to `END`
+/// or `DONE` and targets which cannot be resolved yield `None`.
+pub fn resolve_address(
+    target: &str,
+    location: Option<&Location>,
+    data: &ValidationData,
+) -> Option<Symbol> {
+    let current_address = get_current_address(location, data)?;
+    let address = Address::from_target(target, &current_address, data).ok()?;
+
+    match address {
+        Address::Validated(AddressKind::Location { knot, stitch }) => {
+            let is_knot_name = !target.contains('.') && target.trim() == knot;
+
+            if is_knot_name || stitch == ROOT_KNOT_NAME {
+                Some(get_knot_symbol(&knot, data))
+            } else {
+                let meta_data = data
+                    .knots
+                    .get(&knot)?
+                    .stitches
+                    .get(&stitch)?
+                    .meta_data
+                    .clone();
+
+                Some(Symbol {
+                    name: stitch,
+                    kind: SymbolKind::Stitch { knot },
+                    meta_data,
+                })
+            }
+        }
+        Address::Validated(AddressKind::GlobalVariable { name }) => {
+            let info = data.follow_data.variables.get(&name)?;
+
+            Some(Symbol {
+                kind: SymbolKind::Variable {
+                    value: info.variable.clone(),
+                    is_const: info.is_const,
+                },
+                meta_data: info.meta_data.clone(),
+                name,
+            })
+        }
+        Address::Raw(..) | Address::End => None,
+    }
+}
+
+/// Get the address of a location, or of the content before the first knot if it is `None`.
+fn get_current_address(location: Option<&Location>, data: &ValidationData) -> Option<Address> {
+    let knot = location
+        .map(|location| location.knot.as_str())
+        .unwrap_or(ROOT_KNOT_NAME);
+
+    let knot_info = data.knots.get(knot)?;
+    let stitch = location
+        .and_then(|location| location.stitch.clone())
+        .unwrap_or_else(|| knot_info.default_stitch.clone());
+
+    if knot_info.stitches.contains_key(&stitch) {
+        Some(Address::Validated(AddressKind::Location {
+            knot: knot.to_string(),
+            stitch,
+        }))
+    } else {
+        None
+    }
+}
+
+fn get_knot_symbol(name: &str, data: &ValidationData) -> Symbol {
+    Symbol {
+        name: name.to_string(),
+        kind: SymbolKind::Knot,
+        meta_data: data.knots[name].meta_data.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::story::read_story_from_string;
+
+    const CONTENT: &str = "\
+VAR gold = 3
+CONST price = 2
+-> market
+
+== market
+-> stall
+
+= stall
+-> counter
+
+= counter
+-> END
+
+== shop
+= stall
+-> END
+";
+
+    #[test]
+    fn symbols_are_knots_stitches_and_variables_in_the_order_they_are_defined() {
+        let symbols = read_story_from_string(CONTENT).unwrap().get_symbols();
+
+        let names = symbols
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.meta_data.line()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            &names,
+            &[
+                ("gold", 1),
+                ("price", 2),
+                ("market", 5),
+                ("stall", 8),
+                ("counter", 11),
+                ("shop", 14),
+                ("stall", 15)
+            ]
+        );
+
+        assert_eq!(
+            symbols[1].kind,
+            SymbolKind::Variable {
+                value: Variable::Int(2),
+                is_const: true
+            }
+        );
+    }
+
+    #[test]
+    fn stitch_names_resolve_to_stitches_in_the_current_knot_first() {
+        let story = read_story_from_string(CONTENT).unwrap();
+
+        let in_market = story
+            .resolve_address("stall", Some(&Location::from("market.counter")))
+            .unwrap();
+        let in_shop = story
+            .resolve_address("stall", Some(&Location::from("shop")))
+            .unwrap();
+
+        assert_eq!(in_market.meta_data.line(), 8);
+        assert_eq!(in_shop.meta_data.line(), 15);
+    }
+
+    #[test]
+    fn knot_names_resolve_to_knots_and_full_addresses_to_stitches() {
+        let story = read_story_from_string(CONTENT).unwrap();
+        let knot = story.resolve_address("shop", None).unwrap();
+        let stitch = story.resolve_address("shop.stall", None).unwrap();
+
+        assert_eq!(knot.kind, SymbolKind::Knot);
+        assert_eq!(knot.meta_data.line(), 14);
+        assert_eq!(stitch.meta_data.line(), 15);
+    }
+
+    #[test]
+    fn variable_names_resolve_to_variables_and_unknown_names_to_nothing() {
+        let story = read_story_from_string(CONTENT).unwrap();
+        assert_eq!(&story.resolve_address("gold", None).unwrap().name, "gold");
+        assert!(story.resolve_address("bakery", None).is_none());
+        assert!(story.resolve_address("END", None).is_none());
+    }
+}
@@ -0,0 +1,302 @@
+//! Tracing of events while a story is followed.
+//!
+//! A [`Tracer`] set on the story is shared with the caller and receives a [`TraceEvent`]
+//! for every knot or stitch that is entered, divert, evaluated condition, alternative
+//! sequence, choice and variable access. Events are only created when a tracer is set.
+
+use crate::{
+    line::Variable,
+    story::types::{Choice, Location},
+};
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+/// Receiver of events from following a story.
+///
+/// Set with [`set_tracer`][crate::story::Story::set_tracer()]. Since the tracer is shared
+/// with the caller it is not saved with the story, nor restored when rewinding.
+///
+/// # Examples
+/// ```
+/// # use inkling::{read_story_from_string, TraceEvent, Tracer};
+/// # use std::sync::{Arc, Mutex};
+/// #[derive(Default)]
+/// struct Conditions(Vec<String>);
+///
+/// impl Tracer for Conditions {
+///     fn trace(&mut self, event: &TraceEvent) {
+///         if let TraceEvent::Condition { .. } = event {
+///             self.0.push(event.to_string());
+///         }
+///     }
+/// }
+///
+/// let content = "\
+/// VAR coins = 1
+/// {coins > 2: You can afford it.}
+/// ";
+///
+/// let mut story = read_story_from_string(content).unwrap();
+/// let tracer = Arc::new(Mutex::new(Conditions::default()));
+///
+/// story.set_tracer(tracer.clone());
+/// story.resume(&mut Vec::new()).unwrap();
+///
+/// assert_eq!(&tracer.lock().unwrap().0, &["Condition `coins > 2` is false"]);
+/// ```
+pub trait Tracer: Send {
+    /// Receive an event from the story.
+    fn trace(&mut self, event: &TraceEvent);
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Event from following a story.
+pub enum TraceEvent {
+    /// A knot or stitch was entered from its beginning, which counts as a visit.
+    Enter {
+        /// Location of the knot and stitch.
+        location: Location,
+    },
+    /// The story diverted from a knot or stitch to another.
+    Divert {
+        /// Location that the divert was followed from.
+        from: Location,
+        /// Location that the divert leads to.
+        to: Location,
+    },
+    /// The story reached a divert to `END` or `DONE`.
+    End {
+        /// Location that the divert was followed from.
+        location: Location,
+    },
+    /// A condition for a line, part of a line or choice was evaluated.
+    ///
+    /// Variables that are read to evaluate the condition are traced before this.
+    Condition {
+        /// Condition as written in `Ink` source, without the enclosing braces.
+        condition: String,
+        /// Whether the condition was fulfilled.
+        result: bool,
+    },
+    /// An alternative sequence picked the item to show.
+    Alternative {
+        /// Alternative sequence as written in `Ink` source.
+        alternative: String,
+        /// Index of the picked item, or `None` if a once-only sequence has run out.
+        index: Option<usize>,
+        /// Number of items in the sequence.
+        num_items: usize,
+    },
+    /// Choices were presented to the player.
+    PresentChoices {
+        /// Location of the knot and stitch with the choices.
+        location: Location,
+        /// Choices which were presented.
+        choices: Vec<Choice>,
+    },
+    /// A choice was selected.
+    SelectChoice {
+        /// Location of the knot and stitch with the choice.
+        location: Location,
+        /// Selected choice.
+        choice: Choice,
+        /// Whether the choice is a fallback choice which was selected since no other
+        /// choices were available.
+        is_fallback: bool,
+    },
+    /// The value of a global variable was read.
+    ReadVariable {
+        /// Name of variable.
+        name: String,
+        /// Value of variable.
+        value: Variable,
+    },
+    /// A global variable was assigned a new value.
+    WriteVariable {
+        /// Name of variable.
+        name: String,
+        /// Value before the assignment.
+        old_value: Variable,
+        /// Value after the assignment.
+        new_value: Variable,
+    },
+}
+
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use TraceEvent::*;
+
+        match self {
+            Enter { location } => write!(f, "Entered '{}'", location),
+            Divert { from, to } => write!(f, "Diverted from '{}' to '{}'", from, to),
+            End { location } => write!(f, "Reached the end in '{}'", location),
+            Condition { condition, result } => {
+                write!(f, "Condition `{}` is {}", condition, result)
+            }
+            Alternative {
+                alternative,
+                index: Some(index),
+                num_items,
+            } => write!(
+                f,
+                "Alternative `{}` picked item {} of {}",
+                alternative,
+                index + 1,
+                num_items
+            ),
+            Alternative { alternative, .. } => {
+                write!(f, "Alternative `{}` has no items left", alternative)
+            }
+            PresentChoices { location, choices } => {
+                let texts = choices
+                    .iter()
+                    .map(|choice| format!("'{}'", choice.text))
+                    .collect::<Vec<_>>();
+
+                write!(
+                    f,
+                    "Presented choices in '{}': {}",
+                    location,
+                    texts.join(", ")
+                )
+            }
+            SelectChoice {
+                location,
+                choice,
+                is_fallback,
+            } => {
+                let kind = if *is_fallback {
+                    "fallback choice"
+                } else {
+                    "choice"
+                };
+
+                write!(f, "Selected {} '{}' in '{}'", kind, choice.text, location)
+            }
+            ReadVariable { name, value } => {
+                write!(f, "Read '{}' = {}", name, value.to_ink_source())
+            }
+            WriteVariable {
+                name,
+                old_value,
+                new_value,
+            } => write!(
+                f,
+                "Set '{}' = {} (was {})",
+                name,
+                new_value.to_ink_source(),
+                old_value.to_ink_source()
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+/// Tracer set on a story, if any.
+///
+/// Wrapped to be carried in the follow data, which derives `Debug` and is compared
+/// in tests.
+pub struct TraceHook(Option<Arc<Mutex<dyn Tracer>>>);
+
+impl TraceHook {
+    /// Set or remove the tracer.
+    pub fn set(&mut self, tracer: Option<Arc<Mutex<dyn Tracer>>>) {
+        self.0 = tracer;
+    }
+
+    /// Send an event to the tracer, if one is set.
+    ///
+    /// The event is only created if there is a tracer to receive it.
+    pub fn emit<F>(&self, get_event: F)
+    where
+        F: FnOnce() -> TraceEvent,
+    {
+        if let Some(tracer) = &self.0 {
+            let event = get_event();
+            tracer.lock().unwrap().trace(&event);
+        }
+    }
+}
+
+impl fmt::Debug for TraceHook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("TraceHook")
+            .field(&self.0.as_ref().map(|_| "Tracer"))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for TraceHook {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Some(tracer), Some(other)) => Arc::ptr_eq(tracer, other),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Events(Vec<TraceEvent>);
+
+    impl Tracer for Events {
+        fn trace(&mut self, event: &TraceEvent) {
+            self.0.push(event.clone());
+        }
+    }
+
+    fn get_event() -> TraceEvent {
+        TraceEvent::Enter {
+            location: Location::from("tavern"),
+        }
+    }
+
+    #[test]
+    fn events_are_only_created_if_a_tracer_is_set() {
+        let mut hook = TraceHook::default();
+        hook.emit(|| panic!("event created without a tracer"));
+
+        let events = Arc::new(Mutex::new(Events::default()));
+        hook.set(Some(events.clone()));
+        hook.emit(get_event);
+
+        assert_eq!(&events.lock().unwrap().0, &[get_event()]);
+
+        hook.set(None);
+        hook.emit(get_event);
+
+        assert_eq!(events.lock().unwrap().0.len(), 1);
+    }
+
+    #[test]
+    fn cloned_hooks_share_the_tracer() {
+        let events = Arc::new(Mutex::new(Events::default()));
+
+        let mut hook = TraceHook::default();
+        hook.set(Some(events.clone()));
+
+        let cloned = hook.clone();
+        hook.emit(get_event);
+        cloned.emit(get_event);
+
+        assert_eq!(events.lock().unwrap().0.len(), 2);
+    }
+
+    #[test]
+    fn events_display_addresses_as_in_ink() {
+        let event = TraceEvent::Divert {
+            from: Location::with_stitch("tavern", "bar"),
+            to: Location::from("street"),
+        };
+
+        assert_eq!(&event.to_string(), "Diverted from 'tavern.bar' to 'street'");
+    }
+}
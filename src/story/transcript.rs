@@ -0,0 +1,237 @@
+//! Record of the lines and choices of a story as it is played.
+
+use crate::story::types::{Choice, Line, Location};
+
+#[cfg(feature = "serde_support")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
+/// Transcript of every line and choice that has been encountered in a story.
+///
+/// Recording is started by calling
+/// [`record_transcript`][crate::story::Story::record_transcript()] on the story.
+/// From then on every line that is returned and every choice that is made is added as
+/// an entry, along with the location it came from and the turn it was encountered on.
+///
+/// Turns are counted from the start of the recording: every made choice ends the current
+/// turn. The lines following the first choice are thus in turn 1, and so on.
+///
+/// # Examples
+/// ```
+/// # use inkling::{read_story_from_string, TranscriptEvent};
+/// let content = "\
+/// The ferry was about to leave.
+/// *   I ran to the gangway.
+///     I made it just in time.
+/// *   I let it go.
+/// ";
+///
+/// let mut story = read_story_from_string(content).unwrap();
+/// let mut line_buffer = Vec::new();
+///
+/// story.record_transcript();
+///
+/// story.resume(&mut line_buffer).unwrap();
+/// story.make_choice(0).unwrap();
+/// story.resume(&mut line_buffer).unwrap();
+///
+/// let transcript = story.transcript().unwrap();
+///
+/// let choices = transcript.choices().collect::<Vec<_>>();
+/// assert_eq!(choices[0].text, "I ran to the gangway.");
+///
+/// let entry = transcript.entries.last().unwrap();
+/// assert_eq!(entry.turn, 1);
+///
+/// match &entry.event {
+///     TranscriptEvent::Line(line) => assert_eq!(line.text, "I made it just in time.\n"),
+///     TranscriptEvent::Choice(..) => unreachable!(),
+/// }
+/// ```
+pub struct Transcript {
+    /// Recorded lines and choices, in the order they were encountered.
+    pub entries: Vec<TranscriptEntry>,
+    /// Index of the current turn.
+    turn: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
+/// Single line or choice in a [`Transcript`][crate::story::Transcript].
+pub struct TranscriptEntry {
+    /// Turn that the entry was encountered on.
+    pub turn: usize,
+    /// Location in the story that the line or choice came from.
+    pub location: Location,
+    /// Recorded line or choice.
+    pub event: TranscriptEvent,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
+/// Content of a [`TranscriptEntry`][crate::story::TranscriptEntry].
+pub enum TranscriptEvent {
+    /// Line of text which was returned by the story.
+    Line(Line),
+    /// Choice which was made by the user.
+    Choice(Choice),
+}
+
+impl Transcript {
+    /// Iterate over all recorded lines.
+    pub fn lines(&self) -> impl Iterator<Item = &Line> {
+        self.entries.iter().filter_map(|entry| match &entry.event {
+            TranscriptEvent::Line(line) => Some(line),
+            TranscriptEvent::Choice(..) => None,
+        })
+    }
+
+    /// Iterate over all made choices.
+    pub fn choices(&self) -> impl Iterator<Item = &Choice> {
+        self.entries.iter().filter_map(|entry| match &entry.event {
+            TranscriptEvent::Choice(choice) => Some(choice),
+            TranscriptEvent::Line(..) => None,
+        })
+    }
+
+    /// Get the index of the current turn.
+    pub fn get_turn(&self) -> usize {
+        self.turn
+    }
+
+    /// Add a line to the transcript.
+    pub(crate) fn add_line(&mut self, line: Line, location: Location) {
+        self.add_entry(TranscriptEvent::Line(line), location);
+    }
+
+    /// Add a made choice to the transcript and begin the next turn.
+    pub(crate) fn add_choice(&mut self, choice: Choice, location: Location) {
+        self.add_entry(TranscriptEvent::Choice(choice), location);
+        self.turn += 1;
+    }
+
+    /// Remove the given number of made choices, and all entries after them.
+    ///
+    /// If more choices are removed than were recorded, the transcript is cleared.
+    pub(crate) fn rewind(&mut self, steps: usize) {
+        if steps > self.turn {
+            self.entries.clear();
+            self.turn = 0;
+
+            return;
+        }
+
+        let target_turn = self.turn - steps;
+
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| match entry.event {
+                TranscriptEvent::Choice(..) => entry.turn >= target_turn,
+                TranscriptEvent::Line(..) => entry.turn > target_turn,
+            })
+            .unwrap_or(self.entries.len());
+
+        self.entries.truncate(index);
+        self.turn = target_turn;
+    }
+
+    fn add_entry(&mut self, event: TranscriptEvent, location: Location) {
+        self.entries.push(TranscriptEntry {
+            turn: self.turn,
+            location,
+            event,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_line(text: &str) -> Line {
+        Line {
+            text: text.to_string(),
+            tags: Vec::new(),
+        }
+    }
+
+    fn mock_choice(text: &str) -> Choice {
+        Choice {
+            text: text.to_string(),
+            tags: Vec::new(),
+            id: String::new(),
+            index: 0,
+        }
+    }
+
+    fn mock_transcript() -> Transcript {
+        let mut transcript = Transcript::default();
+
+        transcript.add_line(mock_line("Line 1"), Location::from("knot"));
+        transcript.add_choice(mock_choice("Choice 1"), Location::from("knot"));
+        transcript.add_line(mock_line("Line 2"), Location::from("knot"));
+        transcript.add_choice(mock_choice("Choice 2"), Location::from("knot"));
+        transcript.add_line(mock_line("Line 3"), Location::from("knot"));
+
+        transcript
+    }
+
+    #[test]
+    fn entries_are_marked_with_the_turn_they_were_added_in() {
+        let transcript = mock_transcript();
+
+        let turns = transcript
+            .entries
+            .iter()
+            .map(|entry| entry.turn)
+            .collect::<Vec<_>>();
+
+        assert_eq!(turns, vec![0, 0, 1, 1, 2]);
+        assert_eq!(transcript.get_turn(), 2);
+    }
+
+    #[test]
+    fn lines_and_choices_can_be_iterated_over_separately() {
+        let transcript = mock_transcript();
+
+        let lines = transcript
+            .lines()
+            .map(|line| &line.text)
+            .collect::<Vec<_>>();
+        let choices = transcript
+            .choices()
+            .map(|choice| &choice.text)
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines, vec!["Line 1", "Line 2", "Line 3"]);
+        assert_eq!(choices, vec!["Choice 1", "Choice 2"]);
+    }
+
+    #[test]
+    fn rewinding_removes_choices_and_all_entries_after_them() {
+        let mut transcript = mock_transcript();
+
+        transcript.rewind(1);
+
+        assert_eq!(transcript.entries.len(), 3);
+        assert_eq!(transcript.get_turn(), 1);
+        assert_eq!(transcript.lines().last().unwrap().text, "Line 2");
+
+        transcript.rewind(1);
+
+        assert_eq!(transcript.entries.len(), 1);
+        assert_eq!(transcript.get_turn(), 0);
+    }
+
+    #[test]
+    fn rewinding_past_the_first_recorded_choice_clears_the_transcript() {
+        let mut transcript = mock_transcript();
+
+        transcript.rewind(3);
+
+        assert!(transcript.entries.is_empty());
+        assert_eq!(transcript.get_turn(), 0);
+    }
+}
@@ -3,9 +3,10 @@
 use crate::{
     error::{utils::MetaData, InklingError},
     line::Variable,
+    story::debug::Pause,
 };
 
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
 #[cfg(feature = "serde_support")]
 use serde::{Deserialize, Serialize};
@@ -35,14 +36,205 @@ pub struct Choice {
     pub text: String,
     /// Tags associated with the choice.
     pub tags: Vec<String>,
+    /// Identifier of the choice in the story.
+    ///
+    /// Formed from the knot and stitch that the choice is in and a label made from
+    /// the text of the choice as written in the script: `knot.stitch:label`. If several
+    /// choices in the stitch have the same label, a number is appended to all but the
+    /// first to separate them: `knot.stitch:label:2`.
+    ///
+    /// The identifier does not change between playthroughs or if other content
+    /// in the script is edited, which makes it more robust than the index of the choice.
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub id: String,
     /// Internal index of choice in set.
     pub(crate) index: usize,
 }
 
+impl Line {
+    /// Get the tags of the line parsed into keys, values and flags.
+    ///
+    /// See [`Tag`][crate::story::Tag] for how the tags are parsed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use inkling::{read_story_from_string, Tag};
+    /// let content = "\
+    /// VAR name = \"Anna\"
+    /// Welcome to the lighthouse. # speaker: {name} # mood=weary # important
+    /// ";
+    ///
+    /// let mut story = read_story_from_string(content).unwrap();
+    /// let mut line_buffer = Vec::new();
+    ///
+    /// story.resume(&mut line_buffer).unwrap();
+    /// let tags = line_buffer[0].parsed_tags();
+    ///
+    /// assert_eq!(tags[0], Tag::KeyValue { key: "speaker".to_string(), value: "Anna".to_string() });
+    /// assert_eq!(tags[1], Tag::KeyValue { key: "mood".to_string(), value: "weary".to_string() });
+    /// assert_eq!(tags[2], Tag::Flag("important".to_string()));
+    /// ```
+    pub fn parsed_tags(&self) -> Vec<Tag> {
+        parse_tags(&self.tags)
+    }
+}
+
+impl Choice {
+    /// Get the tags of the choice parsed into keys, values and flags.
+    ///
+    /// See [`Tag`][crate::story::Tag] for how the tags are parsed.
+    pub fn parsed_tags(&self) -> Vec<Tag> {
+        parse_tags(&self.tags)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
+/// Reference to a choice in a set of presented choices.
+///
+/// Used to [replay][crate::story::Story::replay()] a list of choices. Choices can be referred
+/// to by their index in the presented set, their text or their [`id`][crate::story::Choice].
+/// Implements `From<usize>` for indices, `From<&str>` for texts and `From<&Choice>` for ids.
+///
+/// # Examples
+/// ```
+/// # use inkling::ChoiceRef;
+/// assert_eq!(ChoiceRef::from(2), ChoiceRef::Index(2));
+/// assert_eq!(ChoiceRef::from("Open the door."), ChoiceRef::Text("Open the door.".to_string()));
+/// ```
+pub enum ChoiceRef {
+    /// Index of the choice in the presented set.
+    Index(usize),
+    /// Text of the choice.
+    Text(String),
+    /// Identifier of the choice.
+    Id(String),
+}
+
+impl ChoiceRef {
+    /// Find the index of the referred choice in a presented set, if it is in it.
+    pub fn find_in(&self, choices: &[Choice]) -> Option<usize> {
+        match self {
+            ChoiceRef::Index(index) if *index < choices.len() => Some(*index),
+            ChoiceRef::Index(..) => None,
+            ChoiceRef::Text(text) => choices.iter().position(|choice| &choice.text == text),
+            ChoiceRef::Id(id) => choices.iter().position(|choice| &choice.id == id),
+        }
+    }
+}
+
+impl From<usize> for ChoiceRef {
+    fn from(index: usize) -> Self {
+        ChoiceRef::Index(index)
+    }
+}
+
+impl From<&str> for ChoiceRef {
+    fn from(text: &str) -> Self {
+        ChoiceRef::Text(text.to_string())
+    }
+}
+
+impl From<&Choice> for ChoiceRef {
+    fn from(choice: &Choice) -> Self {
+        ChoiceRef::Id(choice.id.clone())
+    }
+}
+
+impl fmt::Display for ChoiceRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChoiceRef::Index(index) => write!(f, "choice {}", index),
+            ChoiceRef::Text(text) => write!(f, "choice '{}'", text),
+            ChoiceRef::Id(id) => write!(f, "choice with id '{}'", id),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
+/// Tag which has been parsed into a key and value or a bare flag.
+///
+/// `inkling` stores tags as plain strings. Tags are commonly written as `key: value`
+/// or `key=value` pairs, or as single flags. This type splits a tag string at the first
+/// `:` or `=` separator. Both parts are trimmed of whitespace. Tags without a separator,
+/// or with nothing before it, are bare flags.
+///
+/// Implements `From<&str>` for strings.
+///
+/// # Examples
+/// ```
+/// # use inkling::Tag;
+/// assert_eq!(
+///     Tag::from("speaker: Anna"),
+///     Tag::KeyValue {
+///         key: "speaker".to_string(),
+///         value: "Anna".to_string(),
+///     }
+/// );
+///
+/// assert_eq!(Tag::from("mood=angry").value(), Some("angry"));
+/// assert_eq!(Tag::from("important"), Tag::Flag("important".to_string()));
+/// ```
+pub enum Tag {
+    /// Tag with a key and a value.
+    KeyValue { key: String, value: String },
+    /// Tag with no value.
+    Flag(String),
+}
+
+impl Tag {
+    /// Get the key of the tag, or the name of the flag if it has no value.
+    pub fn key(&self) -> &str {
+        match self {
+            Tag::KeyValue { key, .. } => key,
+            Tag::Flag(name) => name,
+        }
+    }
+
+    /// Get the value of the tag, if it has one.
+    pub fn value(&self) -> Option<&str> {
+        match self {
+            Tag::KeyValue { value, .. } => Some(value),
+            Tag::Flag(..) => None,
+        }
+    }
+}
+
+impl From<&str> for Tag {
+    fn from(tag: &str) -> Self {
+        match tag.find(&[':', '='][..]) {
+            Some(i) if !tag[..i].trim().is_empty() => Tag::KeyValue {
+                key: tag[..i].trim().to_string(),
+                value: tag[i + 1..].trim().to_string(),
+            },
+            _ => Tag::Flag(tag.trim().to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Tag::KeyValue { key, value } => write!(f, "{}: {}", key, value),
+            Tag::Flag(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Parse a set of tag strings into `Tag`s.
+fn parse_tags(tags: &[String]) -> Vec<Tag> {
+    tags.iter().map(|tag| Tag::from(tag.as_str())).collect()
+}
+
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde_support", derive(Deserialize, Serialize))]
+#[non_exhaustive]
 /// Result from following a `Story`.
 ///
+/// More variants may be added for new ways that a story can stop, so matches must have
+/// a wildcard arm.
+///
 /// # Examples
 /// ```
 /// # use inkling::{read_story_from_string, Prompt};
@@ -63,6 +255,8 @@ pub struct Choice {
 ///         }
 ///     },
 ///     Prompt::Done => { /* the story reached its end */ },
+///     Prompt::Breakpoint(..) => { /* only returned if breakpoints are set */ },
+///     _ => (),
 /// }
 /// ```
 pub enum Prompt {
@@ -70,6 +264,12 @@ pub enum Prompt {
     Done,
     /// A choice was encountered.
     Choice(Vec<Choice>),
+    /// The story paused before following a line, at a breakpoint or after a step.
+    ///
+    /// Call [`resume`][crate::story::Story::resume()] to continue from the line. Only
+    /// returned when [breakpoints][crate::story::Story::add_breakpoint()] are set or
+    /// the story is [stepped][crate::story::Story::step()] through.
+    Breakpoint(Pause),
 }
 
 impl Prompt {
@@ -151,11 +351,27 @@ impl Prompt {
 ///     }
 /// );
 /// ```
+///
+/// Locations are displayed as they are parsed, like addresses are written in `Ink`:
+/// ```
+/// # use inkling::Location;
+/// let location = Location::with_stitch("24th_island_sequence", "pyramids");
+/// assert_eq!(&location.to_string(), "24th_island_sequence.pyramids");
+/// ```
 pub struct Location {
     pub knot: String,
     pub stitch: Option<String>,
 }
 
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.stitch {
+            Some(stitch) => write!(f, "{}.{}", self.knot, stitch),
+            None => write!(f, "{}", self.knot),
+        }
+    }
+}
+
 impl From<&str> for Location {
     fn from(address: &str) -> Self {
         if let Some(i) = address.find('.') {
@@ -217,6 +433,17 @@ impl Location {
     }
 }
 
+#[derive(Clone, Debug, Default, PartialEq)]
+/// Options for reading a story with
+/// [`read_story_from_string_with_options`][crate::story::read_story_from_string_with_options()].
+pub struct ReadOptions {
+    /// Seed for the random number generator of the story.
+    ///
+    /// If not set, a seed is drawn from system entropy. Has no effect unless the `random`
+    /// feature is enabled.
+    pub seed: Option<u64>,
+}
+
 /// Convenience type to indicate when a buffer of `Line` objects is being manipulated.
 pub type LineBuffer = Vec<Line>;
 
@@ -232,6 +459,11 @@ pub struct VariableInfo {
     pub is_const: bool,
     /// Variable data.
     pub variable: Variable,
+    /// Value that the variable is declared with in the script.
+    ///
+    /// Not set for variables in stories which were saved before it was recorded.
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub declared: Option<Variable>,
     /// Information about the origin of the variable in the story file or text.
     pub meta_data: MetaData,
 }
@@ -250,11 +482,20 @@ impl VariableInfo {
         }
     }
 
+    /// Get the value that the variable is declared with, or its current value if that
+    /// is not known.
+    pub fn get_declared(&self) -> &Variable {
+        self.declared.as_ref().unwrap_or(&self.variable)
+    }
+
     #[cfg(test)]
     pub fn new<T: Into<Variable>>(variable: T, line_index: usize) -> Self {
+        let variable = variable.into();
+
         VariableInfo {
             is_const: false,
-            variable: variable.into(),
+            declared: Some(variable.clone()),
+            variable,
             meta_data: line_index.into(),
         }
     }
@@ -289,6 +530,79 @@ mod tests {
         assert_eq!(format!("{:?}", err), format!("{:?}", expected_err));
     }
 
+    fn mock_choices(choices: &[(&str, &str)]) -> Vec<Choice> {
+        choices
+            .iter()
+            .enumerate()
+            .map(|(index, (text, id))| Choice {
+                text: text.to_string(),
+                tags: Vec::new(),
+                id: id.to_string(),
+                index,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn choice_refs_find_choices_by_index_text_or_id() {
+        let choices = mock_choices(&[("Left", "knot:left"), ("Right", "knot:right")]);
+
+        assert_eq!(ChoiceRef::Index(1).find_in(&choices), Some(1));
+        assert_eq!(ChoiceRef::from("Left").find_in(&choices), Some(0));
+        assert_eq!(
+            ChoiceRef::Id("knot:right".to_string()).find_in(&choices),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn choice_refs_not_in_set_are_not_found() {
+        let choices = mock_choices(&[("Left", "knot:left"), ("Right", "knot:right")]);
+
+        assert_eq!(ChoiceRef::Index(2).find_in(&choices), None);
+        assert_eq!(ChoiceRef::from("Up").find_in(&choices), None);
+        assert_eq!(ChoiceRef::Id("knot:up".to_string()).find_in(&choices), None);
+    }
+
+    #[test]
+    fn tags_are_split_at_first_colon_or_equal_sign() {
+        assert_eq!(
+            Tag::from("sound: door.ogg=loud"),
+            Tag::KeyValue {
+                key: "sound".to_string(),
+                value: "door.ogg=loud".to_string()
+            }
+        );
+
+        assert_eq!(
+            Tag::from(" mood = angry "),
+            Tag::KeyValue {
+                key: "mood".to_string(),
+                value: "angry".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn tags_without_separator_or_key_are_flags() {
+        assert_eq!(
+            Tag::from("dark, quiet"),
+            Tag::Flag("dark, quiet".to_string())
+        );
+        assert_eq!(Tag::from(": no key"), Tag::Flag(": no key".to_string()));
+    }
+
+    #[test]
+    fn tags_with_empty_values_keep_their_key() {
+        assert_eq!(
+            Tag::from("speaker:"),
+            Tag::KeyValue {
+                key: "speaker".to_string(),
+                value: "".to_string()
+            }
+        );
+    }
+
     #[test]
     fn location_from_string_sets_knot_if_no_periods_are_involved() {
         assert_eq!(
@@ -1,6 +1,9 @@
 //! Utilities for story content.
 
-use crate::story::types::LineBuffer;
+use crate::{
+    line::{Content, LineChunk},
+    story::types::LineBuffer,
+};
 
 /// Read all text from lines in a buffer into a single string and return it.
 ///
@@ -30,6 +33,27 @@ pub fn copy_lines_into_string(line_buffer: &LineBuffer) -> String {
         .join("")
 }
 
+/// Get the text of a chunk with whitespace collapsed, without alternatives or conditional
+/// content.
+pub(crate) fn get_chunk_text(chunk: &LineChunk) -> String {
+    fn collect_text(chunk: &LineChunk, buffer: &mut String) {
+        for item in &chunk.items {
+            match item {
+                Content::Text(text) => buffer.push_str(text),
+                Content::Nested(nested) if nested.condition.is_none() => {
+                    collect_text(nested, buffer)
+                }
+                _ => (),
+            }
+        }
+    }
+
+    let mut buffer = String::new();
+    collect_text(chunk, &mut buffer);
+
+    buffer.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
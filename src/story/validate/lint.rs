@@ -0,0 +1,643 @@
+//! Lint pass for content which is valid but likely a mistake.
+//!
+//! After a story has been validated its content is walked through once more to find
+//! knots and stitches that are never diverted to, global variables that are never read,
+//! conditions which always evaluate to the same value and sets of choices which may
+//! run out. These are not errors, so they are added as warnings to the log.
+//!
+//! Knots and stitches which are only reached by moving to them from outside of the story
+//! can be marked as entry points with a `// lint: entry` comment, either on the line
+//! of their name or the line above it. These are not warned about if never diverted to.
+
+use crate::{
+    consts::{LINE_COMMENT_MARKER, LINT_ENTRY_MARKER, ROOT_KNOT_NAME},
+    error::utils::MetaData,
+    follow::FollowData,
+    knot::{Address, AddressKind, KnotSet},
+    line::{
+        condition::AndOr, expression::Operand, Condition, ConditionItem, ConditionKind, Content,
+        Expression, InternalLine, LineChunk, StoryCondition, Variable,
+    },
+    log::{Logger, Warning},
+    node::{Branch, NodeItem},
+    process::check_condition,
+};
+
+use std::collections::{HashMap, HashSet};
+
+/// Knot and stitch names of a location in the story.
+type Location = (String, String);
+
+/// Content collected while walking through a story.
+#[derive(Default)]
+struct LintData {
+    /// Locations that every stitch diverts to.
+    diverts: HashMap<Location, HashSet<Location>>,
+    /// Locations which are stored as divert variables.
+    divert_variables: HashSet<Location>,
+    /// Indices of lines with a comment which marks an entry point.
+    entry_lines: HashSet<u32>,
+    /// Names of global variables which are read.
+    read_variables: HashSet<String>,
+    /// Stitches with sets of choices in which every choice is non-sticky with no fallback,
+    /// along with the origin of the first choice in each set.
+    exhaustible_choice_sets: Vec<(Location, MetaData)>,
+    /// Found warnings.
+    warnings: Vec<(Warning, MetaData)>,
+}
+
+/// Find content in a story which is likely a mistake and add warnings about it to the log.
+///
+/// The story must have been validated, since only validated addresses are inspected.
+/// Its content is read for comments which mark entry points. The warnings are added
+/// after any which are already in the log, in the order of their lines.
+pub fn lint_story_content(knots: &KnotSet, data: &FollowData, content: &str, log: &mut Logger) {
+    let mut lint_data = LintData {
+        entry_lines: get_entry_lines(content),
+        ..LintData::default()
+    };
+
+    for (knot_name, knot) in knots {
+        for (stitch_name, stitch) in &knot.stitches {
+            let location = (knot_name.clone(), stitch_name.clone());
+
+            lint_data.diverts.entry(location.clone()).or_default();
+            lint_node_items(&stitch.root.items, &location, data, &mut lint_data);
+        }
+    }
+
+    for info in data.variables.values() {
+        lint_variable(&info.variable, &mut lint_data);
+    }
+
+    add_unreachable_knot_and_stitch_warnings(knots, &mut lint_data);
+    add_unused_variable_warnings(data, &mut lint_data);
+    add_exhaustible_choice_set_warnings(&mut lint_data);
+
+    lint_data
+        .warnings
+        .sort_by_key(|(_, meta_data)| meta_data.line());
+
+    for (warning, meta_data) in lint_data.warnings {
+        log.add_warning(warning, &meta_data);
+    }
+}
+
+/// Get the indices of lines with a comment which marks an entry point.
+fn get_entry_lines(content: &str) -> HashSet<u32> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| match line.find(LINE_COMMENT_MARKER) {
+            Some(i) => line[i + LINE_COMMENT_MARKER.len()..].trim() == LINT_ENTRY_MARKER,
+            None => false,
+        })
+        .map(|(i, _)| i as u32)
+        .collect()
+}
+
+/// Check whether the knot or stitch at the line is marked as an entry point.
+fn is_entry_point(meta_data: &MetaData, entry_lines: &HashSet<u32>) -> bool {
+    let line_index = meta_data.line_index;
+
+    entry_lines.contains(&line_index)
+        || line_index
+            .checked_sub(1)
+            .map(|i| entry_lines.contains(&i))
+            .unwrap_or(false)
+}
+
+/// Warn about knots and stitches which are never diverted to.
+///
+/// Stitches are only checked in knots which are diverted to, to not warn about every
+/// stitch in an unreachable knot. Knots and stitches which are marked as entry points
+/// are always reachable.
+fn add_unreachable_knot_and_stitch_warnings(knots: &KnotSet, lint_data: &mut LintData) {
+    let targets = lint_data
+        .diverts
+        .values()
+        .flatten()
+        .chain(lint_data.divert_variables.iter())
+        .collect::<HashSet<_>>();
+
+    for (knot_name, knot) in knots {
+        let is_reachable = knot_name == ROOT_KNOT_NAME
+            || is_entry_point(&knot.meta_data, &lint_data.entry_lines)
+            || targets.iter().any(|(knot, _)| knot == knot_name);
+
+        if !is_reachable {
+            lint_data.warnings.push((
+                Warning::UnreachableKnot {
+                    name: knot_name.clone(),
+                },
+                knot.meta_data.clone(),
+            ));
+
+            continue;
+        }
+
+        for (stitch_name, stitch) in &knot.stitches {
+            let location = (knot_name.clone(), stitch_name.clone());
+
+            if stitch_name != &knot.default_stitch
+                && !is_entry_point(&stitch.meta_data, &lint_data.entry_lines)
+                && !targets.contains(&location)
+            {
+                lint_data.warnings.push((
+                    Warning::UnreachableStitch {
+                        knot: knot_name.clone(),
+                        stitch: stitch_name.clone(),
+                    },
+                    stitch.meta_data.clone(),
+                ));
+            }
+        }
+    }
+}
+
+/// Warn about global variables which are never read in the story.
+fn add_unused_variable_warnings(data: &FollowData, lint_data: &mut LintData) {
+    for (name, info) in &data.variables {
+        if !lint_data.read_variables.contains(name) {
+            lint_data.warnings.push((
+                Warning::UnusedVariable { name: name.clone() },
+                info.meta_data.clone(),
+            ));
+        }
+    }
+}
+
+/// Warn about sets of choices which can run out because their stitch can be revisited.
+///
+/// A stitch can be revisited if it can divert back to itself through any number of diverts.
+fn add_exhaustible_choice_set_warnings(lint_data: &mut LintData) {
+    for (location, meta_data) in &lint_data.exhaustible_choice_sets {
+        if can_return_to_location(location, &lint_data.diverts) {
+            lint_data
+                .warnings
+                .push((Warning::ChoicesCanRunOut, meta_data.clone()));
+        }
+    }
+}
+
+/// Check whether a location can be returned to by following diverts from it.
+fn can_return_to_location(
+    location: &Location,
+    diverts: &HashMap<Location, HashSet<Location>>,
+) -> bool {
+    let mut visited = HashSet::new();
+    let mut queue = vec![location];
+
+    while let Some(current) = queue.pop() {
+        for target in diverts.get(current).into_iter().flatten() {
+            if target == location {
+                return true;
+            }
+
+            if visited.insert(target) {
+                queue.push(target);
+            }
+        }
+    }
+
+    false
+}
+
+fn lint_node_items(
+    items: &[NodeItem],
+    location: &Location,
+    data: &FollowData,
+    lint_data: &mut LintData,
+) {
+    for item in items {
+        match item {
+            NodeItem::Line(line) => lint_line(line, location, data, lint_data),
+            NodeItem::BranchingPoint(branches) => {
+                lint_branches(branches, location, data, lint_data)
+            }
+        }
+    }
+}
+
+fn lint_branches(
+    branches: &[Branch],
+    location: &Location,
+    data: &FollowData,
+    lint_data: &mut LintData,
+) {
+    let is_exhaustible = branches
+        .iter()
+        .all(|branch| !branch.choice.is_sticky && !branch.choice.is_fallback);
+
+    if let (true, Some(branch)) = (is_exhaustible, branches.first()) {
+        lint_data
+            .exhaustible_choice_sets
+            .push((location.clone(), branch.choice.meta_data.clone()));
+    }
+
+    for branch in branches {
+        let choice = &branch.choice;
+
+        // The display text is the first line of the branch items and is linted with them
+        let selection_text = choice.selection_text.lock().unwrap();
+        lint_line(&selection_text, location, data, lint_data);
+
+        if let Some(condition) = &choice.condition {
+            lint_condition(condition, &choice.meta_data, data, lint_data);
+        }
+
+        lint_node_items(&branch.items, location, data, lint_data);
+    }
+}
+
+fn lint_line(
+    line: &InternalLine,
+    location: &Location,
+    data: &FollowData,
+    lint_data: &mut LintData,
+) {
+    lint_chunk(&line.chunk, location, &line.meta_data, data, lint_data);
+
+    for tag in &line.tags {
+        lint_chunk(tag, location, &line.meta_data, data, lint_data);
+    }
+}
+
+fn lint_chunk(
+    chunk: &LineChunk,
+    location: &Location,
+    meta_data: &MetaData,
+    data: &FollowData,
+    lint_data: &mut LintData,
+) {
+    if let Some(condition) = &chunk.condition {
+        lint_condition(condition, meta_data, data, lint_data);
+    }
+
+    for item in chunk.items.iter().chain(chunk.else_items.iter()) {
+        match item {
+            Content::Alternative(alternative) => {
+                for chunk in &alternative.items {
+                    lint_chunk(chunk, location, meta_data, data, lint_data);
+                }
+            }
+            Content::Divert(address) => lint_divert(address, location, lint_data),
+            Content::Expression(expression) => {
+                lint_expression(expression, lint_data);
+            }
+            Content::Nested(chunk) => lint_chunk(chunk, location, meta_data, data, lint_data),
+            Content::Empty | Content::Text(..) => (),
+        }
+    }
+}
+
+/// Record the target of a divert from a location.
+///
+/// Diverts to divert variables read the variable.
+fn lint_divert(address: &Address, location: &Location, lint_data: &mut LintData) {
+    match address {
+        Address::Validated(AddressKind::Location { knot, stitch }) => {
+            lint_data
+                .diverts
+                .entry(location.clone())
+                .or_default()
+                .insert((knot.clone(), stitch.clone()));
+        }
+        Address::Validated(AddressKind::GlobalVariable { name }) => {
+            lint_data.read_variables.insert(name.clone());
+        }
+        Address::End | Address::Raw(..) => (),
+    }
+}
+
+/// Warn about conditions which do not depend on the story state.
+fn lint_condition(
+    condition: &Condition,
+    meta_data: &MetaData,
+    data: &FollowData,
+    lint_data: &mut LintData,
+) {
+    if !lint_condition_content(condition, lint_data) {
+        if let Ok(value) = check_condition(condition, data) {
+            lint_data
+                .warnings
+                .push((Warning::ConstantCondition { value }, meta_data.clone()));
+        }
+    }
+}
+
+/// Lint the variables of a condition and return whether it contains any address.
+fn lint_condition_content(condition: &Condition, lint_data: &mut LintData) -> bool {
+    let items =
+        std::iter::once(&condition.root).chain(condition.items.iter().map(|item| match item {
+            AndOr::And(item) | AndOr::Or(item) => item,
+        }));
+
+    let mut has_address = false;
+
+    for item in items {
+        has_address |= lint_condition_item(item, lint_data);
+    }
+
+    has_address
+}
+
+fn lint_condition_item(item: &ConditionItem, lint_data: &mut LintData) -> bool {
+    match &item.kind {
+        ConditionKind::True | ConditionKind::False => false,
+        ConditionKind::Nested(condition) => lint_condition_content(condition, lint_data),
+        ConditionKind::Single(StoryCondition::Comparison {
+            lhs_variable,
+            rhs_variable,
+            ..
+        }) => {
+            let lhs = lint_expression(lhs_variable, lint_data);
+            let rhs = lint_expression(rhs_variable, lint_data);
+
+            lhs || rhs
+        }
+        ConditionKind::Single(StoryCondition::IsTrueLike { variable }) => {
+            lint_variable(variable, lint_data)
+        }
+    }
+}
+
+/// Lint the variables of an expression and return whether it contains any address.
+fn lint_expression(expression: &Expression, lint_data: &mut LintData) -> bool {
+    let mut has_address = lint_operand(&expression.head, lint_data);
+
+    for (_, operand) in &expression.tail {
+        has_address |= lint_operand(operand, lint_data);
+    }
+
+    has_address
+}
+
+fn lint_operand(operand: &Operand, lint_data: &mut LintData) -> bool {
+    match operand {
+        Operand::Nested(expression) => lint_expression(expression, lint_data),
+        Operand::Variable(variable) => lint_variable(variable, lint_data),
+    }
+}
+
+/// Record read variables and divert variable targets, and return whether it is an address.
+///
+/// Addresses to locations are not diverts but read the number of visits to them.
+fn lint_variable(variable: &Variable, lint_data: &mut LintData) -> bool {
+    match variable {
+        Variable::Address(address) => {
+            if let Address::Validated(AddressKind::GlobalVariable { name }) = address {
+                lint_data.read_variables.insert(name.clone());
+            }
+
+            true
+        }
+        Variable::Divert(Address::Validated(AddressKind::Location { knot, stitch })) => {
+            lint_data
+                .divert_variables
+                .insert((knot.clone(), stitch.clone()));
+
+            false
+        }
+        // Global variables are not validated, so their diverts are kept as raw addresses.
+        // Their stitch is left empty if it is not given, since default stitches are always
+        // reachable along with their knot.
+        Variable::Divert(Address::Raw(raw)) => {
+            let mut parts = raw.splitn(2, '.');
+            let knot = parts.next().unwrap_or_default().to_string();
+            let stitch = parts.next().unwrap_or_default().to_string();
+
+            lint_data.divert_variables.insert((knot, stitch));
+
+            false
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        follow::FollowDataBuilder,
+        story::{parse::read_story_content_from_string, read_story_from_string},
+    };
+
+    fn get_warnings(content: &str) -> Vec<(Warning, u32)> {
+        let story = read_story_from_string(content).unwrap();
+
+        story
+            .log
+            .warnings
+            .iter()
+            .filter_map(|message| match &message.message {
+                crate::log::MessageKind::Warning(warning) => {
+                    Some((warning.clone(), message.meta_data.line()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn knots_and_stitches_which_are_never_diverted_to_yield_warnings() {
+        let content = "\
+-> tavern
+
+== tavern
+Here it is.
+-> tavern.cellar
+
+= cellar
+Dark.
+-> END
+
+= attic
+Dusty.
+-> END
+
+== harbour
+Wet.
+-> END
+";
+
+        assert_eq!(
+            get_warnings(content),
+            &[
+                (
+                    Warning::UnreachableStitch {
+                        knot: "tavern".to_string(),
+                        stitch: "attic".to_string()
+                    },
+                    11
+                ),
+                (
+                    Warning::UnreachableKnot {
+                        name: "harbour".to_string()
+                    },
+                    15
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn knots_and_stitches_marked_as_entry_points_yield_no_warnings() {
+        let content = "\
+Hello.
+-> END
+
+// lint: entry
+== shop
+Welcome.
+-> END
+
+== harbour // lint: entry
+Wet.
+-> END
+
+= pier
+Wooden.
+-> END
+
+// lint: entry
+= boats
+Many.
+-> END
+";
+
+        assert_eq!(
+            get_warnings(content),
+            &[(
+                Warning::UnreachableStitch {
+                    knot: "harbour".to_string(),
+                    stitch: "pier".to_string()
+                },
+                13
+            )]
+        );
+    }
+
+    #[test]
+    fn warnings_already_in_log_keep_their_order() {
+        let content = "\
+VAR coins = 0
+-> END
+
+== harbour
+Wet.
+-> END
+";
+
+        let mut log = Logger::default();
+        let (knots, variables, _) = read_story_content_from_string(content, &mut log).unwrap();
+        let data = FollowDataBuilder::new().with_variables(variables).build();
+
+        log.add_warning(Warning::ShuffleSequenceNoRandom, &MetaData::from(5));
+        lint_story_content(&knots, &data, content, &mut log);
+
+        let lines = log
+            .warnings
+            .iter()
+            .map(|message| message.meta_data.line())
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines, &[6, 1, 4]);
+    }
+
+    #[test]
+    fn divert_variables_make_their_target_reachable() {
+        let content = "\
+VAR destination = -> harbour
+{destination}
+
+== harbour
+Wet.
+-> END
+";
+
+        assert!(get_warnings(content).is_empty());
+    }
+
+    #[test]
+    fn variables_which_are_never_read_yield_warnings() {
+        let content = "\
+VAR coins = 0
+VAR name = \"Rose\"
+VAR is_rich = false
+Hello, {name}!
+*   {is_rich} Buy the house.
+";
+
+        assert_eq!(
+            get_warnings(content),
+            &[(
+                Warning::UnusedVariable {
+                    name: "coins".to_string()
+                },
+                1
+            )]
+        );
+    }
+
+    #[test]
+    fn conditions_without_variables_or_addresses_yield_warnings_with_value() {
+        let content = "\
+VAR coins = 0
+{1 > 2: Never.}
+{coins > 2: Sometimes.}
+*   {true} Always.
+";
+
+        assert_eq!(
+            get_warnings(content),
+            &[
+                (Warning::ConstantCondition { value: false }, 2),
+                (Warning::ConstantCondition { value: true }, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn non_sticky_choice_sets_without_fallback_yield_warnings_only_if_revisited() {
+        let content = "\
+-> hub
+
+== hub
+*   Go left. -> hub
+*   Go right. -> END
+
+== once
+*   Go left. -> END
+*   Go right. -> END
+";
+
+        assert_eq!(
+            get_warnings(content),
+            &[
+                (Warning::ChoicesCanRunOut, 4),
+                (
+                    Warning::UnreachableKnot {
+                        name: "once".to_string()
+                    },
+                    7
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn revisited_choice_sets_with_sticky_or_fallback_choices_yield_no_warnings() {
+        let content = "\
+-> hub
+
+== hub
++   Go left. -> hub
+*   Go right. -> hub
+*   -> END
+";
+
+        assert!(get_warnings(content).is_empty());
+    }
+}
@@ -1,6 +1,8 @@
 //! Validate story and variable names, addresses, expressions, and conditions.
 
+mod lint;
 pub(self) mod namespace;
 pub(self) mod validate;
 
+pub use lint::lint_story_content;
 pub use validate::{validate_story_content, KnotValidationInfo, ValidateContent, ValidationData};
@@ -5,7 +5,10 @@ use crate::{
     follow::FollowData,
     knot::{get_empty_knot_counts, Address, AddressKind, KnotSet},
     log::Logger,
-    story::{rng::StoryRng, types::VariableSet, validate::namespace::validate_story_name_spaces},
+    story::{
+        debug::Debugger, rng::StoryRng, trace::TraceHook, types::VariableSet,
+        validate::namespace::validate_story_name_spaces,
+    },
 };
 
 use std::collections::HashMap;
@@ -72,6 +75,12 @@ impl ValidationData {
             knot_visit_counts: get_empty_knot_counts(knots),
             variables: variables.clone(),
             rng: StoryRng::default(),
+            coverage: None,
+            divert_limit: None,
+            reached_end: false,
+            tracer: TraceHook::default(),
+            messages: Vec::new(),
+            debugger: Debugger::default(),
         };
 
         ValidationData {
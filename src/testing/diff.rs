@@ -0,0 +1,211 @@
+//! Unified diffs of expected and actual output.
+
+use std::fmt::Write;
+
+/// Number of unchanged lines which are shown around every change.
+const NUM_CONTEXT_LINES: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Edit which turns the expected lines into the actual lines.
+enum Edit {
+    /// Line which is in both, at their index in the expected and actual lines.
+    Keep(usize, usize),
+    /// Line which is only in the expected lines.
+    Remove(usize),
+    /// Line which is only in the actual lines.
+    Add(usize),
+}
+
+/// Write the difference between two sets of lines as a unified diff.
+///
+/// Returns `None` if the lines are equal.
+pub fn unified_diff(
+    expected: &[String],
+    actual: &[String],
+    expected_name: &str,
+    actual_name: &str,
+) -> Option<String> {
+    if expected == actual {
+        return None;
+    }
+
+    let edits = get_edits(expected, actual);
+
+    let mut buffer = String::new();
+    writeln!(buffer, "--- {}", expected_name).unwrap();
+    writeln!(buffer, "+++ {}", actual_name).unwrap();
+
+    for hunk in get_hunks(&edits) {
+        let (expected_start, actual_start) = get_start(hunk);
+        let num_expected = hunk
+            .iter()
+            .filter(|edit| !matches!(edit, Edit::Add(..)))
+            .count();
+        let num_actual = hunk
+            .iter()
+            .filter(|edit| !matches!(edit, Edit::Remove(..)))
+            .count();
+
+        writeln!(
+            buffer,
+            "@@ -{},{} +{},{} @@",
+            expected_start, num_expected, actual_start, num_actual
+        )
+        .unwrap();
+
+        for edit in hunk {
+            match *edit {
+                Edit::Keep(i, _) => writeln!(buffer, " {}", expected[i]).unwrap(),
+                Edit::Remove(i) => writeln!(buffer, "-{}", expected[i]).unwrap(),
+                Edit::Add(j) => writeln!(buffer, "+{}", actual[j]).unwrap(),
+            }
+        }
+    }
+
+    Some(buffer)
+}
+
+/// Get the shortest set of edits from the longest common subsequence of the lines.
+fn get_edits(expected: &[String], actual: &[String]) -> Vec<Edit> {
+    let (n, m) = (expected.len(), actual.len());
+
+    let mut lengths = vec![vec![0; m + 1]; n + 1];
+
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = if expected[i] == actual[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < n || j < m {
+        if i < n && j < m && expected[i] == actual[j] {
+            edits.push(Edit::Keep(i, j));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lengths[i + 1][j] >= lengths[i][j + 1]) {
+            edits.push(Edit::Remove(i));
+            i += 1;
+        } else {
+            edits.push(Edit::Add(j));
+            j += 1;
+        }
+    }
+
+    edits
+}
+
+/// Split edits into groups of changes with surrounding unchanged lines.
+///
+/// Changes which are close enough for their context to overlap are put in the same group.
+fn get_hunks(edits: &[Edit]) -> Vec<&[Edit]> {
+    let changes = edits
+        .iter()
+        .enumerate()
+        .filter(|(_, edit)| !matches!(edit, Edit::Keep(..)))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+
+    let mut hunks = Vec::new();
+    let mut iter = changes.iter().peekable();
+
+    while let Some(&first) = iter.next() {
+        let mut last = first;
+
+        while let Some(&&next) = iter.peek() {
+            if next - last > 2 * NUM_CONTEXT_LINES {
+                break;
+            }
+
+            last = next;
+            iter.next();
+        }
+
+        let start = first.saturating_sub(NUM_CONTEXT_LINES);
+        let end = (last + NUM_CONTEXT_LINES + 1).min(edits.len());
+
+        hunks.push(&edits[start..end]);
+    }
+
+    hunks
+}
+
+/// Get the line numbers, starting from 1, where a group of edits starts.
+///
+/// Since groups include the surrounding unchanged lines, a group only lacks lines from
+/// one side if that side is empty, in which case it starts at line 0.
+fn get_start(hunk: &[Edit]) -> (usize, usize) {
+    let expected = hunk.iter().find_map(|edit| match *edit {
+        Edit::Keep(i, _) | Edit::Remove(i) => Some(i + 1),
+        Edit::Add(..) => None,
+    });
+    let actual = hunk.iter().find_map(|edit| match *edit {
+        Edit::Keep(_, j) | Edit::Add(j) => Some(j + 1),
+        Edit::Remove(..) => None,
+    });
+
+    (expected.unwrap_or(0), actual.unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn equal_lines_have_no_diff() {
+        assert_eq!(unified_diff(&lines("a\nb"), &lines("a\nb"), "a", "b"), None);
+    }
+
+    #[test]
+    fn changed_lines_are_shown_with_three_lines_of_context() {
+        let expected = lines("1\n2\n3\n4\n5\n6\n7\n8");
+        let actual = lines("1\n2\n3\n4\nfive\n6\n7\n8");
+
+        assert_eq!(
+            unified_diff(&expected, &actual, "expected", "actual").unwrap(),
+            "\
+--- expected
++++ actual
+@@ -2,7 +2,7 @@
+ 2
+ 3
+ 4
+-5
++five
+ 6
+ 7
+ 8
+"
+        );
+    }
+
+    #[test]
+    fn changes_far_apart_are_split_into_separate_hunks() {
+        let expected = lines("a\n1\n2\n3\n4\n5\n6\n7\nb");
+        let actual = lines("A\n1\n2\n3\n4\n5\n6\n7\nB");
+
+        let diff = unified_diff(&expected, &actual, "expected", "actual").unwrap();
+
+        assert!(diff.contains("@@ -1,4 +1,4 @@\n-a\n+A\n 1\n 2\n 3\n"));
+        assert!(diff.contains("@@ -6,4 +6,4 @@\n 5\n 6\n 7\n-b\n+B\n"));
+    }
+
+    #[test]
+    fn added_and_removed_lines_at_the_end_are_shown() {
+        let diff = unified_diff(&lines("a\nb"), &lines("a"), "expected", "actual").unwrap();
+        assert!(diff.ends_with("@@ -1,2 +1,1 @@\n a\n-b\n"));
+
+        let diff = unified_diff(&[], &lines("a"), "expected", "actual").unwrap();
+        assert!(diff.ends_with("@@ -0,0 +1,1 @@\n+a\n"));
+    }
+}
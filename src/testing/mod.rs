@@ -0,0 +1,59 @@
+//! Scripted playthroughs of stories which are compared to expected transcripts.
+//!
+//! A test names the story to play and lists the lines that it is expected to print,
+//! the choices to make and the values that variables are expected to have:
+//!
+//! ```text
+//! // Buying fish at the market.
+//! STORY market.ink
+//! SEED 5
+//!
+//! You arrive at the market. # location: market
+//! > Buy fish
+//! You buy a fish.
+//! CHECK gold = 2
+//! -> END
+//! ```
+//!
+//! Choices are made by their number (starting from 1), id or text. Variables are checked
+//! after all the lines which the story prints before its next set of choices.
+//!
+//! Tests are read with [`read_test_spec`][crate::testing::read_test_spec()]. Reading
+//! the story that it names is left to the caller, after which the test is run with
+//! [`run_test`][crate::testing::run_test()]. This is what the `inkling test` command
+//! does for `.inktest` files.
+//!
+//! # Example
+//! ```
+//! # use inkling::{read_story_from_string, testing::{read_test_spec, run_test, TestOutcome}};
+//! let story_content = "\
+//! VAR gold = 3
+//! You arrive at the market.
+//! *   [Buy fish] You buy a fish.
+//!     -> END
+//! ";
+//!
+//! let test_content = "\
+//! STORY market.ink
+//! You arrive at the market.
+//! > Buy fish
+//! You buy a fish.
+//! CHECK gold = 3
+//! -> END
+//! ";
+//!
+//! let spec = read_test_spec(test_content).unwrap();
+//! let mut story = read_story_from_string(story_content).unwrap();
+//!
+//! match run_test(&mut story, &spec, "market.inktest").unwrap() {
+//!     TestOutcome::Passed => (),
+//!     TestOutcome::Failed(report) => panic!("{}", report),
+//! }
+//! ```
+
+mod diff;
+mod run;
+mod spec;
+
+pub use run::{find_choice, play_test, run_test, Playthrough, TestOutcome};
+pub use spec::{read_test_spec, TestSpec, TestStep};
@@ -0,0 +1,292 @@
+//! Playing stories by the steps of a test.
+
+use crate::{
+    error::InklingError,
+    story::{Choice, ChoiceRef, Line, Prompt, Story},
+    testing::{
+        diff::unified_diff,
+        spec::{TestSpec, TestStep},
+    },
+};
+
+#[derive(Clone, Debug, PartialEq)]
+/// Transcript of a story played by the steps of a test.
+pub struct Playthrough {
+    /// Lines printed by the story, with the choices made and variables checked.
+    pub transcript: Vec<String>,
+    /// Why the playthrough was stopped before the last step, if it was.
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Result of a test which could be run.
+pub enum TestOutcome {
+    /// The transcript matched the test.
+    Passed,
+    /// The transcript did not match the test, with a description of how.
+    Failed(String),
+}
+
+/// Play a story by the steps of a test and compare its transcript to the expected one.
+///
+/// The story should be read with the seed of the test, if it has one. If the transcript
+/// differs, the test fails with a unified diff from the expected transcript, which is
+/// named after the test, to the actual one.
+///
+/// # Errors
+/// Any error from following the story.
+pub fn run_test(
+    story: &mut Story,
+    spec: &TestSpec,
+    name: &str,
+) -> Result<TestOutcome, InklingError> {
+    let expected = spec.steps.iter().map(TestStep::render).collect::<Vec<_>>();
+
+    let playthrough = play_test(story, &spec.steps)?;
+    let diff = unified_diff(&expected, &playthrough.transcript, name, "actual");
+
+    match (playthrough.error, diff) {
+        (Some(error), diff) => Ok(TestOutcome::Failed(format!(
+            "{}\n{}",
+            error,
+            diff.unwrap_or_default()
+        ))),
+        (None, Some(diff)) => Ok(TestOutcome::Failed(diff)),
+        (None, None) => Ok(TestOutcome::Passed),
+    }
+}
+
+/// Play the story by the steps of a test and get its transcript.
+///
+/// The story is resumed whenever a step follows a choice, which adds the lines it
+/// prints until the next set of choices. If a choice cannot be made or a variable
+/// does not exist, the playthrough stops with the transcript up to that point.
+///
+/// # Errors
+/// Any error from following the story.
+pub fn play_test(story: &mut Story, steps: &[TestStep]) -> Result<Playthrough, InklingError> {
+    let mut transcript = Vec::new();
+    let mut prompt = None;
+
+    for step in steps {
+        let current = match prompt.take() {
+            Some(current) => current,
+            None => resume(story, &mut transcript)?,
+        };
+
+        match step {
+            TestStep::Line(..) => (),
+            TestStep::Check { name, .. } => match story.get_variable(name) {
+                Some(value) => {
+                    transcript.push(format!("CHECK {} = {}", name, value.to_ink_source()))
+                }
+                None => {
+                    let message = format!("variable '{}' does not exist in the story", name);
+                    return Ok(stopped(transcript, message));
+                }
+            },
+            TestStep::End => {
+                if let Prompt::Done = current {
+                    transcript.push(step.render());
+                }
+            }
+            TestStep::Choice(input) => match &current {
+                Prompt::Done => {
+                    transcript.push(TestStep::End.render());
+
+                    return Ok(Playthrough {
+                        transcript,
+                        error: None,
+                    });
+                }
+                Prompt::Choice(choices) => match find_choice(input, choices) {
+                    Some(index) => {
+                        transcript.push(step.render());
+                        story.make_choice(index)?;
+                        continue;
+                    }
+                    None => {
+                        let message = format!(
+                            "choice '{}' is not among the presented choices:\n{}",
+                            input,
+                            render_choices(choices)
+                        );
+                        return Ok(stopped(transcript, message));
+                    }
+                },
+                _ => unreachable!("no breakpoints are set in tests"),
+            },
+        }
+
+        prompt.replace(current);
+    }
+
+    if prompt.is_none() {
+        resume(story, &mut transcript)?;
+    }
+
+    Ok(Playthrough {
+        transcript,
+        error: None,
+    })
+}
+
+/// Find the index of a choice from its number, id or text.
+///
+/// Numbers start from 1, as the choices are presented to the user.
+pub fn find_choice(input: &str, choices: &[Choice]) -> Option<usize> {
+    match input.parse::<usize>() {
+        Ok(0) => None,
+        Ok(number) => ChoiceRef::Index(number - 1).find_in(choices),
+        Err(_) => ChoiceRef::Id(input.to_string())
+            .find_in(choices)
+            .or_else(|| ChoiceRef::Text(input.to_string()).find_in(choices)),
+    }
+}
+
+fn stopped(transcript: Vec<String>, error: String) -> Playthrough {
+    Playthrough {
+        transcript,
+        error: Some(error),
+    }
+}
+
+/// Resume the story and add the lines it prints to the transcript.
+fn resume(story: &mut Story, transcript: &mut Vec<String>) -> Result<Prompt, InklingError> {
+    let mut line_buffer = Vec::new();
+    let prompt = story.resume(&mut line_buffer)?;
+
+    transcript.extend(line_buffer.iter().filter_map(render_line));
+
+    Ok(prompt)
+}
+
+/// Write a line as it is written in tests: its trimmed text followed by its tags.
+///
+/// Lines without text or tags are skipped.
+fn render_line(line: &Line) -> Option<String> {
+    let mut rendered = line.text.trim().to_string();
+
+    for tag in &line.tags {
+        if !rendered.is_empty() {
+            rendered.push(' ');
+        }
+
+        rendered.push_str("# ");
+        rendered.push_str(tag);
+    }
+
+    if rendered.is_empty() {
+        None
+    } else {
+        Some(rendered)
+    }
+}
+
+fn render_choices(choices: &[Choice]) -> String {
+    choices
+        .iter()
+        .enumerate()
+        .map(|(i, choice)| format!("  {}. {}", i + 1, choice.text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{story::read_story_from_string, testing::spec::read_test_spec};
+
+    const STORY: &str = "\
+VAR gold = 3
+You arrive at the market. # location: market
+*   [Buy fish] You buy a fish. # purchase
+    -> END
+*   Leave
+    -> END
+";
+
+    fn play(content: &str) -> Playthrough {
+        let mut story = read_story_from_string(STORY).unwrap();
+        play_test(&mut story, &read_test_spec(content).unwrap().steps).unwrap()
+    }
+
+    fn get_choices(content: &str) -> Vec<Choice> {
+        let mut story = read_story_from_string(content).unwrap();
+
+        story
+            .resume(&mut Vec::new())
+            .unwrap()
+            .get_choices()
+            .unwrap()
+    }
+
+    #[test]
+    fn choices_are_found_by_number_from_one_then_id_then_text() {
+        let choices = get_choices("*   Go left\n*   $ROOT$:go_left\n");
+
+        assert_eq!(find_choice("1", &choices), Some(0));
+        assert_eq!(find_choice("2", &choices), Some(1));
+        assert_eq!(find_choice("$ROOT$:go_left", &choices), Some(0));
+        assert_eq!(find_choice("Go left", &choices), Some(0));
+
+        assert_eq!(find_choice("0", &choices), None);
+        assert_eq!(find_choice("3", &choices), None);
+        assert_eq!(find_choice("Go right", &choices), None);
+    }
+
+    #[test]
+    fn transcript_has_lines_with_tags_choices_and_checks_in_order() {
+        let content = "STORY market.ink\n> Buy fish\nCHECK gold = 3\n-> END";
+
+        assert_eq!(
+            play(content).transcript,
+            vec![
+                "You arrive at the market. # location: market".to_string(),
+                "> Buy fish".to_string(),
+                "You buy a fish. # purchase".to_string(),
+                "CHECK gold = 3".to_string(),
+                "-> END".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn lines_after_the_last_choice_are_added_to_the_transcript() {
+        let transcript = play("STORY market.ink\n> 2").transcript;
+        assert_eq!(transcript.last().unwrap(), "Leave");
+    }
+
+    #[test]
+    fn choices_which_are_not_presented_stop_the_playthrough_with_a_message() {
+        let playthrough = play("STORY market.ink\n> Buy bread\nCHECK gold = 3");
+        let error = playthrough.error.unwrap();
+
+        assert!(error.contains("'Buy bread'"));
+        assert!(error.contains("  1. Buy fish\n  2. Leave"));
+        assert_eq!(playthrough.transcript.len(), 1);
+    }
+
+    #[test]
+    fn choices_after_the_story_has_ended_end_the_transcript() {
+        let playthrough = play("STORY market.ink\n> 2\n> 1\nNever printed.");
+
+        assert_eq!(playthrough.transcript.last().unwrap(), "-> END");
+        assert!(playthrough.error.is_none());
+    }
+
+    #[test]
+    fn tests_with_other_transcripts_fail_with_a_diff() {
+        let mut story = read_story_from_string(STORY).unwrap();
+        let spec = read_test_spec("STORY market.ink\n> 2\nStay.").unwrap();
+
+        match run_test(&mut story, &spec, "market.inktest").unwrap() {
+            TestOutcome::Failed(diff) => {
+                assert!(diff.starts_with("--- market.inktest\n+++ actual\n"));
+                assert!(diff.contains("-Stay.\n+Leave\n"));
+            }
+            TestOutcome::Passed => panic!("expected the test to fail"),
+        }
+    }
+}
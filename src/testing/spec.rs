@@ -0,0 +1,174 @@
+//! Reading scripted tests.
+
+use crate::error::TestSpecError;
+
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, PartialEq)]
+/// Test for a story.
+pub struct TestSpec {
+    /// Path to the story. The `inkling test` command reads it relative to the test file.
+    pub story: PathBuf,
+    /// Seed for the random number generator, to read the story with.
+    pub seed: Option<u64>,
+    /// Expected lines, choices and checks, in order.
+    pub steps: Vec<TestStep>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Single line of a test.
+pub enum TestStep {
+    /// Line of text that the story is expected to print, with its tags.
+    Line(String),
+    /// Choice to make from the presented choices.
+    Choice(String),
+    /// Expected value of a variable, written as `Ink` source.
+    Check {
+        /// Name of variable.
+        name: String,
+        /// Expected value of variable.
+        value: String,
+    },
+    /// The story is expected to end.
+    End,
+}
+
+impl TestStep {
+    /// Get the line of the transcript which the step expects.
+    pub fn render(&self) -> String {
+        match self {
+            TestStep::Line(text) => text.clone(),
+            TestStep::Choice(input) => format!("> {}", input),
+            TestStep::Check { name, value } => format!("CHECK {} = {}", name, value),
+            TestStep::End => "-> END".to_string(),
+        }
+    }
+}
+
+/// Read a test from the content of its file.
+///
+/// # Errors
+/// *   [`TestSpecError`][crate::error::TestSpecError]: if a line of the test is invalid
+///     or no story is given.
+pub fn read_test_spec(content: &str) -> Result<TestSpec, TestSpecError> {
+    let mut story = None;
+    let mut seed = None;
+    let mut steps = Vec::new();
+
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+
+        let error = |message: String| TestSpecError {
+            line: i + 1,
+            message,
+        };
+
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        } else if let Some(value) = strip_keyword(line, "STORY") {
+            if story.replace(PathBuf::from(value)).is_some() {
+                return Err(error("the story is given more than once".to_string()));
+            }
+        } else if let Some(value) = strip_keyword(line, "SEED") {
+            let value = value.parse::<u64>().map_err(|_| {
+                error(format!(
+                    "invalid seed '{}': must be a positive integer",
+                    value
+                ))
+            })?;
+
+            seed.replace(value);
+        } else if let Some(check) = strip_keyword(line, "CHECK") {
+            let mut parts = check.splitn(2, '=');
+
+            match (parts.next().map(str::trim), parts.next().map(str::trim)) {
+                (Some(name), Some(value)) if !name.is_empty() && !value.is_empty() => {
+                    steps.push(TestStep::Check {
+                        name: name.to_string(),
+                        value: value.to_string(),
+                    })
+                }
+                _ => {
+                    return Err(error(format!(
+                        "invalid check '{}': must be written as `CHECK name = value`",
+                        check
+                    )))
+                }
+            }
+        } else if let Some(input) = line.strip_prefix('>') {
+            let input = input.trim();
+
+            if input.is_empty() {
+                return Err(error("no choice given after '>'".to_string()));
+            }
+
+            steps.push(TestStep::Choice(input.to_string()));
+        } else if line.split_whitespace().collect::<Vec<_>>() == ["->", "END"] {
+            steps.push(TestStep::End);
+        } else {
+            steps.push(TestStep::Line(line.to_string()));
+        }
+    }
+
+    let story = story.ok_or_else(|| TestSpecError {
+        line: 1,
+        message: "no story given, add a `STORY <path>` line".to_string(),
+    })?;
+
+    Ok(TestSpec { story, seed, steps })
+}
+
+/// Get the rest of a line which starts with a keyword followed by whitespace.
+fn strip_keyword<'a>(line: &'a str, keyword: &str) -> Option<&'a str> {
+    line.strip_prefix(keyword)
+        .filter(|rest| rest.starts_with(char::is_whitespace))
+        .map(str::trim)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn specs_read_story_seed_and_steps_and_skip_comments() {
+        let content = "\
+// Buying fish
+STORY market.ink
+SEED 5
+
+You arrive. # location: market
+>  2
+CHECK gold = 3
+-> END
+";
+
+        assert_eq!(
+            read_test_spec(content).unwrap(),
+            TestSpec {
+                story: "market.ink".into(),
+                seed: Some(5),
+                steps: vec![
+                    TestStep::Line("You arrive. # location: market".to_string()),
+                    TestStep::Choice("2".to_string()),
+                    TestStep::Check {
+                        name: "gold".to_string(),
+                        value: "3".to_string()
+                    },
+                    TestStep::End,
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_specs_yield_errors_with_line_numbers() {
+        match read_test_spec("STORY market.ink\n\nCHECK gold") {
+            Err(TestSpecError { line, .. }) => assert_eq!(line, 3),
+            other => panic!("expected invalid test error, got {:?}", other),
+        }
+
+        assert!(read_test_spec("STORY a.ink\nSEED -1").is_err());
+        assert!(read_test_spec("STORY a.ink\n>").is_err());
+        assert!(read_test_spec("Hello.").is_err());
+    }
+}
@@ -14,3 +14,89 @@ pub enum OrderingDerive {
     Less,
     Greater,
 }
+
+/// Find the name among a set of candidates which is closest to a misspelled name.
+///
+/// Names are compared by their edit distance, ignoring case. Only candidates which differ
+/// by at most a third of the length of the name (and at least one edit) are considered
+/// close enough to be suggested. Ties are broken by picking the alphabetically first name.
+pub(crate) fn get_closest_name<'a, I>(name: &str, candidates: I) -> Option<String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let max_distance = (name.chars().count() / 3).max(1);
+    let name = name.to_lowercase();
+
+    candidates
+        .into_iter()
+        .map(|candidate| {
+            (
+                get_edit_distance(&name, &candidate.to_lowercase()),
+                candidate,
+            )
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+/// Get the number of single character insertions, deletions or substitutions which
+/// are needed to turn one string into another.
+fn get_edit_distance(from: &str, to: &str) -> usize {
+    let to = to.chars().collect::<Vec<_>>();
+    let mut distances = (0..=to.len()).collect::<Vec<_>>();
+
+    for (i, c) in from.chars().enumerate() {
+        let mut prev_diagonal = distances[0];
+        distances[0] = i + 1;
+
+        for (j, &d) in to.iter().enumerate() {
+            let substitution = prev_diagonal + if c == d { 0 } else { 1 };
+            prev_diagonal = distances[j + 1];
+
+            distances[j + 1] = substitution.min(distances[j] + 1).min(distances[j + 1] + 1);
+        }
+    }
+
+    distances[to.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distance_counts_insertions_deletions_and_substitutions() {
+        assert_eq!(get_edit_distance("tavern", "tavern"), 0);
+        assert_eq!(get_edit_distance("tavren", "tavern"), 2);
+        assert_eq!(get_edit_distance("tavrn", "tavern"), 1);
+        assert_eq!(get_edit_distance("taverns", "tavern"), 1);
+        assert_eq!(get_edit_distance("", "abc"), 3);
+        assert_eq!(get_edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn closest_name_is_suggested_if_close_enough() {
+        let names = ["tavern", "forest", "harbour"];
+
+        assert_eq!(
+            get_closest_name("tavren", names.iter().cloned()),
+            Some("tavern".to_string())
+        );
+        assert_eq!(
+            get_closest_name("Forest", names.iter().cloned()),
+            Some("forest".to_string())
+        );
+        assert_eq!(get_closest_name("castle", names.iter().cloned()), None);
+    }
+
+    #[test]
+    fn closest_name_ties_are_broken_alphabetically() {
+        let names = ["cat", "bat"];
+
+        assert_eq!(
+            get_closest_name("rat", names.iter().cloned()),
+            Some("bat".to_string())
+        );
+    }
+}
@@ -0,0 +1,283 @@
+#![cfg(feature = "cli")]
+
+use std::{
+    env, fs,
+    path::PathBuf,
+    process::{Command, Output},
+};
+
+const STORY: &str = "
+
+-> crossroads
+
+== crossroads ==
+The road splits in two.
+
++   [Go left] -> forest
++   [Go right] -> village
+
+== forest ==
+Trees surround you. # mood: calm
++   Head back. -> crossroads
+
+== village ==
+You arrive at the village.
+-> END
+
+";
+
+/// Write content to a file in a temporary directory which is unique for the test.
+fn write_file(test_name: &str, file_name: &str, content: &str) -> PathBuf {
+    let mut path = env::temp_dir();
+    path.push(format!("inkling-cli-{}-{}", test_name, std::process::id()));
+
+    fs::create_dir_all(&path).unwrap();
+    path.push(file_name);
+
+    fs::write(&path, content).unwrap();
+    path
+}
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_inkling"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn check_succeeds_for_valid_story() {
+    let path = write_file("check_valid", "story.ink", STORY);
+
+    let output = run(&["check", path.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("no errors"));
+}
+
+#[test]
+fn check_prints_errors_and_fails_for_invalid_story() {
+    let content = "
+-> missing_knot
+";
+    let path = write_file("check_invalid", "story.ink", content);
+
+    let output = run(&["check", path.to_str().unwrap()]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr.contains("missing_knot"));
+}
+
+#[test]
+fn check_with_denied_warnings_fails_if_todo_comments_are_the_only_messages() {
+    let content = "
+TODO: Write more.
+Hello, world!
+";
+    let path = write_file("check_todo", "story.ink", content);
+
+    let output = run(&["check", "--deny-warnings", path.to_str().unwrap()]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success());
+    assert!(stderr.contains("Write more."));
+}
+
+#[test]
+fn check_with_json_prints_diagnostics_with_codes() {
+    let content = "
+VAR knot = 2
+Hello {world
+";
+    let path = write_file("check_json", "story.ink", content);
+
+    let output = run(&["check", "--json", path.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output).trim(),
+        r#"{"code":"E0102","severity":"error","message":"line has unmatched curly '{}' braces","line":3,"column":7,"end_column":8}"#
+    );
+}
+
+#[test]
+fn missing_files_and_invalid_arguments_exit_with_code_two() {
+    assert_eq!(run(&["check", "does-not-exist.ink"]).status.code(), Some(2));
+    assert_eq!(run(&["check"]).status.code(), Some(2));
+    assert_eq!(run(&["unknown"]).status.code(), Some(2));
+}
+
+#[test]
+fn stats_prints_story_counts() {
+    let path = write_file("stats", "story.ink", STORY);
+
+    let output = run(&["stats", path.to_str().unwrap()]);
+    let text = stdout(&output);
+
+    assert!(output.status.success());
+    assert!(text.contains("knots:    3"));
+    assert!(text.contains("stitches: 0"));
+    assert!(text.contains("choices:  3"));
+    assert!(text.contains("words:    19"));
+}
+
+#[test]
+fn graph_prints_dot_or_mermaid_graph() {
+    let path = write_file("graph", "story.ink", STORY);
+
+    let dot = run(&["graph", path.to_str().unwrap()]);
+    let mermaid = run(&["graph", "--format", "mermaid", path.to_str().unwrap()]);
+
+    assert!(dot.status.success());
+    assert!(stdout(&dot).starts_with("digraph story {"));
+    assert!(stdout(&dot).contains("[label=\"Go left\"]"));
+
+    assert!(mermaid.status.success());
+    assert!(stdout(&mermaid).starts_with("flowchart TD"));
+    assert!(stdout(&mermaid).contains("-->|\"Head back.\"|"));
+}
+
+#[test]
+fn strings_prints_table_which_translates_played_story() {
+    let story = write_file("strings", "story.ink", STORY);
+
+    let output = run(&["strings", "--format", "csv", story.to_str().unwrap()]);
+    let table = stdout(&output);
+
+    assert!(output.status.success());
+    assert!(table.starts_with("id,source,translation\n"));
+    assert!(table.contains("crossroads:0,The road splits in two.,\n"));
+
+    let translated = table.replace(
+        "crossroads:0,The road splits in two.,",
+        "crossroads:0,The road splits in two.,La route se divise en deux.",
+    );
+    let strings = write_file("strings", "fr.csv", &translated);
+
+    let output = run(&[
+        "play",
+        "--json",
+        "--strings",
+        strings.to_str().unwrap(),
+        story.to_str().unwrap(),
+    ]);
+
+    assert!(
+        stdout(&output).starts_with(r#"{"event":"line","text":"La route se divise en deux.\n""#)
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("have no translation"));
+}
+
+#[test]
+fn fmt_checks_prints_or_writes_formatted_story() {
+    let path = write_file("fmt", "story.ink", STORY);
+    let path_str = path.to_str().unwrap();
+
+    let check = run(&["fmt", "--check", path_str]);
+    assert_eq!(check.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&check.stderr).contains("is not formatted"));
+
+    let output = run(&["fmt", path_str]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("-> crossroads\n\n=== crossroads ===\n"));
+    assert!(stdout(&output).contains("+   [Go left] -> forest\n"));
+    assert_eq!(fs::read_to_string(&path).unwrap(), STORY);
+
+    assert!(run(&["fmt", "--write", path_str]).status.success());
+    assert_eq!(fs::read_to_string(&path).unwrap(), stdout(&output));
+    assert!(run(&["fmt", "--check", path_str]).status.success());
+}
+
+#[test]
+fn test_runs_spec_files_and_prints_diff_of_failed_transcripts() {
+    let story = write_file("test", "story.ink", STORY);
+    let directory = story.parent().unwrap();
+
+    write_file(
+        "test",
+        "forest.inktest",
+        "\
+// Going to the forest and back
+STORY story.ink
+
+The road splits in two.
+> Go left
+Trees surround you. # mood: calm
+> Head back.
+Head back.
+The road splits in two.
+> 2
+You arrive at the village.
+-> END
+",
+    );
+
+    write_file(
+        "test",
+        "village.inktest",
+        "STORY story.ink\nThe road splits in two.\n> Go right\nYou arrive at the town.\n",
+    );
+
+    let output = run(&["test", directory.to_str().unwrap()]);
+    let text = stdout(&output);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(text.contains("forest.inktest ... ok\n"));
+    assert!(text.contains("village.inktest ... FAILED\n"));
+    assert!(text.contains("-You arrive at the town.\n+You arrive at the village.\n"));
+    assert!(text.ends_with("1 passed, 1 failed\n"));
+
+    let spec = directory.join("forest.inktest");
+    assert!(run(&["test", spec.to_str().unwrap()]).status.success());
+}
+
+#[test]
+fn play_with_choices_file_and_json_output_prints_one_event_per_line() {
+    let story = write_file("play_json", "story.ink", STORY);
+    let choices = write_file(
+        "play_json",
+        "choices.txt",
+        "# Try the forest first\n1\nHead back.\ncrossroads:go_right\n",
+    );
+
+    let output = run(&[
+        "play",
+        "--json",
+        "--choices",
+        choices.to_str().unwrap(),
+        story.to_str().unwrap(),
+    ]);
+    let text = stdout(&output);
+    let events = text.lines().collect::<Vec<_>>();
+
+    assert!(output.status.success());
+    assert_eq!(
+        events[0],
+        r#"{"event":"line","text":"The road splits in two.\n","tags":[]}"#
+    );
+    assert!(events
+        .contains(&r#"{"event":"line","text":"Trees surround you.\n","tags":["mood: calm"]}"#));
+    assert_eq!(events.last(), Some(&r#"{"event":"end"}"#));
+}
+
+#[test]
+fn play_fails_if_a_scripted_choice_is_not_presented() {
+    let story = write_file("play_invalid", "story.ink", STORY);
+    let choices = write_file("play_invalid", "choices.txt", "1\nClimb a tree.\n");
+
+    let output = run(&[
+        "play",
+        "--choices",
+        choices.to_str().unwrap(),
+        story.to_str().unwrap(),
+    ]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr.contains("'Climb a tree.' on line 2"));
+}
@@ -0,0 +1,261 @@
+use inkling::{error::InvalidExpressionKind, *};
+
+const CONTENT: &str = "\
+VAR coins = 2
+-> tavern
+
+== tavern ==
+You have {coins} coins.
+*   [Go down to the cellar]
+    -> cellar
+*   [Leave]
+    You left the tavern.
+- The night went on.
+-> END
+
+== cellar ==
+It was cold in the cellar.
+Barrels lined the walls.
+-> tavern
+";
+
+fn get_pause(prompt: Prompt) -> Pause {
+    match prompt {
+        Prompt::Breakpoint(pause) => pause,
+        other => panic!("expected a pause, got {:?}", other),
+    }
+}
+
+fn get_texts(line_buffer: &LineBuffer) -> Vec<&str> {
+    line_buffer.iter().map(|line| line.text.as_str()).collect()
+}
+
+#[test]
+fn breakpoints_pause_before_their_line_and_resume_from_it() {
+    let mut story = read_story_from_string(CONTENT).unwrap();
+    let mut line_buffer = Vec::new();
+
+    story.add_breakpoint(Breakpoint::Line(9)).unwrap();
+
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(1).unwrap();
+
+    let pause = get_pause(story.resume(&mut line_buffer).unwrap());
+
+    assert_eq!(pause.reason, PauseReason::Breakpoint(Breakpoint::Line(9)));
+    assert_eq!(pause.location, Location::from("tavern"));
+    assert_eq!(pause.line, 9);
+    assert_eq!(&get_texts(&line_buffer), &["You have 2 coins.\n"]);
+
+    match story.resume(&mut line_buffer).unwrap() {
+        Prompt::Done => (),
+        other => panic!("expected the end, got {:?}", other),
+    }
+
+    assert_eq!(
+        &get_texts(&line_buffer),
+        &[
+            "You have 2 coins.\n",
+            "You left the tavern.\n",
+            "The night went on.\n"
+        ]
+    );
+    assert_eq!(story.get_num_visited(&Location::from("tavern")), Some(1));
+}
+
+#[test]
+fn location_breakpoints_pause_when_the_knot_is_entered() {
+    let mut story = read_story_from_string(CONTENT).unwrap();
+    let mut line_buffer = Vec::new();
+
+    let breakpoint = Breakpoint::Location(Location::from("cellar"));
+    story.add_breakpoint(breakpoint.clone()).unwrap();
+
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(0).unwrap();
+
+    let pause = get_pause(story.resume(&mut line_buffer).unwrap());
+
+    assert_eq!(pause.reason, PauseReason::Breakpoint(breakpoint));
+    assert_eq!(pause.line, 14);
+    assert_eq!(story.get_current_location(), Location::from("cellar"));
+
+    line_buffer.clear();
+    let choices = story
+        .resume(&mut line_buffer)
+        .unwrap()
+        .get_choices()
+        .unwrap();
+
+    assert_eq!(&choices[0].text, "Leave");
+    assert_eq!(
+        &get_texts(&line_buffer),
+        &[
+            "It was cold in the cellar.\n",
+            "Barrels lined the walls.\n",
+            "You have 2 coins.\n"
+        ]
+    );
+    assert_eq!(story.get_num_visited(&Location::from("cellar")), Some(1));
+    assert_eq!(story.get_num_visited(&Location::from("tavern")), Some(2));
+}
+
+#[test]
+fn breakpoints_at_unknown_locations_yield_errors() {
+    let mut story = read_story_from_string(CONTENT).unwrap();
+
+    match story.add_breakpoint(Breakpoint::Location(Location::from("celar"))) {
+        Err(InklingError::InvalidAddress { suggestion, .. }) => {
+            assert_eq!(suggestion, Some("cellar".to_string()))
+        }
+        other => panic!("expected an invalid address error, got {:?}", other),
+    }
+
+    assert!(story.get_breakpoints().is_empty());
+}
+
+#[test]
+fn stepping_into_pauses_at_every_line_and_stepping_over_skips_other_knots() {
+    let mut story = read_story_from_string(CONTENT).unwrap();
+    let mut line_buffer = Vec::new();
+
+    let pause = get_pause(story.step(Step::Into, &mut line_buffer).unwrap());
+    assert_eq!(pause.line, 2);
+
+    let pause = get_pause(story.step(Step::Into, &mut line_buffer).unwrap());
+    assert_eq!((pause.line, pause.location), (5, Location::from("tavern")));
+
+    assert!(story
+        .step(Step::Into, &mut line_buffer)
+        .unwrap()
+        .get_choices()
+        .is_some());
+    story.make_choice(0).unwrap();
+
+    let pause = get_pause(story.step(Step::Over, &mut line_buffer).unwrap());
+    assert_eq!(pause.line, 6);
+
+    let pause = get_pause(story.step(Step::Over, &mut line_buffer).unwrap());
+    assert_eq!(pause.line, 7);
+
+    line_buffer.clear();
+
+    let pause = get_pause(story.step(Step::Over, &mut line_buffer).unwrap());
+    assert_eq!((pause.line, pause.location), (5, Location::from("tavern")));
+
+    assert_eq!(
+        &get_texts(&line_buffer),
+        &["It was cold in the cellar.\n", "Barrels lined the walls.\n"]
+    );
+}
+
+#[test]
+fn node_stack_has_a_frame_for_every_choice_made_in_the_stitch() {
+    let mut story = read_story_from_string(CONTENT).unwrap();
+    let mut line_buffer = Vec::new();
+
+    story.resume(&mut line_buffer).unwrap();
+
+    let stack = story.get_node_stack();
+    assert_eq!(stack.len(), 1);
+    assert_eq!(stack[0].line, Some(6));
+
+    story.add_breakpoint(Breakpoint::Line(9)).unwrap();
+    story.make_choice(1).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    assert_eq!(
+        story.get_node_stack(),
+        vec![
+            StackFrame {
+                location: Location::from("tavern"),
+                depth: 1,
+                line: Some(9),
+            },
+            StackFrame {
+                location: Location::from("tavern"),
+                depth: 0,
+                line: Some(8),
+            },
+        ]
+    );
+}
+
+#[test]
+fn watches_are_evaluated_in_the_current_state_of_the_story() {
+    let mut story = read_story_from_string(CONTENT).unwrap();
+    let mut line_buffer = Vec::new();
+
+    story.add_watch("coins * 2").unwrap();
+    story.add_watch("cellar").unwrap();
+
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(0).unwrap();
+    story.set_variable("coins", 5).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    let values = story
+        .get_watches()
+        .into_iter()
+        .map(|watch| (watch.expression, watch.value.unwrap()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        &values,
+        &[
+            ("coins * 2".to_string(), Variable::Int(10)),
+            ("cellar".to_string(), Variable::Int(1)),
+        ]
+    );
+
+    assert!(story.remove_watch("cellar"));
+    assert_eq!(story.get_watches().len(), 1);
+}
+
+#[test]
+fn invalid_watches_yield_errors_from_parsing_or_validating_them() {
+    let mut story = read_story_from_string(CONTENT).unwrap();
+
+    match story.add_watch("coins +") {
+        Err(InklingError::InvalidExpression {
+            kind: InvalidExpressionKind::Parse(..),
+            ..
+        }) => (),
+        other => panic!("expected a parse error, got {:?}", other),
+    }
+
+    match story.add_watch("gold + 1") {
+        Err(err @ InklingError::InvalidExpression { .. }) => {
+            assert!(err
+                .to_string()
+                .starts_with("Invalid expression 'gold + 1': "));
+            assert!(err.to_string().contains("'gold'"));
+        }
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+
+    assert!(story.get_watches().is_empty());
+}
+
+#[test]
+fn rewinding_keeps_breakpoints_and_watches() {
+    let mut story = read_story_from_string(CONTENT).unwrap();
+    story.set_history_limit(1);
+    let mut line_buffer = Vec::new();
+
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(1).unwrap();
+
+    story.add_breakpoint(Breakpoint::Line(10)).unwrap();
+    story.add_watch("coins").unwrap();
+
+    story.rewind(1).unwrap();
+
+    assert_eq!(story.get_breakpoints(), vec![Breakpoint::Line(10)]);
+    assert_eq!(story.get_watches().len(), 1);
+
+    story.make_choice(1).unwrap();
+    let pause = get_pause(story.resume(&mut line_buffer).unwrap());
+
+    assert_eq!(pause.line, 10);
+}
@@ -0,0 +1,119 @@
+use inkling::{error::InvalidExpressionKind, *};
+
+use std::sync::{Arc, Mutex};
+
+const CONTENT: &str = "\
+VAR coins = 8
+VAR bonus = 3
+VAR name = \"Ilsa\"
+-> tavern
+
+== tavern ==
+= bar
+You have {coins} coins.
+*   [Buy a drink]
+    -> cellar
+*   [Leave]
+    -> END
+
+== cellar ==
+It was cold in the cellar.
+-> END
+";
+
+#[test]
+fn expressions_are_evaluated_with_the_current_variables() {
+    let mut story = read_story_from_string(CONTENT).unwrap();
+
+    assert_eq!(
+        story.evaluate_expression("coins * 2 + bonus").unwrap(),
+        Variable::Int(19)
+    );
+
+    story.set_variable("coins", 10).unwrap();
+
+    assert_eq!(
+        story.evaluate_expression("coins * 2 + bonus").unwrap(),
+        Variable::Int(23)
+    );
+    assert_eq!(
+        story.evaluate_expression("name + \" the Brave\"").unwrap(),
+        Variable::from("Ilsa the Brave")
+    );
+}
+
+#[test]
+fn expressions_and_conditions_use_visit_counts_resolved_from_the_current_location() {
+    let mut story = read_story_from_string(CONTENT).unwrap();
+    let mut line_buffer = Vec::new();
+
+    assert!(!story.evaluate_condition("tavern.bar").unwrap());
+
+    story.resume(&mut line_buffer).unwrap();
+
+    assert_eq!(story.evaluate_expression("bar").unwrap(), Variable::Int(1));
+    assert!(story.evaluate_condition("tavern and not cellar").unwrap());
+
+    story.make_choice(0).unwrap();
+    story.set_variable("coins", 6).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    assert!(story.evaluate_condition("cellar and coins > 5").unwrap());
+    assert!(!story.evaluate_condition("cellar and coins > 10").unwrap());
+    assert!(story
+        .evaluate_condition("coins > 10 or (tavern.bar && bonus == 3)")
+        .unwrap());
+}
+
+#[test]
+fn invalid_expressions_and_conditions_yield_errors() {
+    let story = read_story_from_string(CONTENT).unwrap();
+
+    match story.evaluate_expression("coins +") {
+        Err(InklingError::InvalidExpression {
+            kind: InvalidExpressionKind::Parse(..),
+            ..
+        }) => (),
+        other => panic!("expected a parse error, got {:?}", other),
+    }
+
+    match story.evaluate_condition("coins > 1 or") {
+        Err(InklingError::InvalidExpression {
+            kind: InvalidExpressionKind::ParseCondition(..),
+            ..
+        }) => (),
+        other => panic!("expected a condition parse error, got {:?}", other),
+    }
+
+    match story.evaluate_condition("gold > 1") {
+        Err(err @ InklingError::InvalidExpression { .. }) => {
+            assert!(err
+                .to_string()
+                .starts_with("Invalid expression 'gold > 1': "));
+            assert!(err.to_string().contains("'gold'"));
+        }
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+}
+
+#[test]
+fn evaluated_conditions_are_not_sent_to_the_tracer() {
+    #[derive(Default)]
+    struct Conditions(Vec<String>);
+
+    impl Tracer for Conditions {
+        fn trace(&mut self, event: &TraceEvent) {
+            if let TraceEvent::Condition { condition, .. } = event {
+                self.0.push(condition.clone());
+            }
+        }
+    }
+
+    let tracer = Arc::new(Mutex::new(Conditions::default()));
+
+    let mut story = read_story_from_string(CONTENT).unwrap();
+    story.set_tracer(tracer.clone());
+
+    assert!(story.evaluate_condition("coins > 1").unwrap());
+    assert!(tracer.lock().unwrap().0.is_empty());
+}
@@ -0,0 +1,63 @@
+//! Checks the generated C header and runs a C program which uses it.
+
+#![cfg(feature = "ffi")]
+
+use std::{env, path::PathBuf, process::Command};
+
+#[test]
+fn committed_header_is_equal_to_generated_header() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/inkling.h"));
+    let committed = include_str!("../include/inkling.h");
+
+    assert!(
+        committed == generated,
+        "include/inkling.h is out of date, update it with \
+         `INKLING_WRITE_HEADER=1 cargo build --features ffi`"
+    );
+}
+
+/// Build the library as a static library and link a C program to it, which is compiled
+/// against the header generated by this build.
+#[cfg(target_os = "linux")]
+#[test]
+fn c_program_can_play_and_save_story_through_the_header() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let target_dir = env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| manifest_dir.join("target"));
+
+    let status = Command::new(env!("CARGO"))
+        .current_dir(&manifest_dir)
+        .args([
+            "rustc",
+            "--lib",
+            "--features",
+            "ffi",
+            "--crate-type",
+            "staticlib",
+        ])
+        .status()
+        .expect("could not run cargo");
+    assert!(status.success(), "could not build the static library");
+
+    let program = target_dir.join("ffi-test");
+
+    let status = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .arg(manifest_dir.join("tests").join("ffi").join("test.c"))
+        .arg("-I")
+        .arg(env!("OUT_DIR"))
+        .arg(target_dir.join("debug").join("libinkling.a"))
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&program)
+        .status()
+        .expect("could not run the C compiler");
+    assert!(status.success(), "could not compile the C test");
+
+    let output = Command::new(&program).output().unwrap();
+    assert!(
+        output.status.success(),
+        "C test failed ({}):\n{}",
+        output.status,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
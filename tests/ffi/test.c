@@ -0,0 +1,103 @@
+/* Plays a short story through the C interface. Exits with 0 if all checks pass. */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "inkling.h"
+
+static int num_failed = 0;
+
+#define CHECK(condition)                                                        \
+    do {                                                                        \
+        if (!(condition)) {                                                     \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__,    \
+                    #condition);                                                \
+            num_failed++;                                                       \
+        }                                                                       \
+    } while (0)
+
+static int str_eq(const char *string, const char *expected) {
+    return string != NULL && strcmp(string, expected) == 0;
+}
+
+static const char *CONTENT =
+    "VAR coins = 3\n"
+    "VAR name = \"Ali\"\n"
+    "-> market\n"
+    "== market ==\n"
+    "You have {coins} coins. # location: market\n"
+    "*   Buy an apple. # price: 2\n"
+    "    You bought an apple.\n"
+    "*   [Go home] -> home\n"
+    "- -> home\n"
+    "== home ==\n"
+    "Welcome home, {name}.\n"
+    "-> END\n";
+
+int main(void) {
+    CHECK(inkling_story_from_string("-> unknown") == NULL);
+    CHECK(inkling_last_error() != NULL);
+
+    InklingStory *story = inkling_story_from_string(CONTENT);
+    InklingBuffer *buffer = inkling_buffer_new();
+    CHECK(story != NULL);
+
+    CHECK(inkling_story_set_int(story, "coins", 5) == INKLING_STATUS_OK);
+    CHECK(inkling_story_set_int(story, "missing", 5) == INKLING_STATUS_ERROR);
+    CHECK(inkling_last_error() != NULL && strstr(inkling_last_error(), "'missing'") != NULL);
+
+    CHECK(inkling_story_resume(story, buffer) == INKLING_PROMPT_CHOICE);
+    CHECK(inkling_buffer_num_lines(buffer) == 1);
+    CHECK(str_eq(inkling_buffer_line_text(buffer, 0), "You have 5 coins.\n"));
+    CHECK(inkling_buffer_line_num_tags(buffer, 0) == 1);
+    CHECK(str_eq(inkling_buffer_line_tag(buffer, 0, 0), "location: market"));
+    CHECK(inkling_buffer_line_text(buffer, 1) == NULL);
+
+    CHECK(inkling_buffer_num_choices(buffer) == 2);
+    CHECK(str_eq(inkling_buffer_choice_text(buffer, 1), "Go home"));
+    CHECK(str_eq(inkling_buffer_choice_tag(buffer, 0, 0), "price: 2"));
+
+    uint8_t *data = NULL;
+    size_t length = 0;
+    CHECK(inkling_story_save(story, &data, &length) == INKLING_STATUS_OK);
+    CHECK(length > 0);
+
+    CHECK(inkling_story_make_choice(story, 5) == INKLING_STATUS_ERROR);
+    CHECK(inkling_story_make_choice(story, 0) == INKLING_STATUS_OK);
+
+    inkling_buffer_clear(buffer);
+    CHECK(inkling_story_set_string(story, "name", "Bo") == INKLING_STATUS_OK);
+    CHECK(inkling_story_resume(story, buffer) == INKLING_PROMPT_DONE);
+    CHECK(inkling_buffer_num_lines(buffer) == 3);
+    CHECK(str_eq(inkling_buffer_line_text(buffer, 0), "Buy an apple.\n"));
+    CHECK(str_eq(inkling_buffer_line_text(buffer, 2), "Welcome home, Bo.\n"));
+    CHECK(inkling_buffer_num_choices(buffer) == 0);
+
+    const char *name = NULL;
+    CHECK(inkling_story_get_string(story, "name", &name) == INKLING_STATUS_OK);
+    CHECK(str_eq(name, "Bo"));
+
+    float coins_as_float = 0.0f;
+    CHECK(inkling_story_get_float(story, "coins", &coins_as_float) == INKLING_STATUS_ERROR);
+
+    InklingStory *loaded = inkling_story_load(data, length);
+    inkling_bytes_free(data, length);
+    CHECK(loaded != NULL);
+
+    int32_t coins = 0;
+    CHECK(inkling_story_get_int(loaded, "coins", &coins) == INKLING_STATUS_OK);
+    CHECK(coins == 5);
+
+    CHECK(inkling_story_move_to(loaded, "home", NULL) == INKLING_STATUS_OK);
+    CHECK(inkling_story_move_to(loaded, "market", "stall") == INKLING_STATUS_ERROR);
+
+    inkling_buffer_clear(buffer);
+    CHECK(inkling_story_resume(loaded, buffer) == INKLING_PROMPT_DONE);
+    CHECK(str_eq(inkling_buffer_line_text(buffer, 0), "Welcome home, Ali.\n"));
+
+    inkling_story_free(loaded);
+    inkling_story_free(story);
+    inkling_buffer_free(buffer);
+
+    return num_failed == 0 ? 0 : 1;
+}
@@ -0,0 +1,284 @@
+//! Writing stories back to `Ink` source and reading them again.
+
+use inkling::*;
+
+const MARKET: &str = "\
+# author: Quinn
+VAR gold = 3
+VAR name = \"Quinn\"
+CONST price = 2
+VAR rate = 0.5
+VAR open = true
+VAR target = -> market.stall
+
+{name} has {gold} coins.<>
+  You count them {&again|once more|{gold} times}.
+{gold >= price: You can buy something.|You are broke.}
+{gold <= 1 and not open: Poor.} {gold != 3: Changed.}
+{(gold > 1 or open) and name == \"Quinn\": Known as {name + \"!\"}.}
+The total is {gold * 2 + price} or {-(gold + 1) * gold - price % 2}.
+*   {gold > price} {open} [Buy bread]You buy bread.->market
+*   \\{name} picks a choice.
++   Sticky choice# sticky
+    * *   Nested one
+          Nested text.
+    * *   Nested two->market.stall
+    - -   Nested gather # tagged
+    Back in the branch.
+*   ->market
+-   Gather text.    -> END
+
+=== market ===
+# location: market
+Welcome to the market. # greeting # {name}
+{market.stall: Back again.}
+-> stall
+
+= stall
+The {!fresh|old} fish {is|was} {rate * 2} silver.
+We {stay|leave -> END}.
+*   [Leave] -> END
+*   Linger[.] for a while.
+    -> stall
+";
+
+const LEVELS: &str = "\
+Before.
+*   One
+    * * *   Deep
+            Deep text.
+    * *     Shallow
+*   Two
+    ** Two nested
+    --  Two gather
+-   After.
+*   Three -> END
+*   Four -> END
+*   * Five -> END
+";
+
+const COMMENTED: &str = "\
+// The market scene, written for the tutorial
+# author: Quinn
+VAR gold = 3 // Enough for one loaf
+TODO: Add a second currency
+
+-> market // Skip the intro for now
+
+=== market ===
+// Greeting depends on the time of day
+Welcome to the market.
+*   Buy bread -> stall // Cheapest option
+    // Nested comment under the choice
+    You walk over.
+*   Leave -> END
+-   Gather. // Both paths meet here
+
+= stall
+TODO: Let the baker haggle
+The baker nods.
+-> END
+";
+
+const STORIES: &[&str] = &[
+    include_str!("../examples/assets/story.ink"),
+    MARKET,
+    LEVELS,
+    COMMENTED,
+];
+
+/// Follow every path of choices to a maximum depth and record the lines and choices.
+fn get_playthroughs(story: &Story, max_depth: usize) -> Vec<String> {
+    let mut events = Vec::new();
+    let mut queue = vec![(story.clone(), 0)];
+
+    while let Some((mut story, depth)) = queue.pop() {
+        let mut line_buffer = Vec::new();
+
+        match story.resume(&mut line_buffer) {
+            Ok(Prompt::Choice(choices)) => {
+                events.extend(
+                    line_buffer
+                        .iter()
+                        .map(|line| format!("line: {:?} {:?}", line.text, line.tags)),
+                );
+
+                events.extend(
+                    choices
+                        .iter()
+                        .map(|choice| format!("choice: {:?} {:?}", choice.text, choice.tags)),
+                );
+
+                if depth < max_depth {
+                    for i in 0..choices.len() {
+                        let mut branch = story.clone();
+                        branch.make_choice(i).unwrap();
+                        queue.push((branch, depth + 1));
+                    }
+                }
+            }
+            Ok(Prompt::Done) => {
+                events.extend(
+                    line_buffer
+                        .iter()
+                        .map(|line| format!("line: {:?} {:?}", line.text, line.tags)),
+                );
+                events.push("done".to_string());
+            }
+            Ok(..) => unreachable!("no breakpoints are set"),
+            Err(err) => events.push(format!("error: {}", err)),
+        }
+    }
+
+    events
+}
+
+#[test]
+fn formatting_a_formatted_story_does_not_change_it() {
+    for content in STORIES {
+        let formatted = format_ink(content).unwrap();
+        let reformatted = format_ink(&formatted).unwrap();
+
+        assert_eq!(formatted, reformatted);
+    }
+}
+
+#[test]
+fn formatted_stories_play_like_the_original() {
+    for content in STORIES {
+        let story = read_story_from_string(content).unwrap();
+        let formatted = read_story_from_string(&format_ink(content).unwrap()).unwrap();
+
+        assert_eq!(get_playthroughs(&story, 6), get_playthroughs(&formatted, 6));
+    }
+}
+
+#[cfg(feature = "serde_support")]
+#[test]
+fn formatted_stories_have_the_same_knots_except_for_spans() {
+    use serde_json::Value;
+
+    /// Get the serialized knots of a story with the spans of all items removed.
+    fn get_knots_without_spans(story: &Story) -> Value {
+        fn remove_spans(value: &mut Value) {
+            match value {
+                Value::Object(map) => {
+                    map.remove("meta_data");
+                    map.values_mut().for_each(remove_spans);
+                }
+                Value::Array(items) => items.iter_mut().for_each(remove_spans),
+                _ => (),
+            }
+        }
+
+        let mut value = serde_json::to_value(story).unwrap();
+        let mut knots = value["knots"].take();
+
+        remove_spans(&mut knots);
+
+        knots
+    }
+
+    for content in STORIES {
+        let story = read_story_from_string(content).unwrap();
+        let formatted = read_story_from_string(&format_ink(content).unwrap()).unwrap();
+
+        assert_eq!(
+            get_knots_without_spans(&story),
+            get_knots_without_spans(&formatted)
+        );
+    }
+}
+
+#[test]
+fn formatted_stories_have_the_same_variables_and_tags() {
+    let story = read_story_from_string(MARKET).unwrap();
+    let formatted = read_story_from_string(&format_ink(MARKET).unwrap()).unwrap();
+
+    for name in &["gold", "name", "price", "rate", "open", "target"] {
+        assert_eq!(story.get_variable(name), formatted.get_variable(name));
+    }
+
+    assert_eq!(
+        story.get_knot_tags("market").unwrap(),
+        formatted.get_knot_tags("market").unwrap()
+    );
+}
+
+#[test]
+fn formatting_normalizes_indentation_and_tags_and_keeps_diverts_as_written() {
+    let content = "\
+VAR gold = 3
+*{gold > 1}Buy bread.->shop
+  **   Nested[.] choice
+  --Nested gather#tag
+*   Leave
+
+
+
+== shop ==
+= counter
+Welcome.#greeting
+-> counter
+";
+
+    assert_eq!(
+        &format_ink(content).unwrap(),
+        "\
+VAR gold = 3
+*   {gold > 1} Buy bread. -> shop
+    * *     Nested[.] choice
+    - -     Nested gather # tag
+*   Leave
+
+=== shop ===
+= counter
+Welcome. # greeting
+-> counter
+"
+    );
+}
+
+#[test]
+fn formatting_a_commented_story_keeps_every_comment() {
+    assert_eq!(&format_ink(COMMENTED).unwrap(), COMMENTED);
+
+    let content = "\
+  // Intro
+VAR gold = 3   // Declared
+*   Go   ->  END // To the end
+    // About the choice
+   TODO: More choices
+";
+
+    assert_eq!(
+        &format_ink(content).unwrap(),
+        "\
+// Intro
+VAR gold = 3 // Declared
+*   Go -> END // To the end
+    // About the choice
+    TODO: More choices
+"
+    );
+}
+
+#[test]
+fn variables_and_diverts_are_formatted_as_declared() {
+    let content = "\
+VAR gold = 3
+VAR target = -> market
+-> market
+== market
+Welcome.
+-> END
+";
+
+    let formatted = format_ink(content).unwrap();
+    assert!(formatted.starts_with("VAR gold = 3\nVAR target = -> market\n"));
+}
+
+#[test]
+fn stories_with_errors_are_not_formatted() {
+    assert!(format_ink("-> nowhere\n").is_err());
+}
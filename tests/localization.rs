@@ -0,0 +1,175 @@
+use inkling::*;
+
+const CONTENT: &str = "
+VAR coins = 3
+
+-> market
+
+== market ==
+The merchant counts {coins} coins. # mood: {coins}
+{coins > 2: A fine sum|Is that all}<>
+, {&friend|stranger}.
++   [Buy bread] You buy some bread. -> market
++   Leave -> END
+";
+
+const TRANSLATIONS: &[(&str, &str)] = &[
+    ("market:0", "Le marchand compte {1} pièces. "),
+    ("market:1/0", " Une belle somme"),
+    ("market:1/0/else", "C'est tout"),
+    ("market:2", ", {1} !"),
+    ("market:2/1/0", "mon ami"),
+    ("market:2/1/1", "étranger"),
+    ("market:3/selection", "Acheter du pain"),
+    ("market:3/display", " Vous achetez du pain.  {1}"),
+    ("market:4/selection", "Partir "),
+    ("market:4/display", "Partir  {1}"),
+];
+
+fn get_translated_table(story: &Story) -> StringTable {
+    let mut table = story.get_string_table();
+
+    for (id, translation) in TRANSLATIONS {
+        table.get_mut(id).unwrap().translation = Some(translation.to_string());
+    }
+
+    table
+}
+
+#[test]
+fn all_text_in_lines_choices_alternatives_and_conditions_is_extracted() {
+    let story = read_story_from_string(CONTENT).unwrap();
+    let table = story.get_string_table();
+
+    let ids = table
+        .entries
+        .iter()
+        .map(|entry| entry.id.as_str())
+        .collect::<Vec<_>>();
+
+    let expected = TRANSLATIONS.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+
+    assert_eq!(ids, expected);
+    assert_eq!(
+        table.get("market:0").unwrap().source,
+        "The merchant counts {1} coins."
+    );
+}
+
+#[test]
+fn translated_story_keeps_variables_glue_tags_and_branching() {
+    let mut story = read_story_from_string(CONTENT).unwrap();
+    let table = get_translated_table(&story);
+
+    assert!(story.load_string_table(&table).unwrap().is_empty());
+
+    let mut line_buffer = Vec::new();
+
+    let choices = match story.resume(&mut line_buffer).unwrap() {
+        Prompt::Choice(choices) => choices,
+        _ => panic!("expected choices"),
+    };
+
+    assert_eq!(
+        copy_lines_into_string(&line_buffer),
+        "Le marchand compte 3 pièces.\nUne belle somme, mon ami !\n"
+    );
+    assert_eq!(line_buffer[0].tags, &["mood: 3"]);
+    assert_eq!(choices[0].text, "Acheter du pain");
+    assert_eq!(choices[1].text, "Partir");
+
+    line_buffer.clear();
+    story.set_variable("coins", 1).unwrap();
+    story.make_choice(0).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    assert_eq!(
+        copy_lines_into_string(&line_buffer),
+        "Vous achetez du pain. Le marchand compte 1 pièces.\nC'est tout, étranger !\n"
+    );
+}
+
+#[test]
+fn translated_tables_can_be_loaded_from_every_format() {
+    let story = read_story_from_string(CONTENT).unwrap();
+    let table = get_translated_table(&story);
+
+    let tables = vec![
+        StringTable::from_po(&table.to_po()).unwrap(),
+        StringTable::from_xliff(&table.to_xliff("en", Some("fr"))).unwrap(),
+        StringTable::from_csv(&table.to_csv()).unwrap(),
+    ];
+
+    for loaded in tables {
+        let mut story = read_story_from_string(CONTENT).unwrap();
+        assert!(story.load_string_table(&loaded).unwrap().is_empty());
+
+        let mut line_buffer = Vec::new();
+        story.resume(&mut line_buffer).unwrap();
+
+        assert_eq!(line_buffer[0].text, "Le marchand compte 3 pièces.\n");
+    }
+}
+
+#[test]
+fn outdated_and_missing_translations_keep_source_text_and_are_returned() {
+    let mut story = read_story_from_string(CONTENT).unwrap();
+    let mut table = get_translated_table(&story);
+
+    table.get_mut("market:0").unwrap().source = "The merchant counts.".to_string();
+    table.get_mut("market:2/1/1").unwrap().translation = None;
+
+    let untranslated = story.load_string_table(&table).unwrap();
+    assert_eq!(untranslated, &["market:0", "market:2/1/1"]);
+
+    let mut line_buffer = Vec::new();
+    story.resume(&mut line_buffer).unwrap();
+
+    assert_eq!(line_buffer[0].text, "The merchant counts 3 coins.\n");
+}
+
+#[test]
+fn invalid_translation_is_an_error_and_leaves_story_untranslated() {
+    let mut story = read_story_from_string(CONTENT).unwrap();
+    let mut table = get_translated_table(&story);
+
+    table.get_mut("market:4/display").unwrap().translation = Some("Partir".to_string());
+
+    match story.load_string_table(&table) {
+        Err(LocalizationError::InvalidPlaceholders { id, .. }) => {
+            assert_eq!(id, "market:4/display")
+        }
+        other => panic!("expected an error but got {:?}", other),
+    }
+
+    let mut line_buffer = Vec::new();
+    story.resume(&mut line_buffer).unwrap();
+
+    assert_eq!(line_buffer[0].text, "The merchant counts 3 coins.\n");
+}
+
+#[test]
+fn identifiers_are_kept_when_lines_are_added_or_edited_before_them() {
+    let story = read_story_from_string(CONTENT).unwrap();
+    let table = story.get_string_table();
+
+    let edited = CONTENT
+        .replace(
+            "\n-> market\n\n== market ==\n",
+            "\nMorning comes.\n-> square\n\n== square ==\nPeople gather.\n-> market\n\n\
+             == market ==\n// A comment\n\n",
+        )
+        .replace("counts {coins} coins.", "slowly counts {coins} coins.");
+
+    let story = read_story_from_string(&edited).unwrap();
+    let edited_table = story.get_string_table();
+
+    for entry in table.entries.iter().skip(1) {
+        assert_eq!(edited_table.get(&entry.id).unwrap().source, entry.source);
+    }
+
+    assert_eq!(
+        edited_table.get("market:0").unwrap().source,
+        "The merchant slowly counts {1} coins."
+    );
+}
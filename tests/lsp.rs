@@ -0,0 +1,292 @@
+//! Talking to the language server over standard input and output.
+
+#![cfg(feature = "lsp")]
+
+use serde_json::{json, Value};
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+const URI: &str = "file:///story.ink";
+
+const STORY: &str = "\
+VAR gold = 3
+CONST price = 2
+-> market
+
+=== market ===
+{gold > price: You can buy fish.}
+-> stall
+
+= stall
+*   [Buy] -> END
++   [Leave] -> market.stall
+";
+
+struct Client {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: u64,
+}
+
+impl Client {
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_inkling-lsp"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+
+        Client {
+            child,
+            stdin,
+            stdout,
+            next_id: 1,
+        }
+    }
+
+    fn send(&mut self, message: Value) {
+        let body = message.to_string();
+        write!(self.stdin, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+        self.stdin.flush().unwrap();
+    }
+
+    fn receive(&mut self) -> Value {
+        let mut length = 0;
+
+        loop {
+            let mut header = String::new();
+            self.stdout.read_line(&mut header).unwrap();
+
+            match header.trim_end() {
+                "" => break,
+                header => length = header["Content-Length: ".len()..].parse().unwrap(),
+            }
+        }
+
+        let mut body = vec![0; length];
+        self.stdout.read_exact(&mut body).unwrap();
+
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.send(json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}));
+
+        let reply = self.receive();
+        assert_eq!(reply["id"], id);
+
+        reply
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({"jsonrpc": "2.0", "method": method, "params": params}));
+    }
+
+    fn open(&mut self, text: &str) -> Value {
+        self.notify(
+            "textDocument/didOpen",
+            json!({"textDocument": {"uri": URI, "languageId": "ink", "version": 1, "text": text}}),
+        );
+
+        self.receive()
+    }
+
+    fn at(&mut self, method: &str, line: u64, character: u64) -> Value {
+        self.request(
+            method,
+            json!({
+                "textDocument": {"uri": URI},
+                "position": {"line": line, "character": character},
+            }),
+        )["result"]
+            .clone()
+    }
+
+    fn stop(mut self) -> Option<i32> {
+        self.request("shutdown", Value::Null);
+        self.notify("exit", Value::Null);
+
+        self.child.wait().unwrap().code()
+    }
+}
+
+fn initialize() -> Client {
+    let mut client = Client::start();
+
+    let reply = client.request("initialize", json!({"capabilities": {}}));
+    assert_eq!(reply["result"]["capabilities"]["definitionProvider"], true);
+
+    client.notify("initialized", json!({}));
+    client
+}
+
+#[test]
+fn diagnostics_are_published_when_documents_are_opened_and_changed() {
+    let mut client = initialize();
+
+    let published = client.open("-> market\n=== market ===\nHello. -> stal");
+
+    assert_eq!(published["method"], "textDocument/publishDiagnostics");
+    assert_eq!(published["params"]["uri"], URI);
+
+    let diagnostic = &published["params"]["diagnostics"][0];
+    assert_eq!(diagnostic["severity"], 1);
+    assert_eq!(diagnostic["source"], "inkling");
+    assert_eq!(
+        diagnostic["range"],
+        json!({"start": {"line": 2, "character": 10}, "end": {"line": 2, "character": 14}})
+    );
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": {"uri": URI, "version": 2},
+            "contentChanges": [{"text": STORY}],
+        }),
+    );
+
+    let published = client.receive();
+    assert_eq!(published["params"]["diagnostics"], json!([]));
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": {"uri": URI, "version": 3},
+            "contentChanges": [{"text": format!("{}TODO: Sell bread.\n", STORY)}],
+        }),
+    );
+
+    let published = client.receive();
+    assert_eq!(published["params"]["diagnostics"][0]["severity"], 3);
+    assert_eq!(
+        published["params"]["diagnostics"][0]["range"]["start"]["line"],
+        11
+    );
+
+    assert_eq!(client.stop(), Some(0));
+}
+
+#[test]
+fn divert_targets_and_variables_go_to_their_definition() {
+    let mut client = initialize();
+    client.open(STORY);
+
+    assert_eq!(
+        client.at("textDocument/definition", 2, 5),
+        json!({
+            "uri": URI,
+            "range": {"start": {"line": 4, "character": 4}, "end": {"line": 4, "character": 10}},
+        })
+    );
+
+    assert_eq!(
+        client.at("textDocument/definition", 6, 4)["range"]["start"]["line"],
+        8
+    );
+    assert_eq!(
+        client.at("textDocument/definition", 10, 20)["range"]["start"]["line"],
+        8
+    );
+    assert_eq!(
+        client.at("textDocument/definition", 5, 2)["range"]["start"]["line"],
+        0
+    );
+    assert_eq!(client.at("textDocument/definition", 5, 30), Value::Null);
+
+    assert_eq!(client.stop(), Some(0));
+}
+
+#[test]
+fn hovering_a_variable_shows_its_declaration() {
+    let mut client = initialize();
+    client.open(STORY);
+
+    assert_eq!(
+        client.at("textDocument/hover", 5, 10)["contents"]["value"],
+        "```ink\nCONST price = 2\n```"
+    );
+    assert_eq!(client.at("textDocument/hover", 2, 5), Value::Null);
+
+    assert_eq!(client.stop(), Some(0));
+}
+
+#[test]
+fn names_are_completed_at_diverts_and_inside_braces() {
+    let mut client = initialize();
+    client.open(STORY);
+
+    let labels = |items: &Value| {
+        items
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["label"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let at_divert = client.at("textDocument/completion", 9, 13);
+    assert_eq!(
+        labels(&at_divert),
+        &[
+            "gold",
+            "price",
+            "market",
+            "stall",
+            "market.stall",
+            "END",
+            "DONE"
+        ]
+    );
+
+    let in_braces = client.at("textDocument/completion", 5, 1);
+    assert!(!labels(&in_braces).contains(&"END".to_string()));
+
+    assert_eq!(client.at("textDocument/completion", 9, 6), json!([]));
+
+    assert_eq!(client.stop(), Some(0));
+}
+
+#[test]
+fn document_symbols_are_knots_with_their_stitches_and_variables() {
+    let mut client = initialize();
+    client.open(STORY);
+
+    let symbols = client.request(
+        "textDocument/documentSymbol",
+        json!({"textDocument": {"uri": URI}}),
+    )["result"]
+        .clone();
+
+    assert_eq!(symbols[0]["name"], "gold");
+    assert_eq!(symbols[1]["name"], "price");
+    assert_eq!(symbols[2]["name"], "market");
+    assert_eq!(
+        symbols[2]["range"],
+        json!({"start": {"line": 4, "character": 0}, "end": {"line": 10, "character": 27}})
+    );
+    assert_eq!(symbols[2]["children"][0]["name"], "stall");
+    assert_eq!(symbols[2]["children"][0]["range"]["start"]["line"], 8);
+
+    assert_eq!(client.stop(), Some(0));
+}
+
+#[test]
+fn unknown_requests_yield_errors_and_exit_without_shutdown_fails() {
+    let mut client = initialize();
+
+    let reply = client.request("workspace/symbol", json!({"query": ""}));
+    assert_eq!(reply["error"]["code"], -32601);
+
+    client.notify("exit", Value::Null);
+    assert_eq!(client.child.wait().unwrap().code(), Some(1));
+}
@@ -1,6 +1,14 @@
 use inkling::error::parse::print_read_error;
 use inkling::*;
 
+/// Get the lines with errors from a print out, skipping the source snippets.
+fn get_error_lines(error_string: &str) -> Vec<&str> {
+    error_string
+        .lines()
+        .filter(|line| line.starts_with("(line"))
+        .collect()
+}
+
 #[test]
 fn all_line_parsing_errors_are_reported_when_printed() {
     let content = "
@@ -22,7 +30,7 @@ Let's add a couple more errors.
     let error = read_story_from_string(content).unwrap_err();
 
     let error_string = print_read_error(&error).unwrap();
-    let error_lines = error_string.lines().collect::<Vec<_>>();
+    let error_lines = get_error_lines(&error_string);
 
     assert_eq!(error_lines.len(), 4);
 }
@@ -56,7 +64,7 @@ Addressing stitch in other knot: -> stitch
     let error = read_story_from_string(content).unwrap_err();
 
     let error_string = print_read_error(&error).unwrap();
-    let error_lines = error_string.lines().collect::<Vec<_>>();
+    let error_lines = get_error_lines(&error_string);
 
     assert_eq!(error_lines.len(), 9);
 }
@@ -77,7 +85,7 @@ Line one.
     let error = read_story_from_string(content).unwrap_err();
 
     let error_string = print_read_error(&error).unwrap();
-    let error_lines = error_string.lines().collect::<Vec<_>>();
+    let error_lines = get_error_lines(&error_string);
 
     assert_eq!(error_lines.len(), 2);
 }
@@ -108,7 +116,61 @@ And in all knots! {2 + true}.
     let error = read_story_from_string(content).unwrap_err();
 
     let error_string = print_read_error(&error).unwrap();
-    let error_lines = error_string.lines().collect::<Vec<_>>();
+    let error_lines = get_error_lines(&error_string);
 
     assert_eq!(error_lines.len(), 11);
 }
+
+#[test]
+fn printed_errors_underline_the_source_that_caused_them() {
+    let content = "\
+VAR variable = 10
+
+Hello {world
+Go to the -> forest
+";
+
+    let error = read_story_from_string(content).unwrap_err();
+    let error_string = print_read_error(&error).unwrap();
+
+    assert!(error_string.contains("3 | Hello {world\n  |       ^\n"));
+
+    let content = "\
+Go to the -> forest
+";
+
+    let error = read_story_from_string(content).unwrap_err();
+    let error_string = print_read_error(&error).unwrap();
+
+    assert!(error_string.contains("1 | Go to the -> forest\n  |              ^^^^^^\n"));
+}
+
+#[test]
+fn printed_name_space_collisions_underline_the_name() {
+    let content = "\
+VAR knot = 2
+
+== knot
+Line one.
+";
+
+    let error = read_story_from_string(content).unwrap_err();
+    let error_string = print_read_error(&error).unwrap();
+
+    assert!(error_string.contains("1 | VAR knot = 2\n  |     ^^^^\n"));
+}
+
+#[test]
+fn printed_invalid_addresses_suggest_close_names() {
+    let content = "\
+-> tavren
+
+== tavern
+You enter the tavern.
+";
+
+    let error = read_story_from_string(content).unwrap_err();
+    let error_string = print_read_error(&error).unwrap();
+
+    assert!(error_string.contains("'tavren' in the story: did you mean 'tavern'?"));
+}
@@ -587,3 +587,81 @@ An old castle heaves in front of you. -> gates # description
     assert_eq!(&choices[0].text, "Enter it.");
     assert_eq!(&choices[0].tags, &["action".to_string()]);
 }
+
+#[test]
+fn tags_evaluate_embraced_content_and_can_be_parsed() {
+    let content = "
+
+VAR speaker = \"Anna\"
+
+-> hallway
+
+== hallway
+A gust of wind swept through the hallway. # speaker: {speaker} # sound = {&gust|howl}.ogg
+
+*   [Wait.] -> hallway
+*   Leave. # leaving
+
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    let mut line_buffer = Vec::new();
+
+    let choices = story
+        .resume(&mut line_buffer)
+        .unwrap()
+        .get_choices()
+        .unwrap();
+
+    let tags = line_buffer[0].parsed_tags();
+    assert_eq!(tags[0].key(), "speaker");
+    assert_eq!(tags[0].value(), Some("Anna"));
+    assert_eq!(&line_buffer[0].tags[1], "sound = gust.ogg");
+    assert_eq!(
+        choices[1].parsed_tags(),
+        &[Tag::Flag("leaving".to_string())]
+    );
+
+    line_buffer.clear();
+    story.make_choice(0).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    assert_eq!(&line_buffer[0].tags[1], "sound = howl.ogg");
+}
+
+#[test]
+fn sequences_in_choice_tags_only_advance_when_the_choice_is_selected() {
+    let content = "
+-> waiting_room
+
+== waiting_room
++   Wait. # turn: {one|two|three}
++   Look around.
+-   -> waiting_room
+
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    let mut line_buffer = Vec::new();
+
+    let get_wait_tags = |story: &mut Story, line_buffer: &mut LineBuffer| {
+        let choices = story.resume(line_buffer).unwrap().get_choices().unwrap();
+        choices[0].tags.clone()
+    };
+
+    assert_eq!(get_wait_tags(&mut story, &mut line_buffer), &["turn: one"]);
+
+    story.make_choice(1).unwrap();
+    assert_eq!(get_wait_tags(&mut story, &mut line_buffer), &["turn: one"]);
+
+    line_buffer.clear();
+    story.make_choice(0).unwrap();
+    assert_eq!(get_wait_tags(&mut story, &mut line_buffer), &["turn: two"]);
+    assert_eq!(&line_buffer[0].tags, &["turn: one"]);
+
+    story.make_choice(0).unwrap();
+    assert_eq!(
+        get_wait_tags(&mut story, &mut line_buffer),
+        &["turn: three"]
+    );
+}
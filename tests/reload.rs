@@ -0,0 +1,191 @@
+use inkling::{error::ReloadError, *};
+
+const CONTENT: &str = "
+
+VAR coins = 3
+VAR name = \"Ali\"
+CONST price = 2
+
+-> market
+
+== market ==
+You are at the market with {coins} coins.
+
+*   [Buy an apple] -> stall
+*   [Go home] -> home
+
+= stall
+The apple costs {price} coins.
+
+*   [Pay] -> home
+*   [Haggle] -> home
+
+== home ==
+You came home.
+-> END
+
+";
+
+fn start_at_stall() -> Story {
+    let mut story = read_story_from_string(CONTENT).unwrap();
+    let mut line_buffer = Vec::new();
+
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(0).unwrap();
+    story.set_variable("coins", 1).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    story
+}
+
+#[test]
+fn reloading_keeps_variables_visit_counts_and_location() {
+    let mut story = start_at_stall();
+
+    let edited = CONTENT
+        .replace("CONST price = 2", "CONST price = 5")
+        .replace("VAR name = \"Ali\"", "VAR name = 4")
+        .replace("You came home.", "You walked home.");
+
+    story.reload_from_string(&edited).unwrap();
+
+    assert_eq!(story.get_variable("coins").unwrap(), Variable::Int(1));
+    assert_eq!(story.get_variable("price").unwrap(), Variable::Int(5));
+    assert_eq!(story.get_variable("name").unwrap(), Variable::Int(4));
+    assert_eq!(story.get_num_visited(&"market".into()), Some(1));
+    assert_eq!(
+        story.get_current_location(),
+        Location::with_stitch("market", "stall")
+    );
+    assert_eq!(story.get_num_rewind_steps(), 0);
+
+    let mut line_buffer = Vec::new();
+    story.make_choice(0).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    assert_eq!(&line_buffer[0].text, "You walked home.\n");
+}
+
+#[test]
+fn reloading_continues_from_choices_made_in_the_current_stitch() {
+    let content = "
+-> shop
+== shop ==
+*   [Look around]
+    The shelves are empty.
+    *   *   [Leave] Goodbye.
+    *   *   [Wait] You wait.
+*   [Leave] Goodbye.
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    let mut line_buffer = Vec::new();
+
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(0).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    let edited = content.replace("You wait.", "You wait for a while.");
+    story.reload_from_string(&edited).unwrap();
+
+    line_buffer.clear();
+    story.make_choice(1).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    assert_eq!(&line_buffer[0].text, "You wait for a while.\n");
+}
+
+#[test]
+fn reloading_fails_without_changes_if_the_location_is_gone_or_choices_changed() {
+    let mut story = start_at_stall();
+
+    let renamed = CONTENT
+        .replace("= stall", "= counter")
+        .replace("-> stall", "-> counter");
+
+    match story.reload_from_string(&renamed) {
+        Err(ReloadError::MissingLocation { location }) => {
+            assert_eq!(location, Location::with_stitch("market", "stall"))
+        }
+        other => panic!("expected missing location, got {:?}", other),
+    }
+
+    let new_choice = CONTENT.replace(
+        "*   [Pay] -> home",
+        "*   [Pay] -> home\n*   [Steal] -> home",
+    );
+
+    match story.reload_from_string(&new_choice) {
+        Err(ReloadError::ChangedStack { stack, .. }) => assert_eq!(stack, &[1]),
+        other => panic!("expected changed stack, got {:?}", other),
+    }
+
+    assert!(matches!(
+        story.reload_from_string("-> unknown"),
+        Err(ReloadError::Read(..))
+    ));
+
+    let mut line_buffer = Vec::new();
+    story.make_choice(1).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    assert_eq!(&line_buffer[0].text, "You came home.\n");
+    assert_eq!(story.get_variable("coins").unwrap(), Variable::Int(1));
+}
+
+#[test]
+fn reloading_with_new_choice_text_presents_the_new_choices() {
+    let mut story = start_at_stall();
+
+    let edited = CONTENT
+        .replace("[Pay]", "[Pay the merchant]")
+        .replace("[Haggle]", "[Haggle over the price]");
+
+    story.reload_from_string(&edited).unwrap();
+
+    match story.make_choice(2) {
+        Err(InklingError::InvalidChoice {
+            presented_choices, ..
+        }) => {
+            let texts = presented_choices
+                .iter()
+                .map(|choice| choice.text.as_str())
+                .collect::<Vec<_>>();
+
+            assert_eq!(texts, &["Pay the merchant", "Haggle over the price"]);
+            assert_eq!(presented_choices[0].id, "market.stall:pay_the_merchant");
+        }
+        other => panic!("expected an invalid choice, got {:?}", other),
+    }
+
+    let mut line_buffer = Vec::new();
+    story.make_choice(1).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    assert_eq!(&line_buffer[0].text, "You came home.\n");
+}
+
+#[test]
+fn reloading_replaces_script_messages_and_keeps_runtime_messages() {
+    let content = "\
+-> hall
+== hall
+*   [] You wait.
+    -> room
+== room
+TODO: Describe the room
+*   [Look] -> END
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    story.resume(&mut Vec::new()).unwrap();
+
+    assert_eq!(story.log.todo_comments.len(), 1);
+    assert_eq!(story.log.runtime_messages.len(), 1);
+
+    let edited = content.replace("TODO: Describe the room\n", "");
+    story.reload_from_string(&edited).unwrap();
+
+    assert!(story.log.todo_comments.is_empty());
+    assert_eq!(story.log.runtime_messages.len(), 1);
+}
@@ -0,0 +1,205 @@
+use inkling::*;
+
+const STORY: &str = "
+
+-> crossroads
+
+== crossroads ==
+The road splits in two.
+
++   [Go left] -> forest
++   [Go right] -> village
+
+== forest ==
+Trees surround you.
+
+*   Climb a tree. -> crossroads
++   Head back. -> crossroads
+
+== village ==
+You arrive at the village.
+-> END
+
+";
+
+#[test]
+fn choices_are_identified_by_location_and_text() {
+    let mut story = read_story_from_string(STORY).unwrap();
+    let mut line_buffer = Vec::new();
+
+    let choices = story
+        .resume(&mut line_buffer)
+        .unwrap()
+        .get_choices()
+        .unwrap();
+
+    assert_eq!(&choices[0].id, "crossroads:go_left");
+    assert_eq!(&choices[1].id, "crossroads:go_right");
+}
+
+#[test]
+fn choice_ids_do_not_change_when_content_above_them_is_edited() {
+    let edited = STORY.replace(
+        "The road splits in two.",
+        "The road splits in two.\nA signpost stands between them.",
+    );
+
+    let mut story = read_story_from_string(&edited).unwrap();
+    let mut line_buffer = Vec::new();
+
+    let choices = story
+        .resume(&mut line_buffer)
+        .unwrap()
+        .get_choices()
+        .unwrap();
+
+    assert_eq!(&choices[0].id, "crossroads:go_left");
+    assert_eq!(&choices[1].id, "crossroads:go_right");
+}
+
+#[test]
+fn choices_with_the_same_text_in_a_stitch_are_numbered_after_the_first() {
+    let content = "
+*   Wait.
+    Nothing happens.
+    *   *   Wait.
+            Still nothing.
+*   Leave.
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    let mut line_buffer = Vec::new();
+
+    let choices = story
+        .resume(&mut line_buffer)
+        .unwrap()
+        .get_choices()
+        .unwrap();
+
+    assert_eq!(&choices[0].id, "$ROOT$:wait");
+    assert_eq!(&choices[1].id, "$ROOT$:leave");
+
+    story.make_choice(0).unwrap();
+
+    let choices = story
+        .resume(&mut line_buffer)
+        .unwrap()
+        .get_choices()
+        .unwrap();
+
+    assert_eq!(&choices[0].id, "$ROOT$:wait:2");
+}
+
+#[test]
+fn replaying_choices_by_index_text_and_id_follows_the_story() {
+    let mut story = read_story_from_string(STORY).unwrap();
+    let mut line_buffer = Vec::new();
+
+    let choices = [
+        ChoiceRef::Index(0),
+        ChoiceRef::from("Climb a tree."),
+        ChoiceRef::Id("crossroads:go_right".to_string()),
+    ];
+
+    let prompt = story.replay(&choices, &mut line_buffer).unwrap();
+
+    assert!(prompt.get_choices().is_none());
+
+    let text = copy_lines_into_string(&line_buffer);
+
+    assert_eq!(
+        text,
+        "The road splits in two.
+Trees surround you.
+Climb a tree. The road splits in two.
+You arrive at the village.
+"
+    );
+}
+
+#[test]
+fn replaying_a_transcript_reproduces_the_playthrough() {
+    let mut story = read_story_from_string(STORY).unwrap();
+    let mut line_buffer = Vec::new();
+
+    story.record_transcript();
+
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(0).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(1).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(1).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    let choices = story
+        .transcript()
+        .unwrap()
+        .choices()
+        .map(ChoiceRef::from)
+        .collect::<Vec<_>>();
+
+    let mut replayed_story = read_story_from_string(STORY).unwrap();
+    let mut replayed_buffer = Vec::new();
+
+    replayed_story
+        .replay(&choices, &mut replayed_buffer)
+        .unwrap();
+
+    assert_eq!(replayed_buffer, line_buffer);
+}
+
+#[test]
+fn replay_reports_where_a_choice_could_not_be_made() {
+    let mut story = read_story_from_string(STORY).unwrap();
+    let mut line_buffer = Vec::new();
+
+    let choices = [
+        ChoiceRef::from("Go left"),
+        ChoiceRef::from("Climb a tree."),
+        ChoiceRef::from("Go left"),
+        ChoiceRef::from("Climb a tree."),
+    ];
+
+    match story.replay(&choices, &mut line_buffer) {
+        Err(InklingError::ReplayDiverged {
+            step,
+            choice,
+            location,
+            presented_choices,
+        }) => {
+            assert_eq!(step, 3);
+            assert_eq!(choice, ChoiceRef::from("Climb a tree."));
+            assert_eq!(location, Location::from("forest"));
+            assert_eq!(presented_choices.len(), 1);
+            assert_eq!(&presented_choices[0].text, "Head back.");
+        }
+        other => panic!(
+            "expected `InklingError::ReplayDiverged` but got {:?}",
+            other
+        ),
+    }
+}
+
+#[test]
+fn replay_reports_if_the_story_ends_before_all_choices_are_made() {
+    let mut story = read_story_from_string(STORY).unwrap();
+    let mut line_buffer = Vec::new();
+
+    let choices = [ChoiceRef::Index(1), ChoiceRef::Index(0)];
+
+    match story.replay(&choices, &mut line_buffer) {
+        Err(InklingError::ReplayDiverged {
+            step,
+            presented_choices,
+            ..
+        }) => {
+            assert_eq!(step, 1);
+            assert!(presented_choices.is_empty());
+        }
+        other => panic!(
+            "expected `InklingError::ReplayDiverged` but got {:?}",
+            other
+        ),
+    }
+}
@@ -0,0 +1,243 @@
+use inkling::*;
+
+#[test]
+fn rewinding_restores_variables_visit_counts_and_location() {
+    let content = "
+
+VAR coins = 3
+
+-> market
+
+== market ==
+You are at the market with {coins} coins.
+
++   Buy an apple. -> market
++   Go home. -> home
+
+== home ==
+You came home.
+-> END
+
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    story.set_history_limit(10);
+    let mut line_buffer = Vec::new();
+
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(0).unwrap();
+    story.set_variable("coins", 2).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(1).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    assert_eq!(story.get_variable("coins").unwrap(), Variable::Int(2));
+    assert_eq!(story.get_num_visited(&"market".into()), Some(2));
+    assert_eq!(story.get_num_rewind_steps(), 2);
+
+    story.rewind(2).unwrap();
+
+    assert_eq!(story.get_variable("coins").unwrap(), Variable::Int(3));
+    assert_eq!(story.get_num_visited(&"market".into()), Some(1));
+    assert_eq!(story.get_current_location(), Location::from("market"));
+    assert_eq!(story.get_num_rewind_steps(), 0);
+
+    line_buffer.clear();
+
+    let choices = story
+        .resume(&mut line_buffer)
+        .unwrap()
+        .get_choices()
+        .unwrap();
+
+    assert!(line_buffer.is_empty());
+    assert_eq!(&choices[0].text, "Buy an apple.");
+    assert_eq!(&choices[1].text, "Go home.");
+
+    story.make_choice(1).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    assert_eq!(line_buffer[0].text.trim(), "Go home.");
+    assert_eq!(&line_buffer[1].text, "You came home.\n");
+}
+
+#[test]
+fn rewinding_restores_alternative_sequences_and_used_up_choices() {
+    let content = "
+
+-> hallway
+
+== hallway ==
+{The clock ticks.|The clock tocks.|The clock is silent.}
+
+*   Try to {&open|close} the door. -> hallway
+*   Wait. -> hallway
+
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    story.set_history_limit(10);
+    let mut line_buffer = Vec::new();
+
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(1).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    assert_eq!(&line_buffer[2].text, "The clock tocks.\n");
+
+    story.rewind(1).unwrap();
+    line_buffer.clear();
+
+    story.make_choice(0).unwrap();
+
+    let choices = story
+        .resume(&mut line_buffer)
+        .unwrap()
+        .get_choices()
+        .unwrap();
+
+    assert_eq!(line_buffer[0].text.trim(), "Try to open the door.");
+    assert_eq!(&line_buffer[1].text, "The clock tocks.\n");
+    assert_eq!(choices.len(), 1);
+    assert_eq!(&choices[0].text, "Wait.");
+
+    story.rewind(1).unwrap();
+
+    let choices = story
+        .resume(&mut line_buffer)
+        .unwrap()
+        .get_choices()
+        .unwrap();
+
+    assert_eq!(choices.len(), 2);
+    assert_eq!(&choices[0].text, "Try to open the door.");
+}
+
+#[test]
+fn rewinding_more_steps_than_recorded_yields_error_and_keeps_state() {
+    let content = "
+
+*   Left.
+*   Right.
+
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    story.set_history_limit(10);
+    let mut line_buffer = Vec::new();
+
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(1).unwrap();
+
+    match story.rewind(2) {
+        Err(InklingError::InvalidRewind {
+            steps,
+            num_available,
+        }) => {
+            assert_eq!(steps, 2);
+            assert_eq!(num_available, 1);
+        }
+        other => panic!("expected `InklingError::InvalidRewind` but got {:?}", other),
+    }
+
+    story.resume(&mut line_buffer).unwrap();
+    assert_eq!(&line_buffer[0].text, "Right.\n");
+}
+
+#[test]
+fn history_limit_drops_oldest_recorded_choices() {
+    let content = "
+
+-> loop
+
+== loop ==
++   Again. -> loop
+
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    story.set_history_limit(10);
+    let mut line_buffer = Vec::new();
+
+    story.set_history_limit(2);
+
+    for _ in 0..4 {
+        story.resume(&mut line_buffer).unwrap();
+        story.make_choice(0).unwrap();
+    }
+
+    assert_eq!(story.get_num_rewind_steps(), 2);
+    assert!(story.rewind(3).is_err());
+    assert!(story.rewind(2).is_ok());
+}
+
+#[test]
+fn cloned_stories_can_be_followed_independently() {
+    let content = "
+
+-> hallway
+
+== hallway ==
+*   Try to {&open|close|kick} the door. -> hallway
++   Wait. -> hallway
+
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    story.set_history_limit(10);
+    let mut line_buffer = Vec::new();
+
+    story.resume(&mut line_buffer).unwrap();
+
+    let mut clone = story.clone();
+
+    story.make_choice(1).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(1).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    clone.make_choice(1).unwrap();
+
+    let choices = clone
+        .resume(&mut line_buffer)
+        .unwrap()
+        .get_choices()
+        .unwrap();
+
+    assert_eq!(&choices[0].text, "Try to close the door.");
+}
+
+#[cfg(feature = "random")]
+#[test]
+fn rewinding_restores_random_number_generator() {
+    let content = "
+
+-> roll
+
+== roll ==
+{~1|2|3|4|5|6|7|8|9|10}
++   Roll again. -> roll
+
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    story.set_history_limit(10);
+    let mut line_buffer = Vec::new();
+
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(0).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    let first_rolls = line_buffer.clone();
+
+    story.rewind(1).unwrap();
+    line_buffer.clear();
+
+    story.resume(&mut line_buffer).unwrap();
+    line_buffer.clear();
+
+    story.make_choice(0).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    assert_eq!(line_buffer.last(), first_rolls.last());
+}
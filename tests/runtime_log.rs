@@ -0,0 +1,152 @@
+use inkling::{
+    error::{Diagnostic, Severity},
+    log::{LogMessage, MessageKind, RuntimeMessage},
+    *,
+};
+
+fn get_runtime_messages(story: &mut Story) -> Vec<LogMessage> {
+    story.log.drain_runtime_messages().collect()
+}
+
+#[test]
+fn fallback_choices_and_exhausted_alternatives_are_logged_with_location() {
+    let content = "
+-> hallway
+== hallway
+{!A door creaks.|A door slams.}
++   {hallway < 3} [Open the door] -> hallway
+*   [] The hallway is silent.
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    let mut line_buffer = Vec::new();
+
+    story.resume(&mut line_buffer).unwrap();
+    assert!(get_runtime_messages(&mut story).is_empty());
+
+    story.make_choice(0).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+    get_runtime_messages(&mut story);
+
+    story.make_choice(0).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    let messages = get_runtime_messages(&mut story);
+
+    assert_eq!(messages.len(), 2);
+
+    assert!(matches!(
+        messages[0].message,
+        MessageKind::Runtime(RuntimeMessage::AlternativeExhausted)
+    ));
+    assert_eq!(messages[0].meta_data.line(), 4);
+    assert_eq!(messages[0].location, Some(Location::from("hallway")));
+
+    assert!(matches!(
+        messages[1].message,
+        MessageKind::Runtime(RuntimeMessage::FallbackChoice)
+    ));
+    assert_eq!(messages[1].meta_data.line(), 6);
+    assert_eq!(messages[1].severity(), Severity::Note);
+    assert_eq!(Diagnostic::from(&messages[1]).code, "N0002");
+}
+
+#[test]
+fn exhausted_alternatives_are_only_logged_the_first_time() {
+    let content = "
+-> hallway
+== hallway
+{!A door creaks.|A door slams.}
++   [Open the door] -> hallway
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    let mut line_buffer = Vec::new();
+
+    story.resume(&mut line_buffer).unwrap();
+
+    for _ in 0..4 {
+        story.make_choice(0).unwrap();
+        story.resume(&mut line_buffer).unwrap();
+    }
+
+    let num_exhausted = get_runtime_messages(&mut story)
+        .iter()
+        .filter(|message| {
+            matches!(
+                message.message,
+                MessageKind::Runtime(RuntimeMessage::AlternativeExhausted)
+            )
+        })
+        .count();
+
+    assert_eq!(num_exhausted, 1);
+}
+
+#[test]
+fn revisited_sticky_choices_and_ends_in_nested_weaves_are_logged() {
+    let content = "
+-> square
+== square
++   [Look around] -> square
+*   [Leave]
+    You walk away. -> END
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    let mut line_buffer = Vec::new();
+
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(0).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(0).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    let messages = get_runtime_messages(&mut story);
+    let revisits = messages
+        .iter()
+        .map(|message| match &message.message {
+            MessageKind::Runtime(RuntimeMessage::StickyChoicesRevisited { num_offered }) => {
+                *num_offered
+            }
+            other => panic!("expected revisited choices, got {:?}", other),
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(&revisits, &[2, 3]);
+
+    story.make_choice(1).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    let messages = get_runtime_messages(&mut story);
+
+    assert_eq!(messages.len(), 1);
+    assert!(matches!(
+        messages[0].message,
+        MessageKind::Runtime(RuntimeMessage::EndInNestedWeave { depth: 1 })
+    ));
+    assert_eq!(Diagnostic::from(&messages[0]).code, "N0005");
+    assert_eq!(
+        &messages[0].to_string(),
+        "[line 6 in 'square'] NOTE: story was ended by a divert to `END` or `DONE` from \
+         inside 1 level(s) of choices: content gathered after them is not shown"
+    );
+}
+
+#[test]
+fn draining_runtime_messages_keeps_warnings_from_reading_the_script() {
+    let content = "
+VAR unused = 1
+*   [] Nothing happens.
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    let num_warnings = story.log.warnings.len();
+
+    story.resume(&mut Vec::new()).unwrap();
+
+    assert!(num_warnings > 0);
+    assert_eq!(get_runtime_messages(&mut story).len(), 1);
+    assert!(story.log.runtime_messages.is_empty());
+    assert_eq!(story.log.warnings.len(), num_warnings);
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "random")]
+
+use inkling::*;
+
+use std::sync::{Arc, Mutex};
+
+const STORY: &str = "
+
+-> roll
+
+== roll ==
+{~1|2|3|4|5|6|7|8|9|10}
++   Roll again. -> roll
+
+";
+
+fn roll_dice(story: &mut Story, num_rolls: usize) -> LineBuffer {
+    let mut line_buffer = Vec::new();
+
+    story.resume(&mut line_buffer).unwrap();
+
+    for _ in 1..num_rolls {
+        story.make_choice(0).unwrap();
+        story.resume(&mut line_buffer).unwrap();
+    }
+
+    line_buffer
+}
+
+#[test]
+fn stories_with_the_same_seed_shuffle_identically() {
+    let mut story = read_story_from_string(STORY).unwrap();
+    let mut other = read_story_from_string(STORY).unwrap();
+
+    story.set_seed(1234);
+    other.set_seed(1234);
+
+    assert_eq!(story.get_seed(), 1234);
+    assert_eq!(roll_dice(&mut story, 20), roll_dice(&mut other, 20));
+}
+
+#[test]
+fn stories_can_be_read_with_a_seed() {
+    let options = ReadOptions { seed: Some(1234) };
+
+    let mut story = read_story_from_string_with_options(STORY, &options).unwrap();
+    let mut other = read_story_from_string(STORY).unwrap();
+
+    other.set_seed(1234);
+
+    assert_eq!(story.get_seed(), 1234);
+    assert_eq!(roll_dice(&mut story, 20), roll_dice(&mut other, 20));
+}
+
+#[derive(Debug)]
+struct SharedSource {
+    state: u64,
+    num_drawn: usize,
+}
+
+impl RandomSource for SharedSource {
+    fn next_u64(&mut self) -> u64 {
+        // Simple xorshift generator, deterministic from its initial state
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.num_drawn += 1;
+
+        self.state
+    }
+}
+
+#[test]
+fn shared_random_source_is_used_instead_of_the_story_generator() {
+    let source = Arc::new(Mutex::new(SharedSource {
+        state: 88172645463325252,
+        num_drawn: 0,
+    }));
+    let other_source = Arc::new(Mutex::new(SharedSource {
+        state: 88172645463325252,
+        num_drawn: 0,
+    }));
+
+    let mut story = read_story_from_string(STORY).unwrap();
+    let mut other = read_story_from_string(STORY).unwrap();
+
+    // Different seeds do not matter when the same source is used
+    story.set_seed(1);
+    other.set_seed(2);
+
+    story.set_random_source(source.clone());
+    other.set_random_source(other_source.clone());
+
+    assert_eq!(roll_dice(&mut story, 20), roll_dice(&mut other, 20));
+    assert!(source.lock().unwrap().num_drawn > 0);
+
+    story.clear_random_source();
+    let num_drawn = source.lock().unwrap().num_drawn;
+
+    roll_dice(&mut story, 20);
+
+    assert_eq!(source.lock().unwrap().num_drawn, num_drawn);
+}
+
+#[test]
+fn cloned_stories_do_not_share_the_random_source() {
+    let source = Arc::new(Mutex::new(SharedSource {
+        state: 88172645463325252,
+        num_drawn: 0,
+    }));
+
+    let mut story = read_story_from_string(STORY).unwrap();
+    story.set_random_source(source.clone());
+
+    let mut clone = story.clone();
+    roll_dice(&mut clone, 20);
+
+    assert_eq!(source.lock().unwrap().num_drawn, 0);
+}
+
+#[test]
+fn rewinding_keeps_the_current_random_source() {
+    let source = Arc::new(Mutex::new(SharedSource {
+        state: 88172645463325252,
+        num_drawn: 0,
+    }));
+
+    let mut story = read_story_from_string(STORY).unwrap();
+    story.set_history_limit(20);
+    story.set_random_source(source.clone());
+    roll_dice(&mut story, 20);
+
+    // A source which was cleared after the choices were made is not restored
+    story.clear_random_source();
+    story.rewind(10).unwrap();
+
+    let num_drawn = source.lock().unwrap().num_drawn;
+    roll_dice(&mut story, 20);
+
+    assert_eq!(source.lock().unwrap().num_drawn, num_drawn);
+
+    // A source which was set after the choices were made is kept
+    story.set_random_source(source.clone());
+    story.rewind(10).unwrap();
+
+    roll_dice(&mut story, 20);
+
+    assert!(source.lock().unwrap().num_drawn > num_drawn);
+}
@@ -71,4 +71,126 @@ You head back.
         assert_eq!(choices_without_torch.len(), 1);
         assert_eq!(choices_with_torch.len(), 2);
     }
+
+    #[test]
+    fn transcripts_can_be_serialized() {
+        let content = "
+
+The tram rattled past.
+
+*   I got on.
+*   I waited for the next one.
+
+";
+
+        let mut story = read_story_from_string(content).unwrap();
+        let mut line_buffer = Vec::new();
+
+        story.record_transcript();
+
+        story.resume(&mut line_buffer).unwrap();
+        story.make_choice(1).unwrap();
+        story.resume(&mut line_buffer).unwrap();
+
+        let transcript = story.transcript().unwrap();
+
+        let serialized = serde_json::to_string(transcript).unwrap();
+        let deserialized: Transcript = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(&deserialized, transcript);
+    }
+
+    #[test]
+    fn stories_saved_at_a_breakpoint_resume_from_the_paused_line() {
+        let content = "\
+The lift doors opened.
+*   I stepped in.
+    The lift went up.
+    It stopped at the top floor.
+";
+
+        let mut story = read_story_from_string(content).unwrap();
+        let mut line_buffer = Vec::new();
+
+        story.add_breakpoint(Breakpoint::Line(4)).unwrap();
+
+        story.resume(&mut line_buffer).unwrap();
+        story.make_choice(0).unwrap();
+
+        match story.resume(&mut line_buffer).unwrap() {
+            Prompt::Breakpoint(pause) => assert_eq!(pause.line, 4),
+            _ => panic!("expected a pause"),
+        }
+
+        let serialized = serde_json::to_string(&story).unwrap();
+        let mut loaded: Story = serde_json::from_str(&serialized).unwrap();
+
+        line_buffer.clear();
+        loaded.resume(&mut line_buffer).unwrap();
+
+        assert_eq!(&line_buffer[0].text, "It stopped at the top floor.\n");
+        assert_eq!(loaded.get_breakpoints(), vec![Breakpoint::Line(4)]);
+    }
+
+    #[cfg(feature = "random")]
+    #[test]
+    fn stories_saved_before_rewinding_was_added_can_be_loaded_and_continued() {
+        // Saved at the choice in the cellar of this story:
+        //
+        //     VAR coins = 3
+        //
+        //     -> tavern
+        //
+        //     == tavern ==
+        //     You enter the tavern. {~It is busy.|It is quiet.}
+        //
+        //     *   [Order a drink] -> cellar
+        //     +   Leave. -> END
+        //
+        //     == cellar ==
+        //     The barkeep sends you to the cellar.
+        //     *   Look at the barrels. # inspect
+        //         You find {coins} coins behind them.
+        //         -> END
+        //     +   Go back up. -> tavern
+        let saved = include_str!("data/baseline_save.json");
+
+        let mut story: Story = serde_json::from_str(saved).unwrap();
+        let mut line_buffer = Vec::new();
+
+        assert_eq!(story.get_num_rewind_steps(), 0);
+        assert_eq!(story.get_variable("coins").unwrap(), Variable::Int(3));
+
+        story.make_choice(0).unwrap();
+        story.resume(&mut line_buffer).unwrap();
+
+        assert_eq!(&line_buffer[0].tags, &["inspect".to_string()]);
+        assert_eq!(
+            &line_buffer.last().unwrap().text,
+            "You find 3 coins behind them.\n"
+        );
+    }
+
+    #[test]
+    fn diagnostics_are_serialized_with_their_code_severity_and_columns() {
+        let error = read_story_from_string("\nVAR knot = 2\nHello {world\n").unwrap_err();
+        let diagnostic = error::get_diagnostics(&error).remove(0);
+
+        let serialized = serde_json::to_string(&diagnostic).unwrap();
+
+        assert_eq!(
+            serialized,
+            r#"{"code":"E0102","severity":"error","message":"line has unmatched curly '{}' braces","line":3,"column":7,"end_column":8}"#
+        );
+
+        let deserialized: error::Diagnostic = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(deserialized.code, diagnostic.code);
+        assert_eq!(deserialized.severity, diagnostic.severity);
+        assert_eq!(deserialized.meta_data.line(), 3);
+        assert_eq!(
+            deserialized.meta_data.column_range(),
+            diagnostic.meta_data.column_range()
+        );
+    }
 }
@@ -0,0 +1,105 @@
+use inkling::*;
+
+use std::sync::{Arc, Mutex};
+
+const CONTENT: &str = "
+VAR coins = 1
+-> market
+
+== market ==
+{&Busy|Quiet} day at the market.
+{coins > 2: You can afford an apple.}
+*   {coins > 0} [Buy bread] -> home
+*   [Steal an apple] -> home
+
+== home ==
+You came home.
+-> END
+";
+
+#[derive(Default)]
+struct Events(Vec<String>);
+
+impl Tracer for Events {
+    fn trace(&mut self, event: &TraceEvent) {
+        self.0.push(event.to_string());
+    }
+}
+
+fn play_with_tracer() -> (Story, Arc<Mutex<Events>>) {
+    let mut story = read_story_from_string(CONTENT).unwrap();
+    let events = Arc::new(Mutex::new(Events::default()));
+
+    story.set_tracer(events.clone());
+
+    (story, events)
+}
+
+#[test]
+fn tracer_receives_events_from_following_the_story() {
+    let (mut story, events) = play_with_tracer();
+    let mut line_buffer = Vec::new();
+
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(0).unwrap();
+    story.set_variable("coins", 0).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    assert_eq!(
+        &events.lock().unwrap().0,
+        &[
+            "Entered '$ROOT$'",
+            "Diverted from '$ROOT$' to 'market'",
+            "Entered 'market'",
+            "Alternative `{&Busy|Quiet}` picked item 1 of 2",
+            "Read 'coins' = 1",
+            "Condition `coins > 2` is false",
+            "Read 'coins' = 1",
+            "Condition `coins > 0` is true",
+            "Presented choices in 'market': 'Buy bread', 'Steal an apple'",
+            "Selected choice 'Buy bread' in 'market'",
+            "Set 'coins' = 0 (was 1)",
+            "Diverted from 'market' to 'home'",
+            "Entered 'home'",
+            "Reached the end in 'home'",
+        ]
+    );
+}
+
+#[test]
+fn tracer_is_kept_when_rewinding_and_can_be_cleared() {
+    let (mut story, events) = play_with_tracer();
+    let mut line_buffer = Vec::new();
+
+    story.set_history_limit(1);
+
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(1).unwrap();
+    story.rewind(1).unwrap();
+
+    events.lock().unwrap().0.clear();
+
+    story.make_choice(1).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    assert_eq!(
+        events.lock().unwrap().0.first().unwrap(),
+        "Selected choice 'Steal an apple' in 'market'"
+    );
+
+    story.clear_tracer();
+    events.lock().unwrap().0.clear();
+
+    story.set_variable("coins", 5).unwrap();
+
+    assert!(events.lock().unwrap().0.is_empty());
+}
+
+#[test]
+fn explored_stories_do_not_send_events() {
+    let (story, events) = play_with_tracer();
+
+    story.explore(&ExploreOptions::default());
+
+    assert!(events.lock().unwrap().0.is_empty());
+}
@@ -0,0 +1,154 @@
+use inkling::*;
+
+#[test]
+fn transcript_records_lines_and_choices_with_locations_and_turns() {
+    let content = "
+
+-> station
+
+== station ==
+The train pulled into the station.
+-> platform
+
+= platform
+A conductor waved at me.
+
+*   I boarded the train. -> carriage
+*   I stayed behind. -> END
+
+== carriage ==
+The carriage was empty.
+-> END
+
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    let mut line_buffer = Vec::new();
+
+    story.record_transcript();
+
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(0).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    let transcript = story.transcript().unwrap();
+
+    let entries = transcript
+        .entries
+        .iter()
+        .map(|entry| {
+            let text = match &entry.event {
+                TranscriptEvent::Line(line) => line.text.trim().to_string(),
+                TranscriptEvent::Choice(choice) => format!("> {}", choice.text),
+            };
+
+            (entry.turn, entry.location.clone(), text)
+        })
+        .collect::<Vec<_>>();
+
+    let station = Location::from("station");
+    let platform = Location::with_stitch("station", "platform");
+    let carriage = Location::from("carriage");
+
+    assert_eq!(
+        entries,
+        vec![
+            (0, station, "The train pulled into the station.".to_string()),
+            (0, platform.clone(), "A conductor waved at me.".to_string()),
+            (0, platform.clone(), "> I boarded the train.".to_string()),
+            (1, platform, "I boarded the train.".to_string()),
+            (1, carriage, "The carriage was empty.".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn transcript_is_only_recorded_when_enabled() {
+    let content = "
+
+The kettle whistled.
+
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    let mut line_buffer = Vec::new();
+
+    story.resume(&mut line_buffer).unwrap();
+
+    assert!(story.transcript().is_none());
+}
+
+#[test]
+fn taking_transcript_stops_recording() {
+    let content = "
+
+*   Left.
+*   Right.
+
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    let mut line_buffer = Vec::new();
+
+    story.record_transcript();
+
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(0).unwrap();
+
+    let transcript = story.take_transcript().unwrap();
+    assert_eq!(transcript.choices().count(), 1);
+
+    story.resume(&mut line_buffer).unwrap();
+    assert!(story.transcript().is_none());
+}
+
+#[test]
+fn rewinding_the_story_removes_rewound_content_from_transcript() {
+    let content = "
+
+-> corridor
+
+== corridor ==
+You stand in a long corridor.
+
++   Walk on. -> corridor
++   Turn back. -> END
+
+";
+
+    let mut story = read_story_from_string(content).unwrap();
+    let mut line_buffer = Vec::new();
+
+    story.set_history_limit(2);
+    story.record_transcript();
+
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(0).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(0).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    story.rewind(1).unwrap();
+
+    let transcript = story.transcript().unwrap();
+
+    assert_eq!(transcript.get_turn(), 1);
+    assert_eq!(transcript.choices().count(), 1);
+    assert_eq!(
+        transcript.lines().last().unwrap().text,
+        "You stand in a long corridor.\n"
+    );
+
+    story.resume(&mut line_buffer).unwrap();
+    story.make_choice(1).unwrap();
+    story.resume(&mut line_buffer).unwrap();
+
+    let choices = story
+        .transcript()
+        .unwrap()
+        .choices()
+        .map(|choice| choice.text.clone())
+        .collect::<Vec<_>>();
+
+    assert_eq!(choices, vec!["Walk on.", "Turn back."]);
+}